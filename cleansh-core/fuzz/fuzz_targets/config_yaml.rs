@@ -0,0 +1,18 @@
+// cleansh-core/fuzz/fuzz_targets/config_yaml.rs
+//! Fuzzes YAML rule configuration parsing.
+//!
+//! Parsing must never panic, and any configuration it accepts must compile into an engine.
+
+#![no_main]
+
+use cleansh_core::{RedactionConfig, RegexEngine};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(config) = RedactionConfig::from_yaml_str(text) {
+        assert!(RegexEngine::new(config).is_ok(), "accepted config did not compile");
+    }
+});
@@ -0,0 +1,54 @@
+// cleansh-core/fuzz/fuzz_targets/sanitize.rs
+//! Fuzzes the regex engine with arbitrary input and arbitrary rule sets.
+//!
+//! The data is split at the first NUL byte: each line before it is a rule pattern and
+//! everything after it is the content to sanitize. Rule sets that fail to compile are
+//! skipped; for the rest, the edits must be valid byte ranges that reproduce the output.
+
+#![no_main]
+
+use cleansh_core::{apply_redactions, RedactionConfig, RedactionRule, RegexEngine, SanitizationEngine};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some(split) = data.iter().position(|b| *b == 0) else {
+        return;
+    };
+    let (Ok(patterns), Ok(input)) = (std::str::from_utf8(&data[..split]), std::str::from_utf8(&data[split + 1..])) else {
+        return;
+    };
+
+    let rules = patterns
+        .lines()
+        .take(8)
+        .enumerate()
+        .map(|(i, pattern)| RedactionRule {
+            name: format!("fuzz_{}", i),
+            pattern: Some(pattern.to_string()),
+            replace_with: format!("[R{}]", i),
+            ..Default::default()
+        })
+        .collect();
+    let Ok(engine) = RegexEngine::new(RedactionConfig { rules, ..Default::default() }) else {
+        return;
+    };
+    let Ok((sanitized, _, edits)) = engine.sanitize_with_edits(input, "") else {
+        return;
+    };
+
+    let mut previous_end = 0;
+    for edit in &edits {
+        let (start, end) = (edit.start as usize, edit.end as usize);
+        assert!(previous_end <= start && start <= end && end <= input.len());
+        assert!(input.is_char_boundary(start) && input.is_char_boundary(end));
+        previous_end = end;
+    }
+    assert_eq!(apply_redactions(input, &edits), sanitized);
+
+    if let Ok(matches) = engine.find_matches_for_ui(input, "") {
+        for m in matches {
+            assert!(m.start <= m.end && m.end as usize <= input.len());
+            assert!(input.is_char_boundary(m.start as usize) && input.is_char_boundary(m.end as usize));
+        }
+    }
+});
@@ -0,0 +1,113 @@
+//! Incremental redaction summaries.
+//!
+//! Streaming and parallel callers see their results a piece at a time: the matches of one
+//! line, or the summary of one file, pass or worker. `Aggregator` folds these pieces into
+//! one [`RedactionSummaryItem`] per rule, keeping rules in the order they were first
+//! seen, and returns the summary whenever it is asked for.
+//! License: BUSL-1.1
+
+use std::collections::HashMap;
+
+use crate::config::RedactionSummaryItem;
+use crate::redaction_match::RedactionMatch;
+
+/// Builds a redaction summary from matches and partial summaries as they arrive.
+#[derive(Debug, Clone, Default)]
+pub struct Aggregator {
+    items: Vec<RedactionSummaryItem>,
+    index: HashMap<String, usize>,
+    text_limit: Option<usize>,
+}
+
+impl Aggregator {
+    /// Creates an empty aggregator that keeps every original and sanitized text.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty aggregator that keeps at most `limit` original and sanitized texts
+    /// per rule. Occurrences are still counted in full, so a stream that never ends does
+    /// not grow the summary without bound.
+    pub fn with_text_limit(limit: usize) -> Self {
+        Self { text_limit: Some(limit), ..Self::default() }
+    }
+
+    fn item_for(&mut self, rule_name: &str) -> &mut RedactionSummaryItem {
+        let index = match self.index.get(rule_name) {
+            Some(&index) => index,
+            None => {
+                self.items.push(RedactionSummaryItem {
+                    rule_name: rule_name.to_string(),
+                    occurrences: 0,
+                    original_texts: Vec::new(),
+                    sanitized_texts: Vec::new(),
+                });
+                self.index.insert(rule_name.to_string(), self.items.len() - 1);
+                self.items.len() - 1
+            }
+        };
+        &mut self.items[index]
+    }
+
+    /// Counts one match.
+    pub fn add_match(&mut self, m: &RedactionMatch) {
+        self.add_item(RedactionSummaryItem {
+            rule_name: m.rule_name.clone(),
+            occurrences: 1,
+            original_texts: vec![m.original_string.clone()],
+            sanitized_texts: vec![m.sanitized_string.clone()],
+        });
+    }
+
+    /// Counts each of `matches`.
+    pub fn add_matches<'a>(&mut self, matches: impl IntoIterator<Item = &'a RedactionMatch>) {
+        for m in matches {
+            self.add_match(m);
+        }
+    }
+
+    /// Adds a summary item from a partial summary to the item of the same rule.
+    pub fn add_item(&mut self, item: RedactionSummaryItem) {
+        let limit = self.text_limit;
+        let existing = self.item_for(&item.rule_name);
+        existing.occurrences += item.occurrences;
+        for (texts, more) in [(&mut existing.original_texts, item.original_texts), (&mut existing.sanitized_texts, item.sanitized_texts)] {
+            let room = limit.map_or(usize::MAX, |limit| limit.saturating_sub(texts.len()));
+            texts.extend(more.into_iter().take(room));
+        }
+    }
+
+    /// Adds every item of a partial summary, such as the one returned by
+    /// `SanitizationEngine::sanitize` for one chunk of input.
+    pub fn add_summary(&mut self, items: impl IntoIterator<Item = RedactionSummaryItem>) {
+        for item in items {
+            self.add_item(item);
+        }
+    }
+
+    /// Adds everything `other` has collected, e.g. to combine the aggregators of parallel
+    /// workers. Rules new to `self` follow its own rules in `other`'s order.
+    pub fn merge(&mut self, other: Aggregator) {
+        self.add_summary(other.items);
+    }
+
+    /// Returns `true` if nothing has been counted yet.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Total number of occurrences over all rules.
+    pub fn total_occurrences(&self) -> usize {
+        self.items.iter().map(|item| item.occurrences).sum()
+    }
+
+    /// Returns the summary so far, one item per rule in the order rules were first seen.
+    pub fn summary(&self) -> Vec<RedactionSummaryItem> {
+        self.items.clone()
+    }
+
+    /// Returns the summary, consuming the aggregator.
+    pub fn into_summary(self) -> Vec<RedactionSummaryItem> {
+        self.items
+    }
+}
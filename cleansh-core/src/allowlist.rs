@@ -0,0 +1,76 @@
+//! Values that are never redacted, whichever rule matches them.
+//!
+//! An [`Allowlist`] holds literal values, such as a documented example address, and
+//! patterns written between slashes, such as `/192\.0\.2\.\d+/`. A match is left alone when
+//! its text equals one of the literals or one of the patterns matches all of it. Rules can
+//! also list their own `exceptions`, and a line containing [`IGNORE_LINE_MARKER`] is not
+//! redacted at all.
+//! License: BUSL-1.1
+
+use regex::RegexSet;
+use std::collections::HashSet;
+
+/// Marks a line whose matches are left alone, e.g. in a comment:
+/// `api_key = "sk-test-1234" # cleansh:ignore-line`.
+pub const IGNORE_LINE_MARKER: &str = "cleansh:ignore-line";
+
+/// A compiled set of allowlisted values. See the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Allowlist {
+    literals: HashSet<String>,
+    patterns: RegexSet,
+}
+
+impl Allowlist {
+    /// Compiles `entries`. An entry of the form `/pattern/` is a regex; any other entry is
+    /// a literal value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is not a valid regex.
+    pub fn new(entries: &[String]) -> Result<Self, regex::Error> {
+        let mut literals = HashSet::new();
+        let mut patterns = Vec::new();
+        for entry in entries {
+            match entry.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+                Some(pattern) if entry.len() > 1 => patterns.push(format!("^(?:{})$", pattern)),
+                _ => {
+                    literals.insert(entry.clone());
+                }
+            }
+        }
+        Ok(Self { literals, patterns: RegexSet::new(patterns)? })
+    }
+
+    /// Reads the entries of an allowlist file: one per line, with blank lines and lines
+    /// starting with `#` skipped and surrounding whitespace trimmed.
+    pub fn parse_entries(text: &str) -> Vec<String> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns `true` if `value` must not be redacted.
+    pub fn allows(&self, value: &str) -> bool {
+        self.literals.contains(value) || self.patterns.is_match(value)
+    }
+}
+
+/// Returns the spans of the lines of `input` that contain [`IGNORE_LINE_MARKER`], each
+/// from the start of the line to the end of its line break, in order.
+pub fn ignored_lines(input: &str) -> Vec<(u64, u64)> {
+    let mut spans = Vec::new();
+    if !input.contains(IGNORE_LINE_MARKER) {
+        return spans;
+    }
+    let mut start = 0;
+    for line in input.split_inclusive('\n') {
+        if line.contains(IGNORE_LINE_MARKER) {
+            spans.push((start as u64, (start + line.len()) as u64));
+        }
+        start += line.len();
+    }
+    spans
+}
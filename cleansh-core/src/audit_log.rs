@@ -7,11 +7,21 @@
 //! performed.
 
 use crate::redaction_match::RedactionLog;
+use crate::schema::SCHEMA_VERSION;
 use anyhow::{Context, Result};
+use serde::Serialize;
 use std::fs::{self, OpenOptions};
 use std::io::{Write, BufWriter};
 use std::path::{Path, PathBuf};
 
+/// One line of the audit log: the entry, stamped with the schema version.
+#[derive(Serialize)]
+struct AuditLine<'a> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    entry: &'a RedactionLog,
+}
+
 /// Manages an append-only audit log file for redaction events.
 ///
 /// The `AuditLog` struct provides a simple interface for writing `RedactionLog`
@@ -61,8 +71,8 @@ impl AuditLog {
 
     /// Appends a new `RedactionLog` entry to the audit log file.
     ///
-    /// The entry is serialized to a JSON string and written to the file,
-    /// followed by a newline character. This ensures the log remains
+    /// The entry is serialized to a JSON string, with a leading `schema_version`
+    /// field, and written to the file, followed by a newline character. This ensures the log remains
     /// a stream of valid JSON Lines, which is easy to parse.
     ///
     /// # Arguments
@@ -73,14 +83,30 @@ impl AuditLog {
     ///
     /// A `Result` indicating success or failure of the write operation.
     pub fn append(&mut self, log_entry: &RedactionLog) -> Result<()> {
-        let json_line = serde_json::to_string(log_entry)
-            .context("Failed to serialize RedactionLog to JSON")?;
-        self.writer
-            .write_all(json_line.as_bytes())
-            .context("Failed to write log entry")?;
-        self.writer
-            .write_all(b"\n")
-            .context("Failed to write newline")?;
+        self.append_all(std::slice::from_ref(log_entry))
+    }
+
+    /// Appends several `RedactionLog` entries, one line each, flushing once after the
+    /// last.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_entries` - The entries to be written, in order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure of the write operations.
+    pub fn append_all(&mut self, log_entries: &[RedactionLog]) -> Result<()> {
+        for log_entry in log_entries {
+            let json_line = serde_json::to_string(&AuditLine { schema_version: SCHEMA_VERSION, entry: log_entry })
+                .context("Failed to serialize RedactionLog to JSON")?;
+            self.writer
+                .write_all(json_line.as_bytes())
+                .context("Failed to write log entry")?;
+            self.writer
+                .write_all(b"\n")
+                .context("Failed to write newline")?;
+        }
         self.writer.flush().context("Failed to flush audit log after append")?;
         Ok(())
     }
@@ -134,16 +160,51 @@ mod tests {
             match_hash: "matchhash456".to_string(),
             start: 10,
             end: 25,
+            replacement: "[EMAIL_REDACTED]".to_string(),
         };
 
         audit_log.append(&log_entry)?;
         audit_log.flush()?; // Ensure it’s persisted for the test
 
         let log_content = fs::read_to_string(&log_path)?;
-        let expected_json = serde_json::to_string(&log_entry)?;
+        let mut expected_json = serde_json::to_value(&log_entry)?;
+        expected_json["schema_version"] = SCHEMA_VERSION.into();
+        let line: serde_json::Value = serde_json::from_str(log_content.strip_suffix('\n').unwrap())?;
         
-        assert_eq!(log_content, format!("{}\n", expected_json));
+        assert_eq!(line, expected_json);
+        assert!(log_content.starts_with("{\"schema_version\":\"1.0\","));
 
         Ok(())
     }
+
+    #[test]
+    fn test_audit_log_append_all_keeps_existing_lines() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let log_path = temp_dir.path().join("audit.jsonl");
+        let entry = |start: u64| RedactionLog {
+            timestamp: "2026-10-16T09:30:00Z".to_string(),
+            run_id: "run".to_string(),
+            file_path: "stdin".to_string(),
+            user_id: String::new(),
+            reason_for_redaction: "email".to_string(),
+            redaction_outcome: "redacted".to_string(),
+            rule_name: "email".to_string(),
+            input_hash: RedactionLog::value_hash("to jane@example.com"),
+            match_hash: RedactionLog::value_hash("jane@example.com"),
+            start,
+            end: start + 16,
+            replacement: "[EMAIL_REDACTED]".to_string(),
+        };
+
+        AuditLog::new(&log_path)?.append_all(&[entry(3)])?;
+        AuditLog::new(&log_path)?.append_all(&[entry(3), entry(40)])?;
+
+        let log_content = fs::read_to_string(&log_path)?;
+        assert_eq!(log_content.lines().count(), 3);
+        assert!(!log_content.contains("jane@example.com"));
+        let last: serde_json::Value = serde_json::from_str(log_content.lines().last().unwrap())?;
+        assert_eq!(last["start"], 40);
+        assert_eq!(last["match_hash"], "8c87b489ce35cf2e2f39f80e282cb2e804932a56a213983eeeb428407d43b52d");
+        Ok(())
+    }
 }
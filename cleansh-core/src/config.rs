@@ -11,17 +11,32 @@
 
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use log::{debug, info, warn};
 use std::fmt;
 use regex::Regex;
 use std::hash::{Hash, Hasher}; // <-- Added for Hash implementation
+use crate::replacement::ReplacementTemplate;
+use crate::sanitizers::compiler;
+use sha2::{Digest, Sha256};
 
 /// Maximum allowed length for a regex pattern string.
 /// This prevents excessively large or potentially malicious regexes.
 pub const MAX_PATTERN_LENGTH: usize = 500;
 
+/// The built-in rules, embedded at build time.
+const DEFAULT_RULES_YAML: &str = include_str!("../config/default_rules.yaml");
+
+/// `sha256sum` output for `default_rules.yaml`, checked before the built-in rules are used.
+/// Regenerate it with `sha256sum default_rules.yaml > default_rules.yaml.sha256` in
+/// `config/` whenever the rules change.
+const DEFAULT_RULES_CHECKSUM_FILE: &str = include_str!("../config/default_rules.yaml.sha256");
+
+/// Tag that marks a user rule as a deliberate downgrade of the rule it overrides, which
+/// silences the warning [`merge_rules`] logs for it.
+pub const ACCEPT_DOWNGRADE_TAG: &str = "accept-downgrade";
+
 /// Represents a single redaction rule.
 ///
 /// Each rule defines a regular expression pattern to search for, the text to replace
@@ -31,7 +46,8 @@ pub const MAX_PATTERN_LENGTH: usize = 500;
 ///
 /// * `name`: A unique identifier for the rule (e.g., "email", "ipv4_address").
 /// * `pattern`: The regular expression string to match sensitive data.
-/// * `replace_with`: The string used to replace matches of the `pattern`.
+/// * `replace_with`: The string used to replace matches of the `pattern`; a template that may
+///   use capture groups and placeholders (see [`crate::replacement`]).
 /// * `description`: An optional, human-readable explanation of what the rule targets.
 /// * `multiline`: If `true`, the regex `.` will match newlines, and `^`/`$` match line start/end.
 /// * `dot_matches_new_line`: If `true`, the `.` character in the pattern matches newlines.
@@ -40,6 +56,19 @@ pub const MAX_PATTERN_LENGTH: usize = 500;
 ///                              validation beyond just regex matching (e.g., Luhn check for credit cards).
 /// * `enabled`: An optional boolean to explicitly enable or disable a rule, overriding default behavior.
 /// * `severity`: An optional string indicating the severity of the rule.
+/// * `priority`: Controls application order and overlap tie-breaking. Rules with a higher
+///   priority are applied first; rules with equal priority keep their declared order.
+/// * `context`: An optional window of neighboring text the engine inspects around each match
+///   (see [`ContextWindow`]).
+/// * `paths`: Optional path globs limiting the files the rule runs on, e.g.
+///   `["src/**", "!tests/**"]`. Only checked when scanning named files
+///   (see [`crate::path_scope::PathScope`]).
+/// * `deprecated`: If `true`, referencing the rule by name (in enable/disable lists or
+///   profiles) logs a warning that it may be removed.
+/// * `aliases`: Former names of the rule. Enable/disable lists, groups, profiles and user
+///   overrides that use an old name apply to this rule instead, with a warning.
+/// * `exceptions`: Regex patterns for values the rule must leave alone, such as documented
+///   example addresses. A match is not redacted when one of them matches all of it.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(default)]
 pub struct RedactionRule {
@@ -59,6 +88,31 @@ pub struct RedactionRule {
     pub enabled: Option<bool>,
     pub severity: Option<String>,
     pub tags: Option<Vec<String>>,
+    pub priority: u32,
+    pub context: Option<ContextWindow>,
+    pub paths: Option<Vec<String>>,
+    pub deprecated: bool,
+    pub aliases: Option<Vec<String>>,
+    pub exceptions: Option<Vec<String>>,
+}
+
+/// Neighboring text a rule can inspect around each of its matches.
+///
+/// This lets a rule require a label such as `password:` near a value without capturing
+/// the label in its pattern, so replacement offsets still cover only the value itself.
+///
+/// # Fields
+///
+/// * `before`: Number of bytes of context to expose before the match.
+/// * `after`: Number of bytes of context to expose after the match.
+/// * `keywords`: If non-empty, a match is only redacted when at least one of these keywords
+///   appears (case-insensitively) within the context window.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ContextWindow {
+    pub before: usize,
+    pub after: usize,
+    pub keywords: Vec<String>,
 }
 
 // Manually implement the Hash trait for RedactionRule.
@@ -83,6 +137,12 @@ impl Hash for RedactionRule {
         self.programmatic_validation.hash(state);
         self.enabled.hash(state);
         self.severity.hash(state);
+        self.priority.hash(state);
+        self.context.hash(state);
+        self.paths.hash(state);
+        self.deprecated.hash(state);
+        self.aliases.hash(state);
+        self.exceptions.hash(state);
         // We're not hashing the tags since it's an Option<Vec<String>>
         // and we need to be careful with its Hash implementation.
         // For simplicity and correctness, we will omit it. If a more
@@ -109,6 +169,42 @@ impl Default for RedactionRule {
             enabled: None,
             severity: None,
             tags: None,
+            priority: 0,
+            context: None,
+            paths: None,
+            deprecated: false,
+            aliases: None,
+            exceptions: None,
+        }
+    }
+}
+
+/// The identifying metadata of a rule, as embedded in machine-readable outputs.
+///
+/// Exports carry this block next to their findings so a consumer can pin each finding to
+/// the rule revision that produced it. The pattern itself is deliberately left out.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RuleMetadata {
+    pub version: String,
+    pub author: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+}
+
+impl RedactionRule {
+    /// Returns the rule's version, authorship, timestamps, tags and severity.
+    pub fn metadata(&self) -> RuleMetadata {
+        RuleMetadata {
+            version: self.version.clone(),
+            author: self.author.clone(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+            tags: self.tags.clone().unwrap_or_default(),
+            severity: self.severity.clone(),
         }
     }
 }
@@ -117,9 +213,34 @@ impl Default for RedactionRule {
 ///
 /// This struct holds a vector of `RedactionRule` instances and provides methods
 /// for loading rule sets from various sources and managing their active state.
+///
+/// `groups` maps a group name to the rules it stands for, so related rules (for example
+/// every cloud credential) can be enabled, disabled, or overridden in a profile by a
+/// single name.
+///
+/// `tenant` names the rule whose capture group identifies the customer a line belongs to,
+/// so scan summaries can be broken down per tenant (see [`TenantKey`]).
 #[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 pub struct RedactionConfig {
     pub rules: Vec<RedactionRule>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<TenantKey>,
+}
+
+/// Identifies the tenant (customer) of a line: the value of capture group `group` in a
+/// match of rule `rule` on that line, for example the `id` group of a `tenant_id` rule.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TenantKey {
+    pub rule: String,
+    /// A named capture group, or a group number such as `"1"`.
+    #[serde(default = "default_tenant_group")]
+    pub group: String,
+}
+
+fn default_tenant_group() -> String {
+    "1".to_string()
 }
 
 /// Represents a single item in the redaction summary, including examples and occurrences.
@@ -141,6 +262,22 @@ pub struct RedactionSummaryItem {
     pub sanitized_texts: Vec<String>,
 }
 
+/// A rule left out of a configuration because it is invalid, with the reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedRule {
+    pub name: String,
+    pub reason: String,
+}
+
+/// A problem found in a configuration by [`RedactionConfig::problems`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigProblem {
+    /// The position in `rules` of the rule the problem was found in, or `None` for a
+    /// problem with the groups.
+    pub rule_index: Option<usize>,
+    pub message: String,
+}
+
 /// Custom error type for when a specific rule configuration is not found.
 #[derive(Debug)]
 pub struct RuleConfigNotFoundError {
@@ -155,7 +292,41 @@ impl fmt::Display for RuleConfigNotFoundError {
 
 impl std::error::Error for RuleConfigNotFoundError {}
 
+/// A reference to a rule by a name that still works but should be updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeprecatedName {
+    /// `old` is a former name of the rule now called `new`.
+    Alias { old: String, new: String },
+    /// The rule `name` is deprecated.
+    Rule { name: String },
+}
+
+impl fmt::Display for DeprecatedName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeprecatedName::Alias { old, new } => {
+                write!(f, "Rule '{}' has been renamed to '{}'; update your configuration to use the new name.", old, new)
+            }
+            DeprecatedName::Rule { name } => write!(f, "Rule '{}' is deprecated and may be removed in a future release.", name),
+        }
+    }
+}
+
 impl RedactionConfig {
+    /// Collects [`RuleMetadata`] for the named rules, keyed and ordered by rule name.
+    ///
+    /// Names that are not rules in this configuration are skipped.
+    pub fn rule_metadata<'a, I>(&self, names: I) -> BTreeMap<String, RuleMetadata>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        names
+            .into_iter()
+            .filter_map(|name| self.rules.iter().find(|rule| rule.name == name))
+            .map(|rule| (rule.name.clone(), rule.metadata()))
+            .collect()
+    }
+
     /// Loads redaction rules from a YAML file at the specified path.
     ///
     /// This function is typically used to load user-defined or custom rule sets.
@@ -195,11 +366,117 @@ impl RedactionConfig {
             .with_context(|| format!("Failed to parse config file {}", path.display()))?;
 
         validate_rules(&config.rules)?;
+        validate_groups(&config)?;
         info!("Loaded {} rules from file {}.", config.rules.len(), path.display());
         
         Ok(config)
     }
 
+    /// Parses and validates a configuration from YAML text.
+    ///
+    /// Applies the same rule and group validation as [`RedactionConfig::load_from_file`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::RedactionConfig;
+    /// let config = RedactionConfig::from_yaml_str("rules:\n  - name: ticket\n    pattern: 'TICKET-[0-9]+'\n    replace_with: '[TICKET]'\n").unwrap();
+    /// assert_eq!(config.rules[0].name, "ticket");
+    /// assert!(RedactionConfig::from_yaml_str("rules:\n  - name: broken\n    pattern: '('\n    replace_with: x\n").is_err());
+    /// ```
+    pub fn from_yaml_str(text: &str) -> Result<Self> {
+        let config: RedactionConfig = serde_yml::from_str(text)?;
+        validate_rules(&config.rules)?;
+        validate_groups(&config)?;
+        Ok(config)
+    }
+
+    /// Like [`RedactionConfig::load_from_file`], but rules that are invalid or do not
+    /// compile are left out and returned instead of failing the whole file, so one broken
+    /// rule does not stop the others from being applied.
+    ///
+    /// Problems that are not confined to one rule, such as duplicate rule names or
+    /// invalid groups, still fail the load.
+    pub fn load_from_file_skipping_invalid<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<SkippedRule>)> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::from_yaml_str_skipping_invalid(&text).with_context(|| format!("Failed to load config file {}", path.display()))
+    }
+
+    /// Like [`RedactionConfig::from_yaml_str`], but skips invalid rules as
+    /// [`RedactionConfig::load_from_file_skipping_invalid`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::RedactionConfig;
+    /// let yaml = "rules:\n  - name: ticket\n    pattern: 'TICKET-[0-9]+'\n    replace_with: '[TICKET]'\n  - name: broken\n    pattern: '('\n    replace_with: x\n";
+    /// let (config, skipped) = RedactionConfig::from_yaml_str_skipping_invalid(yaml).unwrap();
+    /// assert_eq!(config.rules.len(), 1);
+    /// assert_eq!(skipped[0].name, "broken");
+    /// ```
+    pub fn from_yaml_str_skipping_invalid(text: &str) -> Result<(Self, Vec<SkippedRule>)> {
+        let mut config: RedactionConfig = serde_yml::from_str(text)?;
+        let skipped = config.remove_invalid_rules();
+        validate_rules(&config.rules)?;
+        validate_groups(&config)?;
+        Ok((config, skipped))
+    }
+
+    /// Returns every problem that loading this configuration and compiling its rules
+    /// would report, instead of stopping at the first step that fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::RedactionConfig;
+    /// let yaml = "rules:\n  - name: ticket\n    pattern: 'TICKET-[0-9]+'\n    replace_with: '$2'\n  - name: broken\n    pattern: '('\n    replace_with: x\n";
+    /// let config: RedactionConfig = serde_yml::from_str(yaml).unwrap();
+    /// let problems = config.problems();
+    /// assert_eq!(problems.iter().map(|p| p.rule_index).collect::<Vec<_>>(), [Some(0), Some(1)]);
+    /// ```
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems: Vec<ConfigProblem> = rule_set_problems(&self.rules)
+            .into_iter()
+            .map(|(index, message)| ConfigProblem { rule_index: Some(index), message })
+            .collect();
+        // Rules that pass validation can still fail to compile, e.g. on a pattern that is too long.
+        for (index, rule) in self.rules.iter().enumerate() {
+            if problems.iter().any(|problem| problem.rule_index == Some(index)) {
+                continue;
+            }
+            if let Err(e) = compiler::compile_rule(rule.clone()) {
+                problems.push(ConfigProblem { rule_index: Some(index), message: e.to_string() });
+            }
+        }
+        problems.sort_by_key(|problem| problem.rule_index);
+        problems.extend(group_problems(self).into_iter().map(|message| ConfigProblem { rule_index: None, message }));
+        problems
+    }
+
+    /// Removes the rules that are invalid or do not compile, and returns them with the reason.
+    pub fn remove_invalid_rules(&mut self) -> Vec<SkippedRule> {
+        let mut skipped = Vec::new();
+        self.rules.retain(|rule| {
+            let problems = rule_problems(rule);
+            let reason = if problems.is_empty() {
+                compiler::compile_rule(rule.clone()).err().map(|e| e.to_string())
+            } else {
+                Some(problems.join(" "))
+            };
+            match reason {
+                Some(reason) => {
+                    debug!("Skipping invalid rule '{}': {}", rule.name, reason);
+                    skipped.push(SkippedRule { name: rule.name.clone(), reason });
+                    false
+                }
+                None => true,
+            }
+        });
+        skipped
+    }
+
     /// Loads default redaction rules from an embedded string.
     ///
     /// This function provides a baseline set of rules that are compiled directly
@@ -212,8 +489,9 @@ impl RedactionConfig {
     ///
     /// # Errors
     ///
-    /// This function will return an error if the embedded YAML string is malformed,
-    /// which should ideally not happen in a released version of the library.
+    /// This function will return an error if the embedded YAML does not match its shipped
+    /// checksum (see [`verify_default_rules`]) or is malformed, which should ideally not
+    /// happen in a released version of the library.
     ///
     /// # Examples
     ///
@@ -228,15 +506,97 @@ impl RedactionConfig {
     /// ```
     pub fn load_default_rules() -> Result<Self> {
         debug!("Loading default rules from embedded string...");
-        let default_yaml = include_str!("../config/default_rules.yaml");
-        let config: RedactionConfig = serde_yml::from_str(default_yaml)
+        verify_default_rules()?;
+        let config: RedactionConfig = serde_yml::from_str(DEFAULT_RULES_YAML)
             .context("Failed to parse default rules")?;
 
-        // No need to validate default rules as they are internal and trusted.
+        // No need to validate default rules as they are internal and trusted: the
+        // checksum above ensures they are the ones that were reviewed.
         debug!("Loaded {} default rules.", config.rules.len());
         Ok(config)
     }
 
+    /// Returns the current name of the rule that lists `name` among its `aliases`.
+    ///
+    /// Returns `None` when `name` is not an alias, or when a rule is itself called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::{RedactionConfig, RedactionRule};
+    /// let mut config = RedactionConfig::default();
+    /// config.rules.push(RedactionRule { name: "ssn_us".to_string(), aliases: Some(vec!["us_ssn".to_string()]), ..Default::default() });
+    ///
+    /// assert_eq!(config.resolve_alias("us_ssn"), Some("ssn_us"));
+    /// assert_eq!(config.resolve_alias("ssn_us"), None);
+    /// ```
+    pub fn resolve_alias(&self, name: &str) -> Option<&str> {
+        if self.rules.iter().any(|rule| rule.name == name) {
+            return None;
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.aliases.as_ref().is_some_and(|aliases| aliases.iter().any(|alias| alias == name)))
+            .map(|rule| rule.name.as_str())
+    }
+
+    /// Lists the names in `names` that refer to a rule by a former name or to a deprecated
+    /// rule, in the order given.
+    pub fn deprecated_names(&self, names: &[String]) -> Vec<DeprecatedName> {
+        let mut notices = Vec::new();
+        for name in names {
+            let current = match self.resolve_alias(name) {
+                Some(new) => {
+                    notices.push(DeprecatedName::Alias { old: name.clone(), new: new.to_string() });
+                    new
+                }
+                None => name.as_str(),
+            };
+            if self.rules.iter().any(|rule| rule.name == current && rule.deprecated) {
+                notices.push(DeprecatedName::Rule { name: current.to_string() });
+            }
+        }
+        notices
+    }
+
+    /// Replaces every group name in `names` with the rules of that group, and every former
+    /// rule name (see [`RedactionRule::aliases`]) with the rule's current name.
+    ///
+    /// Names that are not groups are kept as they are, and a name that is both a rule and a
+    /// group refers to the rule. The result keeps the first occurrence of each name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::RedactionConfig;
+    /// let mut config = RedactionConfig::default();
+    /// config.groups.insert("cloud".to_string(), vec!["aws_access_key".to_string(), "gcp_api_key".to_string()]);
+    ///
+    /// let names = config.expand_groups(&["email".to_string(), "cloud".to_string()]);
+    /// assert_eq!(names, vec!["email", "aws_access_key", "gcp_api_key"]);
+    /// ```
+    pub fn expand_groups(&self, names: &[String]) -> Vec<String> {
+        let rule_names: HashSet<&str> = self.rules.iter().map(|r| r.name.as_str()).collect();
+        let mut seen = HashSet::new();
+        let mut expanded = Vec::new();
+        for name in names {
+            let members = match self.groups.get(name) {
+                Some(members) if !rule_names.contains(name.as_str()) => {
+                    debug!("Expanding rule group '{}' to {:?}", name, members);
+                    members.as_slice()
+                }
+                _ => std::slice::from_ref(name),
+            };
+            for member in members {
+                let member = self.resolve_alias(member).unwrap_or(member).to_string();
+                if seen.insert(member.clone()) {
+                    expanded.push(member);
+                }
+            }
+        }
+        expanded
+    }
+
     /// Filters the rules within the configuration based on the provided lists of rules to enable or disable.
     ///
     /// This method modifies the `rules` vector in-place, removing rules that are either explicitly
@@ -248,6 +608,8 @@ impl RedactionConfig {
     ///                    Only opt-in rules need to be explicitly enabled.
     /// * `disable_rules` - A slice of `String`s representing the names of rules to explicitly disable.
     ///
+    /// Both lists may name rule groups, which stand for all of their rules.
+    ///
     /// # Examples
     ///
     /// ```
@@ -255,9 +617,9 @@ impl RedactionConfig {
     /// # use anyhow::Result;
     /// # fn main() -> Result<()> {
     /// let mut config = RedactionConfig::default();
-    /// config.rules.push(RedactionRule { name: "default_rule".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
-    /// config.rules.push(RedactionRule { name: "opt_in_rule".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: true, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
-    /// config.rules.push(RedactionRule { name: "another_default".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
+    /// config.rules.push(RedactionRule { name: "default_rule".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
+    /// config.rules.push(RedactionRule { name: "opt_in_rule".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: true, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
+    /// config.rules.push(RedactionRule { name: "another_default".to_string(), pattern: Some("".to_string()), replace_with: "".to_string(), description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()});
     ///
     /// // Initially, there are 3 rules.
     /// assert_eq!(config.rules.len(), 3);
@@ -276,6 +638,11 @@ impl RedactionConfig {
     /// # }
     /// ```
     pub fn set_active_rules(&mut self, enable_rules: &[String], disable_rules: &[String]) {
+        for notice in self.deprecated_names(enable_rules).into_iter().chain(self.deprecated_names(disable_rules)) {
+            warn!("{}", notice);
+        }
+        let enable_rules = self.expand_groups(enable_rules);
+        let disable_rules = self.expand_groups(disable_rules);
         let enable_set: HashSet<&str> = enable_rules.iter().map(String::as_str).collect();
         let disable_set: HashSet<&str> = disable_rules.iter().map(String::as_str).collect();
 
@@ -312,6 +679,30 @@ impl RedactionConfig {
 
         debug!("Final active rules count after filtering: {}", self.rules.len());
     }
+
+    /// Reorders the rules by descending `priority`, keeping declared order for equal priorities.
+    ///
+    /// This is the order in which engines apply rules, and the order used to break ties
+    /// between overlapping matches that start at the same position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cleansh_core::config::{RedactionConfig, RedactionRule};
+    /// let mut config = RedactionConfig::default();
+    /// config.rules.push(RedactionRule { name: "low".to_string(), ..Default::default() });
+    /// config.rules.push(RedactionRule { name: "high".to_string(), priority: 10, ..Default::default() });
+    /// config.rules.push(RedactionRule { name: "also_low".to_string(), ..Default::default() });
+    ///
+    /// config.sort_by_priority();
+    ///
+    /// let names: Vec<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    /// assert_eq!(names, vec!["high", "low", "also_low"]);
+    /// ```
+    pub fn sort_by_priority(&mut self) {
+        // `sort_by_key` is stable, so equal priorities keep their declared order.
+        self.rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+    }
 }
 
 /// Merges user-defined rules with default rules.
@@ -319,6 +710,13 @@ impl RedactionConfig {
 /// User-defined rules will override default rules that have the same name.
 /// Rules present only in the user configuration will be added.
 ///
+/// The resulting order is deterministic: default rules keep their original order,
+/// overridden rules stay in the position of the default they replace, and new user
+/// rules are appended in the order they appear in the user configuration.
+///
+/// An override that weakens the rule it replaces (see [`override_downgrade`]) is logged
+/// as a warning, so a policy is never downgraded silently.
+///
 /// # Arguments
 ///
 /// * `default_config` - The base `RedactionConfig`, typically loaded from default rules.
@@ -339,22 +737,22 @@ impl RedactionConfig {
 /// let mut default_config = RedactionConfig::default();
 /// default_config.rules.push(RedactionRule {
 ///     name: "email".to_string(), pattern: Some(".*@.*".to_string()), replace_with: "[EMAIL]".to_string(),
-///     description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
+///     description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
 /// });
 /// default_config.rules.push(RedactionRule {
 ///     name: "phone".to_string(), pattern: Some(r"\d{3}-\d{3}-\d{4}".to_string()), replace_with: "[PHONE]".to_string(),
-///     description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
+///     description: None, multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
 /// });
 ///
 /// // Simulate user config (overrides "phone", adds "ssn")
 /// let mut user_config = RedactionConfig::default();
 /// user_config.rules.push(RedactionRule {
 ///     name: "phone".to_string(), pattern: Some(r"\(?\d{3}\)?[-.\s]?\d{3}[-.\s]?\d{4}".to_string()), replace_with: "[PHONE_NUMBER]".to_string(),
-///     description: Some("More flexible phone number".to_string()), multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
+///     description: Some("More flexible phone number".to_string()), multiline: false, dot_matches_new_line: false, opt_in: false, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
 /// });
 /// user_config.rules.push(RedactionRule {
 ///     name: "ssn".to_string(), pattern: Some(r"\d{3}-\d{2}-\d{4}".to_string()), replace_with: "[SSN]".to_string(),
-///     description: None, multiline: false, dot_matches_new_line: false, opt_in: true, programmatic_validation: false, enabled: None, severity: None, tags: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
+///     description: None, multiline: false, dot_matches_new_line: false, opt_in: true, programmatic_validation: false, enabled: None, severity: None, tags: None, priority: 0, context: None, paths: None, deprecated: false, aliases: None, exceptions: None, pattern_type: "regex".to_string(), version: "1.0.0".to_string(), created_at: "1970-01-01T00:00:00Z".to_string(), updated_at: "1970-01-01T00:00:00Z".to_string(), author: "Obscura Team".to_string()
 /// });
 ///
 /// let merged_config = merge_rules(default_config, Some(user_config));
@@ -376,94 +774,295 @@ pub fn merge_rules(
 ) -> RedactionConfig {
     debug!("merge_rules called. Initial default rules count: {}", default_config.rules.len());
     
-    let mut final_rules_map: HashMap<String, RedactionRule> = default_config.rules.into_iter()
-        .map(|rule| (rule.name.clone(), rule))
+    // Rules are kept in a Vec so the merged order is stable across runs: defaults first in
+    // file order, user overrides replaced in place, and new user rules appended in their order.
+    let mut final_rules: Vec<RedactionRule> = Vec::with_capacity(default_config.rules.len());
+    let mut index_by_name: HashMap<String, usize> = HashMap::new();
+
+    for rule in default_config.rules {
+        match index_by_name.get(&rule.name) {
+            Some(&idx) => final_rules[idx] = rule,
+            None => {
+                index_by_name.insert(rule.name.clone(), final_rules.len());
+                final_rules.push(rule);
+            }
+        }
+    }
+
+    // Groups are merged by name; a user group replaces a default group of the same name.
+    let mut final_groups = default_config.groups;
+    let mut tenant = default_config.tenant;
+
+    // Former names of default rules, so overrides written against an old name still apply.
+    let alias_index: HashMap<String, usize> = final_rules
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, rule)| rule.aliases.iter().flatten().map(move |alias| (alias.clone(), idx)))
         .collect();
 
     if let Some(user_cfg) = user_config {
         debug!("User config provided. Merging {} user rules.", user_cfg.rules.len());
-        for user_rule in user_cfg.rules {
-            if final_rules_map.contains_key(&user_rule.name) {
-                debug!("Overriding default rule '{}' with user configuration.", user_rule.name);
-            } else {
-                debug!("Adding new user rule: '{}'", user_rule.name);
+        final_groups.extend(user_cfg.groups);
+        tenant = user_cfg.tenant.or(tenant);
+        for mut user_rule in user_cfg.rules {
+            let existing = match index_by_name.get(&user_rule.name) {
+                Some(&idx) => Some(idx),
+                None => match alias_index.get(&user_rule.name) {
+                    Some(&idx) => {
+                        warn!("{}", DeprecatedName::Alias { old: user_rule.name.clone(), new: final_rules[idx].name.clone() });
+                        user_rule.name = final_rules[idx].name.clone();
+                        Some(idx)
+                    }
+                    None => None,
+                },
+            };
+            match existing {
+                Some(idx) => {
+                    debug!("Overriding default rule '{}' with user configuration.", user_rule.name);
+                    if user_rule.aliases.is_none() {
+                        user_rule.aliases = final_rules[idx].aliases.clone();
+                    }
+                    if let Some(downgrade) = override_downgrade(&final_rules[idx], &user_rule) {
+                        warn!("{}", downgrade);
+                    }
+                    final_rules[idx] = user_rule;
+                }
+                None => {
+                    debug!("Adding new user rule: '{}'", user_rule.name);
+                    index_by_name.insert(user_rule.name.clone(), final_rules.len());
+                    final_rules.push(user_rule);
+                }
             }
-            final_rules_map.insert(user_rule.name.clone(), user_rule);
         }
     } else {
         debug!("No user configuration provided. Using default rules.");
     }
 
-    let final_rules: Vec<RedactionRule> = final_rules_map.into_values().collect();
     debug!("Final total rules after merge: {}", final_rules.len());
 
-    RedactionConfig { rules: final_rules }
+    RedactionConfig { rules: final_rules, groups: final_groups, tenant }
 }
 
-/// Validates a slice of `RedactionRule`s, checking for duplicate names,
-/// empty names/patterns, and invalid replacement string syntax.
+/// Returns the SHA-256 of the embedded default rules, as lowercase hex.
 ///
-/// This function is intended to be called after a configuration has been loaded
-/// to ensure its integrity before it is used.
-fn validate_rules(rules: &[RedactionRule]) -> Result<()> {
-    let mut rule_names = HashSet::new();
-    let mut errors = Vec::new();
-    let capture_group_regex = Regex::new(r"\$(\d+)").unwrap();
+/// Line endings are normalized to `\n` first, so a checkout that converted the file to
+/// CRLF hashes the same as the `sha256sum` output taken on Unix.
+pub fn default_rules_checksum() -> String {
+    hex::encode(Sha256::digest(DEFAULT_RULES_YAML.replace("\r\n", "\n").as_bytes()))
+}
+
+/// Checks the embedded default rules against the checksum shipped with them.
+///
+/// A mismatch means `default_rules.yaml` was edited without regenerating
+/// `default_rules.yaml.sha256`. The check is a guard against that mistake only: both
+/// files live side by side in the source tree, so anyone able to change the rules can
+/// change the checksum too, and it is no protection against deliberate tampering.
+///
+/// # Errors
+///
+/// Returns an error naming both checksums if they differ.
+pub fn verify_default_rules() -> Result<()> {
+    let expected = DEFAULT_RULES_CHECKSUM_FILE.split_whitespace().next().unwrap_or_default();
+    let actual = default_rules_checksum();
+    if actual != expected {
+        return Err(anyhow!(
+            "The embedded default rules do not match their shipped checksum (expected SHA-256 {}, found {}); default_rules.yaml was changed without regenerating default_rules.yaml.sha256.",
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+/// A user rule that overrides a rule in a way that can let secrets through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDowngrade {
+    pub rule: String,
+    /// What the override changes, e.g. `it disables the rule`.
+    pub reasons: Vec<String>,
+}
+
+impl fmt::Display for RuleDowngrade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Rule '{}' is overridden with a weaker version: {}. Secrets it used to catch may now pass through; tag the override '{}' if this is intended.",
+            self.rule,
+            self.reasons.join("; "),
+            ACCEPT_DOWNGRADE_TAG
+        )
+    }
+}
+
+/// Whether `pattern` is `base` itself, or an alternation with `base` as one branch, and so
+/// matches at least everything `base` does. Other patterns may still be broader; this
+/// only recognizes the safe cases it can prove.
+fn pattern_covers(pattern: &str, base: &str) -> bool {
+    if pattern == base {
+        return true;
+    }
+    let parse = |p: &str| regex_syntax::Parser::new().parse(p).ok();
+    let (Some(pattern), Some(base)) = (parse(pattern), parse(base)) else {
+        return false;
+    };
+    match pattern.kind() {
+        regex_syntax::hir::HirKind::Alternation(branches) => branches.contains(&base),
+        _ => pattern == base,
+    }
+}
+
+/// Compares a user rule with the rule it overrides, and describes how it weakens it.
+///
+/// Returns `None` when the override is not weaker as far as can be told, or when it
+/// carries the [`ACCEPT_DOWNGRADE_TAG`] tag.
+///
+/// # Examples
+///
+/// ```
+/// # use cleansh_core::config::{override_downgrade, RedactionRule};
+/// let base = RedactionRule { name: "aws_key".to_string(), pattern: Some("AKIA[0-9A-Z]{16}".to_string()), ..Default::default() };
+/// let narrowed = RedactionRule { pattern: Some("AKIA[0-9A-Z]{16}-prod".to_string()), ..base.clone() };
+/// let widened = RedactionRule { pattern: Some("AKIA[0-9A-Z]{16}|ASIA[0-9A-Z]{16}".to_string()), ..base.clone() };
+///
+/// assert!(override_downgrade(&base, &narrowed).is_some());
+/// assert!(override_downgrade(&base, &widened).is_none());
+/// ```
+pub fn override_downgrade(base: &RedactionRule, user_rule: &RedactionRule) -> Option<RuleDowngrade> {
+    if user_rule.tags.iter().flatten().any(|tag| tag == ACCEPT_DOWNGRADE_TAG) {
+        return None;
+    }
+    let mut reasons = Vec::new();
+    if user_rule.enabled == Some(false) && base.enabled != Some(false) {
+        reasons.push("it disables the rule".to_string());
+    }
+    if user_rule.opt_in && !base.opt_in {
+        reasons.push("it makes the rule opt-in".to_string());
+    }
+    match (&base.pattern, &user_rule.pattern) {
+        (Some(base_pattern), Some(pattern)) if !pattern_covers(pattern, base_pattern) => {
+            reasons.push("its pattern may not match everything the original pattern does".to_string());
+        }
+        (Some(_), None) => reasons.push("it has no pattern".to_string()),
+        _ => {}
+    }
+    if user_rule.paths.is_some() && base.paths.is_none() {
+        reasons.push("it only runs on some paths".to_string());
+    }
+    if user_rule.context.is_some() && base.context.is_none() {
+        reasons.push("it only matches near context keywords".to_string());
+    }
+    if user_rule.exceptions.as_ref().is_some_and(|exceptions| !exceptions.is_empty()) && base.exceptions.is_none() {
+        reasons.push("it has exceptions".to_string());
+    }
+    if reasons.is_empty() {
+        None
+    } else {
+        Some(RuleDowngrade { rule: base.name.clone(), reasons })
+    }
+}
 
-    for rule in rules {
-        if rule.name.is_empty() {
-            errors.push("A rule has an empty `name` field.".to_string());
-        } else if !rule_names.insert(rule.name.clone()) {
-            errors.push(format!("Duplicate rule name found: '{}'.", rule.name));
+/// Returns the problems with the groups of `config`: a group named like a rule of the
+/// same file, or a group without rules.
+fn group_problems(config: &RedactionConfig) -> Vec<String> {
+    let rule_names: HashSet<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    let mut errors = Vec::new();
+    for (group, members) in &config.groups {
+        if rule_names.contains(group.as_str()) {
+            errors.push(format!("Group '{}' has the same name as a rule.", group));
         }
+        if members.is_empty() {
+            errors.push(format!("Group '{}' has no rules.", group));
+        }
+    }
+    errors
+}
 
-        let pattern = match &rule.pattern {
-            Some(p) => p,
-            None => {
-                errors.push(format!("Rule '{}' is missing the `pattern` field.", rule.name));
-                continue;
-            }
-        };
+/// Checks that group names do not shadow rules of the same file and that no group is empty.
+fn validate_groups(config: &RedactionConfig) -> Result<()> {
+    let errors = group_problems(config);
+    if !errors.is_empty() {
+        Err(anyhow!("Rule group validation failed:\n{}", errors.join("\n")))
+    } else {
+        Ok(())
+    }
+}
 
-        if pattern.is_empty() {
-            errors.push(format!("Rule '{}' has an empty `pattern` field.", rule.name));
+/// Returns the problems confined to a single rule: an empty name, a missing, empty or
+/// invalid pattern, or a replacement that uses an unknown placeholder or references
+/// capture groups the pattern lacks.
+fn rule_problems(rule: &RedactionRule) -> Vec<String> {
+    let mut errors = Vec::new();
+    if rule.name.is_empty() {
+        errors.push("A rule has an empty `name` field.".to_string());
+    }
+
+    let pattern = match &rule.pattern {
+        Some(p) => p,
+        None => {
+            errors.push(format!("Rule '{}' is missing the `pattern` field.", rule.name));
+            return errors;
         }
-        
-        // Check for regex compilation errors
-        if let Err(e) = Regex::new(pattern) {
+    };
+
+    if pattern.is_empty() {
+        errors.push(format!("Rule '{}' has an empty `pattern` field.", rule.name));
+    }
+
+    // Check for regex compilation errors
+    let regex = match Regex::new(pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
             errors.push(format!("Rule '{}' has an invalid regex pattern: {}", rule.name, e));
-            continue; // Skip further validation for this rule if the regex is invalid
+            return errors; // Skip further validation for this rule if the regex is invalid
         }
-        
-        // Count the number of capturing groups in the pattern.
-        // We use a simplified approach that counts unescaped parentheses.
-        let mut group_count = 0;
-        let mut is_escaped = false;
-        for c in pattern.chars() {
-            match c {
-                '\\' => is_escaped = !is_escaped,
-                '(' if !is_escaped => group_count += 1,
-                _ => is_escaped = false,
-            }
+    };
+
+    for exception in rule.exceptions.iter().flatten() {
+        if let Err(e) = Regex::new(exception) {
+            errors.push(format!("Rule '{}' has an invalid exception pattern '{}': {}", rule.name, exception, e));
         }
+    }
 
-        // Validate the replacement string
-        for cap in capture_group_regex.captures_iter(&rule.replace_with) {
-            if let Some(group_num_str) = cap.get(1) {
-                if let Ok(group_num) = group_num_str.as_str().parse::<usize>() {
-                    // Check if the group number is valid.
-                    // Group $0 is the full match, so we check against <= group_count.
-                    if group_num > group_count {
-                        errors.push(format!(
-                            "Rule '{}': replacement string references non-existent capture group '${}'. Pattern has only {} capturing groups.",
-                            rule.name, group_num, group_count
-                        ));
-                    }
-                }
+    // Validate the replacement template against the pattern's capture groups.
+    if let Err(e) = ReplacementTemplate::parse(&rule.replace_with).and_then(|template| template.check_groups(&regex)) {
+        errors.push(format!("Rule '{}': {}", rule.name, e));
+    }
+    errors
+}
+
+/// Returns the problems with a slice of `RedactionRule`s, each with the index of the rule
+/// it was found in: duplicate names, the problems of each rule, and clashing aliases.
+fn rule_set_problems(rules: &[RedactionRule]) -> Vec<(usize, String)> {
+    let mut rule_names = HashSet::new();
+    let mut errors = Vec::new();
+
+    for (index, rule) in rules.iter().enumerate() {
+        if !rule.name.is_empty() && !rule_names.insert(rule.name.clone()) {
+            errors.push((index, format!("Duplicate rule name found: '{}'.", rule.name)));
+        }
+        errors.extend(rule_problems(rule).into_iter().map(|problem| (index, problem)));
+    }
+
+    let mut aliases = HashSet::new();
+    for (index, rule) in rules.iter().enumerate() {
+        for alias in rule.aliases.iter().flatten() {
+            if rule_names.contains(alias) {
+                errors.push((index, format!("Rule '{}' lists alias '{}', which is already the name of a rule.", rule.name, alias)));
+            } else if !aliases.insert(alias.as_str()) {
+                errors.push((index, format!("Alias '{}' is listed by more than one rule.", alias)));
             }
         }
     }
+    errors
+}
 
+/// Validates a slice of `RedactionRule`s, checking for duplicate names,
+/// empty names/patterns, and invalid replacement string syntax.
+///
+/// This function is intended to be called after a configuration has been loaded
+/// to ensure its integrity before it is used.
+fn validate_rules(rules: &[RedactionRule]) -> Result<()> {
+    let errors: Vec<String> = rule_set_problems(rules).into_iter().map(|(_, problem)| problem).collect();
     if !errors.is_empty() {
         let full_error_message = format!("Rule validation failed:\n{}", errors.join("\n"));
         Err(anyhow!(full_error_message))
@@ -0,0 +1,106 @@
+//! Structured descriptions of what an engine will do with each rule.
+//!
+//! [`SanitizationEngine::describe`](crate::engine::SanitizationEngine::describe) returns an
+//! [`EngineDescription`]: the engine's rules in application order, whether each one was
+//! compiled and is active, the anchors and required literals found in its pattern, and
+//! the programmatic validator its matches go through. Front ends and embedders can list
+//! or explain rules from this instead of re-parsing patterns or repeating the engine's
+//! dispatch logic.
+//!
+//! License: BUSL-1.1
+
+use regex_syntax::hir::Look;
+use serde::Serialize;
+
+use crate::config::{RedactionConfig, RedactionRule};
+use crate::prefilter::required_literals;
+use crate::profiles::EngineOptions;
+use crate::sanitizers::compiler::CompiledRules;
+use crate::validators;
+
+/// Zero-width assertions that every match of a pattern is tied to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RuleAnchors {
+    /// Matches begin at the start of the input or, in multi-line mode, of a line.
+    pub start: bool,
+    /// Matches end at the end of the input or, in multi-line mode, of a line.
+    pub end: bool,
+    /// The pattern uses a word boundary (`\b` or `\B`) somewhere.
+    pub word_boundary: bool,
+}
+
+/// What an engine does with one rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleDescription {
+    pub name: String,
+    /// Rules with a higher priority win overlap ties.
+    pub priority: u32,
+    pub opt_in: bool,
+    /// `false` for rules switched off with `enabled: false`; they are compiled but never run.
+    pub enabled: bool,
+    /// Whether the engine compiled the rule's pattern. Rules without a pattern are not.
+    pub compiled: bool,
+    pub anchors: RuleAnchors,
+    /// Literals, in lowercase, one of which every match contains; `None` if there are none.
+    /// These are what the prefilter searches for.
+    pub required_literals: Option<Vec<String>>,
+    /// The validator every match is checked with, e.g. `luhn` for card numbers.
+    pub validator: Option<String>,
+}
+
+/// What an engine does with its rules, in the order it applies them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EngineDescription {
+    pub rules: Vec<RuleDescription>,
+    /// Whether rules are skipped for inputs that lack their required literals.
+    pub prefilter: bool,
+}
+
+fn anchors(pattern: &str, rule: &RedactionRule) -> RuleAnchors {
+    let Ok(hir) = regex_syntax::ParserBuilder::new().multi_line(rule.multiline).build().parse(pattern) else {
+        return RuleAnchors::default();
+    };
+    let properties = hir.properties();
+    let prefix = properties.look_set_prefix();
+    let suffix = properties.look_set_suffix();
+    RuleAnchors {
+        start: [Look::Start, Look::StartLF, Look::StartCRLF].into_iter().any(|look| prefix.contains(look)),
+        end: [Look::End, Look::EndLF, Look::EndCRLF].into_iter().any(|look| suffix.contains(look)),
+        word_boundary: properties.look_set().contains_word(),
+    }
+}
+
+impl EngineDescription {
+    /// Describes `config`'s rules as compiled into `compiled` and run under `options`.
+    pub fn new(config: &RedactionConfig, compiled: &CompiledRules, options: &EngineOptions) -> Self {
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let pattern = rule.pattern.as_deref();
+                RuleDescription {
+                    name: rule.name.clone(),
+                    priority: rule.priority,
+                    opt_in: rule.opt_in,
+                    enabled: rule.enabled != Some(false),
+                    compiled: compiled.rules.iter().any(|c| c.name == rule.name),
+                    anchors: pattern.map(|p| anchors(p, rule)).unwrap_or_default(),
+                    required_literals: pattern.and_then(required_literals).map(|set| {
+                        set.iter().map(|literal| String::from_utf8_lossy(literal).into_owned()).collect()
+                    }),
+                    validator: rule
+                        .programmatic_validation
+                        .then(|| validators::validator_for_rule(&rule.name))
+                        .flatten()
+                        .map(str::to_string),
+                }
+            })
+            .collect();
+        Self { rules, prefilter: options.prefilter }
+    }
+
+    /// Returns the description of the rule named `name`.
+    pub fn rule(&self, name: &str) -> Option<&RuleDescription> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+}
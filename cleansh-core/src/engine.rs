@@ -8,13 +8,17 @@
 //! License: BUSL-1.1
 
 use anyhow::Result;
+use std::io::{BufRead, Write};
 
 // Publicly exposed types from other modules
 use crate::config::{RedactionConfig, RedactionSummaryItem};
+use crate::description::EngineDescription;
 use crate::profiles::EngineOptions;
 use crate::sanitizers::compiler::CompiledRules;
 use crate::audit_log::AuditLog;
-use crate::redaction_match::RedactionMatch;
+use crate::redaction_match::{AppliedRedaction, RedactionMatch};
+use crate::rule_profile::RuleProfile;
+use crate::stream::{self, StreamOptions};
 
 /// A trait that defines the core functionality of a sanitization engine.
 ///
@@ -49,6 +53,106 @@ pub trait SanitizationEngine: Send + Sync {
         audit_log: Option<&mut AuditLog>,
     ) -> Result<(String, Vec<RedactionSummaryItem>)>;
 
+    /// Sanitizes the content and also returns every edit that was applied to it.
+    ///
+    /// The edits are in application order, carry byte offsets into the original
+    /// `content`, and exclude matches that were skipped because an earlier replacement
+    /// already covered them. They never contain the original sensitive text.
+    ///
+    /// # Arguments
+    /// * `content` - The input string to sanitize.
+    /// * `source_id` - The name or identifier of the source being processed.
+    ///
+    /// The default implementation applies each match's own replacement through
+    /// [`SanitizationEngine::sanitize_with`] and takes the summary from
+    /// [`SanitizationEngine::analyze_for_stats`], so it scans the content twice.
+    fn sanitize_with_edits(&self, content: &str, source_id: &str) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        let (sanitized, edits) = self.sanitize_with(content, source_id, &mut |m| m.sanitized_string.clone())?;
+        let summary = self.analyze_for_stats(content, source_id)?;
+        Ok((sanitized, summary, edits))
+    }
+
+    /// Sanitizes the content using caller-supplied replacements.
+    ///
+    /// The engine still finds the matches, resolves overlaps, and splices the results
+    /// at the correct offsets, but the replacement text for each applied match comes from
+    /// `replace` (e.g. a token looked up in a vault, or format-preserving encryption).
+    /// `replace` is not called for matches skipped as overlaps. Returns the sanitized
+    /// content and the applied edits, whose `replacement` is the callback's output.
+    ///
+    /// The `RedactionMatch` passed to `replace` includes the original sensitive text;
+    /// its `start`/`end` are byte offsets into the original `content`.
+    ///
+    /// # Arguments
+    /// * `content` - The input string to sanitize.
+    /// * `source_id` - The name or identifier of the source being processed.
+    /// * `replace` - Produces the replacement text for each applied match.
+    ///
+    /// The default implementation splices the matches of
+    /// [`SanitizationEngine::find_matches_for_ui`] from left to right, trimming a match that
+    /// starts inside an earlier replacement and skipping one it fully covers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cleansh_core::{RedactionConfig, RegexEngine, SanitizationEngine};
+    ///
+    /// let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    /// let mut next_id = 0;
+    /// let (sanitized, edits) = engine.sanitize_with("Contact: test@example.com", "", &mut |m| {
+    ///     next_id += 1;
+    ///     format!("<{}:{}>", m.rule_name, next_id)
+    /// })?;
+    /// assert_eq!(sanitized, "Contact: <email:1>");
+    /// assert_eq!(edits[0].replacement, "<email:1>");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn sanitize_with(
+        &self,
+        content: &str,
+        source_id: &str,
+        replace: &mut dyn FnMut(&RedactionMatch) -> String,
+    ) -> Result<(String, Vec<AppliedRedaction>)> {
+        let matches = self.find_matches_for_ui(content, source_id)?;
+        Ok(splice_matches(content, &matches, replace))
+    }
+
+    /// Sanitizes everything read from `reader` into `writer` without holding the whole
+    /// input in memory, and returns the summary of all redactions.
+    ///
+    /// The input is read in chunks of whole lines, each sanitized like
+    /// [`SanitizationEngine::sanitize`]. The end of every chunk is searched again together
+    /// with the next one, so matches that span a chunk boundary are still found as long as
+    /// they are shorter than `options.overlap_bytes`.
+    ///
+    /// # Arguments
+    /// * `reader` - The input to sanitize.
+    /// * `writer` - Receives the sanitized output; it is flushed at the end.
+    /// * `source_id` - The name or identifier of the source being processed.
+    /// * `options` - Chunk and overlap sizes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cleansh_core::{RedactionConfig, RegexEngine, SanitizationEngine, StreamOptions};
+    ///
+    /// let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    /// let mut output = Vec::new();
+    /// let summary = engine.sanitize_reader(&mut "Contact: test@example.com\n".as_bytes(), &mut output, "", StreamOptions::default())?;
+    /// assert_eq!(String::from_utf8(output)?, "Contact: [EMAIL_REDACTED]\n");
+    /// assert_eq!(summary[0].occurrences, 1);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn sanitize_reader(
+        &self,
+        reader: &mut dyn BufRead,
+        writer: &mut dyn Write,
+        source_id: &str,
+        options: StreamOptions,
+    ) -> Result<Vec<RedactionSummaryItem>> {
+        stream::sanitize_stream(self, reader, writer, source_id, options)
+    }
+
     /// Analyzes the provided content for sensitive data without performing redaction.
     ///
     /// This method is used specifically for the `--stats-only` command. It returns
@@ -69,6 +173,25 @@ pub trait SanitizationEngine: Send + Sync {
     /// * `source_id` - An identifier for the source of the content (e.g., a file path).
     fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>>;
 
+    /// Finds all matches in the content of the file at `path`, like
+    /// [`SanitizationEngine::find_matches_for_ui`] with `path` as the source id.
+    ///
+    /// Only here, and in [`SanitizationEngine::sanitize_file_with_edits`], do rules limited
+    /// with `paths` skip files outside their scope. The other methods take `source_id` as
+    /// a label and run every rule, so stdin and other unnamed sources are never mistaken
+    /// for files. The default implementation, for engines without path scopes, runs every
+    /// rule too.
+    fn find_file_matches(&self, content: &str, path: &str) -> Result<Vec<RedactionMatch>> {
+        self.find_matches_for_ui(content, path)
+    }
+
+    /// Sanitizes the content of the file at `path`, like
+    /// [`SanitizationEngine::sanitize_with_edits`] with `path` as the source id, skipping
+    /// rules whose `paths` scope does not include `path`.
+    fn sanitize_file_with_edits(&self, content: &str, path: &str) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        self.sanitize_with_edits(content, path)
+    }
+
     /// Returns a reference to the `CompiledRules` used by the engine.
     ///
     /// This is used by external components, such as the statistics command,
@@ -81,4 +204,66 @@ pub trait SanitizationEngine: Send + Sync {
 
     /// Returns a reference to the engine's options.
     fn get_options(&self) -> &EngineOptions;
-}
\ No newline at end of file
+
+    /// Returns the per-rule evaluation counts, matches, and timings collected so far,
+    /// slowest rule first.
+    ///
+    /// Engines collect these only when `EngineOptions::profile_rules` is set; otherwise,
+    /// and for engines that do not support profiling, the list is empty.
+    fn rule_profile(&self) -> Vec<RuleProfile> {
+        Vec::new()
+    }
+
+    /// Describes each rule as this engine will apply it: whether it was compiled and is
+    /// active, its anchors and required literals, and the validator its matches pass
+    /// through. Rules are listed in application order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use cleansh_core::{RedactionConfig, RegexEngine, SanitizationEngine};
+    ///
+    /// let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+    /// let description = engine.describe();
+    /// let github = description.rule("github_pat").unwrap();
+    /// assert_eq!(github.validator.as_deref(), Some("github_checksum"));
+    /// assert_eq!(github.required_literals, Some(vec!["ghp_".to_string()]));
+    /// assert!(github.anchors.word_boundary);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    fn describe(&self) -> EngineDescription {
+        EngineDescription::new(self.get_rules(), self.compiled_rules(), self.get_options())
+    }
+}
+
+/// Splices a replacement for each of `matches`, sorted by `start`, into `content` from
+/// left to right. Used by the default [`SanitizationEngine::sanitize_with`].
+fn splice_matches(
+    content: &str,
+    matches: &[RedactionMatch],
+    replace: &mut dyn FnMut(&RedactionMatch) -> String,
+) -> (String, Vec<AppliedRedaction>) {
+    let mut sanitized = String::with_capacity(content.len());
+    let mut edits = Vec::new();
+    let mut last_end = 0usize;
+    for m in matches {
+        let (start, end) = (m.start as usize, m.end as usize);
+        if end <= last_end {
+            continue;
+        }
+        let start = start.max(last_end);
+        sanitized.push_str(&content[last_end..start]);
+        let replacement = replace(m);
+        sanitized.push_str(&replacement);
+        edits.push(AppliedRedaction {
+            rule_name: m.rule_name.clone(),
+            start: start as u64,
+            end: end as u64,
+            length: (end - start) as u64,
+            replacement,
+        });
+        last_end = end;
+    }
+    sanitized.push_str(&content[last_end..]);
+    (sanitized, edits)
+}
@@ -4,19 +4,29 @@
 //! to identify and redact sensitive data.
 //! License: BUSL-1.1
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Instant;
 use anyhow::{Result, Context, anyhow};
 use log::debug;
-use strip_ansi_escapes::strip;
+use strip_ansi_escapes::{strip, strip_str};
+use unicode_width::UnicodeWidthStr;
 use sha2::{Digest, Sha256};
 use hex;
-use chrono::Utc;
+use regex::Regex;
+use chrono::{SecondsFormat, Utc};
 
+use crate::aggregator::Aggregator;
+use crate::allowlist::{ignored_lines, Allowlist, IGNORE_LINE_MARKER};
 use crate::config::{RedactionConfig, RedactionSummaryItem, RedactionRule};
-use crate::redaction_match::{RedactionMatch, log_captured_match_debug, redact_sensitive, RedactionLog, ensure_match_hashes};
-use crate::profiles::EngineOptions;
+use crate::redaction_match::{fit_to_width, AppliedRedaction, RedactionMatch, log_captured_match_debug, redact_sensitive, RedactionLog, ensure_match_hashes};
+use crate::profiles::{format_token, EngineOptions, DEFAULT_TOKEN_FORMAT};
 use crate::engine::SanitizationEngine;
+use crate::events::{ChunkProcessed, EngineObserver, RuleCompiled};
+use crate::match_context::MatchContext;
+use crate::prefilter::Prefilter;
+use crate::replacement::MatchInfo;
+use crate::rule_profile::{RuleProfile, RuleProfiler};
 use crate::sanitizers::compiler::{get_or_compile_rules, CompiledRules, CompiledRule};
 use crate::validators;
 
@@ -47,7 +57,8 @@ impl StrippedIndexMapper {
             // as they will be skipped until a matching character is found.
             while let Some((orig_index, orig_char)) = current_orig_char {
                 if orig_char == stripped_char {
-                    map.push(orig_index);
+                    // One entry per byte, so regex byte offsets can be looked up directly.
+                    map.extend((0..stripped_char.len_utf8()).map(|offset| orig_index + offset));
                     current_orig_char = original_char_indices.next();
                     break;
                 }
@@ -68,16 +79,116 @@ impl StrippedIndexMapper {
         let idx = stripped_index.min(self.map.len().saturating_sub(1));
         self.map[idx]
     }
+
+    /// Maps the exclusive end of a match from the stripped string to the original string.
+    ///
+    /// The end is taken just past the last matched character, so characters that were
+    /// stripped right after the match (such as the `\r` of a CRLF) stay outside it.
+    fn map_end(&self, original: &str, stripped_end: usize) -> usize {
+        if stripped_end == 0 {
+            return self.map_index(0);
+        }
+        // The last matched byte may sit inside a multi-byte character; step back to its start.
+        let mut last = self.map_index(stripped_end - 1);
+        while !original.is_char_boundary(last) {
+            last -= 1;
+        }
+        last + original[last..].chars().next().map_or(0, char::len_utf8)
+    }
 }
 // --- end mapper ---
 
 pub const BATCH_SIZE: usize = 4096;
 
+/// Longest placeholder, in bytes, that [`RegexEngine`] recognizes around a match.
+const MAX_PLACEHOLDER_LEN: usize = 128;
+
+/// Collects the bracketed words of every rule's `replace_with`, so that
+/// `[EMAIL_REDACTED]` contributes `EMAIL_REDACTED`. Words containing a capture group
+/// reference or a placeholder are skipped, since their final text is not known up front.
+fn placeholder_words(config: &RedactionConfig) -> HashSet<String> {
+    let mut words = HashSet::new();
+    for rule in &config.rules {
+        let mut rest = rule.replace_with.as_str();
+        while let Some(open) = rest.find('[') {
+            let Some(len) = rest[open + 1..].find(']') else { break };
+            let inner = &rest[open + 1..open + 1 + len];
+            if !inner.is_empty() && !inner.contains(|c: char| c.is_whitespace() || c == '$' || c == '{') {
+                words.insert(inner.to_string());
+            }
+            rest = &rest[open + 1 + len..];
+        }
+    }
+    words
+}
+
+/// Builds a regex for the placeholders a custom `token_format` produces, so that
+/// `secret_{rule}_{shorthash}` recognizes `secret_email_1a2b3c4d`. Returns `None` when no
+/// custom format is set, since the default one is recognized by its brackets.
+fn token_pattern(config: &RedactionConfig, options: &EngineOptions) -> Result<Option<Regex>> {
+    let Some(template) = options.post_processing.as_ref().filter(|pp| pp.replace_with_token).and_then(|pp| pp.token_format.as_deref()) else {
+        return Ok(None);
+    };
+    // Render the template with marker values, then turn the markers into patterns.
+    const RULE_MARKER: char = '\u{E000}';
+    const HASH_MARKER: char = '\u{E001}';
+    let rendered = format_token(template, &RULE_MARKER.to_string(), &HASH_MARKER.to_string())?;
+    let mut names: Vec<&str> = config.rules.iter().map(|rule| rule.name.as_str()).collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    let rule_pattern = format!("(?:{})", names.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|"));
+    let mut pattern = String::new();
+    let mut literal = String::new();
+    for c in rendered.chars() {
+        if c == RULE_MARKER || c == HASH_MARKER {
+            pattern.push_str(&regex::escape(&literal));
+            literal.clear();
+            pattern.push_str(if c == RULE_MARKER { &rule_pattern } else { "[0-9a-f]{8}" });
+        } else {
+            literal.push(c);
+        }
+    }
+    pattern.push_str(&regex::escape(&literal));
+    Regex::new(&pattern).map(Some).context("Failed to build the pattern of the token_format placeholders")
+}
+
+/// Merges `spans` into sorted, disjoint intervals for [`overlaps_any`].
+fn merged_spans(mut spans: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    spans.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start < last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Returns `true` if `start..end` overlaps any of the merged `spans`.
+fn overlaps_any(spans: &[(u64, u64)], start: u64, end: u64) -> bool {
+    let next = spans.partition_point(|&(_, span_end)| span_end <= start);
+    spans.get(next).is_some_and(|&(span_start, _)| span_start < end)
+}
+
 #[derive(Debug)]
 pub struct RegexEngine {
     compiled_rules: Arc<CompiledRules>,
     config: RedactionConfig,
     options: EngineOptions,
+    /// Position of each rule in application order (priority first, then declared order).
+    rule_order: HashMap<String, usize>,
+    /// Bracketed words from the rules' replacements, e.g. `EMAIL_REDACTED`.
+    placeholder_words: HashSet<String>,
+    /// Placeholders of a custom `token_format`, built by [`token_pattern`].
+    token_pattern: Option<Regex>,
+    /// Per-rule totals, collected when `options.profile_rules` is set.
+    profiler: Option<RuleProfiler>,
+    /// Literal prefilter over the compiled rules, built when `options.prefilter` is set.
+    prefilter: Option<Prefilter>,
+    /// Receives the engine's events, if an embedder subscribed with [`RegexEngine::with_observer`].
+    observer: Option<Arc<dyn EngineObserver>>,
+    /// Values no rule redacts, built from `options.allowlist` when it is not empty.
+    allowlist: Option<Allowlist>,
 }
 
 impl RegexEngine {
@@ -86,32 +197,168 @@ impl RegexEngine {
     }
 
     pub fn with_options(config: RedactionConfig, options: EngineOptions) -> Result<Self> {
+        Self::build(config, options, None)
+    }
+
+    /// Like [`RegexEngine::with_options`], and reports the engine's events to `observer`,
+    /// starting with one [`EngineObserver::on_rule_compiled`] call per rule.
+    pub fn with_observer(config: RedactionConfig, options: EngineOptions, observer: Arc<dyn EngineObserver>) -> Result<Self> {
+        Self::build(config, options, Some(observer))
+    }
+
+    fn build(mut config: RedactionConfig, options: EngineOptions, observer: Option<Arc<dyn EngineObserver>>) -> Result<Self> {
         if config.rules.is_empty() {
             debug!("RedactionConfig contains no rules. The RegexEngine will perform no sanitization.");
         }
         
+        config.sort_by_priority();
+        if options.entropy_layer {
+            // A stable sort keeps the priority order within each layer.
+            config.rules.sort_by_key(|rule| validators::is_entropy_rule(&rule.name));
+        }
+        let rule_order = config.rules.iter()
+            .enumerate()
+            .map(|(idx, rule)| (rule.name.clone(), idx))
+            .collect();
+
+        let placeholder_words = placeholder_words(&config);
+        let token_pattern = token_pattern(&config, &options)?;
+
         let compiled_rules = get_or_compile_rules(&config)
             .context("Failed to get or compile redaction rules for RegexEngine")?;
+
+        let profiler = options.profile_rules.then(|| {
+            RuleProfiler::new(config.rules.iter().filter(|rule| rule.enabled != Some(false)).map(|rule| rule.name.as_str()))
+        });
+
+        let allowlist = (!options.allowlist.is_empty())
+            .then(|| Allowlist::new(&options.allowlist))
+            .transpose()
+            .context("Failed to compile the allowlist for RegexEngine")?;
+
+        let prefilter = options.prefilter.then(|| Prefilter::new(&compiled_rules))
+            .transpose()
+            .context("Failed to build the literal prefilter for RegexEngine")?;
+
+        if let Some(observer) = &observer {
+            for (position, rule) in compiled_rules.rules.iter().enumerate() {
+                observer.on_rule_compiled(&RuleCompiled { rule_name: rule.name.clone(), position });
+            }
+        }
             
         Ok(Self {
             compiled_rules,
             config,
             options,
+            rule_order,
+            placeholder_words,
+            token_pattern,
+            profiler,
+            prefilter,
+            observer,
+            allowlist,
         })
     }
 
+    /// Returns the application rank of a rule; lower ranks win overlap ties.
+    fn rule_rank(&self, rule_name: &str) -> usize {
+        self.rule_order.get(rule_name).copied().unwrap_or(usize::MAX)
+    }
+
+    /// Narrows a raw regex match for rules whose validator trims surrounding punctuation.
+    ///
+    /// Returns the byte offset of the trimmed text within `original_str` and the trimmed text.
+    fn trim_match<'a>(&self, compiled_rule: &CompiledRule, original_str: &'a str) -> (usize, &'a str) {
+        if !compiled_rule.programmatic_validation {
+            return (0, original_str);
+        }
+        let trimmed = match compiled_rule.name.as_str() {
+            "email" => validators::trim_email_punctuation(original_str),
+            _ => original_str,
+        };
+        (trimmed.as_ptr() as usize - original_str.as_ptr() as usize, trimmed)
+    }
+
+    /// Reports whether `start..end` lies inside a placeholder this engine writes, such as
+    /// `[GITHUB_PAT_REDACTED]`, the token form `[github_pat:1a2b3c4d]`, or a token in a
+    /// custom `token_format`.
+    ///
+    /// Broad rules like `generic_token` would otherwise match the placeholder text itself,
+    /// so sanitizing already sanitized output would change it again.
+    fn inside_placeholder(&self, input: &str, start: usize, end: usize) -> bool {
+        let mut window_start = start.saturating_sub(MAX_PLACEHOLDER_LEN);
+        while !input.is_char_boundary(window_start) {
+            window_start += 1;
+        }
+        if let Some(token_pattern) = &self.token_pattern {
+            let mut window_end = (end + MAX_PLACEHOLDER_LEN).min(input.len());
+            while !input.is_char_boundary(window_end) {
+                window_end -= 1;
+            }
+            let covered = token_pattern
+                .find_iter(&input[window_start..window_end])
+                .any(|token| window_start + token.start() <= start && end <= window_start + token.end());
+            if covered {
+                return true;
+            }
+        }
+        let Some(open) = input[window_start..start].rfind('[').map(|i| window_start + i) else {
+            return false;
+        };
+        let window_end = (end + MAX_PLACEHOLDER_LEN).min(input.len());
+        let Some(close) = input.as_bytes()[end..window_end].iter().position(|b| *b == b']').map(|i| end + i) else {
+            return false;
+        };
+        let inner = &input[open + 1..close];
+        if inner.contains(|c: char| c.is_whitespace() || c == '[') {
+            return false;
+        }
+        match inner.split_once(':') {
+            Some((rule, hash)) => {
+                self.rule_order.contains_key(rule) && !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit())
+            }
+            None => self.placeholder_words.contains(inner),
+        }
+    }
+
+    /// Checks a match against its rule's context window, if the rule declares one.
+    ///
+    /// Returns `false` when the rule lists keywords and none of them appear near the match.
+    fn passes_context_check(&self, compiled_rule: &CompiledRule, input: &str, start: usize, end: usize) -> bool {
+        let Some(window) = compiled_rule.context.as_ref() else { return true; };
+        if window.keywords.is_empty() {
+            return true;
+        }
+        MatchContext::new(input, start, end, window).contains_any_keyword(&window.keywords)
+    }
+
+    /// Reports whether `matched` is an exception of its rule or on the allowlist.
+    fn is_allowed(&self, compiled_rule: &CompiledRule, matched: &str) -> bool {
+        compiled_rule.exceptions.as_ref().is_some_and(|exceptions| exceptions.is_match(matched))
+            || self.allowlist.as_ref().is_some_and(|allowlist| allowlist.allows(matched))
+    }
+
     // A helper function to run programmatic validators. This centralizes validation logic.
     fn run_programmatic_validator(&self, compiled_rule: &CompiledRule, original_str: &str) -> bool {
         if !compiled_rule.programmatic_validation {
             return true;
         }
 
-        match compiled_rule.name.as_str() {
-            "us_ssn" => validators::is_valid_ssn_programmatically(original_str),
-            "uk_nino" => validators::is_valid_uk_nino_programmatically(original_str),
-            "visa_card" | "mastercard_card" | "amex_card" | "discover_card" => {
-                validators::is_valid_credit_card_programmatically(original_str)
-            }
+        match validators::validator_for_rule(&compiled_rule.name) {
+            Some("ssn") => validators::is_valid_ssn_programmatically(original_str),
+            Some("uk_nino") => validators::is_valid_uk_nino_programmatically(original_str),
+            Some("luhn") => validators::is_valid_credit_card_programmatically(original_str),
+            Some("email") => validators::is_valid_email_programmatically(original_str),
+            Some("github_checksum") => validators::is_valid_github_token_programmatically(original_str),
+            Some("public_ipv4") => validators::is_public_ipv4_programmatically(original_str),
+            Some("private_ipv4") => validators::is_private_ipv4_programmatically(original_str),
+            Some("hostname") => validators::is_redactable_hostname(original_str, &self.options.allowed_domains),
+            Some("birthdate") => validators::is_plausible_birthdate(original_str),
+            Some("date_dmy") => validators::is_plausible_date(original_str, validators::DateOrder::DayMonthYear),
+            Some("date_mdy") => validators::is_plausible_date(original_str, validators::DateOrder::MonthDayYear),
+            Some("entropy") => validators::is_high_entropy_string(original_str),
+            Some("secret_near_keyword") => validators::is_secret_value_near_keyword(original_str),
+            Some("url_query_secret") => validators::is_url_query_secret(original_str),
             _ => {
                 debug!("No validator for '{}', redacting by default.", compiled_rule.name);
                 true
@@ -119,6 +366,16 @@ impl RegexEngine {
         }
     }
 
+    /// Returns the profile's token placeholder for `m` when `post_processing.replace_with_token`
+    /// is set, so every sanitization path uses the same placeholder style.
+    fn placeholder_token(&self, m: &RedactionMatch) -> Result<Option<String>> {
+        let Some(pp) = self.options.post_processing.as_ref().filter(|pp| pp.replace_with_token) else {
+            return Ok(None);
+        };
+        let template = pp.token_format.as_deref().unwrap_or(DEFAULT_TOKEN_FORMAT);
+        format_token(template, &m.rule_name, m.sample_hash.as_deref().unwrap_or_default()).map(Some)
+    }
+
     fn create_redaction_match(
         &self,
         rule_config: &RedactionRule,
@@ -161,7 +418,7 @@ impl RegexEngine {
             end,
             sample_hash,
             match_context_hash,
-            timestamp: Some(Utc::now().to_rfc3339()),
+            timestamp: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
             rule: rule_config.clone(),
             source_id: source_id.to_string(),
             line_number,
@@ -169,103 +426,164 @@ impl RegexEngine {
     }
 
     /// Finds all matches in the content, running programmatic validators where applicable.
-    fn find_matches(&self, content: &str, source_id: &str) -> Result<HashMap<String, Vec<RedactionMatch>>> {
+    ///
+    /// `path` is the file the content was read from, if it was; only then do the `paths`
+    /// scopes of the rules apply.
+    fn find_matches(&self, content: &str, source_id: &str, path: Option<&str>) -> Result<HashMap<String, Vec<RedactionMatch>>> {
+        let chunk_started = Instant::now();
+        let mut rules_run = 0;
         let stripped_bytes = strip(content.as_bytes());
         let stripped_input = String::from_utf8_lossy(&stripped_bytes);
+        // Rules run on the stripped text; match offsets are mapped back to `content`.
+        let mapper = StrippedIndexMapper::new(content);
         
         let original_rules_map: HashMap<&str, &RedactionRule> = self.config.rules.iter()
             .map(|rule| (rule.name.as_str(), rule))
             .collect();
     
+        let ignored_lines = ignored_lines(&stripped_input);
         let mut all_matches: HashMap<String, Vec<RedactionMatch>> = HashMap::new();
+        let candidates = self.prefilter.as_ref().map(|prefilter| prefilter.candidates(&stripped_input));
+        // Spans of the regex matches, collected when the first entropy rule runs; entropy
+        // rules run after every other rule.
+        let mut regex_spans: Option<Vec<(u64, u64)>> = None;
     
-        for compiled_rule in &self.compiled_rules.rules {
+        for (index, compiled_rule) in self.compiled_rules.rules.iter().enumerate() {
             if let Some(rule_config) = original_rules_map.get(compiled_rule.name.as_str()) {
                 if let Some(false) = rule_config.enabled {
                     continue;
                 }
+                if candidates.as_ref().is_some_and(|candidates| !candidates[index]) {
+                    continue;
+                }
+                if let (Some(scope), Some(path)) = (compiled_rule.path_scope.as_ref(), path) {
+                    if !scope.applies_to(path) {
+                        debug!("Rule '{}' skipped: '{}' is outside its paths.", compiled_rule.name, path);
+                        continue;
+                    }
+                }
 
+                rules_run += 1;
+                let started = Instant::now();
+                let matches_before = all_matches.get(&compiled_rule.name).map_or(0, Vec::len);
                 for caps in compiled_rule.regex.captures_iter(&stripped_input) {
                     let original_match = caps.get(0).ok_or_else(|| anyhow!("Regex captured a non-existent match group"))?;
-                    
-                    if self.run_programmatic_validator(compiled_rule, original_match.as_str()) {
-                        let mut replacement = compiled_rule.replace_with.clone();
-                        for i in 1..caps.len() {
-                            if let Some(group) = caps.get(i) {
-                                replacement = replacement.replace(&format!("${}", i), group.as_str());
-                            }
-                        }
-                        log_captured_match_debug("cleansh_core::engine", &compiled_rule.name, original_match.as_str());
+                    let (trim_offset, match_str) = self.trim_match(compiled_rule, original_match.as_str());
+                    let match_start = original_match.start() + trim_offset;
 
-                        let redaction_match = self.create_redaction_match(
+                    if self.inside_placeholder(&stripped_input, match_start, match_start + match_str.len()) {
+                        debug!("Match for '{}' skipped: it is part of a redaction placeholder.", compiled_rule.name);
+                        continue;
+                    }
+
+                    if overlaps_any(&ignored_lines, match_start as u64, (match_start + match_str.len()) as u64) {
+                        debug!("Match for '{}' skipped: its line is marked {}.", compiled_rule.name, IGNORE_LINE_MARKER);
+                        continue;
+                    }
+
+                    if self.is_allowed(compiled_rule, match_str) {
+                        debug!("Match for '{}' skipped: the value is allowlisted.", compiled_rule.name);
+                        continue;
+                    }
+
+                    if !match_str.is_empty() && !self.passes_context_check(compiled_rule, &stripped_input, match_start, match_start + match_str.len()) {
+                        debug!("Match for '{}' skipped: no context keyword nearby.", compiled_rule.name);
+                        continue;
+                    }
+
+                    if !match_str.is_empty() && self.run_programmatic_validator(compiled_rule, match_str) {
+                        let info = MatchInfo {
+                            rule_name: &compiled_rule.name,
+                            match_index: all_matches.get(&compiled_rule.name).map_or(0, Vec::len) + 1,
+                            matched: match_str,
+                        };
+                        let replacement = compiled_rule.template.expand(&caps, &info);
+                        log_captured_match_debug("cleansh_core::engine", &compiled_rule.name, match_str);
+
+                        let mut redaction_match = self.create_redaction_match(
                             rule_config,
-                            original_match.as_str(),
-                            original_match.start() as u64,
-                            original_match.end() as u64,
+                            match_str,
+                            match_start as u64,
+                            (match_start + match_str.len()) as u64,
                             replacement,
                             &stripped_input,
                             source_id,
                             None,
                         );
+                        if let Some(token) = self.placeholder_token(&redaction_match)? {
+                            redaction_match.sanitized_string = token;
+                        }
+                        redaction_match.start = mapper.map_index(match_start) as u64;
+                        redaction_match.end = mapper.map_end(content, match_start + match_str.len()) as u64;
+                        if self.options.entropy_layer
+                            && validators::is_entropy_rule(&compiled_rule.name)
+                            && overlaps_any(
+                                regex_spans.get_or_insert_with(|| {
+                                    merged_spans(all_matches.values().flatten().map(|m| (m.start, m.end)).collect())
+                                }),
+                                redaction_match.start,
+                                redaction_match.end,
+                            )
+                        {
+                            debug!("Match for '{}' skipped: a regex rule matched the same text.", compiled_rule.name);
+                            continue;
+                        }
+                        if let Some(observer) = &self.observer {
+                            observer.on_match(&redaction_match);
+                        }
 
                         all_matches.entry(compiled_rule.name.clone()).or_default().push(redaction_match);
                     } else {
                         debug!(
                             "Match for '{}' failed programmatic validation: '{}'",
                             compiled_rule.name,
-                            redact_sensitive(original_match.as_str())
+                            redact_sensitive(match_str)
                         );
                     }
                 }
+                if let Some(profiler) = &self.profiler {
+                    let kept = all_matches.get(&compiled_rule.name).map_or(0, Vec::len) - matches_before;
+                    profiler.record(&compiled_rule.name, kept, started.elapsed());
+                }
             }
         }
-        Ok(all_matches)
-    }
-
-    fn build_summary_from_matches(&self, all_matches: &HashMap<String, Vec<RedactionMatch>>) -> Vec<RedactionSummaryItem> {
-        let mut summary_items = Vec::new();
-        for (rule_name, matches) in all_matches.iter() {
-            let original_texts: Vec<String> = matches.iter().map(|m| m.original_string.clone()).collect();
-            let sanitized_texts: Vec<String> = matches.iter().map(|m| m.sanitized_string.clone()).collect();
-
-            summary_items.push(RedactionSummaryItem {
-                rule_name: rule_name.clone(),
-                occurrences: matches.len(),
-                original_texts,
-                sanitized_texts,
+        if let Some(observer) = &self.observer {
+            observer.on_chunk_processed(&ChunkProcessed {
+                source_id: source_id.to_string(),
+                bytes: content.len(),
+                rules_run,
+                matches: all_matches.values().map(Vec::len).sum(),
+                elapsed: chunk_started.elapsed(),
             });
         }
-        summary_items
+        Ok(all_matches)
     }
-}
 
-impl SanitizationEngine for RegexEngine {
-    fn sanitize(
+    /// Splices a replacement for each of `all_matches` into `content` from left to right.
+    ///
+    /// `replace` is called once per applied match to produce its replacement text, which is
+    /// then fitted to the replaced text with `keep_width`. Returns the sanitized text
+    /// together with each applied edit and the match it came from. Matches fully covered
+    /// by an earlier replacement are skipped without calling `replace`.
+    fn apply_matches<'a>(
         &self,
         content: &str,
-        source_id: &str,
-        run_id: &str,
-        input_hash: &str,
-        user_id: &str,
-        reason: &str,
-        outcome: &str,
-        mut audit_log: Option<&mut crate::audit_log::AuditLog>,
-    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
-        let all_matches = self.find_matches(content, source_id)?;
-
+        all_matches: &'a HashMap<String, Vec<RedactionMatch>>,
+        replace: &mut dyn FnMut(&RedactionMatch) -> String,
+    ) -> (String, Vec<(&'a RedactionMatch, AppliedRedaction)>) {
         let mut sorted_matches: Vec<&RedactionMatch> = all_matches.values()
             .flatten()
             .collect();
-        sorted_matches.sort_by_key(|m| m.start);
-
-        let mapper = StrippedIndexMapper::new(content);
+        // Matches are applied left to right; when two start at the same position, the
+        // higher-priority rule wins and the other is trimmed or skipped as an overlap.
+        sorted_matches.sort_by_key(|m| (m.start, self.rule_rank(&m.rule_name)));
 
         let mut sanitized_content = String::with_capacity(content.len());
+        let mut applied = Vec::new();
         let mut last_end = 0usize;
 
-        for m in sorted_matches.iter() {
-            let original_start_byte = mapper.map_index(m.start as usize);
-            let original_end_byte = mapper.map_index(m.end as usize);
+        for m in sorted_matches {
+            let (original_start_byte, original_end_byte) = (m.start as usize, m.end as usize);
 
             // If this match is fully covered by previous replacements, skip it.
             if original_end_byte <= last_end {
@@ -278,14 +596,80 @@ impl SanitizationEngine for RegexEngine {
             // handling partial overlaps by starting from the last match's end.
             let current_start = original_start_byte.max(last_end);
             sanitized_content.push_str(&content[last_end..current_start]);
-            
-            // Append the sanitized string
-            sanitized_content.push_str(&m.sanitized_string);
-            
+
+            // Append the replacement
+            let mut replacement = replace(m);
+            let replaced = &content[current_start..original_end_byte];
+            if self.options.keep_width && !replaced.contains('\n') {
+                // Escape sequences inside the span take up no columns.
+                replacement = fit_to_width(&replacement, strip_str(replaced).width());
+            }
+            sanitized_content.push_str(&replacement);
+
+            applied.push((m, AppliedRedaction {
+                rule_name: m.rule_name.clone(),
+                start: current_start as u64,
+                end: original_end_byte as u64,
+                length: (original_end_byte - current_start) as u64,
+                replacement,
+            }));
+
             // Update the last_end pointer
             last_end = original_end_byte;
+        }
+
+        sanitized_content.push_str(&content[last_end..]);
+        (sanitized_content, applied)
+    }
+
+    /// Applies `all_matches` to `content` and returns the sanitized text, its summary and
+    /// the applied edits.
+    fn sanitize_matches_with_edits(
+        &self,
+        content: &str,
+        all_matches: HashMap<String, Vec<RedactionMatch>>,
+    ) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, &mut |m| m.sanitized_string.clone());
+        let summary = self.build_summary_from_matches(&all_matches);
+        Ok((sanitized_content, summary, applied.into_iter().map(|(_, edit)| edit).collect()))
+    }
+
+    /// Flattens the matches of all rules into one list in input order, as
+    /// [`SanitizationEngine::find_matches_for_ui`] returns them.
+    fn flatten_matches(&self, all_map: HashMap<String, Vec<RedactionMatch>>) -> Vec<RedactionMatch> {
+        let mut out: Vec<RedactionMatch> = all_map.into_values().flatten().collect();
+        ensure_match_hashes(&mut out);
+        out.sort_by_key(|m| (m.start, self.rule_rank(&m.rule_name)));
+        out
+    }
+
+    fn build_summary_from_matches(&self, all_matches: &HashMap<String, Vec<RedactionMatch>>) -> Vec<RedactionSummaryItem> {
+        let mut aggregator = Aggregator::new();
+        for matches in all_matches.values() {
+            aggregator.add_matches(matches);
+        }
+        let mut summary_items = aggregator.into_summary();
+        summary_items.sort_by_key(|item| self.rule_rank(&item.rule_name));
+        summary_items
+    }
+}
+impl SanitizationEngine for RegexEngine {
+    fn sanitize(
+        &self,
+        content: &str,
+        source_id: &str,
+        run_id: &str,
+        input_hash: &str,
+        user_id: &str,
+        reason: &str,
+        outcome: &str,
+        mut audit_log: Option<&mut crate::audit_log::AuditLog>,
+    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        let all_matches = self.find_matches(content, source_id, None)?;
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, &mut |m| m.sanitized_string.clone());
 
-            if let Some(log) = audit_log.as_mut() {
+        if let Some(log) = audit_log.as_mut() {
+            for (m, edit) in &applied {
                 let rlog = RedactionLog {
                     timestamp: m.timestamp.clone().unwrap_or_default(),
                     run_id: run_id.to_string(),
@@ -295,37 +679,50 @@ impl SanitizationEngine for RegexEngine {
                     redaction_outcome: outcome.to_string(),
                     rule_name: m.rule_name.clone(),
                     input_hash: input_hash.to_string(),
-                    match_hash: m.sample_hash.clone().unwrap_or_default(),
+                    match_hash: RedactionLog::value_hash(&m.original_string),
                     start: m.start,
                     end: m.end,
+                    replacement: edit.replacement.clone(),
                 };
                 log.append(&rlog)?;
             }
         }
 
-        sanitized_content.push_str(&content[last_end..]);
-
         let summary = self.build_summary_from_matches(&all_matches);
         Ok((sanitized_content, summary))
     }
 
+    fn sanitize_with_edits(&self, content: &str, source_id: &str) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        self.sanitize_matches_with_edits(content, self.find_matches(content, source_id, None)?)
+    }
+
+    fn sanitize_file_with_edits(&self, content: &str, path: &str) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        self.sanitize_matches_with_edits(content, self.find_matches(content, path, Some(path))?)
+    }
+
+    fn sanitize_with(
+        &self,
+        content: &str,
+        source_id: &str,
+        replace: &mut dyn FnMut(&RedactionMatch) -> String,
+    ) -> Result<(String, Vec<AppliedRedaction>)> {
+        let all_matches = self.find_matches(content, source_id, None)?;
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, replace);
+        Ok((sanitized_content, applied.into_iter().map(|(_, edit)| edit).collect()))
+    }
+
     fn analyze_for_stats(&self, content: &str, source_id: &str) -> Result<Vec<RedactionSummaryItem>> {
-        let all_matches = self.find_matches(content, source_id)?;
+        let all_matches = self.find_matches(content, source_id, None)?;
         let summary = self.build_summary_from_matches(&all_matches);
         Ok(summary)
     }
 
     fn find_matches_for_ui(&self, content: &str, source_id: &str) -> Result<Vec<RedactionMatch>> {
-        let all_map = self.find_matches(content, source_id)?;
-        let mut out: Vec<RedactionMatch> = Vec::new();
-
-        for (_rule, mut vec_matches) in all_map.into_iter() {
-            out.append(&mut vec_matches);
-        }
+        Ok(self.flatten_matches(self.find_matches(content, source_id, None)?))
+    }
 
-        ensure_match_hashes(&mut out);
-        out.sort_by_key(|m| m.start);
-        Ok(out)
+    fn find_file_matches(&self, content: &str, path: &str) -> Result<Vec<RedactionMatch>> {
+        Ok(self.flatten_matches(self.find_matches(content, path, Some(path))?))
     }
 
     fn compiled_rules(&self) -> &CompiledRules {
@@ -339,4 +736,24 @@ impl SanitizationEngine for RegexEngine {
     fn get_options(&self) -> &EngineOptions {
         &self.options
     }
-}
\ No newline at end of file
+
+    fn rule_profile(&self) -> Vec<RuleProfile> {
+        self.profiler.as_ref().map_or_else(Vec::new, RuleProfiler::snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlap_search_uses_merged_spans() {
+        let spans = merged_spans(vec![(30, 40), (0, 10), (5, 20), (8, 12), (50, 60)]);
+        assert_eq!(spans, [(0, 20), (30, 40), (50, 60)]);
+        assert!(overlaps_any(&spans, 15, 25));
+        assert!(overlaps_any(&spans, 39, 45));
+        assert!(!overlaps_any(&spans, 20, 30));
+        assert!(!overlaps_any(&spans, 60, 70));
+        assert!(!overlaps_any(&[], 0, 10));
+    }
+}
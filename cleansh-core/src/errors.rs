@@ -21,6 +21,15 @@ pub enum CleanshError {
     #[error("Rule '{0}': pattern length ({1}) exceeds maximum allowed ({2})")]
     PatternLengthExceeded(String, usize, usize),
 
+    #[error("Rule '{0}': invalid path glob: {1}")]
+    InvalidPathGlob(String, String),
+
+    #[error("Rule '{0}': {1}")]
+    InvalidReplacement(String, String),
+
+    #[error("Unsupported schema_version '{0}'; this version of cleansh reads schema version {v}", v = crate::schema::SCHEMA_VERSION)]
+    UnsupportedSchemaVersion(String),
+
     #[error("Failed to serialize configuration for hashing: {0}")]
     SerializationError(String),
 
@@ -0,0 +1,63 @@
+//! Structured events for embedders that want to follow an engine's work.
+//!
+//! An [`EngineObserver`] passed to [`RegexEngine::with_observer`](crate::RegexEngine::with_observer)
+//! is told when each rule is ready, when a match is found, and when an input (a chunk: a
+//! file, a line, or a pass) has been processed. Embedders can feed these into their own
+//! metrics or drive a progress display without parsing log output.
+//!
+//! Observers are called synchronously from the sanitizing thread, so they should return
+//! quickly. Every method has an empty default, so an observer implements only the events
+//! it needs.
+//!
+//! License: BUSL-1.1
+
+use serde::Serialize;
+use std::fmt;
+use std::time::Duration;
+
+use crate::redaction_match::RedactionMatch;
+
+/// A rule that was compiled and is ready to run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleCompiled {
+    pub rule_name: String,
+    /// Position of the rule in application order (priority first, then declared order).
+    pub position: usize,
+}
+
+/// One input the engine has finished searching.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChunkProcessed {
+    /// The source the input came from, as passed to the engine; often a file path.
+    pub source_id: String,
+    /// Length of the input in bytes.
+    pub bytes: usize,
+    /// Number of rules run over the input; disabled, out-of-scope and prefiltered rules are not.
+    pub rules_run: usize,
+    /// Number of matches found, before overlapping matches are resolved.
+    pub matches: usize,
+    /// Time spent searching the input.
+    pub elapsed: Duration,
+}
+
+/// Receives events from an engine.
+pub trait EngineObserver: Send + Sync {
+    /// Called once per rule, in application order, when the engine is built.
+    fn on_rule_compiled(&self, _rule: &RuleCompiled) {}
+
+    /// Called for every match that passed its context check and validator, before
+    /// overlapping matches are resolved.
+    ///
+    /// The match carries the original value. Observers must not log or export it unless
+    /// that is the point of the embedding.
+    fn on_match(&self, _redaction_match: &RedactionMatch) {}
+
+    /// Called when the engine has finished searching one input.
+    fn on_chunk_processed(&self, _chunk: &ChunkProcessed) {}
+}
+
+impl fmt::Debug for dyn EngineObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EngineObserver")
+    }
+}
@@ -62,6 +62,12 @@ mod tests {
                     programmatic_validation: false,
                     opt_in: false,
                     tags: None,
+                    priority: 0,
+                    context: None,
+                    paths: None,
+                    deprecated: false,
+                    aliases: None,
+                    exceptions: None,
                     pattern_type: "regex".to_string(),
                     version: "0.1.8".to_string(),
                     created_at: "2025-01-01T00:00:00Z".to_string(),
@@ -69,6 +75,7 @@ mod tests {
                     author: "Obscura Team".to_string(),
                 },
             ],
+            ..Default::default()
         };
         let options = EngineOptions::default();
         
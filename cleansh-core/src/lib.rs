@@ -20,6 +20,14 @@
 //! * `audit_log`: Defines the structure and logic for writing redaction events to a log file.
 //! * `engines`: Contains concrete implementations of the `SanitizationEngine` trait.
 //! * `headless`: Convenience wrappers for using core engines in a non-interactive mode.
+//! * `path_scope`: Limits rules to files matching path globs.
+//! * `allowlist`: Values that are never redacted, and the `cleansh:ignore-line` marker.
+//! * `replacement`: Parses and expands the templates in a rule's `replace_with`.
+//! * `soft_wrap`: Rejoins secrets that a terminal hard-wrapped across lines.
+//! * `passes`: Re-runs sanitization on its own output until the text is stable.
+//! * `events`: Observer callbacks for embedders following an engine's work.
+//! * `schema`: The version stamped into every machine-readable document.
+//! * `testing`: Helpers for building rules and asserting on matches in tests (feature `test-utils`).
 //!
 //! ## Public API
 //!
@@ -32,7 +40,9 @@
 //! * [`RedactionRule`]: Defines a single rule for identifying and replacing sensitive patterns.
 //! * [`merge_rules`]: Merges default and user-defined configurations.
 //! * [`RedactionConfig::load_from_file`]: Loads rules from a YAML file.
-//! * [`RedactionConfig::load_default_rules`]: Loads the built-in set of default rules.
+//! * [`RedactionConfig::load_default_rules`]: Loads the built-in set of default rules, after
+//!   [`verify_default_rules`] has checked them against their shipped checksum.
+//! * [`override_downgrade`]: Tells whether a user rule weakens the rule it overrides.
 //!
 //! **Sanitization Engine**
 //!
@@ -47,6 +57,7 @@
 //!
 //! * [`RedactionMatch`]: A detailed record of a single matched and redacted item, including its location.
 //! * [`RedactionSummaryItem`]: A summary of all matches for a specific rule.
+//! * [`Aggregator`]: Builds a summary incrementally from matches and partial summaries.
 //!
 //! **Audit Logging**
 //!
@@ -101,14 +112,30 @@
 //! License: BUSL-1.1
 
 // All modules must be declared before they can be used.
+pub mod aggregator;
+pub mod allowlist;
 pub mod audit_log;
 pub mod config;
+pub mod description;
 pub mod engine;
 pub mod engines;
+pub mod events;
 pub mod headless;
+pub mod match_context;
+pub mod passes;
+pub mod path_scope;
+pub mod prefilter;
 pub mod profiles;
 pub mod redaction_match;
+pub mod replacement;
+pub mod rule_profile;
 pub mod sanitizers;
+pub mod schema;
+pub mod soft_wrap;
+pub mod stream;
+pub mod tenant;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 pub mod validators;
 pub mod errors;
 
@@ -118,10 +145,20 @@ pub mod errors;
 /// Re-exports the public configuration types and functions for managing redaction rules.
 pub use config::{
     merge_rules,
+    override_downgrade,
+    verify_default_rules,
+    ConfigProblem,
+    ContextWindow,
+    DeprecatedName,
     RedactionConfig,
     RedactionRule,
     RedactionSummaryItem,
     RuleConfigNotFoundError,
+    RuleDowngrade,
+    RuleMetadata,
+    SkippedRule,
+    TenantKey,
+    ACCEPT_DOWNGRADE_TAG,
     MAX_PATTERN_LENGTH,
 };
 
@@ -131,11 +168,50 @@ pub use errors::CleanshError;
 /// Re-exports types related to the core sanitization engine trait.
 pub use engine::SanitizationEngine;
 
+/// Re-exports the structured rule descriptions returned by `SanitizationEngine::describe`.
+pub use description::{EngineDescription, RuleAnchors, RuleDescription};
+
 /// Re-exports the concrete `RegexEngine` implementation from its new location.
 pub use engines::regex_engine::RegexEngine;
 
 /// Re-exports types for detailed redaction matches and sensitive data reporting.
-pub use redaction_match::{RedactionLog, RedactionMatch, redact_sensitive};
+pub use redaction_match::{apply_redactions, fit_to_width, AppliedRedaction, RedactionLog, RedactionMatch, redact_sensitive, set_pii_debug_allowed};
+
+/// Re-exports the view of neighboring text that rules with a `context` window are checked against.
+pub use match_context::MatchContext;
+
+/// Re-exports the compiled set of values that are never redacted.
+pub use allowlist::{Allowlist, IGNORE_LINE_MARKER};
+
+/// Re-exports the compiled form of a rule's `paths` globs.
+pub use path_scope::PathScope;
+
+/// Re-exports the incremental summary builder for streaming and parallel callers.
+pub use aggregator::Aggregator;
+
+/// Re-exports the multi-pass sanitization helper and its result types.
+pub use passes::{sanitize_passes, PassStop, PassesOutcome};
+
+/// Re-exports the chunk sizes of `SanitizationEngine::sanitize_reader`.
+pub use stream::StreamOptions;
+
+/// Re-exports the parsed form of a rule's `replace_with`.
+pub use replacement::{MatchInfo, ReplacementTemplate};
+
+/// Re-exports the line-joining helper used for terminal-wrapped input.
+pub use soft_wrap::SoftWrap;
+
+/// Re-exports the per-rule counts and timings collected with `EngineOptions::profile_rules`.
+pub use rule_profile::{RuleProfile, RuleProfiler};
+
+/// Re-exports the observer interface for engine events.
+pub use events::{ChunkProcessed, EngineObserver, RuleCompiled};
+
+/// Re-exports the schema version of machine-readable documents and its compatibility check.
+pub use schema::{check_schema_version, SCHEMA_VERSION, SCHEMA_VERSION_FIELD};
+
+/// Re-exports the per-line tenant lookup used to break findings down by customer.
+pub use tenant::{TenantCounts, TenantLines};
 
 /// Re-exports types related to profile configuration, which allows for custom
 /// redaction behavior and reporting.
@@ -143,6 +219,7 @@ pub use profiles::{
     apply_profile_to_config,
     compute_run_seed,
     DedupeConfig,
+    DEFAULT_TOKEN_FORMAT,
     EngineOptions,
     format_token,
     load_profile_by_name,
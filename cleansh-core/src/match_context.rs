@@ -0,0 +1,65 @@
+//! Access to the text surrounding a candidate match.
+//!
+//! Some rules can only tell a secret from harmless text by looking at what is next to it,
+//! such as a `password:` label before a value. Instead of widening the rule's regex to
+//! capture that label (which would also widen the replaced span), a rule declares a
+//! `context` window with keywords, and the engine builds a `MatchContext` view of each
+//! candidate match to look for them. Validators still see only the matched text.
+//! License: BUSL-1.1
+
+use crate::config::ContextWindow;
+
+/// A view of a match together with up to `before`/`after` bytes of neighboring text.
+///
+/// Window edges are moved inward to the nearest UTF-8 character boundary, so the
+/// surrounding slices may be slightly shorter than requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchContext<'a> {
+    /// Text immediately before the match.
+    pub before: &'a str,
+    /// The matched text itself.
+    pub matched: &'a str,
+    /// Text immediately after the match.
+    pub after: &'a str,
+}
+
+impl<'a> MatchContext<'a> {
+    /// Builds the context for the match at `start..end` in `input`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start..end` is not a valid character-aligned range of `input`.
+    pub fn new(input: &'a str, start: usize, end: usize, window: &ContextWindow) -> Self {
+        let mut before_start = start.saturating_sub(window.before);
+        while !input.is_char_boundary(before_start) {
+            before_start += 1;
+        }
+        let mut after_end = end.saturating_add(window.after).min(input.len());
+        while !input.is_char_boundary(after_end) {
+            after_end -= 1;
+        }
+
+        Self {
+            before: &input[before_start..start],
+            matched: &input[start..end],
+            after: &input[end..after_end],
+        }
+    }
+
+    /// Returns `true` if `keyword` appears, ignoring case, before or after the match.
+    pub fn contains_keyword(&self, keyword: &str) -> bool {
+        self.contains_any_keyword(&[keyword])
+    }
+
+    /// Returns `true` if any of `keywords` appears, ignoring case, near the match.
+    ///
+    /// The surrounding text is lowercased once for all keywords.
+    pub fn contains_any_keyword<K: AsRef<str>>(&self, keywords: &[K]) -> bool {
+        let before = self.before.to_lowercase();
+        let after = self.after.to_lowercase();
+        keywords.iter().any(|keyword| {
+            let keyword = keyword.as_ref().to_lowercase();
+            !keyword.is_empty() && (before.contains(&keyword) || after.contains(&keyword))
+        })
+    }
+}
@@ -0,0 +1,78 @@
+//! Repeated sanitization until the output stops changing.
+//!
+//! A single pass applies each rule to the original text only. When rules are layered on
+//! purpose (one rule's replacement is meant to be picked up by another), or when a
+//! replacement happens to complete a new match together with its surroundings, a second
+//! pass over the sanitized output finds more. `sanitize_passes` re-runs the engine until
+//! the text is stable, a pass limit is hit, or the output starts repeating itself.
+//! License: BUSL-1.1
+
+use anyhow::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::aggregator::Aggregator;
+use crate::config::RedactionSummaryItem;
+use crate::engine::SanitizationEngine;
+
+/// Why [`sanitize_passes`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassStop {
+    /// The last pass left the text unchanged.
+    Fixpoint,
+    /// The pass limit was reached while the text was still changing.
+    Limit,
+    /// A pass reproduced the output of an earlier pass, so the rules would loop forever.
+    Cycle,
+}
+
+/// The result of a multi-pass sanitization.
+#[derive(Debug, Clone)]
+pub struct PassesOutcome {
+    pub sanitized: String,
+    /// Redactions from every pass that changed the text, merged by rule.
+    pub summary: Vec<RedactionSummaryItem>,
+    /// Number of passes that were run, including a final pass that found nothing to change.
+    pub passes: usize,
+    pub stop: PassStop,
+}
+
+/// Sanitizes `content` up to `max_passes` times, feeding each pass the previous output.
+///
+/// A pass whose output equals its input ends the loop with [`PassStop::Fixpoint`]; its
+/// matches are not counted, since they replaced text with itself. Hashes of earlier
+/// outputs are kept so that rules rewriting each other's replacements are reported as a
+/// [`PassStop::Cycle`] instead of running until the limit.
+pub fn sanitize_passes(
+    engine: &dyn SanitizationEngine,
+    content: &str,
+    source_id: &str,
+    max_passes: usize,
+) -> Result<PassesOutcome> {
+    let mut current = content.to_string();
+    let mut summary = Aggregator::new();
+    let mut seen = vec![hash_text(&current)];
+
+    for pass in 1..=max_passes.max(1) {
+        let (sanitized, items) = engine.sanitize(&current, source_id, "", "", "", "", "", None)?;
+        if sanitized == current {
+            return Ok(PassesOutcome { sanitized, summary: summary.into_summary(), passes: pass, stop: PassStop::Fixpoint });
+        }
+        summary.add_summary(items);
+
+        let hash = hash_text(&sanitized);
+        current = sanitized;
+        if seen.contains(&hash) {
+            return Ok(PassesOutcome { sanitized: current, summary: summary.into_summary(), passes: pass, stop: PassStop::Cycle });
+        }
+        seen.push(hash);
+    }
+
+    Ok(PassesOutcome { sanitized: current, summary: summary.into_summary(), passes: max_passes.max(1), stop: PassStop::Limit })
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,86 @@
+//! Path scoping for redaction rules.
+//!
+//! A rule may list `paths` globs so that it only runs on some files, e.g. a generic
+//! token rule that should apply to `config/**` but not to `tests/fixtures/**`. Globs
+//! starting with `!` exclude paths. Scopes are only checked when the caller says the
+//! content was read from a file, through [`crate::engine::SanitizationEngine::find_file_matches`]
+//! or [`crate::engine::SanitizationEngine::sanitize_file_with_edits`]; stdin and other
+//! unnamed sources always use every rule.
+//! License: BUSL-1.1
+
+use regex::Regex;
+
+/// A compiled set of include and exclude path globs.
+///
+/// Supported syntax: `*` matches within one path segment, `?` matches one character
+/// other than `/`, and `**` matches across segments. A glob does not need to match from
+/// the start of the path: `tests/**` matches `tests/a.rs` as well as
+/// `/home/me/repo/tests/a.rs`. A glob that matches a directory also matches everything
+/// inside it, so `tests` and `tests/**` are equivalent. Backslashes in paths and globs
+/// are treated as `/`.
+#[derive(Debug, Clone)]
+pub struct PathScope {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl PathScope {
+    /// Compiles `globs` into a scope. Entries starting with `!` are exclusions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a glob cannot be translated into a valid regular expression.
+    pub fn new(globs: &[String]) -> Result<Self, regex::Error> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for glob in globs {
+            match glob.strip_prefix('!') {
+                Some(negated) => exclude.push(glob_to_regex(negated)?),
+                None => include.push(glob_to_regex(glob)?),
+            }
+        }
+        Ok(Self { include, exclude })
+    }
+
+    /// Returns `true` if a rule with this scope should run on `path`.
+    ///
+    /// A path is in scope when it matches at least one include glob (or there are none)
+    /// and no exclude glob.
+    pub fn applies_to(&self, path: &str) -> bool {
+        let path = normalize(path);
+        (self.include.is_empty() || self.include.iter().any(|re| re.is_match(&path)))
+            && !self.exclude.iter().any(|re| re.is_match(&path))
+    }
+}
+
+/// Converts separators to `/` and drops a leading `./`.
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix("./").map(str::to_string).unwrap_or(path)
+}
+
+/// Translates a path glob into an anchored regular expression.
+fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let glob = normalize(glob);
+    let glob = glob.trim_end_matches('/');
+    let mut re = String::from("^(?:.*/)?");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            _ => re.push_str(&regex::escape(c.encode_utf8(&mut [0u8; 4]))),
+        }
+    }
+    re.push_str("(?:/.*)?$");
+    Regex::new(&re)
+}
@@ -0,0 +1,206 @@
+//! An Aho-Corasick prefilter that skips rules which cannot match an input.
+//!
+//! Many rules can only match text containing a fixed literal: `AKIA` for AWS access keys,
+//! `ghp_` for GitHub tokens, `@` for email addresses. [`required_literals`] derives such a
+//! set of literals from a rule's pattern, one of which every match must contain.
+//! [`Prefilter`] searches an input for all of these literals in a single pass, and the
+//! engine then runs only the rules whose literals were found, plus the rules no literal
+//! could be derived for. On short inputs such as single log lines, where most rules have
+//! nothing to find, this saves most of the regex work.
+//!
+//! Literals are searched ASCII case-insensitively, so the prefilter may let through a
+//! rule that then finds nothing, but never skips a rule that would have matched.
+//!
+//! License: BUSL-1.1
+
+use aho_corasick::AhoCorasick;
+use regex_syntax::hir::{Class, Hir, HirKind};
+
+use crate::sanitizers::compiler::CompiledRules;
+
+/// Largest number of alternative literals kept for one rule.
+const MAX_LITERALS: usize = 64;
+
+/// Longest literal kept, in bytes; longer literals are cut, which keeps them necessary.
+const MAX_LITERAL_LEN: usize = 32;
+
+/// Returns the single lowercase ASCII letter a class stands for, if it is exactly that
+/// letter in either case, as `(?i)a` produces.
+fn case_folded_letter(class: &Class) -> Option<u8> {
+    let Class::Unicode(class) = class else {
+        return match class {
+            Class::Bytes(bytes) => {
+                let ranges: Vec<(u8, u8)> = bytes.ranges().iter().map(|r| (r.start(), r.end())).collect();
+                match ranges.as_slice() {
+                    [(a, a2), (b, b2)] if a == a2 && b == b2 && a.is_ascii_uppercase() && a.to_ascii_lowercase() == *b => Some(*b),
+                    _ => None,
+                }
+            }
+            Class::Unicode(_) => None,
+        };
+    };
+    let ranges: Vec<(char, char)> = class.ranges().iter().map(|r| (r.start(), r.end())).collect();
+    match ranges.as_slice() {
+        [(a, a2), (b, b2)] if a == a2 && b == b2 && a.is_ascii_uppercase() && a.to_ascii_lowercase() == *b => Some(*b as u8),
+        _ => None,
+    }
+}
+
+/// Keeps the better of two literal sets: fewer short literals select more precisely.
+fn better(current: Option<Vec<Vec<u8>>>, candidate: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+    let shortest = |set: &[Vec<u8>]| set.iter().map(Vec::len).min().unwrap_or(0);
+    match current {
+        Some(current) if shortest(&current) >= shortest(&candidate) => Some(current),
+        _ => Some(candidate),
+    }
+}
+
+/// Computes literals one of which every match of `hir` contains, in lowercase.
+///
+/// The flag tells whether the literals are exact, i.e. each one is a whole match of `hir`;
+/// only exact literals of adjacent parts can be joined into longer ones.
+fn literals(hir: &Hir) -> Option<(Vec<Vec<u8>>, bool)> {
+    match hir.kind() {
+        HirKind::Literal(literal) => {
+            let lower: Vec<u8> = literal.0.iter().take(MAX_LITERAL_LEN).map(u8::to_ascii_lowercase).collect();
+            Some((vec![lower], literal.0.len() <= MAX_LITERAL_LEN))
+        }
+        HirKind::Class(class) => case_folded_letter(class).map(|letter| (vec![vec![letter]], true)),
+        HirKind::Capture(capture) => literals(&capture.sub),
+        HirKind::Repetition(repetition) if repetition.min >= 1 => literals(&repetition.sub).map(|(set, _)| (set, false)),
+        HirKind::Alternation(alternatives) => {
+            let mut all = Vec::new();
+            let mut exact = true;
+            for alternative in alternatives {
+                let (set, alternative_exact) = literals(alternative)?;
+                all.extend(set);
+                exact &= alternative_exact;
+                if all.len() > MAX_LITERALS {
+                    return None;
+                }
+            }
+            Some((all, exact))
+        }
+        HirKind::Concat(parts) => {
+            // Runs of adjacent exact parts combine into longer literals; the best run or
+            // inexact part wins.
+            let mut best = None;
+            let mut run: Option<Vec<Vec<u8>>> = None;
+            let mut exact = true;
+            for part in parts {
+                match literals(part) {
+                    Some((next, true)) => {
+                        run = match run.take() {
+                            Some(prefixes)
+                                if prefixes.len() * next.len() <= MAX_LITERALS
+                                    && prefixes.iter().chain(&next).map(Vec::len).max().unwrap_or(0) * 2 <= MAX_LITERAL_LEN =>
+                            {
+                                Some(
+                                    prefixes
+                                        .iter()
+                                        .flat_map(|prefix| next.iter().map(move |suffix| [prefix.as_slice(), suffix].concat()))
+                                        .collect(),
+                                )
+                            }
+                            Some(prefixes) => {
+                                exact = false;
+                                best = better(best, prefixes);
+                                Some(next)
+                            }
+                            None => Some(next),
+                        };
+                    }
+                    Some((next, false)) => {
+                        exact = false;
+                        if let Some(done) = run.take() {
+                            best = better(best, done);
+                        }
+                        best = better(best, next);
+                    }
+                    None => {
+                        exact = false;
+                        if let Some(done) = run.take() {
+                            best = better(best, done);
+                        }
+                    }
+                }
+            }
+            match run {
+                Some(done) if exact => Some((done, true)),
+                Some(done) => better(best, done).map(|set| (set, false)),
+                None => best.map(|set| (set, false)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Derives literals, in lowercase, one of which every match of `pattern` must contain,
+/// or `None` when the pattern has no such literals (or does not parse).
+pub fn required_literals(pattern: &str) -> Option<Vec<Vec<u8>>> {
+    let hir = regex_syntax::parse(pattern).ok()?;
+    let (mut set, _) = literals(&hir)?;
+    if set.is_empty() || set.iter().any(Vec::is_empty) {
+        return None;
+    }
+    set.sort();
+    set.dedup();
+    Some(set)
+}
+
+/// One automaton over the required literals of every rule in a compiled rule set.
+#[derive(Debug)]
+pub struct Prefilter {
+    automaton: AhoCorasick,
+    /// The rule each automaton pattern belongs to, by index into the compiled rules.
+    pattern_rules: Vec<usize>,
+    /// Rules without required literals, which always run.
+    unfiltered: Vec<bool>,
+}
+
+impl Prefilter {
+    /// Builds the prefilter for `rules`.
+    pub fn new(rules: &CompiledRules) -> anyhow::Result<Self> {
+        let mut patterns = Vec::new();
+        let mut pattern_rules = Vec::new();
+        let mut unfiltered = Vec::with_capacity(rules.rules.len());
+        for (index, rule) in rules.rules.iter().enumerate() {
+            match required_literals(rule.regex.as_str()) {
+                Some(set) => {
+                    pattern_rules.extend(std::iter::repeat_n(index, set.len()));
+                    patterns.extend(set);
+                    unfiltered.push(false);
+                }
+                None => unfiltered.push(true),
+            }
+        }
+        let automaton = AhoCorasick::builder().ascii_case_insensitive(true).build(&patterns)?;
+        Ok(Self { automaton, pattern_rules, unfiltered })
+    }
+
+    /// Returns, for each rule, whether it may match `input`.
+    pub fn candidates(&self, input: &str) -> Vec<bool> {
+        let mut candidates = self.unfiltered.clone();
+        for found in self.automaton.find_overlapping_iter(input) {
+            candidates[self.pattern_rules[found.pattern().as_usize()]] = true;
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_literals_every_match_contains() {
+        assert_eq!(required_literals(r"\b(AKIA|ASIA)[0-9A-Z]{16}\b"), Some(vec![b"akia".to_vec(), b"asia".to_vec()]));
+        assert_eq!(required_literals(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"), Some(vec![b"@".to_vec()]));
+        assert_eq!(required_literals(r"(?i)bearer\s+\S+"), Some(vec![b"bearer".to_vec()]));
+        assert_eq!(required_literals(r"x(?:[0-9]+yz)+"), Some(vec![b"yz".to_vec()]));
+        assert_eq!(required_literals(r"(?:sk|rk)_(?:live|test)_[0-9a-z]{24}"), Some(vec![b"rk_live_".to_vec(), b"rk_test_".to_vec(), b"sk_live_".to_vec(), b"sk_test_".to_vec()]));
+        assert_eq!(required_literals(r"\b[0-9]{3}-[0-9]{2}-[0-9]{4}\b"), Some(vec![b"-".to_vec()]));
+        assert_eq!(required_literals(r"\b[A-Za-z0-9]{16,}\b"), None);
+        assert_eq!(required_literals(r"x?y?"), None);
+    }
+}
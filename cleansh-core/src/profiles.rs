@@ -27,7 +27,7 @@ use log::{debug, warn};
 use chrono::NaiveDate;
 use serde_yml::Value; // Corrected from serde_yaml
 
-use crate::config::{RedactionConfig, RedactionRule};
+use crate::config::{RedactionConfig, RedactionRule, SkippedRule};
 use crate::redaction_match::RedactionMatch;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -51,6 +51,8 @@ pub struct ProfileConfig {
     pub dedupe: Option<DedupeConfig>,
     pub post_processing: Option<PostProcessingConfig>,
     pub reporting: Option<ReportingConfig>,
+    /// Domains the `hostname` rule never redacts, in addition to any given on the command line.
+    pub allowed_domains: Vec<String>,
 }
 
 impl ProfileConfig {
@@ -61,9 +63,8 @@ impl ProfileConfig {
 
         let default_rule_names: HashSet<&str> = default_config.rules.iter().map(|r| r.name.as_str()).collect();
         for rule_override in &self.rules {
-            if !default_rule_names.contains(rule_override.name.as_str()) {
-                bail!("Profile '{}' validation failed: rule '{}' not found in default configuration.",
-                    self.profile_name, rule_override.name);
+            if let Some(problem) = rule_override_problem(rule_override, &default_rule_names, default_config) {
+                bail!("Profile '{}' validation failed: {}", self.profile_name, problem);
             }
         }
 
@@ -85,6 +86,23 @@ impl ProfileConfig {
         Ok(())
     }
 
+    /// Removes the rule entries that [`ProfileConfig::validate`] would reject (unknown
+    /// rule names), and returns them with the reason. Used by
+    /// `--skip-invalid-rules`, so that a central profile written for a newer rule set
+    /// does not stop the run.
+    pub fn remove_invalid_rules(&mut self, default_config: &RedactionConfig) -> Vec<SkippedRule> {
+        let default_rule_names: HashSet<&str> = default_config.rules.iter().map(|r| r.name.as_str()).collect();
+        let mut skipped = Vec::new();
+        self.rules.retain(|rule_override| match rule_override_problem(rule_override, &default_rule_names, default_config) {
+            Some(reason) => {
+                skipped.push(SkippedRule { name: rule_override.name.clone(), reason });
+                false
+            }
+            None => true,
+        });
+        skipped
+    }
+
     /// Verifies the HMAC-SHA256 signature of the profile against the provided secret key.
     ///
     /// This method is crucial for ensuring the integrity and authenticity of a profile
@@ -149,6 +167,16 @@ fn get_raw_profile_for_signature(raw_bytes: &[u8]) -> Result<Vec<u8>> {
         .map(|s| s.as_bytes().to_vec())
 }
 
+/// Returns why a profile's rule entry cannot be applied to `default_config`, if it cannot.
+fn rule_override_problem(rule_override: &ProfileRule, default_rule_names: &HashSet<&str>, default_config: &RedactionConfig) -> Option<String> {
+    let name = rule_override.name.as_str();
+    if !default_rule_names.contains(name) && !default_config.groups.contains_key(name)
+        && default_config.resolve_alias(name).is_none()
+    {
+        return Some(format!("rule '{}' not found in default configuration.", name));
+    }
+    None
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
@@ -172,6 +200,14 @@ pub struct DedupeConfig {
     pub use_hash: bool,
 }
 
+/// The placeholder template used when `replace_with_token` is set without a `token_format`.
+pub const DEFAULT_TOKEN_FORMAT: &str = "[{rule}:{shorthash}]";
+
+/// Replaces each match with a stable per-value token instead of the rule's `replace_with`.
+///
+/// `token_format` is a template with `{rule}` and `{shorthash}` (the first 8 hex digits
+/// of the match's hash), defaulting to [`DEFAULT_TOKEN_FORMAT`]. Tokens in either format
+/// are recognized when sanitized output is sanitized again, and are left as they are.
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct PostProcessingConfig {
@@ -271,22 +307,32 @@ pub fn sign_profile(path: &Path, key: &[u8]) -> Result<()> {
 pub fn apply_profile_to_config(profile: &ProfileConfig, mut default: RedactionConfig) -> RedactionConfig {
     debug!("Applying profile '{}' to default rules.", profile.profile_name);
 
+    let names: Vec<String> = profile.rules.iter().map(|o| o.name.clone()).collect();
+    for notice in default.deprecated_names(&names) {
+        warn!("Profile '{}': {}", profile.profile_name, notice);
+    }
+
+    // An entry naming a rule group applies to every rule in the group.
+    let overrides: Vec<(String, &ProfileRule)> = profile.rules.iter()
+        .flat_map(|o| default.expand_groups(std::slice::from_ref(&o.name)).into_iter().map(move |name| (name, o)))
+        .collect();
+
     let mut default_rules_map: HashMap<String, &mut RedactionRule> = default.rules.iter_mut()
         .map(|r| (r.name.clone(), r))
         .collect();
 
-    for profile_rule_override in &profile.rules {
-        if let Some(rule_to_update) = default_rules_map.get_mut(&profile_rule_override.name) {
+    for (rule_name, profile_rule_override) in overrides {
+        if let Some(rule_to_update) = default_rules_map.get_mut(&rule_name) {
             if let Some(enabled) = profile_rule_override.enabled {
-                debug!("Applying enabled={} override for rule '{}'", enabled, &profile_rule_override.name);
+                debug!("Applying enabled={} override for rule '{}'", enabled, &rule_name);
                 rule_to_update.enabled = Some(enabled);
             }
             if let Some(severity) = profile_rule_override.severity.clone() {
-                debug!("Applying severity='{}' override for rule '{}'", severity, &profile_rule_override.name);
+                debug!("Applying severity='{}' override for rule '{}'", severity, &rule_name);
                 rule_to_update.severity = Some(severity);
             }
         } else {
-            warn!("Profile rule '{}' not found in default configuration. It will be ignored.", profile_rule_override.name);
+            warn!("Profile rule '{}' not found in default configuration. It will be ignored.", rule_name);
         }
     }
     
@@ -382,7 +428,13 @@ pub struct ProfileMeta {
     pub version: String,
 }
 
+/// Options that change how an engine sanitizes, on top of its rules.
+///
+/// The struct is `#[non_exhaustive]` because options are added in minor releases: build
+/// it with [`EngineOptions::default`] or from a profile, then set fields or chain the
+/// `with_*` methods.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct EngineOptions {
     pub post_processing: Option<PostProcessingConfig>,
     pub samples_config: Option<SamplesConfig>,
@@ -394,6 +446,41 @@ pub struct EngineOptions {
     
     pub run_id: Option<String>,
     pub input_hash: Option<String>,
+
+    /// Domains (and their subdomains) that the `hostname` rule never redacts.
+    #[serde(default)]
+    pub allowed_domains: Vec<String>,
+
+    /// Values that no rule redacts: literals, or regexes written as `/pattern/` that must
+    /// match a whole value. See [`crate::allowlist::Allowlist`].
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+
+    /// Count evaluations and matches and time every rule, for `SanitizationEngine::rule_profile`.
+    #[serde(default)]
+    pub profile_rules: bool,
+
+    /// Seed for every random choice made under these options, such as which findings a
+    /// bounded store keeps. The same seed and input give the same result on every run.
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// Skip, for each input, the rules whose required literals it does not contain. See
+    /// [`crate::prefilter`]. The matches found are the same either way.
+    #[serde(default)]
+    pub prefilter: bool,
+
+    /// Pad or cut every replacement to the display width of the text it replaces, with
+    /// [`crate::redaction_match::fit_to_width`], so column-aligned output stays aligned.
+    /// Matches that span a line break keep their replacement as it is.
+    #[serde(default)]
+    pub keep_width: bool,
+
+    /// Run entropy rules (see [`crate::validators::is_entropy_rule`]) as a layer under
+    /// the regex rules: they run after every other rule, and a hit that overlaps a match
+    /// of another rule is dropped, so the regex rule's name and replacement are used.
+    #[serde(default)]
+    pub entropy_layer: bool,
 }
 
 impl From<ProfileConfig> for EngineOptions {
@@ -410,6 +497,13 @@ impl From<ProfileConfig> for EngineOptions {
             },
             run_id: None,
             input_hash: None,
+            allowed_domains: profile.allowed_domains,
+            allowlist: Vec::new(),
+            profile_rules: false,
+            seed: None,
+            prefilter: false,
+            keep_width: false,
+            entropy_layer: false,
         }
     }
 }
@@ -435,6 +529,56 @@ impl EngineOptions {
         self.engine_version = Some(ver);
         self
     }
+
+    /// Sets the domains the `hostname` rule never redacts. See [`EngineOptions::allowed_domains`].
+    pub fn with_allowed_domains(mut self, domains: Vec<String>) -> Self {
+        self.allowed_domains = domains;
+        self
+    }
+
+    /// Sets the values no rule redacts. See [`EngineOptions::allowlist`].
+    pub fn with_allowlist(mut self, entries: Vec<String>) -> Self {
+        self.allowlist = entries;
+        self
+    }
+
+    /// Sets the post-processing, such as replacing matches with tokens.
+    pub fn with_post_processing(mut self, post_processing: PostProcessingConfig) -> Self {
+        self.post_processing = Some(post_processing);
+        self
+    }
+
+    /// Turns on rule profiling, so that `SanitizationEngine::rule_profile` reports the
+    /// evaluations, matches and time of every rule.
+    pub fn with_rule_profiling(mut self) -> Self {
+        self.profile_rules = true;
+        self
+    }
+
+    /// Seeds every random choice made under these options, making runs on the same input
+    /// reproducible. See [`EngineOptions::seed`].
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Turns on the literal prefilter. See [`EngineOptions::prefilter`].
+    pub fn with_prefilter(mut self) -> Self {
+        self.prefilter = true;
+        self
+    }
+
+    /// Keeps the display width of replaced text. See [`EngineOptions::keep_width`].
+    pub fn with_keep_width(mut self) -> Self {
+        self.keep_width = true;
+        self
+    }
+
+    /// Runs entropy rules as a layer under the regex rules. See [`EngineOptions::entropy_layer`].
+    pub fn with_entropy_layer(mut self) -> Self {
+        self.entropy_layer = true;
+        self
+    }
 }
 // -----------------------------------------------------------------------
 
@@ -3,7 +3,7 @@
 //!
 //! This module defines `RedactionMatch` for detailed reporting of sanitization
 //! operations and includes helper functions for conditionally redacting
-//! sensitive information in debug logs based on environment variables,
+//! sensitive information in debug logs unless explicitly allowed by the caller,
 //! ensuring PII is not accidentally exposed. It also defines the `RedactionLog`
 //! for creating an auditable, immutable log of all redaction events.
 //! License: BUSL-1.1
@@ -12,18 +12,26 @@ use serde::{Serialize, Deserialize};
 use log::debug;
 use crate::config::RedactionRule;
 
-use lazy_static::lazy_static;
 use sha2::{Sha256, Digest};
 use hex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-lazy_static! {
-    /// A static boolean that is initialized once to determine if PII is allowed in debug logs.
-    /// This improves performance by avoiding repeated environment variable lookups.
-    static ref PII_DEBUG_ALLOWED: bool = {
-        std::env::var("CLEANSH_ALLOW_DEBUG_PII")
-            .map(|s| s.eq_ignore_ascii_case("true"))
-            .unwrap_or(false)
-    };
+/// Whether original PII may appear in debug logs. Off by default; the CLI only turns it on
+/// through the explicit `--unsafe-debug-pii` flag, never through the environment.
+static PII_DEBUG_ALLOWED: AtomicBool = AtomicBool::new(false);
+
+/// Allows or forbids original sensitive content in debug logs for this process.
+///
+/// This is an unsafe debugging aid: when enabled, the `log_*_debug` helpers below
+/// print matched secrets verbatim. Callers are responsible for warning the user.
+pub fn set_pii_debug_allowed(allowed: bool) {
+    PII_DEBUG_ALLOWED.store(allowed, Ordering::Relaxed);
+}
+
+/// Returns `true` if original sensitive content may be written to debug logs.
+pub fn pii_debug_allowed() -> bool {
+    PII_DEBUG_ALLOWED.load(Ordering::Relaxed)
 }
 
 /// Represents a single instance of a matched and potentially redacted string.
@@ -50,6 +58,9 @@ pub struct RedactionMatch {
 }
 
 /// Represents a single, auditable log entry for a redaction event.
+///
+/// The entry never holds the original value: `input_hash` and `match_hash` are the
+/// SHA-256 of the whole input and of the matched value, from [`RedactionLog::value_hash`].
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RedactionLog {
     pub timestamp: String,
@@ -64,6 +75,102 @@ pub struct RedactionLog {
     // Changed to u64 for compatibility with file I/O
     pub start: u64,
     pub end: u64,
+    /// The text the value was (or, for a scan, would be) replaced with.
+    #[serde(default)]
+    pub replacement: String,
+}
+
+impl RedactionLog {
+    /// Returns the SHA-256 of `value` as lowercase hex, as stored in `input_hash` and
+    /// `match_hash`.
+    pub fn value_hash(value: &str) -> String {
+        hex::encode(Sha256::digest(value.as_bytes()))
+    }
+}
+
+/// A single replacement that was applied to the input during sanitization.
+///
+/// Unlike [`RedactionMatch`], this never carries the original sensitive text. `start` and
+/// `end` are byte offsets into the original input, already trimmed for partial overlaps,
+/// so applying every edit in order with [`apply_redactions`] reproduces the sanitized output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedRedaction {
+    pub rule_name: String,
+    pub start: u64,
+    pub end: u64,
+    /// Length in bytes of the original text that was replaced (`end - start`).
+    pub length: u64,
+    pub replacement: String,
+}
+
+/// Re-applies a list of edits, sorted by `start` and non-overlapping, to `content`.
+///
+/// # Examples
+///
+/// ```
+/// use cleansh_core::redaction_match::{apply_redactions, AppliedRedaction};
+///
+/// let edits = vec![AppliedRedaction {
+///     rule_name: "email".to_string(),
+///     start: 7,
+///     end: 23,
+///     length: 16,
+///     replacement: "[EMAIL_REDACTED]".to_string(),
+/// }];
+/// assert_eq!(apply_redactions("Email: test@example.com!", &edits), "Email: [EMAIL_REDACTED]!");
+/// ```
+pub fn apply_redactions(content: &str, edits: &[AppliedRedaction]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last_end = 0usize;
+    for edit in edits {
+        out.push_str(&content[last_end..edit.start as usize]);
+        out.push_str(&edit.replacement);
+        last_end = edit.end as usize;
+    }
+    out.push_str(&content[last_end..]);
+    out
+}
+
+/// Pads or cuts `replacement` to exactly `width` display columns, so that fixed-width and
+/// column-aligned text keeps its layout.
+///
+/// Columns are counted as a terminal shows them: most CJK characters and emoji take two.
+/// A shorter replacement is padded with spaces. A longer one is cut, keeping its closing
+/// `]` when it has one so that it still reads as a placeholder; a wide character that no
+/// longer fits is replaced by a space.
+///
+/// # Examples
+///
+/// ```
+/// use cleansh_core::redaction_match::fit_to_width;
+///
+/// assert_eq!(fit_to_width("[EMAIL_REDACTED]", 20), "[EMAIL_REDACTED]    ");
+/// assert_eq!(fit_to_width("[EMAIL_REDACTED]", 6), "[EMAI]");
+/// assert_eq!(fit_to_width("[名前]", 3), "[ ]");
+/// ```
+pub fn fit_to_width(replacement: &str, width: usize) -> String {
+    let length = replacement.width();
+    if length <= width {
+        return format!("{}{}", replacement, " ".repeat(width - length));
+    }
+    let (body, closing) = match replacement.strip_suffix(']') {
+        Some(inner) if width >= 2 => (inner, "]"),
+        _ => (replacement, ""),
+    };
+    let room = width - closing.len();
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in body.chars() {
+        let columns = c.width().unwrap_or(0);
+        if used + columns > room {
+            break;
+        }
+        used += columns;
+        fitted.push(c);
+    }
+    fitted.push_str(&" ".repeat(room - used));
+    fitted.push_str(closing);
+    fitted
 }
 
 /// Redacts sensitive information from a string for logging or display purposes.
@@ -78,7 +185,7 @@ pub fn redact_sensitive(s: &str) -> String {
 
 /// Private helper to get the appropriate string for logging based on PII permission.
 fn get_loggable_content(sensitive_content: &str) -> String {
-    if *PII_DEBUG_ALLOWED {
+    if pii_debug_allowed() {
         sensitive_content.to_string()
     } else {
         redact_sensitive(sensitive_content)
@@ -86,8 +193,7 @@ fn get_loggable_content(sensitive_content: &str) -> String {
 }
 
 /// Logs a debug message for a `RedactionMatch`, conditionally redacting
-/// the original sensitive content based on the `CLEANSH_ALLOW_DEBUG_PII`
-/// environment variable.
+/// the original sensitive content unless [`set_pii_debug_allowed`] has enabled it.
 pub fn log_redaction_match_debug(
     module_path: &str,
     rule_name: &str,
@@ -103,8 +209,7 @@ pub fn log_redaction_match_debug(
 }
 
 /// Logs a debug message for a 'captured match', conditionally redacting
-/// the original sensitive content based on the `CLEANSH_ALLOW_DEBUG_PII`
-/// environment variable.
+/// the original sensitive content unless [`set_pii_debug_allowed`] has enabled it.
 pub fn log_captured_match_debug(
     module_path: &str,
     rule_name: &str,
@@ -118,7 +223,7 @@ pub fn log_captured_match_debug(
 }
 
 /// Logs a debug message for a redaction action (i.e., when a replacement occurs),
-/// conditionally redacting the original sensitive content based on `CLEANSH_ALLOW_DEBUG_PII`.
+/// conditionally redacting the original sensitive content unless [`set_pii_debug_allowed`] has enabled it.
 pub fn log_redaction_action_debug(
     module_path: &str,
     original_sensitive_content: &str,
@@ -180,6 +285,15 @@ mod tests {
         assert_eq!(redact_sensitive("long_sensitive_data"), "[REDACTED: 19 chars]".to_string());
     }
 
+    #[test]
+    fn test_loggable_content_redacted_unless_explicitly_allowed() {
+        assert_eq!(get_loggable_content("secret_value_123"), "[REDACTED: 16 chars]");
+        set_pii_debug_allowed(true);
+        assert_eq!(get_loggable_content("secret_value_123"), "secret_value_123");
+        set_pii_debug_allowed(false);
+        assert!(!pii_debug_allowed());
+    }
+
     #[test]
     fn test_canonical_sample_hash_consistency() {
         let h1 = canonical_sample_hash("email", "Test@Example.COM ");
@@ -0,0 +1,199 @@
+//! Replacement templates: how a rule's `replace_with` is turned into the text of a match.
+//!
+//! A template mixes literal text with capture group references and match placeholders:
+//!
+//! * `$1` or `${1}` inserts a numbered group, `${name}` a named group, and `$$` a literal
+//!   `$`. Only the braced form takes names, so `$1_REDACTED` is group 1 followed by
+//!   `_REDACTED`. A `$` not followed by a reference is kept as it is.
+//! * `{rule_name}` inserts the name of the rule, `{match_index}` the number of the match
+//!   among the rule's matches in the input (counting from 1), and `{hash8}` the first 8 hex
+//!   digits of the SHA-256 of the matched text, so `[EMAIL#{hash8}]` gives the same
+//!   placeholder for every occurrence of one address. `{{` is a literal `{`.
+//!
+//! Templates are parsed once, when the rule is compiled, and references to groups the
+//! pattern does not have are reported then rather than expanding to nothing.
+//! License: BUSL-1.1
+
+use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
+
+/// The `{...}` placeholders a template may use.
+pub const PLACEHOLDERS: [&str; 3] = ["rule_name", "match_index", "hash8"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Group(usize),
+    NamedGroup(String),
+    RuleName,
+    MatchIndex,
+    Hash8,
+}
+
+/// What a template can refer to besides the capture groups of a match.
+#[derive(Debug, Clone, Copy)]
+pub struct MatchInfo<'a> {
+    /// The name of the rule that matched.
+    pub rule_name: &'a str,
+    /// The number of the match among the rule's matches in the input, from 1.
+    pub match_index: usize,
+    /// The matched text.
+    pub matched: &'a str,
+}
+
+/// A parsed `replace_with`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacementTemplate {
+    parts: Vec<Part>,
+}
+
+/// Returns `true` if `name` could be a placeholder name: an identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl ReplacementTemplate {
+    /// Parses `template`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the placeholder if the template uses a `{name}` that is
+    /// not one of [`PLACEHOLDERS`].
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(at) = rest.find(['$', '{']) {
+            literal.push_str(&rest[..at]);
+            let after = &rest[at + 1..];
+            if rest[at..].starts_with('{') {
+                if let Some(tail) = after.strip_prefix('{') {
+                    literal.push('{');
+                    rest = tail;
+                    continue;
+                }
+                let Some((name, tail)) = after.split_once('}').filter(|(name, _)| is_identifier(name)) else {
+                    literal.push('{');
+                    rest = after;
+                    continue;
+                };
+                let part = match name {
+                    "rule_name" => Part::RuleName,
+                    "match_index" => Part::MatchIndex,
+                    "hash8" => Part::Hash8,
+                    _ => {
+                        return Err(format!(
+                            "replacement uses unknown placeholder '{{{}}}'; use {{rule_name}}, {{match_index}} or {{hash8}}, or write '{{{{' for a literal '{{'.",
+                            name
+                        ))
+                    }
+                };
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+                parts.push(part);
+                rest = tail;
+                continue;
+            }
+
+            if let Some(tail) = after.strip_prefix('$') {
+                literal.push('$');
+                rest = tail;
+                continue;
+            }
+            let (reference, tail) = match after.strip_prefix('{').and_then(|inner| inner.find('}').map(|close| (inner, close))) {
+                Some((inner, close)) => (&inner[..close], &inner[close + 1..]),
+                None => {
+                    let digits = after.bytes().take_while(u8::is_ascii_digit).count();
+                    (&after[..digits], &after[digits..])
+                }
+            };
+            if reference.is_empty() {
+                literal.push('$');
+                rest = after;
+                continue;
+            }
+            parts.push(Part::Literal(std::mem::take(&mut literal)));
+            parts.push(match reference.parse::<usize>() {
+                Ok(index) => Part::Group(index),
+                Err(_) => Part::NamedGroup(reference.to_string()),
+            });
+            rest = tail;
+        }
+        literal.push_str(rest);
+        parts.push(Part::Literal(literal));
+        parts.retain(|part| !matches!(part, Part::Literal(text) if text.is_empty()));
+        Ok(Self { parts })
+    }
+
+    /// Checks that every capture group the template refers to exists in `regex`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first reference to a group the pattern does not have.
+    pub fn check_groups(&self, regex: &Regex) -> Result<(), String> {
+        for part in &self.parts {
+            match part {
+                Part::Group(index) if *index >= regex.captures_len() => {
+                    return Err(format!(
+                        "replacement references non-existent capture group '${}'. Pattern has only {} capturing groups.",
+                        index,
+                        regex.captures_len() - 1
+                    ));
+                }
+                Part::NamedGroup(name) if !regex.capture_names().flatten().any(|group| group == name) => {
+                    return Err(format!("replacement references capture group '${{{}}}', which the pattern does not name.", name));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the template is plain text, the same for every match.
+    pub fn is_literal(&self) -> bool {
+        self.parts.iter().all(|part| matches!(part, Part::Literal(_)))
+    }
+
+    /// Expands the template for one match. Groups that did not take part in the match
+    /// expand to nothing.
+    pub fn expand(&self, caps: &Captures, info: &MatchInfo) -> String {
+        let mut expanded = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => expanded.push_str(text),
+                Part::Group(index) => expanded.push_str(caps.get(*index).map_or("", |group| group.as_str())),
+                Part::NamedGroup(name) => expanded.push_str(caps.name(name).map_or("", |group| group.as_str())),
+                Part::RuleName => expanded.push_str(info.rule_name),
+                Part::MatchIndex => expanded.push_str(&info.match_index.to_string()),
+                Part::Hash8 => expanded.push_str(&hex::encode(&Sha256::digest(info.matched.as_bytes())[..4])),
+            }
+        }
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_references_placeholders_and_escapes() {
+        let regex = Regex::new(r"(?P<user>\w+)@(\w+)").unwrap();
+        let template = ReplacementTemplate::parse("${user}:$2$$ {{x} {rule_name}#{match_index}/{hash8} $ {").unwrap();
+        assert!(template.check_groups(&regex).is_ok());
+        let caps = regex.captures("ops@example").unwrap();
+        let info = MatchInfo { rule_name: "email", match_index: 3, matched: "ops@example" };
+        let digest = hex::encode(Sha256::digest(b"ops@example"));
+        assert_eq!(template.expand(&caps, &info), format!("ops:example$ {{x}} email#3/{} $ {{", &digest[..8]));
+        assert!(ReplacementTemplate::parse("[EMAIL]").unwrap().is_literal());
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders_and_missing_groups() {
+        assert!(ReplacementTemplate::parse("[{hash}]").unwrap_err().contains("'{hash}'"));
+        let regex = Regex::new(r"(a)(?:b)").unwrap();
+        assert!(ReplacementTemplate::parse("$2").unwrap().check_groups(&regex).is_err());
+        assert!(ReplacementTemplate::parse("${name}").unwrap().check_groups(&regex).is_err());
+        assert!(ReplacementTemplate::parse("$1$0").unwrap().check_groups(&regex).is_ok());
+    }
+}
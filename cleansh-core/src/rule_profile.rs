@@ -0,0 +1,60 @@
+//! Per-rule evaluation counts and timings.
+//!
+//! When `EngineOptions::profile_rules` is set, the engine times every rule each time it
+//! runs over an input and counts the matches it keeps. The totals show which rules cost
+//! the most and which never match, so users can trim their rule set for performance.
+//! License: BUSL-1.1
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Totals for one rule over all inputs an engine has processed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RuleProfile {
+    pub rule_name: String,
+    /// Number of times the rule ran over an input (a file, a line, or a pass).
+    pub evaluations: u64,
+    /// Matches that passed context checks and validators.
+    pub matches: u64,
+    /// Time spent running the rule, including its validators.
+    pub total_time: Duration,
+}
+
+/// Collects [`RuleProfile`]s; shared by the engine across calls, hence the lock.
+#[derive(Debug, Default)]
+pub struct RuleProfiler {
+    profiles: Mutex<HashMap<String, RuleProfile>>,
+}
+
+impl RuleProfiler {
+    /// Creates a profiler listing `rule_names` with zero totals, so rules that never ran
+    /// are reported too.
+    pub fn new<'a>(rule_names: impl IntoIterator<Item = &'a str>) -> Self {
+        let profiles = rule_names
+            .into_iter()
+            .map(|name| (name.to_string(), RuleProfile { rule_name: name.to_string(), ..Default::default() }))
+            .collect();
+        Self { profiles: Mutex::new(profiles) }
+    }
+
+    /// Records one evaluation of `rule_name` that kept `matches` matches in `elapsed`.
+    pub fn record(&self, rule_name: &str, matches: usize, elapsed: Duration) {
+        let mut profiles = self.profiles.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let profile = profiles
+            .entry(rule_name.to_string())
+            .or_insert_with(|| RuleProfile { rule_name: rule_name.to_string(), ..Default::default() });
+        profile.evaluations += 1;
+        profile.matches += matches as u64;
+        profile.total_time += elapsed;
+    }
+
+    /// Returns the totals so far, slowest rule first.
+    pub fn snapshot(&self) -> Vec<RuleProfile> {
+        let profiles = self.profiles.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut snapshot: Vec<RuleProfile> = profiles.values().cloned().collect();
+        snapshot.sort_by(|a, b| b.total_time.cmp(&a.total_time).then_with(|| a.rule_name.cmp(&b.rule_name)));
+        snapshot
+    }
+}
@@ -9,15 +9,17 @@
 
 use anyhow::Result;
 use log::{debug, warn};
-use regex::{Regex, RegexBuilder};
+use regex::{Regex, RegexBuilder, RegexSet};
 use lazy_static::lazy_static;
 use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 
-use crate::config::{RedactionRule, RedactionConfig, MAX_PATTERN_LENGTH};
+use crate::config::{ContextWindow, RedactionRule, RedactionConfig, MAX_PATTERN_LENGTH};
 use crate::errors::CleanshError;
+use crate::path_scope::PathScope;
+use crate::replacement::ReplacementTemplate;
 
 /// Represents a single compiled redaction rule.
 ///
@@ -29,10 +31,18 @@ pub struct CompiledRule {
     pub regex: Regex,
     /// The string to replace matches of this rule's pattern with.
     pub replace_with: String,
+    /// `replace_with`, parsed into its text, capture group references and placeholders.
+    pub template: ReplacementTemplate,
     /// The unique name of the redaction rule.
     pub name: String,
     /// A flag indicating if this rule requires additional programmatic validation.
     pub programmatic_validation: bool,
+    /// The neighboring text the engine inspects around each match, if any.
+    pub context: Option<ContextWindow>,
+    /// The files this rule is limited to when scanning named sources, if any.
+    pub path_scope: Option<PathScope>,
+    /// The rule's `exceptions`, each anchored to match a whole value, if it has any.
+    pub exceptions: Option<RegexSet>,
 }
 
 /// Represents a collection of all compiled rules for efficient sanitization.
@@ -66,60 +76,96 @@ fn hash_config(config: &RedactionConfig) -> u64 {
     hasher.finish()
 }
 
+/// Compiles a single rule.
+///
+/// # Returns
+///
+/// `Ok(None)` for a rule without a pattern, which is skipped with a warning, and an
+/// error if the pattern is too long, the pattern or an exception does not compile, the rule's `paths` globs are invalid,
+/// or `replace_with` uses an unknown placeholder or a capture group the pattern lacks.
+pub fn compile_rule(rule: RedactionRule) -> Result<Option<CompiledRule>, CleanshError> {
+    let Some(pattern) = rule.pattern.as_ref() else {
+        warn!("Skipping rule '{}' because its pattern is missing.", &rule.name);
+        return Ok(None);
+    };
+    debug!(
+        "Attempting to compile rule: '{}' with pattern '{:?}'",
+        &rule.name, pattern
+    );
+
+    if pattern.len() > MAX_PATTERN_LENGTH {
+        return Err(CleanshError::PatternLengthExceeded(
+            rule.name,
+            pattern.len(),
+            MAX_PATTERN_LENGTH
+        ));
+    }
+
+    let regex_result = RegexBuilder::new(pattern)
+        .multi_line(rule.multiline)
+        .dot_matches_new_line(rule.dot_matches_new_line)
+        .size_limit(10 * (1 << 20)) // 10 MB limit for compiled regex
+        .build();
+
+    let path_scope = match rule.paths.as_deref().map(PathScope::new).transpose() {
+        Ok(scope) => scope,
+        Err(e) => return Err(CleanshError::InvalidPathGlob(rule.name, e.to_string())),
+    };
+
+    let exceptions = rule
+        .exceptions
+        .as_ref()
+        .filter(|exceptions| !exceptions.is_empty())
+        .map(|exceptions| RegexSet::new(exceptions.iter().map(|exception| format!("^(?:{})$", exception))))
+        .transpose();
+    let exceptions = match exceptions {
+        Ok(exceptions) => exceptions,
+        Err(e) => return Err(CleanshError::RuleCompilationError(rule.name, e)),
+    };
+
+    match regex_result {
+        Ok(regex) => {
+            let template = match ReplacementTemplate::parse(&rule.replace_with)
+                .and_then(|template| template.check_groups(&regex).map(|()| template))
+            {
+                Ok(template) => template,
+                Err(e) => return Err(CleanshError::InvalidReplacement(rule.name, e)),
+            };
+            log::debug!(
+                target: "cleansh_core::sanitizer",
+                "Rule '{}' compiled successfully.",
+                &rule.name
+            );
+            Ok(Some(CompiledRule {
+                regex,
+                replace_with: rule.replace_with,
+                template,
+                name: rule.name,
+                programmatic_validation: rule.programmatic_validation,
+                context: rule.context,
+                path_scope,
+                exceptions,
+            }))
+        }
+        Err(e) => Err(CleanshError::RuleCompilationError(rule.name, e)),
+    }
+}
+
 /// Compiles a list of `RedactionRule`s into `CompiledRules` for efficient matching.
 /// This is the low-level function that performs the actual regex compilation.
-pub fn compile_rules(rules_to_compile: Vec<RedactionRule>) -> Result<CompiledRules, CleanshError> {
+/// Rules are compiled in application order: descending `priority`, then declared order.
+pub fn compile_rules(mut rules_to_compile: Vec<RedactionRule>) -> Result<CompiledRules, CleanshError> {
     debug!("Starting compilation of {} rules.", rules_to_compile.len());
+    rules_to_compile.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
 
     let mut compiled_rules = Vec::new();
     let mut compilation_errors = Vec::new();
 
     for rule in rules_to_compile {
-        match rule.pattern.as_ref() {
-            Some(pattern) => {
-                debug!(
-                    "Attempting to compile rule: '{}' with pattern '{:?}'",
-                    &rule.name, pattern
-                );
-                
-                if pattern.len() > MAX_PATTERN_LENGTH {
-                    compilation_errors.push(CleanshError::PatternLengthExceeded(
-                        rule.name, 
-                        pattern.len(), 
-                        MAX_PATTERN_LENGTH
-                    ));
-                    continue;
-                }
-
-                let regex_result = RegexBuilder::new(pattern)
-                    .multi_line(rule.multiline)
-                    .dot_matches_new_line(rule.dot_matches_new_line)
-                    .size_limit(10 * (1 << 20)) // 10 MB limit for compiled regex
-                    .build();
-
-                match regex_result {
-                    Ok(regex) => {
-                        log::debug!(
-                            target: "cleansh_core::sanitizer",
-                            "Rule '{}' compiled successfully.",
-                            &rule.name
-                        );
-                        compiled_rules.push(CompiledRule {
-                            regex,
-                            replace_with: rule.replace_with,
-                            name: rule.name,
-                            programmatic_validation: rule.programmatic_validation,
-                        });
-                    }
-                    Err(e) => {
-                        compilation_errors.push(CleanshError::RuleCompilationError(rule.name, e));
-                    }
-                }
-            }
-            None => {
-                warn!("Skipping rule '{}' because its pattern is missing.", &rule.name);
-                continue;
-            }
+        match compile_rule(rule) {
+            Ok(Some(compiled)) => compiled_rules.push(compiled),
+            Ok(None) => {}
+            Err(e) => compilation_errors.push(e),
         }
     }
 
@@ -14,12 +14,14 @@ use log::debug;
 use strip_ansi_escapes::strip;
 use sha2::{Digest, Sha256};
 use hex;
-use chrono::Utc;
+use chrono::{SecondsFormat, Utc};
 
+use crate::aggregator::Aggregator;
 use crate::config::{RedactionConfig, RedactionSummaryItem, RedactionRule};
-use crate::redaction_match::{RedactionMatch, log_captured_match_debug, redact_sensitive, RedactionLog, ensure_match_hashes};
+use crate::redaction_match::{AppliedRedaction, RedactionMatch, log_captured_match_debug, redact_sensitive, RedactionLog, ensure_match_hashes};
 use crate::profiles::EngineOptions;
 use crate::engine::SanitizationEngine;
+use crate::replacement::MatchInfo;
 use crate::sanitizers::compiler::{get_or_compile_rules, CompiledRules, CompiledRule};
 use crate::validators;
 
@@ -45,7 +47,8 @@ impl StrippedIndexMapper {
         for stripped_char in stripped_str.chars() {
             while let Some((orig_index, orig_char)) = current_orig_char {
                 if orig_char == stripped_char {
-                    map.push(orig_index);
+                    // One entry per byte, so regex byte offsets can be looked up directly.
+                    map.extend((0..stripped_char.len_utf8()).map(|offset| orig_index + offset));
                     current_orig_char = orig_char_indices.next();
                     break;
                 }
@@ -65,6 +68,22 @@ impl StrippedIndexMapper {
         let idx = stripped_index.min(self.map.len().saturating_sub(1));
         self.map[idx]
     }
+
+    /// Maps the exclusive end of a match from the stripped string to the original string.
+    ///
+    /// The end is taken just past the last matched character, so characters that were
+    /// stripped right after the match (such as the `\r` of a CRLF) stay outside it.
+    fn map_end(&self, original: &str, stripped_end: usize) -> usize {
+        if stripped_end == 0 {
+            return self.map_index(0);
+        }
+        // The last matched byte may sit inside a multi-byte character; step back to its start.
+        let mut last = self.map_index(stripped_end - 1);
+        while !original.is_char_boundary(last) {
+            last -= 1;
+        }
+        last + original[last..].chars().next().map_or(0, char::len_utf8)
+    }
 }
 // --- end mapper ---
 
@@ -159,7 +178,7 @@ impl RegexEngine {
             end,
             sample_hash,
             match_context_hash,
-            timestamp: Some(Utc::now().to_rfc3339()),
+            timestamp: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
             rule: rule_config.clone(),
             source_id: source_id.to_string(),
             line_number,
@@ -170,6 +189,8 @@ impl RegexEngine {
     fn find_matches(&self, content: &str, source_id: &str) -> Result<HashMap<String, Vec<RedactionMatch>>> {
         let stripped_bytes = strip(content.as_bytes());
         let stripped_input = String::from_utf8_lossy(&stripped_bytes);
+        // Rules run on the stripped text; match offsets are mapped back to `content`.
+        let mapper = StrippedIndexMapper::new(content);
         
         let original_rules_map: HashMap<&str, &RedactionRule> = self.config.rules.iter()
             .map(|rule| (rule.name.as_str(), rule))
@@ -187,15 +208,15 @@ impl RegexEngine {
                     let original_match = caps.get(0).ok_or_else(|| anyhow!("Regex captured a non-existent match group"))?;
                     
                     if self.run_programmatic_validator(compiled_rule, original_match.as_str()) {
-                        let mut replacement = compiled_rule.replace_with.clone();
-                        for i in 1..caps.len() {
-                            if let Some(group) = caps.get(i) {
-                                replacement = replacement.replace(&format!("${}", i), group.as_str());
-                            }
-                        }
+                        let info = MatchInfo {
+                            rule_name: &compiled_rule.name,
+                            match_index: all_matches.get(&compiled_rule.name).map_or(0, Vec::len) + 1,
+                            matched: original_match.as_str(),
+                        };
+                        let replacement = compiled_rule.template.expand(&caps, &info);
                         log_captured_match_debug("cleansh_core::engine", &compiled_rule.name, original_match.as_str());
 
-                        let redaction_match = self.create_redaction_match(
+                        let mut redaction_match = self.create_redaction_match(
                             rule_config,
                             original_match.as_str(),
                             original_match.start() as u64,
@@ -205,6 +226,8 @@ impl RegexEngine {
                             source_id,
                             None,
                         );
+                        redaction_match.start = mapper.map_index(original_match.start()) as u64;
+                        redaction_match.end = mapper.map_end(content, original_match.end()) as u64;
 
                         all_matches.entry(compiled_rule.name.clone()).or_default().push(redaction_match);
                     } else {
@@ -220,52 +243,30 @@ impl RegexEngine {
         Ok(all_matches)
     }
 
-    fn build_summary_from_matches(&self, all_matches: &HashMap<String, Vec<RedactionMatch>>) -> Vec<RedactionSummaryItem> {
-        let mut summary_items = Vec::new();
-        for (rule_name, matches) in all_matches.iter() {
-            let original_texts: Vec<String> = matches.iter().map(|m| m.original_string.clone()).collect();
-            let sanitized_texts: Vec<String> = matches.iter().map(|m| m.sanitized_string.clone()).collect();
-
-            summary_items.push(RedactionSummaryItem {
-                rule_name: rule_name.clone(),
-                occurrences: matches.len(),
-                original_texts,
-                sanitized_texts,
-            });
-        }
-        summary_items
-    }
-}
-
-impl SanitizationEngine for RegexEngine {
-    fn sanitize(
+    /// Splices a replacement for each of `all_matches` into `content` from left to right.
+    ///
+    /// `replace` is called once per applied match to produce its replacement text. Returns
+    /// the sanitized text together with each applied edit and the match it came from.
+    /// Matches fully covered by an earlier replacement are skipped without calling `replace`.
+    fn apply_matches<'a>(
         &self,
         content: &str,
-        source_id: &str,
-        run_id: &str,
-        input_hash: &str,
-        user_id: &str,
-        reason: &str,
-        outcome: &str,
-        mut audit_log: Option<&mut crate::audit_log::AuditLog>,
-    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
-        let all_matches = self.find_matches(content, source_id)?;
-
+        all_matches: &'a HashMap<String, Vec<RedactionMatch>>,
+        replace: &mut dyn FnMut(&RedactionMatch) -> String,
+    ) -> (String, Vec<(&'a RedactionMatch, AppliedRedaction)>) {
         let mut sorted_matches: Vec<&RedactionMatch> = all_matches.values()
             .flatten()
             .collect();
         sorted_matches.sort_by_key(|m| m.start);
 
-        let mapper = StrippedIndexMapper::new(content);
-
         let mut sanitized_content = String::with_capacity(content.len());
+        let mut applied = Vec::new();
         let mut last_end = 0usize;
 
-        for m in sorted_matches.iter() {
-            let original_start_byte = mapper.map_index(m.start as usize);
-            let original_end_byte = mapper.map_index(m.end as usize);
+        for m in sorted_matches {
+            let (original_start_byte, original_end_byte) = (m.start as usize, m.end as usize);
 
-            // Handle overlaps
+            // If this match is fully covered by previous replacements, skip it.
             if original_end_byte <= last_end {
                 debug!("Skipping fully-overlapped match for '{}': mapped range {}..{} already covered (last_end={})",
                     m.rule_name, original_start_byte, original_end_byte, last_end);
@@ -276,14 +277,53 @@ impl SanitizationEngine for RegexEngine {
             // handling partial overlaps by starting from the last match's end.
             let current_start = original_start_byte.max(last_end);
             sanitized_content.push_str(&content[last_end..current_start]);
-            
-            // Append the sanitized string
-            sanitized_content.push_str(&m.sanitized_string);
-            
+
+            // Append the replacement
+            let replacement = replace(m);
+            sanitized_content.push_str(&replacement);
+
+            applied.push((m, AppliedRedaction {
+                rule_name: m.rule_name.clone(),
+                start: current_start as u64,
+                end: original_end_byte as u64,
+                length: (original_end_byte - current_start) as u64,
+                replacement,
+            }));
+
             // Update the last_end pointer
             last_end = original_end_byte;
+        }
+
+        sanitized_content.push_str(&content[last_end..]);
+        (sanitized_content, applied)
+    }
+
+    fn build_summary_from_matches(&self, all_matches: &HashMap<String, Vec<RedactionMatch>>) -> Vec<RedactionSummaryItem> {
+        let mut aggregator = Aggregator::new();
+        for matches in all_matches.values() {
+            aggregator.add_matches(matches);
+        }
+        aggregator.into_summary()
+    }
+}
+
+impl SanitizationEngine for RegexEngine {
+    fn sanitize(
+        &self,
+        content: &str,
+        source_id: &str,
+        run_id: &str,
+        input_hash: &str,
+        user_id: &str,
+        reason: &str,
+        outcome: &str,
+        mut audit_log: Option<&mut crate::audit_log::AuditLog>,
+    ) -> Result<(String, Vec<RedactionSummaryItem>)> {
+        let all_matches = self.find_matches(content, source_id)?;
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, &mut |m| m.sanitized_string.clone());
 
-            if let Some(log) = audit_log.as_mut() {
+        if let Some(log) = audit_log.as_mut() {
+            for (m, edit) in &applied {
                 let rlog = RedactionLog {
                     timestamp: m.timestamp.clone().unwrap_or_default(),
                     run_id: run_id.to_string(),
@@ -293,20 +333,37 @@ impl SanitizationEngine for RegexEngine {
                     redaction_outcome: outcome.to_string(),
                     rule_name: m.rule_name.clone(),
                     input_hash: input_hash.to_string(),
-                    match_hash: m.sample_hash.clone().unwrap_or_default(),
+                    match_hash: RedactionLog::value_hash(&m.original_string),
                     start: m.start,
                     end: m.end,
+                    replacement: edit.replacement.clone(),
                 };
                 log.append(&rlog)?;
             }
         }
 
-        sanitized_content.push_str(&content[last_end..]);
-
         let summary = self.build_summary_from_matches(&all_matches);
         Ok((sanitized_content, summary))
     }
 
+    fn sanitize_with_edits(&self, content: &str, source_id: &str) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+        let all_matches = self.find_matches(content, source_id)?;
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, &mut |m| m.sanitized_string.clone());
+        let summary = self.build_summary_from_matches(&all_matches);
+        Ok((sanitized_content, summary, applied.into_iter().map(|(_, edit)| edit).collect()))
+    }
+
+    fn sanitize_with(
+        &self,
+        content: &str,
+        source_id: &str,
+        replace: &mut dyn FnMut(&RedactionMatch) -> String,
+    ) -> Result<(String, Vec<AppliedRedaction>)> {
+        let all_matches = self.find_matches(content, source_id)?;
+        let (sanitized_content, applied) = self.apply_matches(content, &all_matches, replace);
+        Ok((sanitized_content, applied.into_iter().map(|(_, edit)| edit).collect()))
+    }
+
     fn analyze_for_stats(&self, content: &str, source_id: &str) -> Result<Vec<RedactionSummaryItem>> {
         let all_matches = self.find_matches(content, source_id)?;
         let summary = self.build_summary_from_matches(&all_matches);
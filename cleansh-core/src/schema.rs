@@ -0,0 +1,47 @@
+//! Versioning of the JSON documents cleansh writes for other programs.
+//!
+//! Every JSON document the CLI writes for other programs, from the `scan` summary and the
+//! audit log to the `--json` output of its reporting commands, carries a
+//! `"schema_version"` field holding [`SCHEMA_VERSION`], and so does the HTML report; the
+//! CLI ships a JSON Schema for each document. The minor number goes up when fields are
+//! added, which readers can ignore; the major number goes up when fields are removed,
+//! renamed or change meaning. A reader written for one major version can therefore read
+//! any document with the same major version.
+//!
+//! License: BUSL-1.1
+
+use crate::errors::CleanshError;
+
+/// The schema version written into every machine-readable document.
+pub const SCHEMA_VERSION: &str = "1.0";
+
+/// Name of the field that holds the schema version.
+pub const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+/// Checks that a document with schema version `found` can be read by this version of
+/// cleansh. Documents written before schema versions existed carry none and are read as
+/// version 1.0.
+pub fn check_schema_version(found: Option<&str>) -> Result<(), CleanshError> {
+    let Some(found) = found else {
+        return Ok(());
+    };
+    let major = |version: &str| version.split('.').next().and_then(|major| major.parse::<u64>().ok());
+    match major(found) {
+        Some(major_found) if major(SCHEMA_VERSION) == Some(major_found) => Ok(()),
+        _ => Err(CleanshError::UnsupportedSchemaVersion(found.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_the_same_major_version_only() {
+        assert!(check_schema_version(None).is_ok());
+        assert!(check_schema_version(Some(SCHEMA_VERSION)).is_ok());
+        assert!(check_schema_version(Some("1.7")).is_ok());
+        assert!(check_schema_version(Some("2.0")).is_err());
+        assert!(check_schema_version(Some("latest")).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Reassembly of secrets that a terminal hard-wrapped across lines.
+//!
+//! Text copied from a terminal often has long tokens broken at the terminal width, so a
+//! rule that needs the whole token never matches. [`SoftWrap::join`] removes the line
+//! breaks that look like such wraps, the engine runs on the joined text, and
+//! [`SoftWrap::restore`] puts the breaks back around the applied redactions so the output
+//! keeps the original number of lines.
+//! License: BUSL-1.1
+
+use crate::redaction_match::AppliedRedaction;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Input with wrap-induced line breaks removed, plus what is needed to put them back.
+#[derive(Debug, Clone)]
+pub struct SoftWrap {
+    joined: String,
+    /// Removed breaks as (byte offset in `joined`, original break text).
+    breaks: Vec<(usize, &'static str)>,
+}
+
+impl SoftWrap {
+    /// Joins every line of `input` that looks hard-wrapped at `max_width` terminal columns.
+    ///
+    /// A line counts as wrapped when it fills the width, is followed by another line, and
+    /// neither side of the break is whitespace. Widths are display widths, so a CJK
+    /// character or an emoji takes two columns; a line one column short also fills the
+    /// width when the next line starts with such a character, since a terminal moves a
+    /// double-width character that does not fit to the next line. Shorter lines are never
+    /// joined, so ordinary text passes through unchanged.
+    pub fn join(input: &str, max_width: usize) -> Self {
+        let mut joined = String::with_capacity(input.len());
+        let mut breaks = Vec::new();
+        let mut rest = input;
+
+        while let Some(newline) = rest.find('\n') {
+            let (line, break_text) = match rest[..newline].strip_suffix('\r') {
+                Some(line) => (line, "\r\n"),
+                None => (&rest[..newline], "\n"),
+            };
+            let next = &rest[newline + 1..];
+            joined.push_str(line);
+
+            let width = line.width();
+            let first = next.chars().next();
+            let fills = width == max_width || (width + 1 == max_width && first.and_then(|c| c.width()) == Some(2));
+            let wrapped = max_width > 0
+                && fills
+                && line.chars().last().is_some_and(|c| !c.is_whitespace())
+                && first.is_some_and(|c| !c.is_whitespace());
+            if wrapped {
+                breaks.push((joined.len(), break_text));
+            } else {
+                joined.push_str(break_text);
+            }
+            rest = next;
+        }
+        joined.push_str(rest);
+
+        Self { joined, breaks }
+    }
+
+    /// The joined text that should be passed to the engine.
+    pub fn text(&self) -> &str {
+        &self.joined
+    }
+
+    /// Returns `true` if at least one line break was removed.
+    pub fn has_joins(&self) -> bool {
+        !self.breaks.is_empty()
+    }
+
+    /// Rebuilds the output from `edits` applied to [`SoftWrap::text`], restoring the
+    /// removed line breaks.
+    ///
+    /// Breaks outside a redacted span, or at its edges, go back where they were. Breaks
+    /// inside a span are emitted right after its replacement, so the output has the same
+    /// number of lines as the input. The returned edits have their offsets mapped to the
+    /// original input.
+    pub fn restore(&self, edits: &[AppliedRedaction]) -> (String, Vec<AppliedRedaction>) {
+        let mut out = String::with_capacity(self.joined.len());
+        let mut mapped = Vec::with_capacity(edits.len());
+        let mut breaks = self.breaks.iter().peekable();
+        let mut last_end = 0usize;
+
+        for edit in edits {
+            let (start, end) = (edit.start as usize, edit.end as usize);
+            self.copy_with_breaks(&mut out, last_end, start, &mut breaks);
+
+            let mut inner = String::new();
+            while let Some(&&(pos, text)) = breaks.peek() {
+                if pos >= end {
+                    break;
+                }
+                inner.push_str(text);
+                breaks.next();
+            }
+
+            out.push_str(&edit.replacement);
+            out.push_str(&inner);
+
+            let mut edit = edit.clone();
+            edit.start = self.original_offset(start, false) as u64;
+            edit.end = self.original_offset(end, true) as u64;
+            edit.length = edit.end - edit.start;
+            mapped.push(edit);
+            last_end = end;
+        }
+        self.copy_with_breaks(&mut out, last_end, self.joined.len(), &mut breaks);
+
+        (out, mapped)
+    }
+
+    /// Copies `joined[from..to]` to `out`, re-inserting the breaks that fall in that range,
+    /// including one exactly at `to`.
+    fn copy_with_breaks<'a>(
+        &self,
+        out: &mut String,
+        from: usize,
+        to: usize,
+        breaks: &mut std::iter::Peekable<impl Iterator<Item = &'a (usize, &'static str)>>,
+    ) {
+        let mut pos = from;
+        while let Some(&&(at, text)) = breaks.peek() {
+            if at > to {
+                break;
+            }
+            out.push_str(&self.joined[pos..at]);
+            out.push_str(text);
+            pos = at;
+            breaks.next();
+        }
+        out.push_str(&self.joined[pos..to]);
+    }
+
+    /// Maps a byte offset in the joined text to the original input.
+    ///
+    /// For a span end (`is_end`), a break exactly at the offset is not counted, so the
+    /// span does not swallow the following line break.
+    fn original_offset(&self, offset: usize, is_end: bool) -> usize {
+        let shift: usize = self
+            .breaks
+            .iter()
+            .take_while(|(at, _)| if is_end { *at < offset } else { *at <= offset })
+            .map(|(_, text)| text.len())
+            .sum();
+        offset + shift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: usize, end: usize, replacement: &str) -> AppliedRedaction {
+        AppliedRedaction {
+            rule_name: "test".to_string(),
+            start: start as u64,
+            end: end as u64,
+            length: (end - start) as u64,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn joins_only_full_width_lines() {
+        let wrap = SoftWrap::join("key=abcdef\nghij end\nshort\nline", 10);
+        assert_eq!(wrap.text(), "key=abcdefghij end\nshort\nline");
+        assert!(wrap.has_joins());
+
+        let untouched = SoftWrap::join("key=abcde \nfghij", 10);
+        assert_eq!(untouched.text(), "key=abcde \nfghij");
+        assert!(!untouched.has_joins());
+    }
+
+    #[test]
+    fn measures_lines_in_terminal_columns() {
+        // Five double-width characters fill ten columns.
+        assert_eq!(SoftWrap::join("鍵鍵鍵鍵鍵\nabc", 10).text(), "鍵鍵鍵鍵鍵abc");
+        // A double-width character that does not fit in the last column moves down.
+        assert_eq!(SoftWrap::join("鍵=abcdef\n鍵x", 10).text(), "鍵=abcdef鍵x");
+        assert_eq!(SoftWrap::join("鍵=abcdef\nxy", 10).text(), "鍵=abcdef\nxy");
+        // Ten characters of which some are double-width are wider than ten columns.
+        assert!(!SoftWrap::join("鍵鍵abcdefgh\nij", 10).has_joins());
+    }
+
+    #[test]
+    fn restore_keeps_line_structure() {
+        let input = "key=abcdef\r\nghij end\nok";
+        let wrap = SoftWrap::join(input, 10);
+        let (out, mapped) = wrap.restore(&[edit(4, 14, "[SECRET]")]);
+        assert_eq!(out, "key=[SECRET]\r\n end\nok");
+        assert_eq!(&input[mapped[0].start as usize..mapped[0].end as usize], "abcdef\r\nghij");
+
+        let (unchanged, _) = wrap.restore(&[]);
+        assert_eq!(unchanged, input);
+    }
+}
@@ -0,0 +1,110 @@
+//! Chunked sanitization of inputs too large to hold in memory.
+//!
+//! `sanitize_stream` reads whole lines into a chunk of about `chunk_bytes`, sanitizes it,
+//! and writes the result before reading on. The last `overlap_bytes` of each chunk are
+//! held back and searched again at the start of the next one, so a multi-line match that
+//! straddles two chunks is still found. A match that starts before the held-back part and
+//! runs into it is applied with its chunk, which then ends at the line the match ends on.
+//! Matches longer than the overlap can still be split between chunks.
+//!
+//! Non-UTF-8 bytes are replaced with U+FFFD, as when the whole input is decoded at once.
+//! License: BUSL-1.1
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+
+use crate::aggregator::Aggregator;
+use crate::config::RedactionSummaryItem;
+use crate::engine::SanitizationEngine;
+
+/// Chunk sizes for [`SanitizationEngine::sanitize_reader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamOptions {
+    /// Bytes of input sanitized at a time, rounded up to whole lines.
+    pub chunk_bytes: usize,
+    /// Bytes at the end of each chunk that are searched again with the next chunk.
+    pub overlap_bytes: usize,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self { chunk_bytes: 1024 * 1024, overlap_bytes: 64 * 1024 }
+    }
+}
+
+/// Returns the offset just past the last line break at or before `offset`, or 0.
+fn line_start_before(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Returns the offset just past the first line break at or after `offset`, or the end.
+fn line_start_after(text: &str, offset: usize) -> usize {
+    text[offset..].find('\n').map_or(text.len(), |i| offset + i + 1)
+}
+
+/// Decides how much of `buffer` can be sanitized now: everything up to the line that
+/// starts the held-back overlap, extended past any match that runs across it.
+fn commit_point<E: SanitizationEngine + ?Sized>(engine: &E, buffer: &str, source_id: &str, overlap: usize) -> Result<usize> {
+    let Some(limit) = buffer.len().checked_sub(overlap) else {
+        return Ok(0);
+    };
+    let mut commit = line_start_before(buffer, limit);
+    if commit == 0 {
+        return Ok(0);
+    }
+
+    // Only a match that starts within the overlap before the commit point can cross it.
+    let window = line_start_before(buffer, commit.saturating_sub(overlap));
+    for m in engine.find_matches_for_ui(&buffer[window..], source_id)? {
+        let (start, end) = (window + m.start as usize, window + m.end as usize);
+        if start < commit && end > commit {
+            commit = line_start_after(buffer, end);
+        }
+    }
+    Ok(commit)
+}
+
+/// Sanitizes everything `reader` yields into `writer` a chunk at a time. Used by the
+/// default [`SanitizationEngine::sanitize_reader`].
+pub fn sanitize_stream<E: SanitizationEngine + ?Sized>(
+    engine: &E,
+    reader: &mut dyn BufRead,
+    writer: &mut dyn Write,
+    source_id: &str,
+    options: StreamOptions,
+) -> Result<Vec<RedactionSummaryItem>> {
+    let mut summary = Aggregator::new();
+    let mut buffer = String::new();
+    let mut line = Vec::new();
+    let mut eof = false;
+    while !eof {
+        // Read at least one line, so that a single line longer than a chunk still grows
+        // the buffer until a commit point is found.
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line).context("Failed to read input")? == 0 {
+                eof = true;
+                break;
+            }
+            buffer.push_str(&String::from_utf8_lossy(&line));
+            if buffer.len() >= options.chunk_bytes {
+                break;
+            }
+        }
+
+        let commit = if eof {
+            buffer.len()
+        } else {
+            commit_point(engine, &buffer, source_id, options.overlap_bytes)?
+        };
+        if commit == 0 {
+            continue;
+        }
+        let (sanitized, items) = engine.sanitize(&buffer[..commit], source_id, "", "", "", "", "", None)?;
+        writer.write_all(sanitized.as_bytes()).context("Failed to write sanitized output")?;
+        summary.add_summary(items);
+        buffer.drain(..commit);
+    }
+    writer.flush().context("Failed to write sanitized output")?;
+    Ok(summary.into_summary())
+}
@@ -0,0 +1,115 @@
+//! Attribution of findings to tenants.
+//!
+//! Multi-tenant services usually log a tenant or customer id on every line. When a config
+//! sets `tenant`, the capture group of that rule identifies the tenant of each line, and
+//! every finding on the line is counted for that tenant. Leak investigations can then see
+//! which customers are affected instead of only how many secrets were found.
+//!
+//! Tenant names identify customers, so reports that are written to disk usually carry
+//! [`tenant_hash`] labels instead of the names (see [`TenantLines::hashed`]).
+//! License: BUSL-1.1
+
+use crate::config::TenantKey;
+use crate::redaction_match::RedactionMatch;
+use crate::sanitizers::compiler::CompiledRules;
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Bucket for findings on lines where no tenant was found.
+pub const UNATTRIBUTED: &str = "(none)";
+
+/// Hex digits of the SHA-256 kept in a [`tenant_hash`] label.
+const TENANT_HASH_LEN: usize = 16;
+
+/// Returns the label that stands for `tenant` in reports: `sha256:` followed by the first
+/// 16 hex digits of the SHA-256 of the name. The hash is unsalted, so the label of a
+/// known tenant can be computed to look it up, and short or guessable names can be
+/// recovered by trying candidates.
+///
+/// ```
+/// let label = cleansh_core::tenant::tenant_hash("acme");
+/// assert_eq!(label, "sha256:822b33ad87c148a0");
+/// ```
+pub fn tenant_hash(tenant: &str) -> String {
+    let digest = hex::encode(Sha256::digest(tenant.as_bytes()));
+    format!("sha256:{}", &digest[..TENANT_HASH_LEN])
+}
+
+/// Finding counts per tenant, then per rule.
+pub type TenantCounts = BTreeMap<String, BTreeMap<String, usize>>;
+
+/// The tenant of every line of one input.
+#[derive(Debug, Clone)]
+pub struct TenantLines {
+    /// Byte offset at which each line starts.
+    line_starts: Vec<usize>,
+    tenants: Vec<Option<String>>,
+}
+
+impl TenantLines {
+    /// Extracts the tenant of each line of `content` using the rule named by `key`.
+    ///
+    /// The first match of the rule on a line that sets the capture group decides the tenant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rule is not among the active rules or its pattern has no
+    /// such capture group.
+    pub fn new(key: &TenantKey, rules: &CompiledRules, content: &str) -> Result<Self> {
+        let rule = rules
+            .rules
+            .iter()
+            .find(|rule| rule.name == key.rule)
+            .ok_or_else(|| anyhow!("Tenant rule '{}' is not an active rule.", key.rule))?;
+        let group_index = match key.group.parse::<usize>() {
+            Ok(index) if index < rule.regex.captures_len() => Some(index),
+            Ok(_) => None,
+            Err(_) => rule.regex.capture_names().position(|name| name == Some(key.group.as_str())),
+        }
+        .ok_or_else(|| anyhow!("Tenant rule '{}' has no capture group '{}'.", key.rule, key.group))?;
+
+        let mut line_starts = Vec::new();
+        let mut tenants = Vec::new();
+        let mut offset = 0;
+        for line in content.split_inclusive('\n') {
+            line_starts.push(offset);
+            offset += line.len();
+            let tenant = rule
+                .regex
+                .captures_iter(line)
+                .find_map(|caps| caps.get(group_index))
+                .map(|group| group.as_str().to_string());
+            tenants.push(tenant);
+        }
+        Ok(Self { line_starts, tenants })
+    }
+
+    /// Replaces every tenant name by its [`tenant_hash`] label, so that counts built from
+    /// these lines never hold a name.
+    pub fn hashed(mut self) -> Self {
+        for tenant in self.tenants.iter_mut().flatten() {
+            *tenant = tenant_hash(tenant);
+        }
+        self
+    }
+
+    /// Returns the tenant of the line containing byte `offset`, if one was found.
+    pub fn tenant_at(&self, offset: u64) -> Option<&str> {
+        let line = self.line_starts.partition_point(|&start| start as u64 <= offset).checked_sub(1)?;
+        self.tenants[line].as_deref()
+    }
+
+    /// Adds `matches` (found in the same content) to `counts`, under their line's tenant
+    /// or [`UNATTRIBUTED`].
+    pub fn count_into(&self, matches: &[RedactionMatch], counts: &mut TenantCounts) {
+        for m in matches {
+            let tenant = self.tenant_at(m.start).unwrap_or(UNATTRIBUTED);
+            *counts
+                .entry(tenant.to_string())
+                .or_default()
+                .entry(m.rule_name.clone())
+                .or_default() += 1;
+        }
+    }
+}
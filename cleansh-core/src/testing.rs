@@ -0,0 +1,105 @@
+//! Helpers for testing rules, profiles and engines, enabled by the `test-utils` feature.
+//!
+//! Plugin and profile authors can add `cleansh-core` as a dev-dependency with
+//! `features = ["test-utils"]` and write tests like:
+//!
+//! ```rust,ignore
+//! use cleansh_core::testing::{assert_matches, default_rules_engine};
+//!
+//! let engine = default_rules_engine(&["email"])?;
+//! assert_matches(&engine, "mail alice@example.com", &[("email", "alice@example.com")]);
+//! ```
+//!
+//! License: BUSL-1.1
+
+use crate::config::{RedactionConfig, RedactionRule};
+use crate::engine::SanitizationEngine;
+use crate::engines::regex_engine::RegexEngine;
+use anyhow::Result;
+
+/// Source id passed to the engine by these helpers.
+pub const TEST_SOURCE: &str = "test";
+
+/// Builds a regex rule with the given name, pattern and replacement and default settings
+/// otherwise; set further fields with struct update syntax.
+pub fn rule(name: &str, pattern: &str, replace_with: &str) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(pattern.to_string()),
+        replace_with: replace_with.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Builds an engine containing only the named built-in rules, opt-in rules included.
+pub fn default_rules_engine(names: &[&str]) -> Result<RegexEngine> {
+    let mut config = RedactionConfig::load_default_rules()?;
+    config.rules.retain(|rule| names.contains(&rule.name.as_str()));
+    RegexEngine::new(config)
+}
+
+/// Builds an engine from `rules` alone.
+pub fn engine_from_rules(rules: Vec<RedactionRule>) -> Result<RegexEngine> {
+    RegexEngine::new(RedactionConfig { rules, ..Default::default() })
+}
+
+/// Builds an engine from a rules file given as YAML, as written by users.
+pub fn engine_from_yaml(yaml: &str) -> Result<RegexEngine> {
+    RegexEngine::new(RedactionConfig::from_yaml_str(yaml)?)
+}
+
+/// Sanitizes `input` and returns only the sanitized text.
+pub fn sanitize(engine: &dyn SanitizationEngine, input: &str) -> Result<String> {
+    let (sanitized, _) = engine.sanitize(input, TEST_SOURCE, "", "", "", "", "", None)?;
+    Ok(sanitized)
+}
+
+/// Returns `(rule name, matched text)` for every match in `input`, in input order.
+pub fn matched(engine: &dyn SanitizationEngine, input: &str) -> Result<Vec<(String, String)>> {
+    Ok(engine
+        .find_matches_for_ui(input, TEST_SOURCE)?
+        .into_iter()
+        .map(|m| (m.rule_name, m.original_string))
+        .collect())
+}
+
+/// Asserts that the matches in `input` are exactly `expected`, given as
+/// `(rule name, matched text)` pairs in input order.
+///
+/// # Panics
+///
+/// Panics with the input, the expected and the actual matches when they differ, or when
+/// the engine fails.
+#[track_caller]
+pub fn assert_matches(engine: &dyn SanitizationEngine, input: &str, expected: &[(&str, &str)]) {
+    let actual = matched(engine, input).unwrap_or_else(|e| panic!("engine failed on {:?}: {:#}", input, e));
+    let actual: Vec<(&str, &str)> = actual.iter().map(|(rule, text)| (rule.as_str(), text.as_str())).collect();
+    assert_eq!(actual, expected, "unexpected matches in {:?}", input);
+}
+
+/// Asserts that nothing in `input` matches.
+///
+/// # Panics
+///
+/// Panics with the matches found, or when the engine fails.
+#[track_caller]
+pub fn assert_no_matches(engine: &dyn SanitizationEngine, input: &str) {
+    assert_matches(engine, input, &[]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helpers_build_and_run_engines() -> Result<()> {
+        let engine = engine_from_rules(vec![rule("order_id", r"ORD-\d{6}", "[ORDER]")])?;
+        assert_matches(&engine, "paid ORD-123456 and ORD-654321", &[("order_id", "ORD-123456"), ("order_id", "ORD-654321")]);
+        assert_eq!(sanitize(&engine, "paid ORD-123456")?, "paid [ORDER]");
+        assert_no_matches(&engine, "paid ORD-12");
+
+        let engine = engine_from_yaml("rules:\n  - name: ticket\n    pattern: 'TKT-[0-9]+'\n    replace_with: '[TICKET]'\n")?;
+        assert_eq!(sanitize(&engine, "see TKT-42")?, "see [TICKET]");
+        Ok(())
+    }
+}
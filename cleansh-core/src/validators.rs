@@ -2,15 +2,58 @@
 //! Programmatic validation functions for specific sensitive data types.
 //!
 //! This module provides additional validation logic beyond regular expression matching
-//! for sensitive information such as SSN and UK NINO. These functions help reduce
+//! for sensitive information such as SSN, UK NINO, email addresses, GitHub tokens, IPv4
+//! addresses, hostnames, dates, and random-looking secrets. These functions help reduce
 //! false positives by applying structural and known invalid pattern checks.
 //!
 //! License: BUSL-1.1
 
+use chrono::{Datelike, NaiveDate, Utc};
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::net::Ipv4Addr;
 use once_cell::sync::Lazy;
 
+/// The built-in rule that flags long random-looking strings by their Shannon entropy.
+pub const HIGH_ENTROPY_RULE: &str = "high_entropy_string";
+
+/// Names the validator the engine runs on matches of a built-in rule with
+/// `programmatic_validation` set, or `None` if the rule has none.
+///
+/// # Examples
+///
+/// ```
+/// use cleansh_core::validators::validator_for_rule;
+///
+/// assert_eq!(validator_for_rule("visa_card"), Some("luhn"));
+/// assert_eq!(validator_for_rule("my_rule"), None);
+/// ```
+pub fn validator_for_rule(rule_name: &str) -> Option<&'static str> {
+    Some(match rule_name {
+        "us_ssn" => "ssn",
+        "uk_nino" => "uk_nino",
+        "visa_card" | "mastercard_card" | "amex_card" | "discover_card" => "luhn",
+        "email" => "email",
+        "github_pat" | "github_oauth_token" => "github_checksum",
+        "ipv4_address" => "public_ipv4",
+        "ipv4_private" => "private_ipv4",
+        "hostname" => "hostname",
+        "date_of_birth" => "birthdate",
+        "date_dmy" => "date_dmy",
+        "date_mdy" => "date_mdy",
+        HIGH_ENTROPY_RULE => "entropy",
+        "secret_near_keyword" => "secret_near_keyword",
+        "url_query_secret" => "url_query_secret",
+        _ => return None,
+    })
+}
+
+/// Returns `true` if matches of `rule_name` are judged by entropy rather than by their
+/// shape, so any regex rule that matches the same text identifies it better.
+pub fn is_entropy_rule(rule_name: &str) -> bool {
+    validator_for_rule(rule_name) == Some("entropy")
+}
+
 /// Helper function to validate SSN based on US Social Security Administration rules.
 ///
 /// This implementation aims for a robust programmatic check without external data.
@@ -181,4 +224,472 @@ pub fn is_valid_credit_card_programmatically(cc_number: &str) -> bool {
         return false;
     }
     is_valid_luhn(&digits)
-}
\ No newline at end of file
+}
+
+/// Characters that may appear in an email local part but never at its start.
+const EMAIL_LEADING_PUNCTUATION: &[char] = &['.', '_', '%', '+', '-'];
+
+/// Characters that may appear in an email domain but never at its end.
+const EMAIL_TRAILING_PUNCTUATION: &[char] = &['.', '-'];
+
+/// Trims surrounding punctuation that a permissive email pattern can pick up from prose,
+/// such as leading dots in `...user@example.com` or a trailing hyphen after the domain.
+///
+/// # Arguments
+///
+/// * `candidate` - The raw email match.
+///
+/// # Returns
+///
+/// The trimmed sub-slice of `candidate`.
+pub fn trim_email_punctuation(candidate: &str) -> &str {
+    candidate
+        .trim_start_matches(EMAIL_LEADING_PUNCTUATION)
+        .trim_end_matches(EMAIL_TRAILING_PUNCTUATION)
+}
+
+/// Helper function to validate email addresses, including internationalized (IDN) domains.
+///
+/// The local part must be 1-64 characters with no leading, trailing, or consecutive dots.
+/// The domain must have at least two labels, be at most 253 bytes, and each label must be
+/// 1-63 characters without a leading or trailing hyphen. Punycode (`xn--`) labels must be
+/// ASCII, and the top-level domain cannot be purely numeric.
+///
+/// # Arguments
+///
+/// * `email` - The email string slice to validate, already trimmed.
+///
+/// # Returns
+///
+/// `true` if the address is structurally valid, `false` otherwise.
+pub fn is_valid_email_programmatically(email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else { return false; };
+
+    let local_len = local.chars().count();
+    if local_len == 0 || local_len > 64 || local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        return false;
+    }
+
+    if domain.len() > 253 {
+        return false;
+    }
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return false;
+    }
+    let labels_valid = labels.iter().all(|label| {
+        let len = label.chars().count();
+        let is_punycode = label.get(..4).is_some_and(|prefix| prefix.eq_ignore_ascii_case("xn--"));
+        (1..=63).contains(&len)
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+            && (!is_punycode || label.is_ascii())
+    });
+    let tld_is_numeric = labels.last().is_some_and(|tld| tld.chars().all(|c| c.is_ascii_digit()));
+
+    labels_valid && !tld_is_numeric
+}
+
+/// Alphabet used by GitHub to encode the checksum embedded in its tokens.
+const BASE62_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `data`.
+///
+/// # Arguments
+///
+/// * `data` - The bytes to checksum.
+///
+/// # Returns
+///
+/// The CRC-32 value.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Computes the six-character checksum suffix GitHub appends to a token's random part.
+///
+/// The suffix is the CRC-32 of the 30 random characters, base62-encoded and left-padded with `0`.
+///
+/// # Arguments
+///
+/// * `random_part` - The 30 characters following the token prefix (e.g. after `ghp_`).
+///
+/// # Returns
+///
+/// The expected six-character checksum.
+pub fn github_token_checksum(random_part: &str) -> String {
+    let mut value = crc32(random_part.as_bytes());
+    let mut encoded = [b'0'; 6];
+    for slot in encoded.iter_mut().rev() {
+        *slot = BASE62_ALPHABET[(value % 62) as usize];
+        value /= 62;
+    }
+    String::from_utf8_lossy(&encoded).into_owned()
+}
+
+/// Helper function to validate GitHub tokens (`ghp_`, `gho_`, `ghu_`, `ghs_`, `ghr_`) by their embedded checksum.
+///
+/// GitHub tokens consist of a four-character prefix, 30 random base62 characters, and a
+/// six-character checksum of the random part. Strings that merely look like tokens (test
+/// fixtures, hashes that happen to follow the prefix) almost never carry a valid checksum.
+///
+/// # Arguments
+///
+/// * `token` - The token string slice to validate, including its prefix.
+///
+/// # Returns
+///
+/// `true` if the token is well-formed and its checksum matches, `false` otherwise.
+pub fn is_valid_github_token_programmatically(token: &str) -> bool {
+    let Some(body) = token.get(4..) else { return false; };
+    if body.len() != 36 || !body.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return false;
+    }
+    let (random_part, checksum) = body.split_at(30);
+    github_token_checksum(random_part) == checksum
+}
+
+/// Parses a dotted-quad IPv4 address, accepting octets with leading zeros as the default rule pattern does.
+fn parse_ipv4(ip: &str) -> Option<Ipv4Addr> {
+    let mut octets = [0u8; 4];
+    let mut parts = ip.split('.');
+    for octet in octets.iter_mut() {
+        *octet = parts.next()?.parse().ok()?;
+    }
+    parts.next().is_none().then(|| Ipv4Addr::from(octets))
+}
+
+/// Returns `true` if `addr` is in a private (RFC 1918), shared (RFC 6598), loopback,
+/// link-local, documentation, benchmarking, multicast, or otherwise reserved range.
+fn is_private_or_reserved_ipv4(addr: Ipv4Addr) -> bool {
+    let [a, b, c, _] = addr.octets();
+    addr.is_private()
+        || addr.is_loopback()
+        || addr.is_link_local()
+        || addr.is_unspecified()
+        || addr.is_broadcast()
+        || addr.is_documentation()
+        || addr.is_multicast()
+        || a == 0
+        || a >= 240
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b == 18 || b == 19))
+}
+
+/// Helper function to identify publicly routable IPv4 addresses.
+///
+/// # Arguments
+///
+/// * `ip` - The IPv4 address string slice to classify.
+///
+/// # Returns
+///
+/// `true` if the address parses and is not in a private or reserved range, `false` otherwise.
+pub fn is_public_ipv4_programmatically(ip: &str) -> bool {
+    parse_ipv4(ip).is_some_and(|addr| !is_private_or_reserved_ipv4(addr))
+}
+
+/// Helper function to identify private and reserved IPv4 addresses (RFC 1918, loopback, link-local, etc.).
+///
+/// # Arguments
+///
+/// * `ip` - The IPv4 address string slice to classify.
+///
+/// # Returns
+///
+/// `true` if the address parses and is in a private or reserved range, `false` otherwise.
+pub fn is_private_ipv4_programmatically(ip: &str) -> bool {
+    parse_ipv4(ip).is_some_and(is_private_or_reserved_ipv4)
+}
+
+/// Public domains the `hostname` rule never redacts, together with their subdomains.
+static DEFAULT_ALLOWED_DOMAINS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "github.com", "githubusercontent.com", "gitlab.com", "bitbucket.org",
+        "docs.rs", "crates.io", "rust-lang.org", "npmjs.com", "npmjs.org", "pypi.org",
+        "python.org", "go.dev", "golang.org", "stackoverflow.com", "wikipedia.org",
+        "mozilla.org", "w3.org", "ietf.org", "example.com", "example.org", "example.net",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Common file extensions that would otherwise make file names such as `main.rs` look like hostnames.
+static FILE_EXTENSIONS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "rs", "py", "js", "ts", "jsx", "tsx", "go", "java", "kt", "rb", "php", "pl", "sh", "ps1", "bat",
+        "c", "h", "cc", "cpp", "hpp", "cs", "swift", "md", "txt", "log", "yaml", "yml", "json", "toml",
+        "lock", "xml", "html", "css", "csv", "sql", "ini", "cfg", "conf", "env", "bak", "tmp", "zip",
+        "gz", "tar", "png", "jpg", "jpeg", "gif", "svg", "pdf", "exe", "dll", "so",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Helper function to decide whether a hostname or FQDN should be redacted.
+///
+/// Hostnames under a built-in set of well-known public domains, or under any of
+/// `allowed_domains`, are kept. Domains match themselves and all of their subdomains,
+/// so allowing `github.com` also keeps `api.github.com`. Candidates whose last label is
+/// a common file extension (e.g. `main.rs`, `config.yaml`) are treated as file names.
+///
+/// # Arguments
+///
+/// * `host` - The hostname string slice to check.
+/// * `allowed_domains` - Additional domains that should never be redacted.
+///
+/// # Returns
+///
+/// `true` if the hostname should be redacted, `false` if it is allowlisted or not a hostname.
+pub fn is_redactable_hostname(host: &str, allowed_domains: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.len() > 253 {
+        return false;
+    }
+    let Some((_, tld)) = host.rsplit_once('.') else { return false; };
+    if FILE_EXTENSIONS.contains(tld) {
+        return false;
+    }
+
+    let is_under = |domain: &str| {
+        let domain = domain.trim().trim_start_matches("*.").trim_matches('.').to_ascii_lowercase();
+        !domain.is_empty()
+            && (host == domain || host.strip_suffix(&domain).is_some_and(|prefix| prefix.ends_with('.')))
+    };
+    !DEFAULT_ALLOWED_DOMAINS.iter().any(|domain| is_under(domain)) && !allowed_domains.iter().any(|domain| is_under(domain))
+}
+
+/// Order of the day and month in a numeric date such as `03/04/1985`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `DD/MM/YYYY`, as written in most of Europe, Latin America, and Asia.
+    DayMonthYear,
+    /// `MM/DD/YYYY`, as written in the United States.
+    MonthDayYear,
+}
+
+/// Earliest year the date validators accept.
+const MIN_PLAUSIBLE_YEAR: i32 = 1900;
+
+/// Years after the current one that `is_plausible_date` still accepts, for appointments and
+/// expiry dates.
+const MAX_YEARS_AHEAD: i32 = 10;
+
+/// Splits a numeric date into its three parts, requiring the same separator (`/`, `.`, or
+/// `-`) twice.
+fn date_parts(date: &str) -> Option<(u32, u32, i32)> {
+    let separator = date.chars().find(|c| matches!(c, '/' | '.' | '-'))?;
+    let mut parts = date.split(separator);
+    let (Some(first), Some(second), Some(third), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return None;
+    };
+    Some((first.parse().ok()?, second.parse().ok()?, third.parse().ok()?))
+}
+
+/// Reads a numeric date in the given order, or an ISO `YYYY-MM-DD` date when `order` is
+/// `None`, returning it only if it exists in the calendar.
+fn calendar_date(date: &str, order: Option<DateOrder>) -> Option<NaiveDate> {
+    let (first, second, third) = date_parts(date)?;
+    match order {
+        Some(DateOrder::DayMonthYear) => NaiveDate::from_ymd_opt(third, second, first),
+        Some(DateOrder::MonthDayYear) => NaiveDate::from_ymd_opt(third, first, second),
+        None => NaiveDate::from_ymd_opt(i32::try_from(first).ok()?, second, u32::try_from(third).ok()?),
+    }
+}
+
+/// Helper function to validate a numeric date in a national format.
+///
+/// Rejects dates that do not exist (such as `31/04/2020` or `29/02/2023`) and years outside
+/// a plausible range: 1900 up to ten years from now.
+///
+/// # Arguments
+///
+/// * `date` - The date string slice to validate, e.g. "24.12.1985".
+/// * `order` - Whether the day or the month comes first.
+///
+/// # Returns
+///
+/// `true` if the date exists and its year is plausible, `false` otherwise.
+pub fn is_plausible_date(date: &str, order: DateOrder) -> bool {
+    let max_year = Utc::now().year() + MAX_YEARS_AHEAD;
+    calendar_date(date, Some(order)).is_some_and(|d| (MIN_PLAUSIBLE_YEAR..=max_year).contains(&d.year()))
+}
+
+/// Helper function to validate a date of birth.
+///
+/// Accepts ISO `YYYY-MM-DD` dates and numeric dates in either day/month order, as long as
+/// the date exists, is not before 1900, and is not in the future.
+///
+/// # Arguments
+///
+/// * `date` - The date string slice to validate, e.g. "1985-12-24" or "12/24/1985".
+///
+/// # Returns
+///
+/// `true` if the date could be a date of birth, `false` otherwise.
+pub fn is_plausible_birthdate(date: &str) -> bool {
+    let today = Utc::now().date_naive();
+    let plausible = |d: NaiveDate| d.year() >= MIN_PLAUSIBLE_YEAR && d <= today;
+    if date.split(['-', '/', '.']).next().is_some_and(|first| first.len() == 4) {
+        return calendar_date(date, None).is_some_and(plausible);
+    }
+    [DateOrder::DayMonthYear, DateOrder::MonthDayYear]
+        .into_iter()
+        .any(|order| calendar_date(date, Some(order)).is_some_and(plausible))
+}
+
+/// Shortest string `is_high_entropy_string` considers.
+const MIN_HIGH_ENTROPY_LEN: usize = 20;
+
+/// Shannon entropy, in bits per character, above which a string looks random.
+const HIGH_ENTROPY_BITS_PER_CHAR: f64 = 3.5;
+
+/// Words that label a secret rather than being one, for `is_secret_value_near_keyword`.
+const SECRET_KEYWORDS: &[&str] = &["password", "passwd", "pwd", "secret", "token", "apikey", "api_key", "credential", "auth"];
+
+/// Shannon entropy of `s`, in bits per character.
+pub fn shannon_entropy(s: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    let mut total = 0usize;
+    for c in s.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Helper function to decide whether a long token looks randomly generated.
+///
+/// The token must be at least 20 characters long, mix letters and digits, and have a
+/// Shannon entropy of at least 3.5 bits per character. Identifiers such as
+/// `customer_account_identifier` or `0000000000000000000000` do not pass.
+///
+/// # Arguments
+///
+/// * `candidate` - The string slice to check.
+///
+/// # Returns
+///
+/// `true` if the string looks like a random secret, `false` otherwise.
+pub fn is_high_entropy_string(candidate: &str) -> bool {
+    let token = candidate.trim_end_matches('=');
+    token.chars().count() >= MIN_HIGH_ENTROPY_LEN
+        && token.chars().any(|c| c.is_ascii_alphabetic())
+        && token.chars().any(|c| c.is_ascii_digit())
+        && shannon_entropy(token) >= HIGH_ENTROPY_BITS_PER_CHAR
+}
+
+/// Helper function for values found next to a keyword such as `password` or `token`.
+///
+/// Rejects values that are themselves such a keyword (`password_hash`, `auth_token`), so
+/// the label of a secret is not mistaken for the secret.
+///
+/// # Arguments
+///
+/// * `value` - The candidate value.
+///
+/// # Returns
+///
+/// `true` if the value may be a secret, `false` if it is a keyword.
+pub fn is_secret_value_near_keyword(value: &str) -> bool {
+    let lower = value.to_ascii_lowercase();
+    !SECRET_KEYWORDS.iter().any(|keyword| lower.contains(keyword))
+}
+
+/// Checks a `name=value` query parameter matched by the `url_query_secret` rule.
+///
+/// Rejects values too short to be a credential and values that are evidently not real:
+/// template placeholders (`{token}`, `<key>`, `$TOKEN`, `%7Btoken%7D`), masks such as
+/// `xxxxxx` or `******`, and literals like `null` or `undefined`.
+///
+/// # Examples
+///
+/// ```
+/// use cleansh_core::validators::is_url_query_secret;
+///
+/// assert!(is_url_query_secret("?token=d41d8cd98f00b204"));
+/// assert!(!is_url_query_secret("&token={token}"));
+/// assert!(!is_url_query_secret("?key=abc"));
+/// ```
+pub fn is_url_query_secret(parameter: &str) -> bool {
+    let Some((_, value)) = parameter.split_once('=') else {
+        return false;
+    };
+    const NOT_SECRETS: &[&str] = &["null", "undefined", "none", "true", "false", "redacted"];
+    let lower = value.to_ascii_lowercase();
+    value.chars().count() >= 6
+        && !value.starts_with(['{', '<', '$', ':'])
+        && !lower.starts_with("%7b")
+        && !lower.starts_with("%3c")
+        && !NOT_SECRETS.contains(&lower.as_str())
+        && value.chars().any(|c| c != value.chars().next().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Random part and checksum of a well-formed GitHub token, split so the literal is not itself a token.
+    const GITHUB_RANDOM_PART: &str = "abcdefghijABCDEFGHIJ0123456789";
+    const GITHUB_CHECKSUM: &str = "2C2O59";
+
+    #[test]
+    fn github_checksum_matches_reference_values() {
+        // Standard CRC-32 check value.
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(github_token_checksum(GITHUB_RANDOM_PART), GITHUB_CHECKSUM);
+
+        let token = format!("ghp_{}{}", GITHUB_RANDOM_PART, GITHUB_CHECKSUM);
+        assert!(is_valid_github_token_programmatically(&token));
+
+        // A single changed character breaks the checksum.
+        let tampered = format!("ghp_{}{}", GITHUB_RANDOM_PART.replacen('a', "b", 1), GITHUB_CHECKSUM);
+        assert!(!is_valid_github_token_programmatically(&tampered));
+        assert!(!is_valid_github_token_programmatically("ghp_short"));
+    }
+
+    #[test]
+    fn date_checks_reject_impossible_and_implausible_dates() {
+        assert!(is_plausible_date("24.12.1985", DateOrder::DayMonthYear));
+        assert!(is_plausible_date("29/02/2024", DateOrder::DayMonthYear));
+        assert!(!is_plausible_date("29/02/2023", DateOrder::DayMonthYear));
+        assert!(!is_plausible_date("31/04/2020", DateOrder::DayMonthYear));
+        assert!(!is_plausible_date("12/24/1985", DateOrder::DayMonthYear));
+        assert!(is_plausible_date("12/24/1985", DateOrder::MonthDayYear));
+        assert!(!is_plausible_date("01/01/1850", DateOrder::MonthDayYear));
+        assert!(!is_plausible_date("01/01/2099", DateOrder::MonthDayYear));
+        assert!(!is_plausible_date("01/02-1985", DateOrder::MonthDayYear));
+
+        assert!(is_plausible_birthdate("1985-12-24"));
+        assert!(is_plausible_birthdate("12/24/1985"));
+        assert!(is_plausible_birthdate("24/12/1985"));
+        assert!(!is_plausible_birthdate("1985-02-30"));
+        assert!(!is_plausible_birthdate("01/01/2090"));
+    }
+
+    #[test]
+    fn entropy_separates_random_strings_from_identifiers() {
+        assert!(is_high_entropy_string("q8ZfT2wLx9mRvB4nKc7H"));
+        assert!(!is_high_entropy_string("customer_account_identifier"));
+        assert!(!is_high_entropy_string("a1a1a1a1a1a1a1a1a1a1a1"));
+    }
+}
@@ -5,15 +5,17 @@ use std::io::Write;
 
 // Import the specific types and functions needed from the main crate's config module
 use cleansh_core::config::{self, RedactionConfig, RedactionRule};
+use cleansh_core::profiles::{apply_profile_to_config, ProfileConfig};
+use cleansh_core::DeprecatedName;
 
 #[test]
 fn test_load_default_rules() {
     let config = RedactionConfig::load_default_rules().unwrap();
     assert!(!config.rules.is_empty());
     assert!(config.rules.iter().any(|r| r.name == "email"));
-    // Check default for programmatic_validation
+    // The email rule relies on a programmatic validator to trim punctuation and check domain labels
     let email_rule = config.rules.iter().find(|r| r.name == "email").unwrap();
-    assert!(!email_rule.programmatic_validation);
+    assert!(email_rule.programmatic_validation);
 }
 
 #[test]
@@ -86,8 +88,15 @@ fn test_merge_rules_no_user_config() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let merged = config::merge_rules(default_config.clone(), None);
     assert_eq!(merged.rules.len(), 1);
@@ -117,6 +126,12 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
             RedactionRule {
                 name: "ipv4_address".to_string(),
@@ -135,8 +150,15 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -157,8 +179,15 @@ fn test_merge_rules_override() {
                 enabled: None,
                 severity: Some("medium".to_string()),
                 tags: Some(vec!["user".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 2);
@@ -192,8 +221,15 @@ fn test_merge_rules_add_new() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -214,8 +250,15 @@ fn test_merge_rules_add_new() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 2);
@@ -245,6 +288,12 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
             RedactionRule {
                 name: "default_non_opt_in".to_string(),
@@ -263,8 +312,15 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let user_config = RedactionConfig {
         rules: vec![
@@ -285,6 +341,12 @@ fn test_merge_rules_with_opt_in() {
                 enabled: None,
                 severity: None,
                 tags: Some(vec!["user".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
             RedactionRule {
                 name: "default_opt_in".to_string(), // Override default opt-in
@@ -303,8 +365,15 @@ fn test_merge_rules_with_opt_in() {
                 enabled: Some(true),
                 severity: Some("high".to_string()),
                 tags: Some(vec!["user".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
     let merged = config::merge_rules(default_config, Some(user_config));
     assert_eq!(merged.rules.len(), 3);
@@ -317,4 +386,341 @@ fn test_merge_rules_with_opt_in() {
 
     assert!(merged.rules.iter().any(|r| r.name == "user_opt_in"));
     assert!(merged.rules.iter().any(|r| r.name == "default_non_opt_in"));
-}
\ No newline at end of file
+}
+
+/// Builds a minimal regex rule for merge-ordering tests.
+fn ordering_rule(name: &str, replace_with: &str) -> RedactionRule {
+    RedactionRule {
+        name: name.to_string(),
+        pattern: Some(name.to_string()),
+        replace_with: replace_with.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_merge_rules_order_is_deterministic() {
+    let default_config = RedactionConfig {
+        rules: vec![
+            ordering_rule("zeta", "[Z]"),
+            ordering_rule("alpha", "[A]"),
+            ordering_rule("mid", "[M]"),
+        ],
+        ..Default::default()
+    };
+    let user_config = RedactionConfig {
+        rules: vec![
+            ordering_rule("new_b", "[NB]"),
+            ordering_rule("alpha", "[USER_A]"),
+            ordering_rule("new_a", "[NA]"),
+        ],
+        ..Default::default()
+    };
+
+    let merged = config::merge_rules(default_config.clone(), Some(user_config.clone()));
+    let names: Vec<&str> = merged.rules.iter().map(|r| r.name.as_str()).collect();
+    // Defaults first in file order, override in place, user additions appended in order.
+    assert_eq!(names, vec!["zeta", "alpha", "mid", "new_b", "new_a"]);
+    assert_eq!(merged.rules[1].replace_with, "[USER_A]");
+
+    // Repeated merges must always produce the same order.
+    for _ in 0..20 {
+        let again = config::merge_rules(default_config.clone(), Some(user_config.clone()));
+        let again_names: Vec<&str> = again.rules.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(again_names, names);
+    }
+}
+
+#[test]
+fn test_merge_rules_preserves_default_file_order() -> Result<()> {
+    let defaults = RedactionConfig::load_default_rules()?;
+    let expected: Vec<String> = defaults.rules.iter().map(|r| r.name.clone()).collect();
+    let merged = config::merge_rules(defaults, None);
+    let actual: Vec<String> = merged.rules.iter().map(|r| r.name.clone()).collect();
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_rule_priority_parsed_from_yaml() -> Result<()> {
+    let yaml_content = r#"
+rules:
+  - name: "high"
+    pattern: "high"
+    replace_with: "[HIGH]"
+    priority: 50
+  - name: "unset"
+    pattern: "unset"
+    replace_with: "[UNSET]"
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+
+    let config = RedactionConfig::load_from_file(file.path())?;
+    assert_eq!(config.rules[0].priority, 50);
+    assert_eq!(config.rules[1].priority, 0); // Defaults to 0 when omitted
+    Ok(())
+}
+
+#[test]
+fn test_rule_priority_breaks_overlap_ties() -> Result<()> {
+    use cleansh_core::{RegexEngine, SanitizationEngine};
+
+    // Both rules match at the same start position; the later-declared rule has higher priority.
+    let config = RedactionConfig {
+        rules: vec![
+            RedactionRule { pattern: Some("abc".to_string()), ..ordering_rule("short_token", "[SHORT]") },
+            RedactionRule { pattern: Some("abcdef".to_string()), priority: 5, ..ordering_rule("long_token", "[LONG]") },
+        ],
+        ..Default::default()
+    };
+
+    let engine = RegexEngine::new(config)?;
+    let (sanitized, summary) = engine.sanitize("id=abcdef", "", "", "", "", "", "", None)?;
+    assert_eq!(sanitized, "id=[LONG]");
+
+    // Summary and rule order follow priority, not declaration order.
+    let names: Vec<&str> = summary.iter().map(|s| s.rule_name.as_str()).collect();
+    assert_eq!(names, vec!["long_token", "short_token"]);
+    assert_eq!(engine.get_rules().rules[0].name, "long_token");
+    Ok(())
+}
+
+#[test]
+fn test_context_window_requires_nearby_keyword() -> Result<()> {
+    use cleansh_core::{ContextWindow, MatchContext, RegexEngine, SanitizationEngine};
+
+    let yaml_content = r#"
+rules:
+  - name: "labelled_secret"
+    pattern: "\\b[A-Za-z0-9]{10,}\\b"
+    replace_with: "[SECRET]"
+    context:
+      before: 12
+      keywords: ["password", "pwd"]
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+    let config = RedactionConfig::load_from_file(file.path())?;
+    let window = config.rules[0].context.clone().expect("context window should parse");
+    assert_eq!((window.before, window.after), (12, 0));
+
+    // Only values near a label are redacted, and the label itself stays in place.
+    let engine = RegexEngine::new(config)?;
+    let (sanitized, _) = engine.sanitize("Password: hunter2secret build: 0123456789abc", "", "", "", "", "", "", None)?;
+    assert_eq!(sanitized, "Password: [SECRET] build: 0123456789abc");
+
+    // Window edges never split a multi-byte character.
+    let input = "pwd→é=value";
+    let start = input.find("value").unwrap();
+    let ctx = MatchContext::new(input, start, input.len(), &ContextWindow { before: 4, after: 8, keywords: vec![] });
+    assert_eq!((ctx.before, ctx.matched, ctx.after), ("é=", "value", ""));
+    Ok(())
+}
+
+/// Tests that `paths` globs limit a rule to matching files, with `!` exclusions, while
+/// sources not passed as files, such as stdin or a crash report, still use every rule.
+#[test]
+fn test_rule_paths_scope_matches_to_files() -> Result<()> {
+    use cleansh_core::{PathScope, RegexEngine, SanitizationEngine};
+
+    let yaml_content = r#"
+rules:
+  - name: "generic_token"
+    pattern: "tok_[a-z0-9]{8}"
+    replace_with: "[TOKEN]"
+    paths: ["config/**", "*.env", "!**/fixtures/**"]
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+    let config = RedactionConfig::load_from_file(file.path())?;
+    let engine = RegexEngine::new(config)?;
+
+    let input = "key=tok_abcd1234";
+    let found = |path: &str| engine.find_file_matches(input, path).map(|m| m.len());
+    assert_eq!(found("config/prod.yaml")?, 1);
+    assert_eq!(found("/srv/app/config/prod.yaml")?, 1);
+    assert_eq!(found(".\\deploy\\prod.env")?, 1);
+    assert_eq!(found("config/fixtures/sample.yaml")?, 0);
+    assert_eq!(found("src/main.rs")?, 0);
+    assert_eq!(engine.sanitize_file_with_edits(input, "src/main.rs")?.0, input);
+    assert_eq!(engine.sanitize_file_with_edits(input, "config/prod.yaml")?.0, "key=[TOKEN]");
+
+    // Sources that are not files use every rule, whatever their name.
+    for source in ["stdin", "", "crash_report", "src/main.rs"] {
+        assert_eq!(engine.find_matches_for_ui(input, source)?.len(), 1, "{}", source);
+    }
+
+    let scope = PathScope::new(&["src/*.rs".to_string()])?;
+    assert!(scope.applies_to("src/lib.rs"));
+    assert!(!scope.applies_to("src/engines/regex_engine.rs"));
+    assert!(!scope.applies_to("docs/src-notes.rs"));
+    Ok(())
+}
+
+/// Tests that rule groups from a user file merge with the built-in groups and can be
+/// enabled or disabled as a unit, and that groups shadowing a rule are rejected.
+#[test]
+fn test_rule_groups_enable_and_disable_as_a_unit() -> Result<()> {
+    let yaml_content = r#"
+rules:
+  - name: "internal_ticket"
+    pattern: "TICKET-[0-9]+"
+    replace_with: "[TICKET]"
+    opt_in: true
+groups:
+  internal: ["internal_ticket", "hostname"]
+  cloud: ["aws_access_key"]
+"#;
+    let mut file = NamedTempFile::new()?;
+    file.write_all(yaml_content.as_bytes())?;
+    let user_config = RedactionConfig::load_from_file(file.path())?;
+    let mut config = cleansh_core::merge_rules(RedactionConfig::load_default_rules()?, Some(user_config));
+
+    assert_eq!(config.groups["cloud"], vec!["aws_access_key"], "user groups replace built-in groups of the same name");
+    assert!(config.groups.contains_key("payment_cards"), "built-in groups are kept");
+
+    config.set_active_rules(&["internal".to_string()], &["cloud".to_string(), "national_ids".to_string()]);
+    let active: Vec<&str> = config.rules.iter().map(|r| r.name.as_str()).collect();
+    assert!(active.contains(&"internal_ticket") && active.contains(&"hostname"), "opt-in group members should be enabled: {:?}", active);
+    for disabled in ["aws_access_key", "us_ssn", "uk_nino", "sa_id"] {
+        assert!(!active.contains(&disabled), "'{}' should be disabled", disabled);
+    }
+    assert!(active.contains(&"email"));
+
+    let mut shadowing = NamedTempFile::new()?;
+    shadowing.write_all(b"rules:\n  - name: \"email\"\n    pattern: \"x@y\"\n    replace_with: \"[E]\"\ngroups:\n  email: [\"email\"]\n  empty: []\n")?;
+    let message = RedactionConfig::load_from_file(shadowing.path()).unwrap_err().to_string();
+    assert!(message.contains("Group 'email' has the same name as a rule.") && message.contains("Group 'empty' has no rules."), "{}", message);
+    Ok(())
+}
+
+#[test]
+fn test_skipping_invalid_rules_keeps_the_valid_ones() -> Result<()> {
+    let yaml = format!(
+        r#"
+rules:
+  - name: ticket
+    pattern: "TICKET-[0-9]+"
+    replace_with: "[TICKET]"
+  - name: unbalanced
+    pattern: "("
+    replace_with: "x"
+  - name: too_long
+    pattern: "{}"
+    replace_with: "x"
+"#,
+        "a".repeat(config::MAX_PATTERN_LENGTH + 1)
+    );
+    assert!(RedactionConfig::from_yaml_str(&yaml).is_err());
+
+    let (config, skipped) = RedactionConfig::from_yaml_str_skipping_invalid(&yaml)?;
+    assert_eq!(config.rules.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["ticket"]);
+    let names: Vec<&str> = skipped.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["unbalanced", "too_long"]);
+    assert!(skipped[0].reason.contains("invalid regex pattern"), "{}", skipped[0].reason);
+    assert!(skipped[1].reason.contains("exceeds"), "{}", skipped[1].reason);
+
+    // Problems spanning rules are not skipped over.
+    let duplicate = "rules:\n  - name: a\n    pattern: x\n    replace_with: y\n  - name: a\n    pattern: z\n    replace_with: y\n";
+    assert!(RedactionConfig::from_yaml_str_skipping_invalid(duplicate).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_default_rules_match_their_checksum_and_downgrades_are_detected() -> Result<()> {
+    config::verify_default_rules()?;
+
+    let defaults = RedactionConfig::load_default_rules()?;
+    let aws = defaults.rules.iter().find(|r| r.name == "aws_access_key").unwrap();
+    let with_pattern = |pattern: &str| RedactionRule { pattern: Some(pattern.to_string()), ..aws.clone() };
+
+    let narrowed = config::override_downgrade(aws, &with_pattern("AKIA[0-9A-Z]{16}-prod")).unwrap();
+    assert_eq!(narrowed.rule, "aws_access_key");
+    assert_eq!(narrowed.reasons.len(), 1);
+    let disabled = RedactionRule { enabled: Some(false), opt_in: true, ..aws.clone() };
+    assert_eq!(config::override_downgrade(aws, &disabled).unwrap().reasons.len(), 2);
+
+    // Same rule, a wider alternation, or an acknowledged override: no warning.
+    assert!(config::override_downgrade(aws, aws).is_none());
+    let widened = format!("{}|ASIA[0-9A-Z]{{16}}", aws.pattern.as_deref().unwrap());
+    assert!(config::override_downgrade(aws, &with_pattern(&widened)).is_none());
+    let acknowledged = RedactionRule { tags: Some(vec![config::ACCEPT_DOWNGRADE_TAG.to_string()]), ..disabled };
+    assert!(config::override_downgrade(aws, &acknowledged).is_none());
+    Ok(())
+}
+
+
+#[test]
+fn test_problems_lists_every_problem_with_its_rule() -> Result<()> {
+    let yaml = format!(
+        r#"
+rules:
+  - name: ticket
+    pattern: "TICKET-([0-9]+)"
+    replace_with: "[TICKET-$2]"
+  - name: ticket
+    pattern: "("
+    replace_with: "x"
+  - name: too_long
+    pattern: "{}"
+    replace_with: "x"
+  - name: ok
+    pattern: "OK"
+    replace_with: "[OK]"
+groups:
+  empty: []
+"#,
+        "a".repeat(config::MAX_PATTERN_LENGTH + 1)
+    );
+    let config: RedactionConfig = serde_yml::from_str(&yaml)?;
+    let problems = config.problems();
+    let found: Vec<(Option<usize>, &str)> = problems.iter().map(|p| (p.rule_index, p.message.as_str())).collect();
+    assert_eq!(found.len(), 5, "{:?}", found);
+    assert!(found[0].0 == Some(0) && found[0].1.contains("'$2'"), "{:?}", found);
+    assert!(found[1].0 == Some(1) && found[1].1.contains("Duplicate rule name"), "{:?}", found);
+    assert!(found[2].0 == Some(1) && found[2].1.contains("invalid regex pattern"), "{:?}", found);
+    assert!(found[3].0 == Some(2) && found[3].1.contains("exceeds"), "{:?}", found);
+    assert_eq!(found[4], (None, "Group 'empty' has no rules."));
+
+    assert!(RedactionConfig::load_default_rules()?.problems().is_empty());
+    Ok(())
+}
+
+/// Tests that a rule's former names still select it in enable/disable lists, groups,
+/// profiles and user overrides, and that old and deprecated names are reported.
+#[test]
+fn test_rule_aliases_resolve_to_the_renamed_rule() -> Result<()> {
+    let config = RedactionConfig::from_yaml_str(
+        "rules:\n  - name: ssn_us\n    pattern: '\\d{3}-\\d{2}-\\d{4}'\n    replace_with: '[SSN]'\n    opt_in: true\n    aliases: [us_ssn]\n  - name: legacy_key\n    pattern: 'LK-[0-9]+'\n    replace_with: '[KEY]'\n    deprecated: true\ngroups:\n  ids: [us_ssn]\n",
+    )?;
+    assert_eq!(config.expand_groups(&["ids".to_string()]), vec!["ssn_us"]);
+    assert_eq!(
+        config.deprecated_names(&["us_ssn".to_string(), "legacy_key".to_string(), "ssn_us".to_string()]),
+        vec![
+            DeprecatedName::Alias { old: "us_ssn".to_string(), new: "ssn_us".to_string() },
+            DeprecatedName::Rule { name: "legacy_key".to_string() },
+        ]
+    );
+
+    let mut enabled = config.clone();
+    enabled.set_active_rules(&["us_ssn".to_string()], &["legacy_key".to_string()]);
+    let names: Vec<&str> = enabled.rules.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["ssn_us"]);
+
+    let profile: ProfileConfig = serde_yml::from_str("profile_name: p\nversion: '1'\nrules:\n  - name: us_ssn\n    severity: high\n")?;
+    profile.validate(&config)?;
+    let profiled = apply_profile_to_config(&profile, config.clone());
+    assert_eq!(profiled.rules[0].severity.as_deref(), Some("high"));
+
+    let user = RedactionConfig::from_yaml_str("rules:\n  - name: us_ssn\n    pattern: 'SSN-[0-9]+'\n    replace_with: '[SSN2]'\n")?;
+    let merged = config::merge_rules(config, Some(user));
+    assert_eq!(merged.rules.len(), 2);
+    assert_eq!(merged.rules[0].name, "ssn_us");
+    assert_eq!(merged.rules[0].replace_with, "[SSN2]");
+    assert_eq!(merged.resolve_alias("us_ssn"), Some("ssn_us"));
+
+    let clash = RedactionConfig::from_yaml_str("rules:\n  - name: a\n    pattern: 'a'\n    replace_with: x\n    aliases: [b]\n  - name: b\n    pattern: 'b'\n    replace_with: y\n");
+    assert!(clash.unwrap_err().to_string().contains("already the name of a rule"));
+    Ok(())
+}
@@ -27,6 +27,12 @@ fn test_profile_validation_success() -> Result<()> {
                 enabled: Some(true),
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
                 opt_in: false,
             },
             RedactionRule {
@@ -45,9 +51,16 @@ fn test_profile_validation_success() -> Result<()> {
                 enabled: Some(true),
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
                 opt_in: false,
             },
         ],
+        ..Default::default()
     };
 
     let profile = ProfileConfig {
@@ -69,6 +82,7 @@ fn test_profile_validation_success() -> Result<()> {
         dedupe: None,
         post_processing: None,
         reporting: None,
+        allowed_domains: Vec::new(),
     };
 
     profile.validate(&default_config)?;
@@ -95,9 +109,16 @@ fn test_profile_validation_fails_on_unknown_rule() {
                 enabled: Some(true),
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
                 opt_in: false,
             },
         ],
+        ..Default::default()
     };
 
     let profile = ProfileConfig {
@@ -118,9 +139,17 @@ fn test_profile_validation_fails_on_unknown_rule() {
         dedupe: None,
         post_processing: None,
         reporting: None,
+        allowed_domains: Vec::new(),
     };
 
     assert!(profile.validate(&default_config).is_err());
+
+    // `--skip-invalid-rules` drops the entry instead, and reports it.
+    let mut profile = profile;
+    let skipped = profile.remove_invalid_rules(&default_config);
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].name, "unknown_rule");
+    assert!(profile.validate(&default_config).is_ok());
 }
 
 #[test]
@@ -143,9 +172,16 @@ fn test_profile_validation_fails_on_invalid_samples() {
                 enabled: Some(true),
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
                 opt_in: false,
             },
         ],
+        ..Default::default()
     };
 
     let profile = ProfileConfig {
@@ -166,6 +202,7 @@ fn test_profile_validation_fails_on_invalid_samples() {
         dedupe: None,
         post_processing: None,
         reporting: None,
+        allowed_domains: Vec::new(),
     };
 
     assert!(profile.validate(&default_config).is_err());
@@ -191,9 +228,16 @@ fn test_profile_validation_handles_unlimited_samples() -> Result<()> {
                 enabled: Some(true),
                 severity: None,
                 tags: None,
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
                 opt_in: false,
             },
         ],
+        ..Default::default()
     };
 
     let profile = ProfileConfig {
@@ -214,6 +258,7 @@ fn test_profile_validation_handles_unlimited_samples() -> Result<()> {
         dedupe: None,
         post_processing: None,
         reporting: None,
+        allowed_domains: Vec::new(),
     };
 
     assert!(profile.validate(&default_config).is_ok());
@@ -267,6 +312,12 @@ fn test_select_samples_correctly_sorts_and_dedupes() -> Result<()> {
         enabled: None,
         severity: None,
         tags: None,
+        priority: 0,
+        context: None,
+        paths: None,
+        deprecated: false,
+        aliases: None,
+        exceptions: None,
         opt_in: false,
     };
 
@@ -353,4 +404,25 @@ fn test_select_samples_correctly_sorts_and_dedupes() -> Result<()> {
     assert!(found_coords.contains(&("file2".to_string(), 200, 210)));
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_profile_rule_entry_can_name_a_rule_group() -> Result<()> {
+    let default_config = RedactionConfig::load_default_rules()?;
+    let profile = ProfileConfig {
+        profile_name: "no_cloud".to_string(),
+        version: "v1.0".to_string(),
+        rules: vec![ProfileRule { name: "cloud".to_string(), enabled: Some(false), severity: Some("low".to_string()) }],
+        ..Default::default()
+    };
+    profile.validate(&default_config)?;
+
+    let applied = apply_profile_to_config(&profile, default_config);
+    for name in &applied.groups["cloud"] {
+        let rule = applied.rules.iter().find(|r| &r.name == name).expect("group member exists");
+        assert_eq!((rule.enabled, rule.severity.as_deref()), (Some(false), Some("low")), "rule '{}'", name);
+    }
+    let email = applied.rules.iter().find(|r| r.name == "email").unwrap();
+    assert_eq!(email.enabled, None);
+    Ok(())
+}
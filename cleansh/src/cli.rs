@@ -10,8 +10,12 @@
 //! The CLI is designed to be user-friendly, with clear help messages and options for customization.
 //! License: Polyform Noncommercial License 1.0.0
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::ui::output_format::SummaryFormat;
+use crate::utils::clipboard::DEFAULT_MAX_CLIPBOARD_BYTES;
 
 /// Top-level CLI definition.
 #[derive(Parser, Debug)]
@@ -26,9 +30,13 @@ use std::path::PathBuf;
 )]
 pub struct Cli {
     /// Disable informational messages
-    #[arg(long, short = 'q', help = "Suppress all informational and debug messages.")]
+    #[arg(long, short = 'q', global = true, help = "Suppress all informational and debug messages.")]
     pub quiet: bool,
 
+    /// Keep informational messages, summaries and the epilogue when stdout is piped.
+    #[arg(long = "verbose", global = true, conflicts_with = "quiet", help = "Keep informational messages, redaction summaries and end-of-run notifications when stdout is piped or redirected, where they are otherwise suppressed as with --quiet --no-epilogue.")]
+    pub verbose: bool,
+
     /// Enable debug logging (overrides RUST_LOG for 'cleansh' crate to DEBUG)
     #[arg(long, short = 'd', help = "Enable debug logging.")]
     pub debug: bool,
@@ -37,9 +45,13 @@ pub struct Cli {
     #[arg(long = "disable-debug", help = "Disable debug logging, overriding RUST_LOG.")]
     pub disable_debug: bool,
 
-    /// Specify the path to a custom YAML theme file.
-    #[arg(long = "theme", value_name = "FILE", help = "Specify the path to a custom YAML theme file.")]
-    pub theme: Option<PathBuf>,
+    /// Show original (unredacted) matches in debug logs. Only allowed on an interactive terminal.
+    #[arg(long = "unsafe-debug-pii", help = "UNSAFE: show original (unredacted) matches in debug logs. Refuses to run unless stdout and stderr are a terminal.")]
+    pub unsafe_debug_pii: bool,
+
+    /// Specify a built-in theme by name, or the path to a custom YAML theme file.
+    #[arg(long = "theme", value_name = "NAME|FILE", global = true, help = "Use a built-in theme (default, solarized-dark, solarized-light, high-contrast) or a custom YAML theme file. Overrides the CLEANSH_THEME_<COMMAND> and CLEANSH_THEME environment variables.")]
+    pub theme: Option<String>,
 
     /// Disable donation prompts that appear after certain usage thresholds
     #[arg(long = "disable-donation-prompts", help = "Disable future prompts for donations.")]
@@ -49,6 +61,18 @@ pub struct Cli {
     #[arg(long = "suppress-donation-prompt", help = "Suppress donation prompt for this run only (does not persist).", global = true)]
     pub suppress_donation_prompt: bool,
 
+    /// How redaction summaries are printed.
+    #[arg(long = "summary-format", value_name = "FORMAT", default_value = "full", global = true, help = "How to print redaction summaries: 'full' (default) or 'compact' for a single line suitable for CI logs.")]
+    pub summary_format: SummaryFormat,
+
+    /// Suppress the epilogue (the end-of-run notifications) printed after a command.
+    #[arg(long = "no-epilogue", global = true, help = "Suppress the notifications (donation, update available, license expiring, outdated profiles) printed after a command finishes, for non-interactive use.")]
+    pub no_epilogue: bool,
+
+    /// Seed for randomized choices, for reproducible runs.
+    #[arg(long = "seed", value_name = "N", global = true, help = "Seed every randomized choice (such as which findings scan --max-matches keeps) so that runs can be reproduced exactly in tests and audits. Without it, a fixed default seed is used.")]
+    pub seed: Option<u64>,
+
     /// The subcommand to run
     #[command(subcommand)]
     pub command: Commands,
@@ -76,51 +100,294 @@ pub enum Commands {
     /// Provides a suite of tools for managing redaction profiles.
     #[command(subcommand, about = "Provides a suite of tools for managing redaction profiles.")]
     Profiles(ProfilesCommand),
+
+    /// Renders a `scan` JSON summary into a shareable report.
+    #[command(about = "Renders a `scan --json-file` summary into a self-contained report file.")]
+    Report(ReportCommand),
+
+    /// Measures rule precision and recall against a labeled corpus.
+    #[command(about = "Measures per-rule precision and recall against a labeled corpus (for rule developers).")]
+    Eval(EvalCommand),
+
+    /// Searches input like `grep`, printing matching lines only after sanitizing them.
+    #[command(about = "Searches files or stdin for a pattern and prints the matching lines sanitized, safe to paste into tickets.")]
+    Grep(GrepCommand),
+
+    /// Tools for writing custom redaction rules.
+    #[command(subcommand, about = "Tools for writing custom redaction rules.")]
+    Rules(RulesCommand),
+
+    /// Writes, checks and shows rule configuration files.
+    #[command(subcommand, about = "Writes a starter rules file, checks rules files for mistakes, and shows the rule set a run would use.")]
+    Config(ConfigCommand),
+
+    /// Shows statistics collected across scans.
+    #[command(subcommand, about = "Shows statistics collected across scans run with --record-history.")]
+    Stats(Box<StatsCommand>),
+
+    /// Installs or removes the optional shell integration.
+    #[command(about = "Installs the `cs` shell function, which re-runs the last command with its output sanitized, into your shell's startup file.")]
+    InstallShellIntegration(ShellIntegrationCommand),
+
+    /// Redacts secrets from a shell history file in place.
+    #[command(about = "Redacts secrets from your shell history file (bash, zsh or fish) in place, keeping a backup of the original.")]
+    History(HistoryCommand),
+
+    /// Checks that a sanitized file matches what the current rules make of its original.
+    #[command(name = "verify-sanitized", about = "Checks, without changing anything, that a sanitized file is what the current rules make of its original: no missed secrets and no unexplained differences.")]
+    VerifySanitized(VerifySanitizedCommand),
+
+    /// Runs a command with its output sanitized as it is written.
+    #[command(visible_alias = "run", about = "Runs a command with its stdout and stderr sanitized as they are written, keeping the streams apart and exiting with the command's exit code.")]
+    Exec(ExecCommand),
+}
+
+impl Commands {
+    /// The subcommand's name as typed on the command line, such as `install-shell-integration`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Sanitize(_) => "sanitize",
+            Commands::Scan(_) => "scan",
+            Commands::Uninstall { .. } => "uninstall",
+            Commands::Profiles(_) => "profiles",
+            Commands::Report(_) => "report",
+            Commands::Eval(_) => "eval",
+            Commands::Grep(_) => "grep",
+            Commands::Rules(_) => "rules",
+            Commands::Config(_) => "config",
+            Commands::Stats(_) => "stats",
+            Commands::InstallShellIntegration(_) => "install-shell-integration",
+            Commands::History(_) => "history",
+            Commands::VerifySanitized(_) => "verify-sanitized",
+            Commands::Exec(_) => "exec",
+        }
+    }
 }
 
 /// Arguments for the `sanitize` command.
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("streaming").args(["line_buffered", "follow"]).multiple(true)))]
 pub struct SanitizeCommand {
+    /// Files to sanitize, in order (reads from stdin if none are given).
+    #[arg(value_name = "FILE", conflicts_with_all = ["input_file", "line_buffered", "stdin_tty_paste"], help = "Files to sanitize. With several files, each output on stdout is preceded by a '==> FILE <==' header; use --out-dir to write one sanitized file per input instead.")]
+    pub files: Vec<PathBuf>,
+
     /// Path to an input file (reads from stdin if not provided).
     #[arg(long, short = 'i', value_name = "FILE", help = "Read input from a specified file instead of stdin.")]
     pub input_file: Option<PathBuf>,
 
+    /// Fetch the input from this HTTP(S) URL.
+    #[arg(long = "url", value_name = "URL", conflicts_with_all = ["input_file", "files", "stdin_tty_paste", "in_place", "annotate"], help = "Fetch the input from an http(s) URL instead of stdin. With --line-buffered, the response is sanitized line by line as it downloads.")]
+    pub url: Option<String>,
+
+    /// Environment variable whose value is sent as the Authorization header of --url.
+    #[arg(long = "url-auth-env", value_name = "VAR", requires = "url", help = "Send the value of environment variable VAR (e.g. 'Bearer <token>') as the Authorization header of the --url request.")]
+    pub url_auth_env: Option<String>,
+
+    /// Directory receiving one sanitized file per positional input.
+    #[arg(long = "out-dir", value_name = "DIR", requires = "files", conflicts_with = "output", help = "Write each sanitized FILE to DIR under its own file name instead of to stdout.")]
+    pub out_dir: Option<PathBuf>,
+
     /// Write sanitized output to this file instead of stdout.
     #[arg(long, short = 'o', value_name = "FILE", help = "Write output to a specified file instead of stdout.")]
     pub output: Option<PathBuf>,
+
+    /// Also write the sanitized output to stdout when writing it to `--output`.
+    #[arg(long = "tee", requires = "output", help = "Write the sanitized output to stdout as well as to --output, like tee, so it can be archived and piped on at once.")]
+    pub tee: bool,
     
     /// Copy sanitized output to the system clipboard.
     #[arg(long, short = 'c', help = "Copy sanitized output to the system clipboard.")]
     pub clipboard: bool,
 
+    /// Largest output, in bytes, that `--clipboard` will copy.
+    #[arg(long = "clipboard-max-bytes", value_name = "BYTES", default_value_t = DEFAULT_MAX_CLIPBOARD_BYTES, requires = "clipboard", help = "Skip copying to the clipboard when the output is larger than BYTES (default 4 MiB); the output is still written to stdout or --output.")]
+    pub clipboard_max_bytes: usize,
+
     /// Show a unified diff to highlight the changes made.
     #[arg(long, short = 'D', help = "Show a unified diff to highlight the changes made.")]
     pub diff: bool,
 
-    /// Path to a custom redaction configuration file (YAML).
-    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML).")]
-    pub config: Option<PathBuf>,
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML), or '-' to read it from stdin when the input comes from a file. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
 
     /// Loads a predefined profile from the local configuration.
     #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
     pub profile: Option<String>,
 
     /// Explicitly enable only these rule names (comma-separated).
-    #[arg(long, short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated).")]
+    #[arg(long, short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
     pub enable: Vec<String>,
 
     /// Explicitly disable these rule names (comma-separated).
-    #[arg(long, short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated).")]
+    #[arg(long, short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
     pub disable: Vec<String>,
 
+    /// Domains (and their subdomains) that the opt-in `hostname` rule should never redact.
+    #[arg(long = "allow-domain", value_name = "DOMAIN", value_delimiter = ',', help = "Never redact hostnames under this domain with the 'hostname' rule (repeatable or comma-separated).")]
+    pub allow_domain: Vec<String>,
+
+    /// File of values that are never redacted.
+    #[arg(long = "allowlist", value_name = "FILE", help = "Never redact the values listed in FILE, one per line: literal values, or /regex/ patterns that must match a whole value. Lines starting with # are comments.")]
+    pub allowlist: Option<PathBuf>,
+
+    /// Favor recall over precision: enable every opt-in rule.
+    #[arg(long = "strict", help = "Redact as much as possible, e.g. before pasting output publicly: enables every opt-in rule, including the entropy-based 'high_entropy_string' and the keyword-proximity 'secret_near_keyword'. Expect false positives. --disable still applies.")]
+    pub strict: bool,
+
+    /// Favor latency over coverage: run only cheap, high-signal rules.
+    #[arg(long = "fast", conflicts_with = "strict", help = "Minimal mode for interactive piping: runs only the cheap, high-signal rules of the 'fast' group (emails, JWTs, keys and tokens with fixed prefixes) behind a literal prefilter that skips rules a line cannot match. --enable adds rules on top; --disable still applies.")]
+    pub fast: bool,
+
+    /// Leave out rules that fail to compile instead of aborting.
+    #[arg(long = "skip-invalid-rules", help = "If a rule from --config or the profile is invalid or fails to compile, leave it out, warn about it in the summary and at the end of the run, and continue with the remaining rules instead of aborting.")]
+    pub skip_invalid_rules: bool,
+
+    /// Replacement text overrides for this run, as `RULE=TEXT`.
+    #[arg(long = "replace", value_name = "RULE=TEXT", value_parser = parse_replacement, help = "Replace matches of RULE with TEXT for this run instead of the rule's configured placeholder, e.g. --replace email='[CUSTOMER_EMAIL]'. Repeatable. TEXT is read like a rule's replace_with, so '$1' inserts a capture group and '$$' a literal '$'.")]
+    pub replace: Vec<(String, String)>,
+
+    /// Keep every redacted span as wide as the text it replaces.
+    #[arg(long = "keep-width", help = "Pad or cut each placeholder to the width of the text it replaces, so column-aligned output (ps, netstat, fixed-width logs) stays aligned.")]
+    pub keep_width: bool,
+
     /// Select which sanitization engine to use.
-    #[arg(long = "engine", value_name = "ENGINE", default_value = "regex", help = "Select a sanitization engine (e.g., 'regex').")]
+    #[arg(long = "engine", value_name = "ENGINE", default_value = "regex", help = "Select a sanitization engine: 'regex' (default), or 'combined' to also run entropy detection, with regex rules taking precedence where both match.")]
     pub engine: EngineChoice,
 
     /// Process input line by line (useful for streaming data from pipes).
     #[arg(long = "line-buffered", help = "Process input line by line (useful for streaming data from pipes).")]
     pub line_buffered: bool,
 
+    /// Follow a growing log file, like `tail -F`, sanitizing lines as they are appended.
+    #[arg(long = "follow", value_name = "FILE", conflicts_with_all = ["files", "input_file", "url", "stdin_tty_paste", "in_place", "annotate", "diff", "clipboard", "join_wrapped", "matches_out", "strict_containment", "post_process", "input_format", "audit_log"], help = "Follow FILE like `tail -F`: sanitize each line appended to it and write it to stdout or --output, reopening the file when it is rotated or truncated. Implies --line-buffered; press Ctrl-C to stop and print the summary.")]
+    pub follow: Option<PathBuf>,
+
+    /// Cache up to N recently seen lines in line-buffered mode to skip re-sanitizing duplicates.
+    #[arg(long = "dedupe-cache", value_name = "N", requires = "streaming", help = "Cache up to N recently seen lines in line-buffered mode to skip re-sanitizing duplicates.")]
+    pub dedupe_cache: Option<usize>,
+
+    /// Write per-minute match counts per rule to a CSV file in line-buffered mode.
+    #[arg(long = "heatmap-out", value_name = "FILE", requires = "streaming", help = "Write per-minute match counts per rule to a CSV file while streaming (line-buffered mode).")]
+    pub heatmap_out: Option<PathBuf>,
+
+    /// Re-emit each sanitized line through a template in line-buffered mode.
+    #[arg(long = "line-template", value_name = "TEMPLATE", requires = "streaming", help = "Re-emit each sanitized line through TEMPLATE in line-buffered mode, e.g. '{timestamp} {level} {sanitized}'. Fields come from named groups of --line-prefix-regex; {sanitized} is the rest of the line and {line} the whole line. Lines without a matching prefix are written unchanged.")]
+    pub line_template: Option<String>,
+
+    /// Regex with named groups that extracts the fields of a line for `--line-template`.
+    #[arg(long = "line-prefix-regex", value_name = "REGEX", requires = "line_template", help = "Regex with named groups matching the structured prefix of each line for --line-template. Defaults to a leading ISO 8601 timestamp and optional log level (groups 'timestamp' and 'level').")]
+    pub line_prefix_regex: Option<String>,
+
+    /// Give up reading an interactive terminal after this long without input.
+    #[arg(long = "stdin-timeout", value_name = "DURATION", value_parser = parse_duration, help = "When reading from a terminal, give up if no input arrives for DURATION (e.g. 30s, 2m, 500ms) instead of waiting for Ctrl-D forever. Has no effect on piped input or files.")]
+    pub stdin_timeout: Option<Duration>,
+
+    /// Paste mode: read stdin until a line containing only `.` (or EOF), then confirm on the terminal before printing.
+    #[arg(long = "stdin-tty-paste", conflicts_with_all = ["input_file", "line_buffered"], help = "Paste mode: read stdin until a line containing only '.' (or EOF), show how many secrets were found, and confirm on the terminal before printing.")]
+    pub stdin_tty_paste: bool,
+
+    /// Print the effective rule set in application order and exit.
+    #[arg(long = "explain-config", help = "Print the effective rule set in application order and exit.")]
+    pub explain_config: bool,
+
+    /// Write every applied redaction (rule, byte offsets, length, replacement) to a JSON file.
+    #[arg(long = "matches-out", value_name = "FILE", conflicts_with = "line_buffered", help = "Write every applied redaction (rule, byte offsets, length, replacement) to a JSON file.")]
+    pub matches_out: Option<PathBuf>,
+
+    /// Append one JSON line per applied redaction to an audit log, without the original values.
+    #[arg(long = "audit-log", value_name = "FILE", conflicts_with_all = ["line_buffered", "follow", "input_format", "passes", "annotate"], help = "Append one JSON line per applied redaction to FILE: the rule, the SHA-256 of the original value, the replacement, the input it was found in, its byte offsets and a timestamp. The original value itself is never written.")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Rejoin lines hard-wrapped at this terminal width before matching.
+    #[arg(long = "join-wrapped", value_name = "WIDTH", conflicts_with = "line_buffered", help = "Rejoin lines that a terminal hard-wrapped at WIDTH columns before matching, keeping the original line layout in the output.")]
+    pub join_wrapped: Option<usize>,
+
+    /// How to interpret the input.
+    #[arg(long = "input-format", alias = "format", value_name = "FORMAT", default_value = "text", conflicts_with_all = ["line_buffered", "join_wrapped", "matches_out"], help = "How to interpret the input: 'text' (default), 'eml' for RFC 822/MIME email messages, 'diff' for unified diffs and patches, 'dotenv' for .env files, or 'json' for JSON-lines logs.")]
+    pub input_format: InputFormat,
+
+    /// With `--input-format diff`, also sanitize file paths and hunk headings.
+    #[arg(long = "diff-headers", help = "With --input-format diff, also sanitize file paths and the section headings of hunk headers. The patch then only applies to a tree with the same paths.")]
+    pub diff_headers: bool,
+
+    /// With `--input-format dotenv`, keys whose values are kept.
+    #[arg(long = "dotenv-allow", value_name = "KEY", value_delimiter = ',', help = "With --input-format dotenv, keep the value of this key (repeatable or comma-separated); it is still passed through the rules. NODE_ENV, PORT and a few other non-secret settings are always kept.")]
+    pub dotenv_allow: Vec<String>,
+
+    /// Encoding of the sanitized output.
+    #[arg(long = "output-encoding", value_name = "ENCODING", default_value = "original", help = "Encoding of the sanitized output: 'original' (default) writes it back in the detected input encoding, 'utf-8' always writes UTF-8.")]
+    pub output_encoding: OutputEncoding,
+
+    /// Re-run the rules on the sanitized output, up to N times or until it stops changing.
+    #[arg(long = "passes", value_name = "N|fixpoint", default_value = "1", value_parser = parse_passes, conflicts_with_all = ["join_wrapped", "matches_out", "input_format"], help = "Re-run the rules on the sanitized output until it stops changing, at most N passes ('fixpoint' allows up to 64). Catches matches formed by earlier replacements.")]
+    pub passes: usize,
+
+    /// Fail if the rules still find anything in the sanitized output.
+    #[arg(long = "verify-idempotent", help = "Re-scan the sanitized output and fail without writing it if any rule still matches, i.e. sanitizing it again would change it.")]
+    pub verify_idempotent: bool,
+
+    /// Write nothing at all unless the whole run succeeds.
+    #[arg(long = "strict-containment", conflicts_with = "line_buffered", help = "Hold back all output (stdout, --output, --out-dir, --matches-out, clipboard) until every input has been sanitized; on any error nothing is written and the exit status is non-zero. Files are replaced atomically.")]
+    pub strict_containment: bool,
+
+    /// Allow the output to replace the input file.
+    #[arg(long = "in-place", conflicts_with_all = ["line_buffered", "stdin_tty_paste", "diff"], help = "Replace each input file with its sanitized version (or allow --output/--out-dir to point at an input). Without this flag, an output that is also an input is refused. Files are replaced atomically.")]
+    pub in_place: bool,
+
+    /// Print the original input with matches highlighted and labeled instead of replaced.
+    #[arg(long = "annotate", conflicts_with_all = ["output", "out_dir", "in_place", "clipboard", "diff", "matches_out", "line_buffered", "stdin_tty_paste"], help = "Print the original input with each match highlighted and labeled with its rule name, without redacting anything. For reviewing rules in a terminal: refused when stdout is not a terminal, and never written to files.")]
+    pub annotate: bool,
+
+    /// Report how long each rule took and how often it matched.
+    #[arg(long = "profile-rules", conflicts_with = "annotate", help = "After sanitizing, print a table to stderr listing each active rule with the number of times it ran, its matches and its cumulative time, slowest first. Use it to find rules worth disabling for performance.")]
+    pub profile_rules: bool,
+
+    /// Shell command that rewrites the sanitized output before it is written.
+    #[arg(long = "post-process", value_name = "CMD", conflicts_with_all = ["line_buffered", "annotate"], help = "Pipe each input's sanitized output through this shell command and write what it prints instead, e.g. to append an attestation footer or rewrite placeholders. The command gets the redaction counts (never the original values) as JSON in $CLEANSH_SUMMARY and the total in $CLEANSH_REDACTIONS. If it fails, nothing is written.")]
+    pub post_process: Option<String>,
+
+    /// Replace matched secrets with reversible reference tokens from a key-management service.
+    #[cfg(feature = "vault")]
+    #[arg(long = "tokenize", value_name = "BACKEND", conflicts_with_all = ["line_buffered", "follow", "keep_width"], help = "Replace matched secrets with reversible reference tokens from a key-management service.")]
+    pub tokenize: Option<TokenizeBackend>,
+
+    /// Address of the Vault server used by `--tokenize vault`.
+    #[cfg(feature = "vault")]
+    #[arg(long = "vault-addr", value_name = "URL", env = "VAULT_ADDR", help = "Address of the Vault server used by --tokenize vault.")]
+    pub vault_addr: Option<String>,
+
+    /// Environment variable holding the Vault token (never passed on the command line).
+    #[cfg(feature = "vault")]
+    #[arg(long = "vault-token-env", value_name = "VAR", default_value = "VAULT_TOKEN", help = "Environment variable holding the Vault token.")]
+    pub vault_token_env: String,
+
+    /// Mount path of the Vault transit secrets engine.
+    #[cfg(feature = "vault")]
+    #[arg(long = "vault-mount", value_name = "PATH", default_value = "transit", help = "Mount path of the Vault transit secrets engine.")]
+    pub vault_mount: String,
+
+    /// Name of the Vault transit key used to tokenize secrets.
+    #[cfg(feature = "vault")]
+    #[arg(long = "vault-transit-key", value_name = "NAME", default_value = "cleansh", help = "Name of the Vault transit key used to tokenize secrets.")]
+    pub vault_transit_key: String,
+
+    /// AWS KMS key used by `--tokenize aws-kms`.
+    #[cfg(feature = "vault")]
+    #[arg(long = "kms-key-id", value_name = "KEY", help = "Id, ARN or alias of the AWS KMS key used by --tokenize aws-kms.")]
+    pub kms_key_id: Option<String>,
+
+    /// AWS region of the KMS key.
+    #[cfg(feature = "vault")]
+    #[arg(long = "kms-region", value_name = "REGION", env = "AWS_REGION", help = "AWS region of the KMS key used by --tokenize aws-kms.")]
+    pub kms_region: Option<String>,
+
+    /// KMS endpoint to use instead of the regional one, such as a VPC endpoint.
+    #[cfg(feature = "vault")]
+    #[arg(long = "kms-endpoint", value_name = "URL", help = "AWS KMS endpoint to use instead of https://kms.REGION.amazonaws.com, such as a VPC endpoint.")]
+    pub kms_endpoint: Option<String>,
+
     /// Suppress the redaction summary.
     #[arg(long = "no-redaction-summary", help = "Suppress the redaction summary.")]
     pub no_summary: bool,
@@ -140,14 +407,75 @@ pub struct SanitizeCommand {
 
 /// Arguments for the `scan` command.
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("file_list").args(["files_from", "dir"])))]
 pub struct ScanCommand {
     /// Path to an input file (reads from stdin if not provided).
     #[arg(long, short = 'i', value_name = "FILE", help = "Read input from a specified file instead of stdin.")]
     pub input_file: Option<PathBuf>,
 
-    /// Path to a custom redaction configuration file (YAML).
-    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML).")]
-    pub config: Option<PathBuf>,
+    /// Read the list of files to scan from FILE ('-' for stdin), one path per line.
+    #[arg(long = "files-from", value_name = "FILE", conflicts_with = "input_file", help = "Read the list of files to scan from FILE ('-' for stdin), one path per line.")]
+    pub files_from: Option<PathBuf>,
+
+    /// Paths in the --files-from list are separated by NUL bytes instead of newlines (e.g. `git ls-files -z`).
+    #[arg(long = "null", short = '0', requires = "files_from", help = "Paths in the --files-from list are separated by NUL bytes instead of newlines.")]
+    pub null_delimited: bool,
+
+    /// Scan the files in this directory.
+    #[arg(long = "dir", value_name = "DIR", conflicts_with_all = ["input_file", "files_from"], help = "Scan the files in DIR, with findings counted per file as well as in total.")]
+    pub dir: Option<PathBuf>,
+
+    /// Scan the lines staged for commit in the current git repository.
+    #[arg(long = "staged", conflicts_with_all = ["input_file", "files_from", "dir", "checkpoint"], help = "Scan only the lines added by the changes staged for commit in the current git repository (`git diff --cached`), reporting each finding with its file and line. For pre-commit hooks.")]
+    pub staged: bool,
+
+    /// Descend into subdirectories of --dir.
+    #[arg(long = "recursive", short = 'r', requires = "dir", help = "Also scan the files in subdirectories of --dir (version control directories such as .git are skipped; symbolic links are not followed).")]
+    pub recursive: bool,
+
+    /// Only scan files of --dir whose path matches one of these globs.
+    #[arg(long = "glob", value_name = "GLOB", requires = "dir", help = "Only scan files of --dir whose path relative to it matches GLOB, e.g. '*.log' or 'config/**'. Repeatable; a leading '!' excludes matching files.")]
+    pub glob: Vec<String>,
+
+    /// Save scan progress to this file so an interrupted scan can be resumed.
+    #[arg(long = "checkpoint", value_name = "FILE", requires = "file_list", help = "Save progress (completed files and their findings, without the matched text) to FILE every few seconds while scanning a --files-from list or a --dir. The file is removed when the scan completes.")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Continue an interrupted scan from its checkpoint.
+    #[arg(long = "resume", requires = "checkpoint", help = "Continue from the --checkpoint file of an interrupted scan of the same file list, skipping the files it completed.")]
+    pub resume: bool,
+
+    /// Report tenants by name instead of by hash label.
+    #[arg(long = "tenant-names", help = "Key the per-tenant counts of the JSON summary, and of a --checkpoint, by the tenant names the config's tenant rule captures. By default they are keyed by 'sha256:' hash labels, so customer names are not written to disk.")]
+    pub tenant_names: bool,
+
+    /// Do not use the per-file scan cache.
+    #[arg(long = "no-cache", help = "Scan every file of a --files-from list even if it is unchanged since an earlier scan with the same configuration, and do not update the scan cache.")]
+    pub no_cache: bool,
+
+    /// Scan only this percentage of the input lines.
+    #[arg(long = "sample-percent", value_name = "PCT", value_parser = clap::value_parser!(u8).range(1..=100), conflicts_with = "checkpoint", help = "Scan only PCT% of the lines (evenly spread over the input) and report estimated totals extrapolated from the sample.")]
+    pub sample_percent: Option<u8>,
+
+    /// Stop scanning after this many seconds.
+    #[arg(long = "max-seconds", value_name = "SECS", conflicts_with = "checkpoint", help = "Stop scanning after SECS seconds and report estimated totals extrapolated from the part that was scanned.")]
+    pub max_seconds: Option<u64>,
+
+    /// Keep at most this many findings in memory.
+    #[arg(long = "max-matches", value_name = "N", value_parser = parse_max_matches, help = "Keep at most N findings in memory (default 1000000), a random sample when more are found. Counts stay exact; sample matches and forwarded findings come from the kept ones.")]
+    pub max_matches: Option<usize>,
+
+    /// Record this scan's finding counts in the local history.
+    #[arg(long = "record-history", help = "Add this scan's finding counts per rule (never matched values, and file names only with --history-files) to the local history shown by 'cleansh stats history' and 'cleansh stats serve'.")]
+    pub record_history: bool,
+
+    /// Also record the findings per file of a multi-file scan in the history.
+    #[arg(long = "history-files", requires = "record_history", help = "With --record-history, also record the paths and finding counts of the files of a --files-from or --dir scan, for the top files of 'cleansh stats serve'.")]
+    pub history_files: bool,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML), or '-' to read it from stdin when the input comes from a file. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
 
     /// Loads a predefined profile from the local configuration.
     #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
@@ -158,17 +486,45 @@ pub struct ScanCommand {
     pub rules: String,
 
     /// Explicitly enable only these rule names (comma-separated).
-    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated).")]
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
     pub enable: Vec<String>,
 
     /// Explicitly disable these rule names (comma-separated).
-    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated).")]
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
     pub disable: Vec<String>,
 
+    /// Domains (and their subdomains) that the opt-in `hostname` rule should never redact.
+    #[arg(long = "allow-domain", value_name = "DOMAIN", value_delimiter = ',', help = "Never redact hostnames under this domain with the 'hostname' rule (repeatable or comma-separated).")]
+    pub allow_domain: Vec<String>,
+
+    /// File of values that are never redacted.
+    #[arg(long = "allowlist", value_name = "FILE", help = "Never redact the values listed in FILE, one per line: literal values, or /regex/ patterns that must match a whole value. Lines starting with # are comments.")]
+    pub allowlist: Option<PathBuf>,
+
+    /// Favor recall over precision: enable every opt-in rule and fail on any finding.
+    #[arg(long = "strict", help = "Scan as aggressively as possible: enables every opt-in rule, including the entropy-based 'high_entropy_string' and the keyword-proximity 'secret_near_keyword', and fails on any finding unless --fail-over-threshold or --fail-on-severity is given. Expect false positives. --disable still applies.")]
+    pub strict: bool,
+
+    /// Favor speed over coverage: run only cheap, high-signal rules.
+    #[arg(long = "fast", conflicts_with = "strict", help = "Scan with only the cheap, high-signal rules of the 'fast' group (emails, JWTs, keys and tokens with fixed prefixes) behind a literal prefilter. --enable adds rules on top; --disable still applies.")]
+    pub fast: bool,
+
+    /// Leave out rules that fail to compile instead of aborting.
+    #[arg(long = "skip-invalid-rules", help = "If a rule from --config or the profile is invalid or fails to compile, leave it out, warn about it in the summary and at the end of the run, and continue with the remaining rules instead of aborting.")]
+    pub skip_invalid_rules: bool,
+
+    /// Exit with a non-zero code if any finding is of this severity or higher.
+    #[arg(long = "fail-on-severity", value_name = "LEVEL", help = "Exit with a non-zero code if any finding comes from a rule of this severity or higher. Findings of rules without a known severity never trigger it. Replaces the fail-on-any-finding behavior of --strict.")]
+    pub fail_on_severity: Option<Severity>,
+
     /// Exit with a non-zero code if the total number of detected secrets exceeds this threshold.
     #[arg(long = "fail-over-threshold", value_name = "N", help = "Exit with a non-zero code if the total number of detected secrets exceeds this threshold.")]
     pub fail_over_threshold: Option<usize>,
 
+    /// Append one JSON line per finding to an audit log, without the original values.
+    #[arg(long = "audit-log", value_name = "FILE", help = "Append one JSON line per finding to FILE: the rule, the SHA-256 of the matched value, the replacement the rule would use, the input it was found in, its byte offsets and a timestamp. The matched value itself is never written. Findings are not taken from the scan cache.")]
+    pub audit_log: Option<PathBuf>,
+
     /// Export scan summary to a JSON file.
     #[arg(long = "json-file", value_name = "FILE", help = "Export the redaction statistics to a JSON file.")]
     pub json_file: Option<PathBuf>,
@@ -180,6 +536,38 @@ pub struct ScanCommand {
     /// Limit the number of unique sample matches displayed per rule in console output.
     #[arg(long = "sample-matches", value_name = "N", help = "Display a sample of up to N unique matches per rule in the console output.")]
     pub sample_matches: Option<usize>,
+
+    /// Webhook URL to notify when --fail-over-threshold is exceeded.
+    #[arg(long = "notify-webhook", value_name = "URL", env = "CLEANSH_NOTIFY_WEBHOOK", hide_env_values = true, help = "Webhook URL (Slack or Teams) to notify when --fail-over-threshold is exceeded.")]
+    pub notify_webhook: Option<String>,
+
+    /// Payload format for --notify-webhook.
+    #[arg(long = "notify-format", value_name = "FORMAT", default_value = "slack", help = "Payload format for --notify-webhook.")]
+    pub notify_format: NotifyFormat,
+
+    /// Message template for --notify-webhook, with {total}, {threshold}, {rules}, {repo}, {job}, {branch}, {commit}, and {run_url} placeholders.
+    #[arg(long = "notify-template", value_name = "TEMPLATE", help = "Message template for --notify-webhook ({total}, {threshold}, {rules}, {repo}, {job}, {branch}, {commit}, {run_url}).")]
+    pub notify_template: Option<String>,
+
+    /// Forward findings (metadata only, never matched values) to a SIEM.
+    #[arg(long = "forward", value_name = "TARGET", requires_all = ["endpoint", "token_env"], help = "Forward findings (metadata only, never matched values) to a SIEM.")]
+    pub forward: Option<ForwardTarget>,
+
+    /// SIEM endpoint URL for --forward (Splunk HEC event URL or Elasticsearch _bulk URL).
+    #[arg(long = "endpoint", value_name = "URL", requires = "forward", help = "SIEM endpoint URL for --forward (Splunk HEC event URL or Elasticsearch _bulk URL).")]
+    pub endpoint: Option<String>,
+
+    /// Environment variable holding the SIEM token for --forward.
+    #[arg(long = "token-env", value_name = "VAR", requires = "forward", help = "Environment variable holding the SIEM token for --forward.")]
+    pub token_env: Option<String>,
+
+    /// Number of findings sent per request when forwarding.
+    #[arg(long = "forward-batch-size", value_name = "N", default_value_t = 100, requires = "forward", help = "Number of findings sent per request when forwarding.")]
+    pub forward_batch_size: usize,
+
+    /// Print the effective rule set in application order and exit.
+    #[arg(long = "explain-config", help = "Print the effective rule set in application order and exit.")]
+    pub explain_config: bool,
 }
 
 /// Arguments for the `verify-artifact` command.
@@ -206,6 +594,412 @@ pub struct SyncProfilesCommand {
     pub org_key: String,
 }
 
+/// Arguments for the `report` command.
+#[derive(Parser, Debug)]
+pub struct ReportCommand {
+    /// Scan summary JSON produced by `cleansh scan --json-file`.
+    #[arg(long = "from", value_name = "FILE", help = "Scan summary JSON produced by `cleansh scan --json-file`.")]
+    pub from: PathBuf,
+
+    /// Output format of the report.
+    #[arg(long = "format", value_enum, default_value = "html", help = "Output format of the report.")]
+    pub format: ReportFormat,
+
+    /// An earlier scan summary to compare against.
+    #[arg(long = "baseline", value_name = "FILE", help = "An earlier scan summary JSON to show per-rule trends against.")]
+    pub baseline: Option<PathBuf>,
+
+    /// Write the report to this file.
+    #[arg(long, short = 'o', value_name = "FILE", default_value = "cleansh-report.html", help = "Write the report to this file.")]
+    pub output: PathBuf,
+
+    /// Custom rule configurations used for rule descriptions and severities, merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Custom redaction configuration (YAML), or '-' for stdin, to read rule descriptions and severities from. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Title shown at the top of the report.
+    #[arg(long = "title", value_name = "TEXT", default_value = "CleanSH Scan Report", help = "Title shown at the top of the report.")]
+    pub title: String,
+}
+
+/// Arguments for the `eval` command.
+#[derive(Parser, Debug)]
+pub struct EvalCommand {
+    /// Directory of sample files to run the rules against.
+    #[arg(long = "corpus", value_name = "DIR", help = "Directory of sample files to run the rules against (searched recursively).")]
+    pub corpus: PathBuf,
+
+    /// JSON file with the expected secrets in the corpus.
+    #[arg(long = "truth", value_name = "FILE", help = "JSON array of expected secrets: {\"file\", \"rule\", \"start\", \"end\"} with byte offsets and paths relative to the corpus.")]
+    pub truth: PathBuf,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML), or '-' to read it from stdin when the input comes from a file. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable only these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
+    pub disable: Vec<String>,
+
+    /// Print the scores as JSON instead of a table.
+    #[arg(long = "json", help = "Print the scores as JSON instead of a table.")]
+    pub json: bool,
+
+    /// Fail if any rule's precision is below this value.
+    #[arg(long = "min-precision", value_name = "RATIO", help = "Exit with a non-zero code if any rule's precision is below RATIO (0.0-1.0).")]
+    pub min_precision: Option<f64>,
+
+    /// Fail if any rule's recall is below this value.
+    #[arg(long = "min-recall", value_name = "RATIO", help = "Exit with a non-zero code if any rule's recall is below RATIO (0.0-1.0).")]
+    pub min_recall: Option<f64>,
+}
+
+/// Arguments for the `grep` command.
+#[derive(Parser, Debug)]
+pub struct GrepCommand {
+    /// Regular expression searched for in the original (unsanitized) lines.
+    #[arg(value_name = "PATTERN", help = "Regular expression to search for. It is matched against the original lines, which are printed only after sanitizing.")]
+    pub pattern: String,
+
+    /// Files to search (reads from stdin if none are given).
+    #[arg(value_name = "FILE", help = "Files to search (reads from stdin if none are given).")]
+    pub files: Vec<PathBuf>,
+
+    /// Match the pattern case-insensitively.
+    #[arg(long = "ignore-case", short = 'i', help = "Match the pattern case-insensitively.")]
+    pub ignore_case: bool,
+
+    /// Select lines that do not match.
+    #[arg(long = "invert-match", short = 'v', help = "Print the lines that do not match the pattern.")]
+    pub invert_match: bool,
+
+    /// Prefix each line with its line number.
+    #[arg(long = "line-number", short = 'n', help = "Prefix each printed line with its 1-based line number.")]
+    pub line_number: bool,
+
+    /// Print only the number of matching lines.
+    #[arg(long = "count", short = 'c', help = "Print only the number of selected lines (per file when searching several).")]
+    pub count: bool,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML), or '-' to read it from stdin when the input comes from a file. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable only these rule names (comma-separated). There is no `-e` short
+    /// form here, since `grep -e` means something else.
+    #[arg(long = "enable", value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
+    pub disable: Vec<String>,
+
+    /// Domains (and their subdomains) that the opt-in `hostname` rule should never redact.
+    #[arg(long = "allow-domain", value_name = "DOMAIN", value_delimiter = ',', help = "Never redact hostnames under this domain with the 'hostname' rule (repeatable or comma-separated).")]
+    pub allow_domain: Vec<String>,
+}
+
+/// Subcommands for the `stats` command.
+#[derive(Subcommand, Debug)]
+pub enum StatsCommand {
+    #[command(about = "Shows findings per day or week for scans run with --record-history, and their trend.")]
+    History(StatsHistoryCommand),
+    #[command(about = "Serves a local dashboard charting the scans recorded with --record-history: findings over time, top rules and top files.")]
+    Serve(StatsServeCommand),
+}
+
+/// Arguments for `stats history`.
+#[derive(Parser, Debug)]
+pub struct StatsHistoryCommand {
+    /// Group scans by day or by week.
+    #[arg(long = "period", value_name = "PERIOD", default_value = "day", help = "Group scans by 'day' (default) or ISO 'week'.")]
+    pub period: HistoryPeriod,
+
+    /// Only count findings of this rule.
+    #[arg(long = "rule", value_name = "NAME", help = "Only count the findings of this rule.")]
+    pub rule: Option<String>,
+
+    /// Only show the most recent periods.
+    #[arg(long = "last", value_name = "N", help = "Only show the N most recent periods.")]
+    pub last: Option<usize>,
+
+    /// Print the history as JSON.
+    #[arg(long, help = "Print the periods as a JSON array instead of a table.")]
+    pub json: bool,
+}
+
+/// Arguments for `stats serve`.
+#[derive(Parser, Debug)]
+pub struct StatsServeCommand {
+    /// Port to listen on.
+    #[arg(long = "port", value_name = "PORT", default_value_t = 8742, help = "Port to listen on (default 8742); 0 picks a free one.")]
+    pub port: u16,
+
+    /// Address to listen on.
+    #[arg(long = "bind", value_name = "ADDR", default_value = "127.0.0.1", help = "Address to listen on (default 127.0.0.1). Any other address makes the dashboard reachable from other machines.")]
+    pub bind: String,
+
+    /// Group scans by day or by week.
+    #[arg(long = "period", value_name = "PERIOD", default_value = "day", help = "Chart findings per 'day' (default) or ISO 'week'.")]
+    pub period: HistoryPeriod,
+
+    /// Number of rules and files to list.
+    #[arg(long = "top", value_name = "N", default_value_t = 10, help = "Number of rules and files to list (default 10).")]
+    pub top: usize,
+}
+
+/// Subcommands for the `rules` command.
+#[derive(Subcommand, Debug)]
+pub enum RulesCommand {
+    #[command(about = "Drafts a rule from an example secret, tests it interactively, and adds it to a config file.")]
+    New(RulesNewCommand),
+    #[command(about = "Estimates the impact of proposed rules on existing logs: new redactions, overlaps with active rules, and sample matches.")]
+    Impact(RulesImpactCommand),
+}
+
+/// Arguments for `rules new`.
+#[derive(Parser, Debug)]
+pub struct RulesNewCommand {
+    /// An example of the secret the rule should match.
+    #[arg(long = "from-example", value_name = "TEXT", help = "An example of the secret the new rule should match; the draft pattern is derived from it.")]
+    pub from_example: String,
+
+    /// Strings the rule must not match.
+    #[arg(long = "counter-example", value_name = "TEXT", help = "A string the new rule must not match (repeatable). More can be entered interactively.")]
+    pub counter_examples: Vec<String>,
+
+    /// Name of the new rule.
+    #[arg(long = "name", value_name = "NAME", help = "Name of the new rule. Asked interactively when omitted.")]
+    pub name: Option<String>,
+
+    /// Config file to add the rule to.
+    #[arg(long = "config", value_name = "FILE", help = "Add the rule to this config file (created if missing). Without it, the rule snippet is printed to stdout.")]
+    pub config: Option<PathBuf>,
+
+    /// Accept every proposed answer without asking.
+    #[arg(long, short = 'y', help = "Accept the proposed pattern, name and replacement without asking any questions.")]
+    pub yes: bool,
+}
+
+/// Subcommands for the `config` command.
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    #[command(about = "Writes a commented starter rules file to edit.")]
+    Init(ConfigInitCommand),
+    #[command(about = "Checks rules files without running anything, reporting every problem with its line number.")]
+    Validate(ConfigValidateCommand),
+    #[command(about = "Prints the rules of the given config files as YAML, or with --effective the whole rule set a run would use.")]
+    Show(ConfigShowCommand),
+}
+
+/// Arguments for `config init`.
+#[derive(Parser, Debug)]
+pub struct ConfigInitCommand {
+    /// Where to write the starter rules file.
+    #[arg(value_name = "FILE", default_value = "cleansh-rules.yaml", help = "Where to write the starter rules file (default: cleansh-rules.yaml). Use - to print it to stdout.")]
+    pub path: PathBuf,
+
+    /// Overwrite the file if it exists.
+    #[arg(long, help = "Overwrite FILE if it already exists.")]
+    pub force: bool,
+}
+
+/// Arguments for `config validate`.
+#[derive(Parser, Debug)]
+pub struct ConfigValidateCommand {
+    /// Rules files to check.
+    #[arg(value_name = "FILE", required = true, help = "Rules file(s) to check. Use - to read one from stdin.")]
+    pub files: Vec<PathBuf>,
+}
+
+/// Arguments for `config show`.
+#[derive(Parser, Debug)]
+pub struct ConfigShowCommand {
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Custom redaction configuration (YAML) to show. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Show the rule set a run would use.
+    #[arg(long = "effective", help = "Show the rule set a run would use: the built-in rules merged with each --config file and the --profile, with --enable/--disable applied.")]
+    pub effective: bool,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", requires = "effective", help = "Apply this profile on top of the rules (with --effective).")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable only these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', requires = "effective", help = "Enable these rules or groups (comma-separated), as in a run (with --effective).")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', requires = "effective", help = "Disable these rules or groups (comma-separated), as in a run (with --effective).")]
+    pub disable: Vec<String>,
+}
+
+/// Arguments for `rules impact`.
+#[derive(Parser, Debug)]
+pub struct RulesImpactCommand {
+    /// Rules file with the proposed rules.
+    #[arg(long = "rule", value_name = "FILE", help = "YAML rules file with the proposed rule(s). Opt-in rules in it are simulated too.")]
+    pub rule: PathBuf,
+
+    /// Logs to simulate the rules against.
+    #[arg(long = "input", value_name = "FILE", required = true, help = "Log file to simulate the proposed rules against (repeatable).")]
+    pub inputs: Vec<PathBuf>,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Custom redaction configuration (YAML) of the rules already in use, to compare against. Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Profile of the rules already in use, to compare against.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable only these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable only these rule names (comma-separated) among the rules already in use.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated) among the rules already in use.")]
+    pub disable: Vec<String>,
+
+    /// Number of sample matches to show per rule.
+    #[arg(long = "samples", value_name = "N", default_value_t = 5, help = "Number of new matches to show per proposed rule (0 to show none). Samples show the sanitized line of each match.")]
+    pub samples: usize,
+
+    /// Print the estimate as JSON instead of a table.
+    #[arg(long = "json", help = "Print the estimate as JSON instead of a table.")]
+    pub json: bool,
+}
+
+/// Arguments for `install-shell-integration`.
+#[derive(Parser, Debug)]
+pub struct ShellIntegrationCommand {
+    /// The shell to generate the integration for.
+    #[arg(long, value_enum, value_name = "SHELL", help = "The shell to install the integration for. Detected from $SHELL when omitted.")]
+    pub shell: Option<Shell>,
+
+    /// Remove a previously installed integration.
+    #[arg(long, help = "Remove the integration from the startup file instead of installing it.")]
+    pub remove: bool,
+
+    /// Print the integration script instead of installing it.
+    #[arg(long, conflicts_with = "remove", help = "Print the integration script to stdout without touching any file, e.g. for a package manager to ship it.")]
+    pub print: bool,
+
+    /// Startup file to install into.
+    #[arg(long = "rc-file", value_name = "FILE", help = "Install into (or remove from) this file instead of the shell's default startup file.")]
+    pub rc_file: Option<PathBuf>,
+}
+
+/// Arguments for the `history` command.
+#[derive(Parser, Debug)]
+pub struct HistoryCommand {
+    /// The shell whose history file is cleaned.
+    #[arg(long, value_enum, value_name = "SHELL", help = "The shell whose history to clean. Detected from $SHELL when omitted.")]
+    pub shell: Option<Shell>,
+
+    /// History file to clean.
+    #[arg(long = "file", value_name = "FILE", help = "Clean this file instead of the shell's history file ($HISTFILE, ~/.bash_history, ~/.zsh_history or fish's fish_history). It is read in the --shell format.")]
+    pub file: Option<PathBuf>,
+
+    /// Report what would be redacted without changing the file.
+    #[arg(long = "dry-run", help = "Only report how many secrets each rule would redact; leave the file unchanged.")]
+    pub dry_run: bool,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML). Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
+    pub disable: Vec<String>,
+}
+
+/// Arguments for the `verify-sanitized` command.
+#[derive(Parser, Debug)]
+pub struct VerifySanitizedCommand {
+    /// The unsanitized original.
+    #[arg(long = "original", value_name = "FILE", help = "The original, unsanitized file.")]
+    pub original: PathBuf,
+
+    /// The sanitized file to check.
+    #[arg(long = "sanitized", value_name = "FILE", help = "The sanitized file to check against the original.")]
+    pub sanitized: PathBuf,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML). Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
+    pub disable: Vec<String>,
+}
+
+/// Arguments for the `exec` command.
+#[derive(Parser, Debug)]
+pub struct ExecCommand {
+    /// The command to run and its arguments, after `--`.
+    #[arg(value_name = "COMMAND", required = true, trailing_var_arg = true, allow_hyphen_values = true, help = "The command to run, with its arguments, after '--': cleansh exec -- kubectl logs web-1")]
+    pub command: Vec<String>,
+
+    /// Give the command pseudo-terminals instead of pipes for stdout and stderr.
+    #[arg(long = "pty", help = "Run the command with pseudo-terminals as its stdout and stderr, for tools that only print colors or progress, or flush their output, when writing to a terminal. Unix only.")]
+    pub pty: bool,
+
+    /// Warn about environment variables whose values match a rule.
+    #[arg(long = "check-env", help = "Before running the command, warn about environment variables it inherits whose values match a rule. Only variable and rule names are printed.")]
+    pub check_env: bool,
+
+    /// Disable the redaction summary printed when the command exits.
+    #[arg(long = "no-redaction-summary", help = "Do not print the redaction summary to stderr when the command exits.")]
+    pub no_redaction_summary: bool,
+
+    /// Paths to custom redaction configuration files (YAML), merged in order.
+    #[arg(long = "config", value_name = "FILE", help = "Path to a custom redaction configuration file (YAML). Repeatable: later files override rules of the same name in earlier ones.")]
+    pub config: Vec<PathBuf>,
+
+    /// Loads a predefined profile from the local configuration.
+    #[arg(long = "profile", value_name = "NAME", help = "Loads a predefined profile from the local configuration.")]
+    pub profile: Option<String>,
+
+    /// Explicitly enable these rule names (comma-separated).
+    #[arg(long = "enable", short = 'e', value_delimiter = ',', help = "Explicitly enable these rule names (comma-separated). Rule group names such as 'cloud' enable every rule in the group.")]
+    pub enable: Vec<String>,
+
+    /// Explicitly disable these rule names (comma-separated).
+    #[arg(long = "disable", short = 'x', value_delimiter = ',', help = "Explicitly disable these rule names (comma-separated). Rule group names such as 'cloud' disable every rule in the group.")]
+    pub disable: Vec<String>,
+}
+
 /// Subcommands for the `profiles` command.
 #[derive(Subcommand, Debug)]
 pub enum ProfilesCommand {
@@ -229,13 +1023,186 @@ pub enum ProfilesCommand {
     },
     #[command(about = "Lists all available local profiles.")]
     List,
+    #[command(about = "Shows the rules and settings that differ between two profiles, or between a profile and the current configuration.")]
+    Diff {
+        /// The profile to compare from, by name or path.
+        #[arg(value_name = "PROFILE_A", help = "The profile to compare from, by name or path.")]
+        a: String,
+        /// The profile to compare to; the current configuration when omitted.
+        #[arg(value_name = "PROFILE_B", help = "The profile to compare to, by name or path. Defaults to the current configuration: the built-in rules, merged with --config if given.")]
+        b: Option<String>,
+        /// Config file making up the current configuration when PROFILE_B is omitted.
+        #[arg(long = "config", value_name = "FILE", conflicts_with = "b", help = "Merge this config file (or '-' for stdin) into the built-in rules to form the current configuration compared against.")]
+        config: Option<PathBuf>,
+        /// Print the differences as JSON.
+        #[arg(long, help = "Print the differences as a JSON object instead of text.")]
+        json: bool,
+    },
 }
 
-/// Enum for selecting the sanitization engine.
+/// Backends available for `--tokenize`.
+#[cfg(feature = "vault")]
+#[derive(Debug, Clone, ValueEnum)]
+pub enum TokenizeBackend {
+    /// HashiCorp Vault transit secrets engine.
+    Vault,
+    /// AWS Key Management Service.
+    #[value(name = "aws-kms")]
+    AwsKms,
+}
+
+/// Input formats supported by `cleansh sanitize --input-format`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum InputFormat {
+    /// Plain text, sanitized as a whole.
+    #[default]
+    Text,
+    /// An RFC 822/MIME email message; text parts are decoded before sanitizing.
+    Eml,
+    /// A unified diff or patch; only added lines are sanitized.
+    Diff,
+    /// A `.env` file; every value is replaced except those of allowed keys.
+    Dotenv,
+    /// JSON lines, such as structured logs; only string values are sanitized.
+    Json,
+}
+
+/// Output encodings supported by `cleansh sanitize --output-encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputEncoding {
+    /// The encoding detected on input (UTF-16 with its BOM, Windows-1252, or UTF-8).
+    Original,
+    /// UTF-8 without a byte order mark.
+    #[value(name = "utf-8")]
+    Utf8,
+}
+
+/// Most passes `--passes fixpoint` will run before giving up on a fixpoint.
+pub const FIXPOINT_PASS_LIMIT: usize = 64;
+
+/// Parses `--passes`: a positive pass count or `fixpoint`.
+fn parse_passes(value: &str) -> Result<usize, String> {
+    if value.eq_ignore_ascii_case("fixpoint") {
+        return Ok(FIXPOINT_PASS_LIMIT);
+    }
+    match value.parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!("expected a positive number of passes or 'fixpoint', got '{}'", value)),
+        Ok(passes) => Ok(passes),
+    }
+}
+
+/// Parses a duration such as `30s`, `2m`, `500ms` or `1h`; a bare number is seconds.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| format!("expected a duration such as 30s or 2m, got '{}'", value))?;
+    let duration = match unit {
+        "ms" => Duration::from_millis(number),
+        "" | "s" => Duration::from_secs(number),
+        "m" => Duration::from_secs(number * 60),
+        "h" => Duration::from_secs(number * 3600),
+        _ => return Err(format!("unknown unit '{}' in '{}'; use ms, s, m or h", unit, value)),
+    };
+    if duration.is_zero() {
+        return Err("the duration must be greater than zero".to_string());
+    }
+    Ok(duration)
+}
+
+/// Parses `--replace`: a rule name and its replacement text, separated by the first `=`.
+fn parse_replacement(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((rule, text)) if !rule.trim().is_empty() => Ok((rule.trim().to_string(), text.to_string())),
+        _ => Err(format!("expected RULE=TEXT, got '{}'", value)),
+    }
+}
+
+/// Parses `--max-matches`: a positive number of findings.
+fn parse_max_matches(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) | Err(_) => Err(format!("expected a positive number of matches, got '{}'", value)),
+        Ok(max) => Ok(max),
+    }
+}
+
+/// Output formats supported by `cleansh report`.
 #[derive(Debug, Clone, ValueEnum)]
+pub enum ReportFormat {
+    /// A single HTML file with inline styles and charts; print it from a browser to get a PDF.
+    Html,
+}
+
+/// Periods `stats history` groups scans by.
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum HistoryPeriod {
+    Day,
+    Week,
+}
+
+/// Webhook payload formats supported by `scan --notify-webhook`.
+#[derive(Debug, Clone, ValueEnum)]
+pub enum NotifyFormat {
+    /// Slack incoming webhook.
+    Slack,
+    /// Microsoft Teams incoming webhook (message card).
+    Teams,
+}
+
+/// Rule severity levels, from lowest to highest, for `scan --fail-on-severity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Severity {
+    /// `severity: low`
+    Low,
+    /// `severity: medium`
+    Medium,
+    /// `severity: high`
+    High,
+    /// `severity: critical`
+    Critical,
+}
+
+impl Severity {
+    /// Parses the `severity` of a rule, ignoring case. Returns `None` for unknown levels.
+    pub fn from_rule(severity: &str) -> Option<Self> {
+        Self::from_str(severity, true).ok()
+    }
+
+    /// Returns the level as written in rules, e.g. `high`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// SIEM destinations supported by `scan --forward`.
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ForwardTarget {
+    /// Splunk HTTP Event Collector.
+    SplunkHec,
+    /// Elasticsearch `_bulk` API.
+    Elastic,
+}
+
+/// Shells supported by `install-shell-integration` and `history`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Enum for selecting the sanitization engine.
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
 pub enum EngineChoice {
     /// The default regular expression engine.
     Regex,
+    /// The regex rules with entropy detection layered under them: where both match,
+    /// the regex rule wins.
+    Combined,
     /// An example of another engine. This would be a future feature.
     Entropy,
 }
\ No newline at end of file
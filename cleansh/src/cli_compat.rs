@@ -0,0 +1,195 @@
+//! Compatibility layer for legacy `cleansh` command-line flags.
+//!
+//! Before the subcommand-based CLI, `cleansh` was invoked with top-level flags only
+//! (e.g. `cleansh --stats-only --enable-rules aws_secret_key`). This module rewrites
+//! such invocations into their modern equivalents (`cleansh scan --enable aws_secret_key`)
+//! before `clap` parses them, and collects a deprecation warning for every legacy
+//! flag it translates, so existing user scripts keep working while being told how
+//! to migrate.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use std::ffi::OsString;
+
+/// Global flags that `clap` expects before the subcommand.
+const GLOBAL_FLAGS: &[&str] = &[
+    "-q",
+    "--quiet",
+    "-d",
+    "--debug",
+    "--disable-debug",
+    "--unsafe-debug-pii",
+    "--disable-donation-prompts",
+    "--suppress-donation-prompt",
+    "--no-epilogue",
+    "--verbose",
+];
+
+/// Global flags that take a separate value argument.
+const GLOBAL_VALUE_FLAGS: &[&str] = &["--theme", "--summary-format", "--seed"];
+
+/// Tokens that already select a subcommand (or short-circuit parsing entirely).
+const SUBCOMMAND_TOKENS: &[&str] = &[
+    "sanitize", "scan", "uninstall", "profiles", "report", "eval", "grep", "rules", "config", "stats", "install-shell-integration", "history", "verify-sanitized", "exec", "run", "help", "-h", "--help", "-V", "--version",
+];
+
+/// Legacy flags that were renamed, mapped to their current spelling.
+const RENAMED_FLAGS: &[(&str, &str)] = &[
+    ("--enable-rules", "--enable"),
+    ("--disable-rules", "--disable"),
+    ("--out", "--output"),
+    ("--stats-json-file", "--json-file"),
+    ("--export-json-to-stdout", "--json-stdout"),
+    ("--fail-over", "--fail-over-threshold"),
+];
+
+/// Legacy flags that only exist in the `scan` command today.
+const SCAN_ONLY_LEGACY_FLAGS: &[&str] = &["--stats-json-file", "--export-json-to-stdout", "--fail-over"];
+
+/// Legacy flags whose behavior is now the default; they are accepted and dropped.
+const REMOVED_NOOP_FLAGS: &[&str] = &["--no-clipboard", "--no-diff"];
+
+/// The result of translating a command line.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LegacyTranslation {
+    /// The rewritten arguments, including the program name, ready for `clap`.
+    pub args: Vec<OsString>,
+    /// One deprecation warning per distinct legacy flag or form that was translated.
+    pub warnings: Vec<String>,
+}
+
+impl LegacyTranslation {
+    fn warn(&mut self, message: String) {
+        if !self.warnings.contains(&message) {
+            self.warnings.push(message);
+        }
+    }
+}
+
+/// Rewrites legacy flags and subcommand-less invocations into the current CLI syntax.
+///
+/// Arguments that are already valid for the current CLI are passed through untouched,
+/// as is everything after a `--` separator.
+pub fn translate_legacy_args<I, T>(args: I) -> LegacyTranslation
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let mut out = LegacyTranslation::default();
+    out.args.extend(args.next());
+
+    let mut wants_scan = false;
+    let mut passthrough = false;
+    for arg in args {
+        let text = match arg.to_str() {
+            Some(text) if !passthrough => text.to_string(),
+            _ => {
+                out.args.push(arg);
+                continue;
+            }
+        };
+        if text == "--" {
+            passthrough = true;
+            out.args.push(arg);
+            continue;
+        }
+
+        let (name, value) = match text.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (text.as_str(), None),
+        };
+
+        if name == "--stats-only" {
+            wants_scan = true;
+            out.warn("'--stats-only' is deprecated and will be removed in a future release; use 'cleansh scan' instead.".to_string());
+        } else if REMOVED_NOOP_FLAGS.contains(&name) {
+            out.warn(format!("'{}' is deprecated and has no effect; this is now the default behavior.", name));
+        } else if let Some((_, new_name)) = RENAMED_FLAGS.iter().find(|(old, _)| *old == name) {
+            wants_scan |= SCAN_ONLY_LEGACY_FLAGS.contains(&name);
+            out.warn(format!("'{}' is deprecated and will be removed in a future release; use '{}' instead.", name, new_name));
+            out.args.push(match value {
+                Some(value) => format!("{}={}", new_name, value).into(),
+                None => (*new_name).into(),
+            });
+        } else {
+            out.args.push(arg);
+        }
+    }
+
+    insert_implicit_subcommand(&mut out, wants_scan);
+    out
+}
+
+/// Inserts `sanitize` (or `scan`, for legacy statistics flags) when the command line has no
+/// subcommand, and switches an explicit `sanitize` to `scan` when `--stats-only` was given.
+fn insert_implicit_subcommand(out: &mut LegacyTranslation, wants_scan: bool) {
+    let mut idx = 1;
+    while idx < out.args.len() {
+        let Some(token) = out.args[idx].to_str() else { break };
+        let flag_name = token.split_once('=').map_or(token, |(name, _)| name);
+        if GLOBAL_FLAGS.contains(&token) {
+            idx += 1;
+        } else if GLOBAL_VALUE_FLAGS.contains(&flag_name) {
+            idx += if flag_name == token { 2 } else { 1 };
+        } else {
+            break;
+        }
+    }
+
+    let Some(token) = out.args.get(idx).map(|arg| arg.to_string_lossy().into_owned()) else {
+        return;
+    };
+    let subcommand = if wants_scan { "scan" } else { "sanitize" };
+
+    if SUBCOMMAND_TOKENS.contains(&token.as_str()) {
+        if wants_scan && token == "sanitize" {
+            out.args[idx] = "scan".into();
+        }
+    } else if token.starts_with('-') {
+        out.warn(format!(
+            "Running cleansh without a subcommand is deprecated and will be removed in a future release; use 'cleansh {} ...' instead.",
+            subcommand
+        ));
+        out.args.insert(idx, subcommand.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(args: &[&str]) -> (Vec<String>, usize) {
+        let result = translate_legacy_args(args.iter().copied());
+        let args = result.args.iter().map(|a| a.to_string_lossy().into_owned()).collect();
+        (args, result.warnings.len())
+    }
+
+    #[test]
+    fn modern_invocations_pass_through_unchanged() {
+        let args = ["cleansh", "-q", "sanitize", "--enable", "a,b", "-o", "out.txt"];
+        let (translated, warnings) = translate(&args);
+        assert_eq!(translated, args);
+        assert_eq!(warnings, 0);
+    }
+
+    #[test]
+    fn stats_only_becomes_scan_and_renames_flags() {
+        let (translated, warnings) = translate(&[
+            "cleansh", "--theme", "t.yaml", "--stats-only", "--enable-rules=a,b", "--fail-over", "3",
+        ]);
+        assert_eq!(
+            translated,
+            ["cleansh", "--theme", "t.yaml", "scan", "--enable=a,b", "--fail-over-threshold", "3"]
+        );
+        // --stats-only, --enable-rules, --fail-over, plus the missing subcommand.
+        assert_eq!(warnings, 4);
+    }
+
+    #[test]
+    fn legacy_flags_inside_subcommands_are_renamed() {
+        let (translated, warnings) = translate(&["cleansh", "sanitize", "--no-clipboard", "--out", "x.txt", "--", "--out"]);
+        assert_eq!(translated, ["cleansh", "sanitize", "--output", "x.txt", "--", "--out"]);
+        assert_eq!(warnings, 2);
+    }
+}
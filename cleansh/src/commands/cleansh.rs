@@ -6,34 +6,90 @@
 //! It orchestrates the flow of data through the redaction pipeline, leveraging
 //! the core logic from the `cleansh-core` crate.
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use log::{debug, info, warn};
 use std::io::{self, Write};
 use std::fs;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 
 // Import from cleansh_core
 use cleansh_core::{
     engine::SanitizationEngine, // Import the SanitizationEngine trait
+    sanitize_passes,
+    Aggregator,
+    AppliedRedaction,
+    PassStop,
+    RedactionConfig,
     RedactionSummaryItem,
+    RuleMetadata,
+    SCHEMA_VERSION,
+    SoftWrap,
 };
+use serde::Serialize;
 
 // Local imports
+use crate::cli::InputFormat;
 use crate::ui::diff_viewer;
 use crate::ui::redaction_summary;
-use crate::ui::output_format;
-use crate::ui::theme::{ThemeMap};
+use crate::ui::output_format::{self, MessageKind};
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+use crate::utils::audit_trail::{AuditTrail, AuditedRedaction};
 use crate::utils::clipboard::copy_to_clipboard;
+use crate::utils::dotenv;
+use crate::utils::eml;
+use crate::utils::json_lines;
+use crate::utils::patch::{self, PatchOptions};
+use crate::utils::platform;
+use crate::utils::encoding::{self, TextEncoding};
+use crate::utils::post_process::PostProcessor;
+use crate::utils::tokenize::Tokenizer;
 use is_terminal::IsTerminal;
 
 /// Grouped options for the new ergonomic API
 pub struct CleanshOptions {
     pub input: String,
     pub clipboard: bool,
+    /// Largest output that is copied to the clipboard (`--clipboard-max-bytes`).
+    pub clipboard_max_bytes: usize,
     pub diff: bool,
     pub output_path: Option<std::path::PathBuf>,
+    /// Also write the output to stdout when `output_path` is set (`--tee`).
+    pub tee: bool,
     pub no_redaction_summary: bool,
     pub quiet: bool,
+    /// Optional path for a JSON export of every applied redaction (`--matches-out`).
+    pub matches_out: Option<std::path::PathBuf>,
+    /// Optional log that each applied redaction is appended to (`--audit-log`), shared by
+    /// every input of a multi-file run.
+    pub audit_log: Option<Rc<RefCell<AuditTrail>>>,
+    /// Name of the input in the audit log: its path, its URL, or `stdin`.
+    pub source: String,
+    /// Optional backend that replaces matches with reversible tokens (`--tokenize`),
+    /// shared by every input of a multi-file run.
+    pub tokenizer: Option<Rc<dyn Tokenizer>>,
+    /// Optional hook that rewrites the sanitized output before it is written (`--post-process`).
+    pub post_processor: Option<Box<dyn PostProcessor>>,
+    /// Terminal width at which hard-wrapped lines are rejoined before matching (`--join-wrapped`).
+    pub join_wrapped: Option<usize>,
+    /// How the input is interpreted (`--input-format`).
+    pub input_format: InputFormat,
+    /// With `--input-format diff`, also sanitize paths and hunk headings (`--diff-headers`).
+    pub diff_headers: bool,
+    /// With `--input-format dotenv`, keys whose values are kept (`--dotenv-allow`).
+    pub dotenv_allow: Vec<String>,
+    /// Encoding used for the primary output; the diff view is always UTF-8.
+    pub output_encoding: TextEncoding,
+    /// Maximum number of sanitization passes (`--passes`); 1 disables re-running.
+    pub passes: usize,
+    /// Fail instead of writing output that the rules would still change (`--verify-idempotent`).
+    pub verify_idempotent: bool,
+    /// Hold back all output until the whole run has succeeded (`--strict-containment`).
+    pub strict_containment: bool,
+    /// The output replaces the input file (`--in-place`), so it is written exactly as
+    /// sanitized, without the trailing newline added for terminal output.
+    pub in_place: bool,
 }
 
 /// Helper for printing info messages to stderr.
@@ -42,6 +98,12 @@ pub fn info_msg(msg: impl AsRef<str>, theme: &ThemeMap) {
     let _ = output_format::print_info_message(&mut std::io::stderr(), msg.as_ref(), theme, stderr_supports_color);
 }
 
+/// Helper for printing progress messages to stderr; suppressed like info messages.
+pub fn progress_msg(msg: impl AsRef<str>, theme: &ThemeMap) {
+    let stderr_supports_color = io::stderr().is_terminal();
+    let _ = output_format::print_message(&mut std::io::stderr(), msg.as_ref(), theme, Some(ThemeEntry::Progress), stderr_supports_color);
+}
+
 /// Helper for printing error messages to stderr.
 pub fn error_msg(msg: impl AsRef<str>, theme: &ThemeMap) {
     let stderr_supports_color = io::stderr().is_terminal();
@@ -54,45 +116,98 @@ pub fn warn_msg(msg: impl AsRef<str>, theme: &ThemeMap) {
     let _ = output_format::print_warn_message(&mut std::io::stderr(), msg.as_ref(), theme, stderr_supports_color);
 }
 
-/// Handles writing sanitized content to the primary output destination (stdout or file).
-fn handle_primary_output(
-    opts: &CleanshOptions,
-    sanitized_content: &str,
-    theme_map: &ThemeMap,
-) -> Result<()> {
-    if let Some(path) = opts.output_path.clone() {
-        info_msg(format!("Writing sanitized content to file: {}", path.display()), theme_map);
-        debug!("[cleansh::commands::cleansh] Outputting to file: {}", path.display());
-        let mut file = fs::File::create(&path)
-            .with_context(|| format!("Failed to create output file: {}", path.display()))?;
-        
-        if opts.diff {
-            debug!("Generating and displaying diff.");
-            diff_viewer::print_diff(&opts.input, sanitized_content, &mut file, theme_map, false)?;
-        } else {
-            writeln!(file, "{}", sanitized_content)
-                .context("Failed to write sanitized content")?;
-        }
+/// Output rendered in memory and written only once the run has produced it completely.
+///
+/// Rendering first means a failure (in the diff view, say) never leaves a half-written
+/// destination; with `--strict-containment` every write of a run is held back until all
+/// of its inputs have been sanitized.
+struct PendingWrite {
+    /// Destination file, or stdout when `None`.
+    path: Option<std::path::PathBuf>,
+    bytes: Vec<u8>,
+    /// Progress message printed just before the write.
+    note: Option<String>,
+}
+
+/// Renders the sanitized content (or the diff view) for an output destination: `path`,
+/// or stdout when `None`.
+fn render_primary_output(opts: &CleanshOptions, path: Option<&std::path::Path>, sanitized_content: &str, theme_map: &ThemeMap) -> Result<PendingWrite> {
+    let mut bytes = Vec::new();
+    if opts.diff {
+        debug!("Generating and displaying diff.");
+        let supports_color = path.is_none() && io::stdout().is_terminal();
+        diff_viewer::print_diff(&opts.input, sanitized_content, &mut bytes, theme_map, supports_color)?;
+    } else if opts.in_place {
+        bytes = encoding::encode(sanitized_content, opts.output_encoding);
     } else {
-        info_msg("Writing sanitized content to stdout.", theme_map);
-        debug!("[cleansh::commands::cleansh] Outputting to stdout.");
-        let stdout = io::stdout();
-        let mut writer = stdout.lock();
-        let supports_color = stdout.is_terminal();
-        
-        if opts.diff {
-            debug!("Generating and displaying diff.");
-            diff_viewer::print_diff(&opts.input, sanitized_content, &mut writer, theme_map, supports_color)?;
-        } else {
-            writeln!(writer, "{}", sanitized_content)
-                .context("Failed to write sanitized content")?;
-        }
+        bytes = encoding::encode(&format!("{}\n", sanitized_content), opts.output_encoding);
+    }
+    let note = match path {
+        Some(path) => format!("Writing sanitized content to file: {}", path.display()),
+        None => "Writing sanitized content to stdout.".to_string(),
     };
+    Ok(PendingWrite { path: path.map(|path| path.to_path_buf()), bytes, note: Some(note) })
+}
+
+/// Writes staged output to its destinations.
+///
+/// With `strict`, files are written to a temporary sibling and renamed into place, so an
+/// error part-way through leaves neither a truncated file nor a partial secret behind.
+fn commit_writes(writes: Vec<PendingWrite>, strict: bool, theme_map: &ThemeMap) -> Result<()> {
+    for write in writes {
+        if let Some(note) = &write.note {
+            progress_msg(note, theme_map);
+        }
+        match &write.path {
+            Some(path) if strict => {
+                let long = platform::long_path(path);
+                let tmp = long.with_file_name(format!(
+                    ".{}.cleansh-tmp",
+                    path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default()
+                ));
+                let result = fs::write(&tmp, &write.bytes).and_then(|_| fs::rename(&tmp, &long));
+                if result.is_err() {
+                    let _ = fs::remove_file(&tmp);
+                }
+                result.with_context(|| format!("Failed to write output file: {}", path.display()))?;
+            }
+            Some(path) => {
+                debug!("[cleansh::commands::cleansh] Outputting to file: {}", path.display());
+                let mut file = fs::File::create(platform::long_path(path))
+                    .with_context(|| format!("Failed to create output file: {}", path.display()))?;
+                file.write_all(&write.bytes).context("Failed to write sanitized content")?;
+            }
+            None => {
+                debug!("[cleansh::commands::cleansh] Outputting to stdout.");
+                io::stdout().lock().write_all(&write.bytes).context("Failed to write sanitized content")?;
+            }
+        }
+    }
     Ok(())
 }
 
 /// Handles copying sanitized content to the clipboard.
-fn handle_clipboard_output(sanitized_content: &str, theme_map: &ThemeMap) {
+///
+/// Output larger than `opts.clipboard_max_bytes` is not copied; it has already been
+/// written to the primary output, so the user is only warned.
+fn handle_clipboard_output(opts: &CleanshOptions, sanitized_content: &str, theme_map: &ThemeMap) {
+    if sanitized_content.len() > opts.clipboard_max_bytes {
+        let destination = opts
+            .output_path
+            .as_ref()
+            .map_or_else(|| "stdout".to_string(), |path| path.display().to_string());
+        warn!("Skipping clipboard copy: {} bytes exceeds the {} byte limit.", sanitized_content.len(), opts.clipboard_max_bytes);
+        warn_msg(
+            format!(
+                "Sanitized output is {} bytes, above the clipboard limit of {} bytes (--clipboard-max-bytes); it was not copied. The output was written to {} instead.",
+                sanitized_content.len(),
+                opts.clipboard_max_bytes,
+                destination
+            ),
+            theme_map,
+        );
+        return;
+    }
     debug!("Attempting to copy sanitized content to clipboard.");
     match copy_to_clipboard(sanitized_content) {
         Ok(_) => {
@@ -106,13 +221,232 @@ fn handle_clipboard_output(sanitized_content: &str, theme_map: &ThemeMap) {
     }
 }
 
+/// Sanitizes `input`, replacing every applied match with a token from `tokenizer`.
+///
+/// Each distinct secret is tokenized once, in a single batch, before the engine splices
+/// the tokens in. If a match has no token, its regular placeholder is used instead, so
+/// the original value can never leak through.
+fn sanitize_with_tokens(
+    engine: &dyn SanitizationEngine,
+    tokenizer: &dyn Tokenizer,
+    input: &str,
+) -> Result<(String, Vec<RedactionSummaryItem>, Vec<AppliedRedaction>)> {
+    let matches = engine.find_matches_for_ui(input, "").context("Sanitization failed")?;
+    let mut secrets: Vec<&str> = Vec::new();
+    for m in &matches {
+        if !secrets.contains(&m.original_string.as_str()) {
+            secrets.push(&m.original_string);
+        }
+    }
+
+    debug!("Tokenizing {} distinct value(s) with the '{}' backend.", secrets.len(), tokenizer.name());
+    let tokens = tokenizer
+        .tokenize(&secrets)
+        .with_context(|| format!("Tokenization with the '{}' backend failed", tokenizer.name()))?;
+    let lookup: HashMap<&str, &str> = secrets.iter().copied().zip(tokens.iter().map(String::as_str)).collect();
+
+    let (sanitized_content, edits) = engine
+        .sanitize_with(input, "", &mut |m| {
+            lookup
+                .get(m.original_string.as_str())
+                .map(|token| token.to_string())
+                .unwrap_or_else(|| m.sanitized_string.clone())
+        })
+        .context("Sanitization failed")?;
+    let summary = engine.analyze_for_stats(input, "").context("Sanitization failed")?;
+    Ok((sanitized_content, summary, edits))
+}
+
+/// Sanitizes an email message for `--input-format eml`.
+///
+/// Each decoded text part and selected header is passed through the engine separately;
+/// their summaries are merged by rule.
+fn sanitize_eml(
+    engine: &dyn SanitizationEngine,
+    input: &str,
+    theme_map: &ThemeMap,
+) -> Result<(String, Vec<RedactionSummaryItem>)> {
+    let mut summary = Aggregator::new();
+    let output = eml::sanitize_message(input, &mut |text| {
+        let (sanitized, items) = engine.sanitize(text, "", "", "", "", "", "", None)?;
+        summary.add_summary(items);
+        Ok(sanitized)
+    })
+    .context("Sanitization failed")?;
+
+    if output.skipped_parts > 0 {
+        warn_msg(
+            format!("Left {} text part(s) unchanged because they could not be decoded.", output.skipped_parts),
+            theme_map,
+        );
+    }
+    Ok((output.message, summary.into_summary()))
+}
+
+/// Sanitizes a unified diff for `--input-format diff`.
+///
+/// Each run of added lines is passed through the engine separately; their summaries are
+/// merged by rule.
+fn sanitize_diff(
+    engine: &dyn SanitizationEngine,
+    input: &str,
+    options: PatchOptions,
+    theme_map: &ThemeMap,
+) -> Result<(String, Vec<RedactionSummaryItem>)> {
+    let mut summary = Aggregator::new();
+    let output = patch::sanitize_patch(
+        input,
+        options,
+        &mut |text| {
+            let (sanitized, items) = engine.sanitize(text, "", "", "", "", "", "", None)?;
+            summary.add_summary(items);
+            Ok(sanitized)
+        },
+        &mut |text| Ok(!engine.find_matches_for_ui(text, "")?.is_empty()),
+    )
+    .context("Sanitization failed")?;
+
+    if output.unchanged_matches > 0 {
+        warn_msg(
+            format!(
+                "Left {} context or removed line(s) with matches unchanged so the patch still applies; regenerate it with less context (e.g. `git diff -U0`) to leave context lines out.",
+                output.unchanged_matches
+            ),
+            theme_map,
+        );
+    }
+    Ok((output.patch, summary.into_summary()))
+}
+
+/// Sanitizes a `.env` file for `--input-format dotenv`.
+///
+/// Replaced values are counted under [`dotenv::DOTENV_RULE_NAME`]; matches in comments and
+/// in the values of allowed keys are merged with them by rule.
+fn sanitize_dotenv(
+    engine: &dyn SanitizationEngine,
+    input: &str,
+    allowed_keys: &[String],
+) -> Result<(String, Vec<RedactionSummaryItem>)> {
+    let mut summary = Aggregator::new();
+    let output = dotenv::sanitize_dotenv(input, allowed_keys, &mut |text| {
+        let (sanitized, items) = engine.sanitize(text, "", "", "", "", "", "", None)?;
+        summary.add_summary(items);
+        Ok(sanitized)
+    })
+    .context("Sanitization failed")?;
+
+    for value in output.redacted {
+        summary.add_item(RedactionSummaryItem {
+            rule_name: dotenv::DOTENV_RULE_NAME.to_string(),
+            occurrences: 1,
+            original_texts: vec![value],
+            sanitized_texts: vec![dotenv::VALUE_PLACEHOLDER.to_string()],
+        });
+    }
+    Ok((output.text, summary.into_summary()))
+}
+
+/// Sanitizes JSON-lines input for `--input-format json`.
+///
+/// Each string value is passed through the engine separately; their summaries are merged
+/// by rule.
+fn sanitize_json(
+    engine: &dyn SanitizationEngine,
+    input: &str,
+    theme_map: &ThemeMap,
+) -> Result<(String, Vec<RedactionSummaryItem>)> {
+    let mut summary = Aggregator::new();
+    let output = json_lines::sanitize_json_lines(input, &mut |text| {
+        let (sanitized, items) = engine.sanitize(text, "", "", "", "", "", "", None)?;
+        summary.add_summary(items);
+        Ok(sanitized)
+    })
+    .context("Sanitization failed")?;
+
+    if output.text_lines > 0 {
+        warn_msg(
+            format!("Sanitized {} line(s) that are not valid JSON as plain text.", output.text_lines),
+            theme_map,
+        );
+    }
+    Ok((output.text, summary.into_summary()))
+}
+
+/// The `--matches-out` JSON document.
+#[derive(Serialize)]
+struct MatchesExport<'a> {
+    schema_version: &'static str,
+    /// Byte length of the original input that the offsets refer to.
+    input_length: usize,
+    /// Byte length of the sanitized output produced by applying `matches` in order.
+    sanitized_length: usize,
+    matches: &'a [AppliedRedaction],
+    /// Version, author, timestamps and tags of every rule that appears in `matches`.
+    rules: BTreeMap<String, RuleMetadata>,
+}
+
+/// Renders the applied redactions as pretty-printed JSON for `--matches-out`.
+///
+/// Offsets are byte offsets into the original input; no original sensitive text is written.
+fn render_matches_file(input: &str, sanitized: &str, edits: &[AppliedRedaction], config: &RedactionConfig) -> Result<Vec<u8>> {
+    let export = MatchesExport {
+        schema_version: SCHEMA_VERSION,
+        input_length: input.len(),
+        sanitized_length: sanitized.len(),
+        matches: edits,
+        rules: config.rule_metadata(edits.iter().map(|edit| edit.rule_name.as_str())),
+    };
+    let json = serde_json::to_string_pretty(&export).context("Failed to serialize applied redactions")?;
+    Ok(json.into_bytes())
+}
+
+/// Reports how a multi-pass (`--passes`) sanitization ended.
+pub fn report_passes(passes: usize, stop: PassStop, theme_map: &ThemeMap) {
+    match stop {
+        PassStop::Fixpoint => info_msg(format!("Output stabilized after {} pass(es).", passes), theme_map),
+        PassStop::Limit => warn_msg(
+            format!("Output was still changing after {} pass(es); raise --passes or use --passes fixpoint.", passes),
+            theme_map,
+        ),
+        PassStop::Cycle => warn_msg(
+            format!("Rules rewrite each other's replacements in a loop; stopped after {} pass(es).", passes),
+            theme_map,
+        ),
+    }
+}
+
+/// Re-scans sanitized output for `--verify-idempotent`.
+///
+/// Returns an error naming each rule that still matches, so sanitizing the output again
+/// would change it.
+pub fn verify_idempotent(engine: &dyn SanitizationEngine, sanitized: &str) -> Result<()> {
+    let leftover = engine
+        .find_matches_for_ui(sanitized, "")
+        .context("Failed to re-scan sanitized output")?;
+    if leftover.is_empty() {
+        return Ok(());
+    }
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for m in &leftover {
+        match counts.iter_mut().find(|(rule, _)| *rule == m.rule_name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((m.rule_name.clone(), 1)),
+        }
+    }
+    let rules: Vec<String> = counts.iter().map(|(rule, count)| format!("{} ({})", rule, count)).collect();
+    Err(anyhow!(
+        "Sanitized output is not idempotent; rules still match it: {}. Use --passes fixpoint or adjust the overlapping rules.",
+        rules.join(", ")
+    ))
+}
+
 /// Displays the redaction summary to stderr.
 fn handle_redaction_summary(
     summary: &[RedactionSummaryItem],
     opts: &CleanshOptions,
     theme_map: &ThemeMap,
 ) -> Result<()> {
-    if !opts.no_redaction_summary && !opts.quiet {
+    if !opts.no_redaction_summary && !opts.quiet && output_format::should_print(MessageKind::Summary) {
         info!("Displaying redaction summary.");
         let stderr_supports_color = io::stderr().is_terminal();
         redaction_summary::print_summary(&summary, &mut io::stderr(), theme_map, stderr_supports_color)?;
@@ -130,18 +464,168 @@ pub fn run_cleansh_opts(
     theme_map: &ThemeMap,
 ) -> Result<()> {
     info!("Starting cleansh operation.");
+    let summary = sanitize_and_write(engine, &opts, theme_map)?;
+    handle_redaction_summary(&summary, &opts, theme_map)?;
+    info!("Cleansh operation completed.");
+    Ok(())
+}
 
-    let (sanitized_content, summary) = engine.sanitize(
-        &opts.input,
-        "",
-        "",
-        "",
-        "",
-        "",
-        "",
-        None,
-    )
-    .context("Sanitization failed")?;
+/// Runs `cleansh sanitize FILE...`: sanitizes each named input in turn.
+///
+/// Each entry pairs a display name with the options for that input. Entries are read one
+/// at a time, so only one input is held in memory unless `strict` (`--strict-containment`)
+/// holds back every write until all inputs are sanitized. Inputs written to stdout are
+/// preceded by a `==> name <==` header, as `head` and `tail` do, and a single redaction
+/// summary covering every input is printed at the end.
+pub fn run_cleansh_files(
+    engine: &dyn SanitizationEngine,
+    inputs: impl IntoIterator<Item = Result<(String, CleanshOptions)>>,
+    strict: bool,
+    theme_map: &ThemeMap,
+) -> Result<()> {
+    let mut staged = Vec::new();
+    let mut combined = Aggregator::new();
+    let mut last = None;
+    for (index, input) in inputs.into_iter().enumerate() {
+        let (name, opts) = input?;
+        if opts.output_path.is_none() {
+            let separator = if index == 0 { "" } else { "\n" };
+            staged.push(PendingWrite { path: None, bytes: format!("{}==> {} <==\n", separator, name).into_bytes(), note: None });
+        }
+        let (summary, _) = sanitize_and_stage(engine, &opts, theme_map, &mut staged)
+            .with_context(|| format!("Failed to sanitize {}", name))?;
+        if !strict {
+            commit_writes(std::mem::take(&mut staged), false, theme_map)?;
+        }
+        combined.add_summary(summary);
+        last = Some(opts);
+    }
+    commit_writes(staged, strict, theme_map)?;
+    if let Some(opts) = last {
+        handle_redaction_summary(&combined.into_summary(), &opts, theme_map)?;
+    }
+    Ok(())
+}
+
+/// Sanitizes one input and writes it to its destinations, returning the redaction summary.
+fn sanitize_and_write(
+    engine: &dyn SanitizationEngine,
+    opts: &CleanshOptions,
+    theme_map: &ThemeMap,
+) -> Result<Vec<RedactionSummaryItem>> {
+    let mut staged = Vec::new();
+    let (summary, sanitized_content) = sanitize_and_stage(engine, opts, theme_map, &mut staged)?;
+    commit_writes(staged, opts.strict_containment, theme_map)?;
+
+    if opts.clipboard {
+        handle_clipboard_output(opts, &sanitized_content, theme_map);
+    }
+    Ok(summary)
+}
+
+/// Sanitizes one input and stages its output in `staged`, returning the redaction summary
+/// and the sanitized text.
+fn sanitize_and_stage(
+    engine: &dyn SanitizationEngine,
+    opts: &CleanshOptions,
+    theme_map: &ThemeMap,
+    staged: &mut Vec<PendingWrite>,
+) -> Result<(Vec<RedactionSummaryItem>, String)> {
+    // With --join-wrapped, the engine sees the rejoined text and the line breaks are
+    // restored from the applied edits afterwards.
+    let soft_wrap = opts.join_wrapped.map(|width| SoftWrap::join(&opts.input, width));
+    let input = soft_wrap.as_ref().map_or(opts.input.as_str(), SoftWrap::text);
+
+    let (sanitized_content, summary, edits) = if opts.input_format == InputFormat::Eml {
+        if opts.tokenizer.is_some() {
+            return Err(anyhow!("--tokenize cannot be combined with --input-format eml"));
+        }
+        let (sanitized_content, summary) = sanitize_eml(engine, &opts.input, theme_map)?;
+        (sanitized_content, summary, None)
+    } else if opts.input_format == InputFormat::Diff {
+        if opts.tokenizer.is_some() {
+            return Err(anyhow!("--tokenize cannot be combined with --input-format diff"));
+        }
+        let options = PatchOptions { headers: opts.diff_headers };
+        let (sanitized_content, summary) = sanitize_diff(engine, &opts.input, options, theme_map)?;
+        (sanitized_content, summary, None)
+    } else if opts.input_format == InputFormat::Dotenv {
+        if opts.tokenizer.is_some() {
+            return Err(anyhow!("--tokenize cannot be combined with --input-format dotenv"));
+        }
+        let (sanitized_content, summary) = sanitize_dotenv(engine, &opts.input, &opts.dotenv_allow)?;
+        (sanitized_content, summary, None)
+    } else if opts.input_format == InputFormat::Json {
+        if opts.tokenizer.is_some() {
+            return Err(anyhow!("--tokenize cannot be combined with --input-format json"));
+        }
+        let (sanitized_content, summary) = sanitize_json(engine, &opts.input, theme_map)?;
+        (sanitized_content, summary, None)
+    } else if let Some(tokenizer) = opts.tokenizer.as_deref() {
+        if opts.passes > 1 {
+            return Err(anyhow!("--passes cannot be combined with --tokenize"));
+        }
+        let (sanitized_content, summary, edits) = sanitize_with_tokens(engine, tokenizer, input)?;
+        (sanitized_content, summary, Some(edits))
+    } else if opts.matches_out.is_some() || opts.audit_log.is_some() || soft_wrap.is_some() {
+        let (sanitized_content, summary, edits) = engine
+            .sanitize_with_edits(input, "")
+            .context("Sanitization failed")?;
+        (sanitized_content, summary, Some(edits))
+    } else if opts.passes > 1 {
+        let outcome = sanitize_passes(engine, input, "", opts.passes).context("Sanitization failed")?;
+        report_passes(outcome.passes, outcome.stop, theme_map);
+        (outcome.sanitized, outcome.summary, None)
+    } else {
+        let (sanitized_content, summary) = engine.sanitize(
+            input,
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            None,
+        )
+        .context("Sanitization failed")?;
+        (sanitized_content, summary, None)
+    };
+
+    let (sanitized_content, edits) = match (soft_wrap.as_ref(), edits) {
+        (Some(wrap), Some(edits)) => {
+            if wrap.has_joins() {
+                debug!("Rejoined hard-wrapped lines before matching.");
+            }
+            let (restored, edits) = wrap.restore(&edits);
+            (restored, Some(edits))
+        }
+        (_, edits) => (sanitized_content, edits),
+    };
+
+    if opts.verify_idempotent {
+        verify_idempotent(engine, &sanitized_content)?;
+    }
+
+    if let (Some(audit_log), Some(edits)) = (opts.audit_log.as_ref(), edits.as_ref()) {
+        let redactions = edits.iter().map(|edit| AuditedRedaction {
+            rule_name: &edit.rule_name,
+            original: opts.input.get(edit.start as usize..edit.end as usize).unwrap_or_default(),
+            replacement: &edit.replacement,
+            start: edit.start,
+            end: edit.end,
+        });
+        audit_log.borrow_mut().record(&opts.source, &opts.input, engine.get_rules(), redactions)?;
+    }
+
+    // The matches file describes the engine's output, so it is rendered from the text
+    // before post-processing.
+    if let (Some(path), Some(edits)) = (opts.matches_out.as_ref(), edits.as_ref()) {
+        staged.push(PendingWrite {
+            path: Some(path.clone()),
+            bytes: render_matches_file(&opts.input, &sanitized_content, edits, engine.get_rules())?,
+            note: Some(format!("Writing {} applied redaction(s) to {}", edits.len(), path.display())),
+        });
+    }
 
     debug!(
         "Content sanitized. Original length: {}, Sanitized length: {}",
@@ -149,16 +633,18 @@ pub fn run_cleansh_opts(
         sanitized_content.len()
     );
     
-    handle_primary_output(&opts, &sanitized_content, theme_map)?;
+    let sanitized_content = match opts.post_processor.as_deref() {
+        Some(post_processor) => post_processor
+            .process(&sanitized_content, &summary)
+            .with_context(|| format!("Post-processing with '{}' failed", post_processor.name()))?,
+        None => sanitized_content,
+    };
 
-    if opts.clipboard {
-        handle_clipboard_output(&sanitized_content, theme_map);
+    staged.push(render_primary_output(opts, opts.output_path.as_deref(), &sanitized_content, theme_map)?);
+    if opts.tee && opts.output_path.is_some() {
+        staged.push(render_primary_output(opts, None, &sanitized_content, theme_map)?);
     }
-    
-    handle_redaction_summary(&summary, &opts, theme_map)?;
-    
-    info!("Cleansh operation completed.");
-    Ok(())
+    Ok((summary, sanitized_content))
 }
 
 /// Sanitizes a single line of input using the provided compiled rules, returning a map of matched rules.
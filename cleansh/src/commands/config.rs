@@ -0,0 +1,208 @@
+//! This module handles the `config` subcommand: `config init` writes a commented starter
+//! rules file, `config validate` checks rules files and reports every problem with the line
+//! of the rule it was found in, and `config show` prints a rule set as YAML.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{ConfigInitCommand, ConfigValidateCommand};
+use crate::commands::cleansh::info_msg;
+use crate::ui::theme::ThemeMap;
+use crate::utils::rules_source;
+use anyhow::{bail, Context, Result};
+use cleansh_core::config::RedactionConfig;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The file `config init` writes. Every rule in it is valid, so it can be used as it is.
+pub const STARTER_CONFIG: &str = r#"# cleansh rules file.
+#
+# Use it with `cleansh sanitize --config <file>` or `cleansh scan --config <file>`, and
+# check it after editing with `cleansh config validate <file>`. Its rules are added to the
+# built-in ones; a rule named like a built-in rule replaces it. To see the rule set a run
+# will use, run `cleansh config show --effective --config <file>`.
+rules:
+  # A rule needs a unique name, a regex pattern, and the text each match is replaced with.
+  - name: employee_id
+    description: "Internal employee IDs such as EMP-12345."
+    pattern: '\bEMP-\d{5}\b'
+    replace_with: '[EMPLOYEE_ID]'
+    severity: low
+
+  # replace_with can keep capture groups of the pattern ($1, ${name}) and use the
+  # placeholders {rule_name}, {match_index} and {hash8}, the first 8 hex digits of the
+  # SHA-256 of the match, so every occurrence of one value gets the same placeholder.
+  - name: ticket_token
+    description: "Tokens of the ticket system, keeping their prefix."
+    pattern: '\b(tkt_)[A-Za-z0-9]{24}\b'
+    replace_with: '${1}[TOKEN#{hash8}]'
+    severity: high
+    # Only match when one of these words is within 40 bytes before the token.
+    context:
+      before: 40
+      keywords: ["ticket", "token"]
+
+  # Opt-in rules only run with `--enable <name>` or `--strict`.
+  - name: internal_host
+    pattern: '\b[a-z0-9-]+\.corp\.example\.com\b'
+    replace_with: '[INTERNAL_HOST]'
+    opt_in: true
+
+# Groups name several rules at once for --enable and --disable.
+groups:
+  internal: [employee_id, ticket_token, internal_host]
+"#;
+
+/// A problem found by `config validate`, with the line it was found on if known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Returns the line numbers, from 1, of the entries of the top-level `rules` list, in order.
+///
+/// Only a block list (`rules:` followed by `- name: ...` lines) is recognized; for a list
+/// written on one line, no lines are returned.
+pub fn rule_lines(text: &str) -> Vec<usize> {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(key) = top_level_key_line(&lines, "rules") else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    let mut indent = None;
+    for (index, line) in lines.iter().enumerate().skip(key + 1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let width = line.len() - trimmed.len();
+        if width == 0 && !trimmed.starts_with('-') {
+            break;
+        }
+        if trimmed.starts_with('-') && *indent.get_or_insert(width) == width {
+            entries.push(index + 1);
+        }
+    }
+    entries
+}
+
+/// Returns the index of the line on which the top-level `key` is defined.
+fn top_level_key_line(lines: &[&str], key: &str) -> Option<usize> {
+    lines.iter().position(|line| line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with(':')))
+}
+
+/// Checks the rules file `text`, returning the number of rules it defines and every
+/// problem found in it.
+pub fn check_config(text: &str) -> (usize, Vec<Problem>) {
+    let config: RedactionConfig = match serde_yaml::from_str(text) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.location().map(|location| location.line());
+            return (0, vec![Problem { line, message: format!("The file is not a valid rules file: {}", e) }]);
+        }
+    };
+    let entries = rule_lines(text);
+    let groups = top_level_key_line(&text.lines().collect::<Vec<_>>(), "groups").map(|index| index + 1);
+    let problems = config
+        .problems()
+        .into_iter()
+        .map(|problem| Problem {
+            line: match problem.rule_index {
+                Some(index) => entries.get(index).copied(),
+                None => groups,
+            },
+            message: problem.message,
+        })
+        .collect();
+    (config.rules.len(), problems)
+}
+
+/// The main entry point for `cleansh config init`.
+pub fn run_config_init(opts: &ConfigInitCommand, theme_map: &ThemeMap) -> Result<()> {
+    if rules_source::is_stdin(&opts.path) {
+        print!("{}", STARTER_CONFIG);
+        return Ok(());
+    }
+    if opts.path.exists() && !opts.force {
+        bail!("{} already exists; pass --force to overwrite it", opts.path.display());
+    }
+    fs::write(&opts.path, STARTER_CONFIG).with_context(|| format!("Failed to write {}", opts.path.display()))?;
+    info_msg(
+        format!(
+            "Wrote starter rules to {}. Edit them, then check the file with `cleansh config validate {}`.",
+            opts.path.display(),
+            opts.path.display()
+        ),
+        theme_map,
+    );
+    Ok(())
+}
+
+/// Reads the rules file at `path`, or from stdin if `path` is `-`.
+fn read_config_text(path: &Path) -> Result<String> {
+    if rules_source::is_stdin(path) {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text).context("Failed to read rules from stdin")?;
+        return Ok(text);
+    }
+    fs::read_to_string(path).with_context(|| format!("Failed to read config file {}", path.display()))
+}
+
+/// The main entry point for `cleansh config validate`. Prints one `FILE:LINE: message` line
+/// per problem to stdout and fails if any were found.
+pub fn run_config_validate(opts: &ConfigValidateCommand, theme_map: &ThemeMap) -> Result<()> {
+    rules_source::ensure_stdin_available(&opts.files, false)?;
+    let mut total = 0;
+    for path in &opts.files {
+        let name = if rules_source::is_stdin(path) { "<stdin>".to_string() } else { path.display().to_string() };
+        let (rules, problems) = check_config(&read_config_text(path)?);
+        if problems.is_empty() {
+            info_msg(format!("{}: {} rule(s), no problems found.", name, rules), theme_map);
+        }
+        for problem in &problems {
+            match problem.line {
+                Some(line) => println!("{}:{}: {}", name, line, problem.message),
+                None => println!("{}: {}", name, problem.message),
+            }
+        }
+        total += problems.len();
+    }
+    if total > 0 {
+        bail!("Found {} problem(s) in the rules file(s)", total);
+    }
+    Ok(())
+}
+
+/// Prints `config` as YAML, for `cleansh config show`.
+pub fn print_config(config: &RedactionConfig) -> Result<()> {
+    print!("{}", serde_yaml::to_string(config).context("Failed to serialize the rules")?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starter_config_is_valid() {
+        let (rules, problems) = check_config(STARTER_CONFIG);
+        assert_eq!(rules, 3);
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(rule_lines(STARTER_CONFIG).len(), 3);
+    }
+
+    #[test]
+    fn reports_problems_on_the_line_of_their_rule() {
+        let text = "# team rules\nrules:\n  - name: ticket\n    pattern: 'T-[0-9]+'\n    replace_with: '[T]'\n\n  # broken\n  - name: broken\n    pattern: '('\n    replace_with: '{hash}'\ngroups:\n  ops: []\n";
+        let (rules, problems) = check_config(text);
+        assert_eq!(rules, 2);
+        let lines: Vec<Option<usize>> = problems.iter().map(|problem| problem.line).collect();
+        assert_eq!(lines, [Some(8), Some(11)], "{:?}", problems);
+        assert!(problems[0].message.contains("invalid regex pattern"), "{:?}", problems);
+
+        let (_, problems) = check_config("rules:\n  - name: a\n    pattern: [1]\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(3), "{:?}", problems);
+    }
+}
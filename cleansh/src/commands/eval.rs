@@ -0,0 +1,273 @@
+//! This module handles the `eval` subcommand, a developer tool that runs the active rules
+//! over a labeled corpus and reports precision and recall per rule. It turns "this regex
+//! looks better" into numbers, and `--min-precision`/`--min-recall` let CI block a rule
+//! change that makes detection worse.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::EvalCommand;
+use crate::commands::cleansh::{info_msg, warn_msg};
+use crate::ui::theme::ThemeMap;
+use crate::utils::encoding;
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::SCHEMA_VERSION;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// One expected secret in the ground-truth file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Label {
+    /// Path of the file, relative to the corpus directory, with `/` separators.
+    pub file: String,
+    pub rule: String,
+    /// Byte offsets of the secret in the file's decoded text.
+    pub start: u64,
+    pub end: u64,
+}
+
+/// A span the rules redacted in a corpus file.
+#[derive(Debug, Clone)]
+pub struct Prediction {
+    pub file: String,
+    pub rule: String,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Detection counts for one rule.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RuleScore {
+    pub rule: String,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+}
+
+impl RuleScore {
+    /// Share of redactions that were expected; `None` when the rule redacted nothing.
+    pub fn precision(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    /// Share of expected secrets that were redacted; `None` when nothing was labeled.
+    pub fn recall(&self) -> Option<f64> {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+/// Scores predictions against labels, one row per rule in name order.
+///
+/// A prediction is a true positive when it has the same file and rule as a label and the
+/// spans overlap; each label is claimed by at most one prediction. Predictions left over
+/// are false positives and unclaimed labels are false negatives. A label attributed to
+/// the wrong rule therefore counts against both rules.
+pub fn score(predictions: &[Prediction], labels: &[Label]) -> Vec<RuleScore> {
+    let mut scores: BTreeMap<&str, RuleScore> = BTreeMap::new();
+    let mut claimed = vec![false; labels.len()];
+
+    for prediction in predictions {
+        let entry = scores.entry(prediction.rule.as_str()).or_default();
+        let hit = labels.iter().enumerate().position(|(i, label)| {
+            !claimed[i]
+                && label.file == prediction.file
+                && label.rule == prediction.rule
+                && label.start < prediction.end
+                && prediction.start < label.end
+        });
+        match hit {
+            Some(i) => {
+                claimed[i] = true;
+                entry.true_positives += 1;
+            }
+            None => entry.false_positives += 1,
+        }
+    }
+    for (label, claimed) in labels.iter().zip(claimed) {
+        if !claimed {
+            scores.entry(label.rule.as_str()).or_default().false_negatives += 1;
+        }
+    }
+
+    scores
+        .into_iter()
+        .map(|(rule, score)| RuleScore { rule: rule.to_string(), ..score })
+        .collect()
+}
+
+/// Lists the files under `dir`, sorted, as (path relative to `dir` with `/` separators, full path).
+fn corpus_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current)
+            .with_context(|| format!("Failed to read corpus directory: {}", current.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                let relative = path.strip_prefix(dir).unwrap_or(&path);
+                let name = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+                files.push((name, path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Prints the scores as an aligned table, followed by the totals over all rules.
+fn print_table<W: Write>(scores: &[RuleScore], total: &RuleScore, writer: &mut W) -> Result<()> {
+    let format = |value: Option<f64>| value.map_or_else(|| "-".to_string(), |v| format!("{:.3}", v));
+    let width = scores.iter().map(|s| s.rule.len()).chain(["overall".len(), "rule".len()]).max().unwrap_or(0);
+
+    writeln!(writer, "{:<width$}  {:>5}  {:>5}  {:>5}  {:>9}  {:>6}", "rule", "TP", "FP", "FN", "precision", "recall")?;
+    for row in scores.iter().chain(std::iter::once(total)) {
+        writeln!(
+            writer,
+            "{:<width$}  {:>5}  {:>5}  {:>5}  {:>9}  {:>6}",
+            row.rule,
+            row.true_positives,
+            row.false_positives,
+            row.false_negatives,
+            format(row.precision()),
+            format(row.recall()),
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    #[serde(flatten)]
+    score: &'a RuleScore,
+    precision: Option<f64>,
+    recall: Option<f64>,
+}
+
+impl<'a> From<&'a RuleScore> for JsonRow<'a> {
+    fn from(score: &'a RuleScore) -> Self {
+        JsonRow { score, precision: score.precision(), recall: score.recall() }
+    }
+}
+
+/// The main entry point for the `cleansh eval` subcommand.
+pub fn run_eval_command(opts: &EvalCommand, theme_map: &ThemeMap, engine: &dyn SanitizationEngine) -> Result<()> {
+    let raw_truth = fs::read_to_string(&opts.truth)
+        .with_context(|| format!("Failed to read ground truth: {}", opts.truth.display()))?;
+    let labels: Vec<Label> = serde_json::from_str(&raw_truth)
+        .with_context(|| format!("'{}' is not a JSON array of labels", opts.truth.display()))?;
+
+    let truth_path = fs::canonicalize(&opts.truth).ok();
+    let files: Vec<(String, PathBuf)> = corpus_files(&opts.corpus)?
+        .into_iter()
+        .filter(|(_, path)| fs::canonicalize(path).ok() != truth_path)
+        .collect();
+
+    let mut predictions = Vec::new();
+    for (name, path) in &files {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read corpus file: {}", path.display()))?;
+        let (content, _) = encoding::decode(&bytes);
+        let (_, _, edits) = engine
+            .sanitize_file_with_edits(&content, name)
+            .with_context(|| format!("Failed to analyze corpus file: {}", path.display()))?;
+        predictions.extend(edits.into_iter().map(|edit| Prediction {
+            file: name.clone(),
+            rule: edit.rule_name,
+            start: edit.start,
+            end: edit.end,
+        }));
+    }
+
+    let known: BTreeSet<&str> = files.iter().map(|(name, _)| name.as_str()).collect();
+    let missing: BTreeSet<&str> = labels.iter().map(|l| l.file.as_str()).filter(|f| !known.contains(f)).collect();
+    for file in &missing {
+        warn_msg(format!("Labeled file '{}' is not in the corpus; its labels count as misses.", file), theme_map);
+    }
+
+    let scores = score(&predictions, &labels);
+    let total = RuleScore {
+        rule: "overall".to_string(),
+        true_positives: scores.iter().map(|s| s.true_positives).sum(),
+        false_positives: scores.iter().map(|s| s.false_positives).sum(),
+        false_negatives: scores.iter().map(|s| s.false_negatives).sum(),
+    };
+
+    let mut stdout = io::stdout().lock();
+    if opts.json {
+        let rows: Vec<JsonRow> = scores.iter().map(JsonRow::from).collect();
+        let json = serde_json::json!({
+            "schema_version": SCHEMA_VERSION,
+            "files": files.len(), "rules": rows, "overall": JsonRow::from(&total) });
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&json).context("Failed to serialize evaluation results")?)?;
+    } else {
+        print_table(&scores, &total, &mut stdout)?;
+        info_msg(format!("Evaluated {} file(s) against {} label(s).", files.len(), labels.len()), theme_map);
+    }
+
+    let below = |threshold: Option<f64>, value: fn(&RuleScore) -> Option<f64>| -> Vec<String> {
+        let Some(threshold) = threshold else { return Vec::new() };
+        scores
+            .iter()
+            .filter_map(|s| value(s).filter(|v| *v < threshold).map(|v| format!("{} ({:.3})", s.rule, v)))
+            .collect()
+    };
+    let low_precision = below(opts.min_precision, RuleScore::precision);
+    let low_recall = below(opts.min_recall, RuleScore::recall);
+    if !low_precision.is_empty() || !low_recall.is_empty() {
+        let mut problems = Vec::new();
+        if !low_precision.is_empty() {
+            problems.push(format!("precision below {}: {}", opts.min_precision.unwrap_or_default(), low_precision.join(", ")));
+        }
+        if !low_recall.is_empty() {
+            problems.push(format!("recall below {}: {}", opts.min_recall.unwrap_or_default(), low_recall.join(", ")));
+        }
+        return Err(anyhow!("Evaluation thresholds not met; {}", problems.join("; ")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(file: &str, rule: &str, start: u64, end: u64) -> Label {
+        Label { file: file.to_string(), rule: rule.to_string(), start, end }
+    }
+
+    fn prediction(file: &str, rule: &str, start: u64, end: u64) -> Prediction {
+        Prediction { file: file.to_string(), rule: rule.to_string(), start, end }
+    }
+
+    #[test]
+    fn scores_overlap_per_rule_and_file() {
+        let labels = vec![
+            label("a.log", "email", 10, 26),
+            label("a.log", "email", 40, 56),
+            label("b.log", "aws_access_key", 0, 20),
+        ];
+        let predictions = vec![
+            prediction("a.log", "email", 12, 26),
+            prediction("a.log", "email", 70, 80),
+            prediction("b.log", "generic_token", 0, 20),
+        ];
+
+        let scores = score(&predictions, &labels);
+        let by_rule: Vec<(&str, usize, usize, usize)> = scores
+            .iter()
+            .map(|s| (s.rule.as_str(), s.true_positives, s.false_positives, s.false_negatives))
+            .collect();
+        assert_eq!(by_rule, vec![("aws_access_key", 0, 0, 1), ("email", 1, 1, 1), ("generic_token", 0, 1, 0)]);
+        assert_eq!(scores[1].precision(), Some(0.5));
+        assert_eq!(scores[0].precision(), None);
+        assert_eq!(scores[2].recall(), None);
+    }
+}
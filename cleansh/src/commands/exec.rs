@@ -0,0 +1,235 @@
+//! This module handles `cleansh exec`, which runs a command with its stdout and stderr
+//! passed through the sanitizer as they are written. It replaces
+//! `tool 2>&1 | cleansh sanitize --line-buffered`, which merges the two streams, loses
+//! the tool's exit code without `pipefail`, and makes the tool believe it is not
+//! writing to a terminal.
+//!
+//! Each stream is read on its own thread and sanitized a line at a time, then written to
+//! the matching stream of cleansh, so output appears as the tool produces it and stdout
+//! and stderr stay separate and interleave as closely as line buffering allows. A line
+//! left unfinished for a moment, such as a prompt, is written without waiting for its end. With
+//! `--pty`, the tool writes to pseudo-terminals instead of pipes.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::ExecCommand;
+use crate::commands::cleansh::warn_msg;
+use crate::ui::redaction_summary;
+use crate::ui::theme::ThemeMap;
+use crate::utils::pty;
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::Aggregator;
+use std::collections::BTreeSet;
+use std::env;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Original texts kept per rule for the summary; the output itself is what matters here.
+const EXEC_SUMMARY_TEXT_LIMIT: usize = 20;
+
+/// Which of cleansh's streams a child stream is copied to.
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Stdout,
+    Stderr,
+}
+
+/// Environment variables whose values the rules match, with the rules that match each.
+/// Only names and rule names are returned, never the values.
+pub fn secrets_in_environment(
+    vars: impl IntoIterator<Item = (String, String)>,
+    engine: &dyn SanitizationEngine,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let mut found = Vec::new();
+    for (name, value) in vars {
+        let rules: BTreeSet<String> = engine.find_matches_for_ui(&value, "")?.into_iter().map(|m| m.rule_name).collect();
+        if !rules.is_empty() {
+            found.push((name, rules.into_iter().collect()));
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// How long the command may leave a line unfinished before what has arrived of it is
+/// sanitized and written anyway, so prompts and progress bars show up.
+const PARTIAL_LINE_FLUSH: Duration = Duration::from_millis(200);
+
+/// Sanitizes one line (or the unfinished start of one) and writes it to `target`.
+fn write_sanitized(line: &[u8], target: Target, engine: &dyn SanitizationEngine, summary: &Mutex<Aggregator>) -> Result<()> {
+    if line.is_empty() {
+        return Ok(());
+    }
+    let text = String::from_utf8_lossy(line);
+    let (sanitized, items) = engine
+        .sanitize(&text, "", "", "", "", "", "", None)
+        .context("Sanitization failed")?;
+    summary.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).add_summary(items);
+
+    let written = match target {
+        Target::Stdout => io::stdout().lock().write_all(sanitized.as_bytes()).and_then(|_| io::stdout().flush()),
+        Target::Stderr => io::stderr().lock().write_all(sanitized.as_bytes()),
+    };
+    // A reader that went away (`| head`) is not an error; the child is still drained.
+    match written {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e).context("Failed to write sanitized output"),
+        _ => Ok(()),
+    }
+}
+
+/// Copies `reader` to `target` a line at a time, sanitizing each line on the way.
+///
+/// A line ends at `\n` or at `\r`, which progress bars use to redraw themselves. When
+/// the command stops writing in the middle of a line, as it does for a prompt, what it
+/// wrote so far is sanitized and written after [`PARTIAL_LINE_FLUSH`]; a secret cut in
+/// two by such a pause can then only be found if each half matches on its own.
+fn pump(reader: impl Read + Send + 'static, target: Target, engine: &dyn SanitizationEngine, summary: &Mutex<Aggregator>) -> Result<()> {
+    // Reads block, so they run on their own thread and arrive here with a timeout.
+    let (sender, receiver) = mpsc::channel::<io::Result<Vec<u8>>>();
+    std::thread::spawn(move || {
+        let mut reader = reader;
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(count) => Ok(chunk[..count].to_vec()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) if pty::is_closed(&e) => Ok(Vec::new()),
+                Err(e) => Err(e),
+            };
+            // An empty chunk marks the end of the output.
+            let done = !matches!(&read, Ok(bytes) if !bytes.is_empty());
+            if sender.send(read).is_err() || done {
+                return;
+            }
+        }
+    });
+
+    let is_line_end = |byte: &u8| *byte == b'\n' || *byte == b'\r';
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let chunk = match receiver.recv_timeout(PARTIAL_LINE_FLUSH) {
+            Ok(Ok(chunk)) => chunk,
+            Ok(Err(e)) => return Err(e).context("Failed to read the command's output"),
+            Err(RecvTimeoutError::Disconnected) => Vec::new(),
+            Err(RecvTimeoutError::Timeout) => {
+                // Keep back a UTF-8 character the command has only written part of.
+                let complete = match std::str::from_utf8(&pending) {
+                    Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                    _ => pending.len(),
+                };
+                let rest = pending.split_off(complete);
+                write_sanitized(&std::mem::replace(&mut pending, rest), target, engine, summary)?;
+                continue;
+            }
+        };
+        if chunk.is_empty() {
+            return write_sanitized(&pending, target, engine, summary);
+        }
+        pending.extend_from_slice(&chunk);
+        if let Some(end) = pending.iter().rposition(is_line_end) {
+            let rest = pending.split_off(end + 1);
+            for line in std::mem::replace(&mut pending, rest).split_inclusive(is_line_end) {
+                write_sanitized(line, target, engine, summary)?;
+            }
+        }
+    }
+}
+
+/// The exit code to leave with for a child's `status`: its own code, or 128 plus the
+/// signal that killed it, as shells report it.
+fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// The main entry point for `cleansh exec`. Returns the exit code of the command.
+pub fn run_exec_command(opts: &ExecCommand, engine: &dyn SanitizationEngine, theme_map: &ThemeMap) -> Result<i32> {
+    let (program, args) = opts.command.split_first().ok_or_else(|| anyhow!("No command given; use 'cleansh exec -- <command>'"))?;
+
+    if opts.check_env {
+        for (name, rules) in secrets_in_environment(env::vars(), engine)? {
+            warn_msg(
+                format!("Environment variable {} holds a value matched by {}; '{}' can read it.", name, rules.join(", "), program),
+                theme_map,
+            );
+        }
+    }
+
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::inherit());
+    let mut ptys = None;
+    if opts.pty {
+        let out = pty::open().context("Failed to open a pseudo-terminal for stdout")?;
+        let err = pty::open().context("Failed to open a pseudo-terminal for stderr")?;
+        command.stdout(Stdio::from(out.slave)).stderr(Stdio::from(err.slave));
+        ptys = Some((out.master, err.master));
+    } else {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    let mut child = command.spawn().with_context(|| format!("Failed to run '{}'", program))?;
+    // The slave ends were moved into `command`; dropping it closes cleansh's copies, so
+    // the masters report the end of output once the child is gone.
+    drop(command);
+    let (stdout, stderr): (Box<dyn Read + Send>, Box<dyn Read + Send>) = match ptys {
+        Some((out, err)) => (Box::new(out), Box::new(err)),
+        None => (
+            Box::new(child.stdout.take().ok_or_else(|| anyhow!("The command has no stdout"))?),
+            Box::new(child.stderr.take().ok_or_else(|| anyhow!("The command has no stderr"))?),
+        ),
+    };
+
+    let summary = Mutex::new(Aggregator::with_text_limit(EXEC_SUMMARY_TEXT_LIMIT));
+    let pumped = std::thread::scope(|scope| {
+        let summary = &summary;
+        let err_pump = scope.spawn(move || pump(stderr, Target::Stderr, engine, summary));
+        let out_result = pump(stdout, Target::Stdout, engine, summary);
+        let err_result = err_pump.join().map_err(|_| anyhow!("Sanitizing the command's stderr panicked"))?;
+        out_result.and(err_result)
+    });
+    // The child is always reaped. If its output can no longer be sanitized, it is stopped
+    // first, since it would otherwise block on a full pipe and never exit.
+    if pumped.is_err() {
+        let _ = child.kill();
+    }
+    let status = child.wait().with_context(|| format!("Failed to wait for '{}'", program));
+    pumped?;
+    let status = status?;
+
+    if !opts.no_redaction_summary {
+        let summary = summary.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()).into_summary();
+        let mut stderr = io::stderr();
+        let enable_colors = stderr.is_terminal();
+        redaction_summary::print_summary(&summary, &mut stderr, theme_map, enable_colors)?;
+    }
+    Ok(exit_code(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_code_passes_through_the_command_status() -> Result<()> {
+        let status = if cfg!(windows) {
+            Command::new("cmd").args(["/C", "exit 3"]).status()?
+        } else {
+            Command::new("sh").args(["-c", "exit 3"]).status()?
+        };
+        assert_eq!(exit_code(status), 3);
+        Ok(())
+    }
+}
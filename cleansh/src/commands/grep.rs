@@ -0,0 +1,160 @@
+//! This module handles the `grep` subcommand: a line search whose output is always
+//! sanitized. The pattern is matched against the original lines, so engineers can search
+//! production logs for an order ID or an error message, but every line is passed through
+//! the active rules before it is printed and can be pasted straight into a ticket.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::GrepCommand;
+use crate::utils::encoding::{self, TextEncoding};
+use anyhow::{Context, Result};
+use cleansh_core::engine::SanitizationEngine;
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+
+/// Line selection and output options, independent of where the lines come from.
+struct Search<'a> {
+    matcher: Regex,
+    opts: &'a GrepCommand,
+    engine: &'a dyn SanitizationEngine,
+}
+
+/// Compiles the search pattern, honouring `-i`.
+fn build_matcher(opts: &GrepCommand) -> Result<Regex> {
+    RegexBuilder::new(&opts.pattern)
+        .case_insensitive(opts.ignore_case)
+        .build()
+        .with_context(|| format!("Invalid search pattern '{}'", opts.pattern))
+}
+
+impl Search<'_> {
+    /// Searches one input, writing its sanitized selected lines (or their count) with an
+    /// optional `label:` prefix. Returns the number of selected lines.
+    ///
+    /// Lines of a file are sanitized as coming from `path`, so rules limited with `paths`
+    /// apply whether or not the output is labelled.
+    fn run<R: BufRead, W: Write>(&self, reader: &mut R, path: Option<&str>, label: Option<&str>, writer: &mut W) -> Result<usize> {
+        let (stream_encoding, bom_len) = encoding::sniff(reader.fill_buf().context("Failed to read input")?);
+        reader.consume(bom_len);
+
+        let mut raw_line = Vec::new();
+        let mut line_number = 0usize;
+        let mut selected = 0usize;
+        while encoding::read_line(reader, stream_encoding, &mut raw_line).context("Failed to read input")? > 0 {
+            line_number += 1;
+            let line = match stream_encoding {
+                TextEncoding::Utf8 { .. } => match String::from_utf8(std::mem::take(&mut raw_line)) {
+                    Ok(line) => line,
+                    Err(e) => encoding::decode_as(e.as_bytes(), TextEncoding::Windows1252),
+                },
+                _ => encoding::decode_as(&raw_line, stream_encoding),
+            };
+            raw_line.clear();
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if self.matcher.is_match(line) == self.opts.invert_match {
+                continue;
+            }
+            selected += 1;
+            if self.opts.count {
+                continue;
+            }
+
+            let sanitized = match path {
+                Some(path) => self.engine.sanitize_file_with_edits(line, path).map(|(sanitized, _, _)| sanitized),
+                None => self.engine.sanitize(line, "", "", "", "", "", "", None).map(|(sanitized, _)| sanitized),
+            }
+            .context("Sanitization failed")?;
+            if let Some(label) = label {
+                write!(writer, "{}:", label)?;
+            }
+            if self.opts.line_number {
+                write!(writer, "{}:", line_number)?;
+            }
+            writeln!(writer, "{}", sanitized)?;
+        }
+
+        if self.opts.count {
+            match label {
+                Some(label) => writeln!(writer, "{}:{}", label, selected)?,
+                None => writeln!(writer, "{}", selected)?,
+            }
+        }
+        Ok(selected)
+    }
+}
+
+/// The main entry point for the `cleansh grep` subcommand.
+///
+/// Returns the number of selected lines, so the caller can exit with status 1 when
+/// nothing matched, as `grep` does.
+pub fn run_grep_command(opts: &GrepCommand, engine: &dyn SanitizationEngine) -> Result<usize> {
+    let search = Search { matcher: build_matcher(opts)?, opts, engine };
+
+    let mut stdout = io::stdout().lock();
+    if opts.files.is_empty() {
+        return search.run(&mut io::stdin().lock(), None, None, &mut stdout);
+    }
+
+    let mut selected = 0;
+    for path in &opts.files {
+        let file = fs::File::open(path).with_context(|| format!("Failed to read input file: {}", path.display()))?;
+        let name = path.display().to_string();
+        let label = (opts.files.len() > 1).then_some(name.as_str());
+        selected += search.run(&mut BufReader::new(file), Some(&name), label, &mut stdout)?;
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::{RedactionConfig, RegexEngine};
+
+    /// Searches `input` as the file `app.log`, labelled as when several files are given.
+    fn grep(args: &[&str], input: &str) -> Result<(usize, String)> {
+        grep_with(RedactionConfig::load_default_rules()?, args, input, Some("app.log"))
+    }
+
+    fn grep_with(config: RedactionConfig, args: &[&str], input: &str, label: Option<&str>) -> Result<(usize, String)> {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from(["cleansh", "grep"].iter().chain(args))?;
+        let crate::cli::Commands::Grep(opts) = cli.command else { unreachable!() };
+        let engine = RegexEngine::new(config)?;
+        let search = Search { matcher: build_matcher(&opts)?, opts: &opts, engine: &engine };
+        let mut out = Vec::new();
+        let selected = search.run(&mut input.as_bytes(), Some("app.log"), label, &mut out)?;
+        Ok((selected, String::from_utf8(out)?))
+    }
+
+    #[test]
+    fn prints_selected_lines_sanitized() -> Result<()> {
+        let input = "login ok for admin@example.com\nlogin failed for root@example.com\nheartbeat\n";
+
+        let (selected, out) = grep(&["-n", "login"], input)?;
+        assert_eq!(selected, 2);
+        assert_eq!(out, "app.log:1:login ok for [EMAIL_REDACTED]\napp.log:2:login failed for [EMAIL_REDACTED]\n");
+
+        // The pattern sees the original text, but only sanitized lines are printed.
+        let (_, out) = grep(&["root@example"], input)?;
+        assert_eq!(out, "app.log:login failed for [EMAIL_REDACTED]\n");
+
+        let (selected, out) = grep(&["-v", "-c", "login"], input)?;
+        assert_eq!((selected, out.as_str()), (1, "app.log:1\n"));
+
+        let (selected, _) = grep(&["-i", "-c", "LOGIN"], input)?;
+        assert_eq!(selected, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn applies_path_scoped_rules_to_a_single_unlabelled_file() -> Result<()> {
+        let config = RedactionConfig::from_yaml_str(
+            "rules:\n  - name: order\n    pattern: 'ord_[0-9]+'\n    replace_with: '[ORDER]'\n    paths: ['*.log']\n",
+        )?;
+        let (_, out) = grep_with(config, &["shipped"], "shipped ord_123\n", None)?;
+        assert_eq!(out, "shipped [ORDER]\n");
+        Ok(())
+    }
+}
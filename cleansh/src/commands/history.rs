@@ -0,0 +1,211 @@
+//! This module handles `cleansh history`, which cleans secrets out of a shell's history
+//! file. Commands typed with a token or password on the command line stay in the history
+//! file long after the session, and history files get copied into dotfile repositories
+//! and support bundles.
+//!
+//! Only the command text of each entry is sanitized; the surrounding format (bash
+//! `#<epoch>` timestamp lines, zsh `: <epoch>:<duration>;` extended-history prefixes, and
+//! fish's `- cmd:` / `when:` records) is left alone, so the shell can still read the file.
+//! The original is copied to a new, timestamped backup next to it before the file is
+//! rewritten, so running the command again never replaces the backup of the real original.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{HistoryCommand, Shell};
+use crate::commands::cleansh::{info_msg, warn_msg};
+use crate::commands::shell_integration::detect_shell;
+use crate::ui::redaction_summary;
+use crate::ui::theme::ThemeMap;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::Aggregator;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Suffix of the backups written next to the history file, after a `.cleansh-<UTC time>`
+/// stamp.
+pub const BACKUP_SUFFIX: &str = ".bak";
+
+/// The history file `shell` writes by default, honoring `$HISTFILE` for bash and zsh.
+fn default_history_file(shell: Shell) -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine your home directory; pass --file"))?;
+    if matches!(shell, Shell::Bash | Shell::Zsh)
+        && let Some(histfile) = env::var_os("HISTFILE").filter(|value| !value.is_empty())
+    {
+        return Ok(PathBuf::from(histfile));
+    }
+    Ok(match shell {
+        Shell::Bash => home.join(".bash_history"),
+        Shell::Zsh => env::var_os("ZDOTDIR").map(PathBuf::from).unwrap_or(home).join(".zsh_history"),
+        Shell::Fish => env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local/share"))
+            .join("fish/fish_history"),
+    })
+}
+
+/// Splits a history line into the part kept as it is and the command text to sanitize.
+/// Lines without command text, such as timestamps, return an empty command.
+pub fn split_entry(shell: Shell, line: &str) -> (&str, &str) {
+    match shell {
+        // `HISTTIMEFORMAT` writes `#1700000000` before each command.
+        Shell::Bash if line.starts_with('#') && line.len() > 1 && line[1..].bytes().all(|b| b.is_ascii_digit()) => (line, ""),
+        Shell::Bash => ("", line),
+        // Extended history: `: 1700000000:0;git push`.
+        Shell::Zsh if line.starts_with(": ") => match line.find(';') {
+            Some(semicolon) if line[2..semicolon].bytes().all(|b| b.is_ascii_digit() || b == b':') => line.split_at(semicolon + 1),
+            _ => ("", line),
+        },
+        Shell::Zsh => ("", line),
+        Shell::Fish => match line.find("- cmd: ") {
+            Some(start) if line[..start].trim().is_empty() => line.split_at(start + "- cmd: ".len()),
+            _ => (line, ""),
+        },
+    }
+}
+
+/// Sanitizes the command text of every entry in `content`, adding what was redacted to
+/// `summary`. Lines that are not valid UTF-8, like the metafied bytes zsh writes for
+/// some characters, are kept unchanged; their number is returned with the output.
+pub fn sanitize_history(
+    shell: Shell,
+    content: &[u8],
+    engine: &dyn SanitizationEngine,
+    summary: &mut Aggregator,
+) -> Result<(Vec<u8>, usize)> {
+    let mut output = Vec::with_capacity(content.len());
+    let mut skipped = 0;
+    for line in content.split_inclusive(|b| *b == b'\n') {
+        let (body, ending) = match line.strip_suffix(b"\n") {
+            Some(body) => (body, &b"\n"[..]),
+            None => (line, &b""[..]),
+        };
+        let Ok(text) = std::str::from_utf8(body) else {
+            output.extend_from_slice(line);
+            skipped += 1;
+            continue;
+        };
+        let (kept, command) = split_entry(shell, text);
+        if command.trim().is_empty() {
+            output.extend_from_slice(line);
+            continue;
+        }
+        let (sanitized, items) = engine
+            .sanitize(command, "", "", "", "", "", "", None)
+            .context("Failed to sanitize a history entry")?;
+        summary.add_summary(items);
+        output.extend_from_slice(kept.as_bytes());
+        output.extend_from_slice(sanitized.as_bytes());
+        output.extend_from_slice(ending);
+    }
+    Ok((output, skipped))
+}
+
+/// Writes `original` to a new backup next to `path`, named after the current time, with
+/// the original's permissions set before anything is written. An existing backup is never
+/// replaced: a second run in the same second gets a numbered name.
+fn write_backup(path: &Path, name: &str, original: &[u8], permissions: &fs::Permissions) -> Result<PathBuf> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut attempt = 0;
+    loop {
+        let numbered = if attempt == 0 { String::new() } else { format!("-{}", attempt) };
+        let backup = path.with_file_name(format!("{}.cleansh-{}{}{}", name, stamp, numbered, BACKUP_SUFFIX));
+        match OpenOptions::new().write(true).create_new(true).open(&backup) {
+            Ok(mut file) => {
+                file.set_permissions(permissions.clone())
+                    .and_then(|_| file.write_all(original))
+                    .with_context(|| format!("Failed to back up {} to {}", path.display(), backup.display()))?;
+                return Ok(backup);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e).with_context(|| format!("Failed to back up {} to {}", path.display(), backup.display())),
+        }
+    }
+}
+
+/// Backs up `original`, the current content of `path`, then replaces the file with `bytes`
+/// through a temporary sibling carrying the original's permissions, so the history is
+/// never left half-written or readable by others.
+fn rewrite_with_backup(path: &Path, original: &[u8], bytes: &[u8]) -> Result<PathBuf> {
+    let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let permissions = fs::metadata(path).with_context(|| format!("Failed to read {}", path.display()))?.permissions();
+    let backup = write_backup(path, &name, original, &permissions)?;
+
+    let tmp = path.with_file_name(format!(".{}.cleansh-tmp", name));
+    let result = fs::write(&tmp, bytes)
+        .and_then(|_| fs::set_permissions(&tmp, permissions))
+        .and_then(|_| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(backup)
+}
+
+/// The main entry point for `cleansh history`.
+pub fn run_history_command(opts: &HistoryCommand, engine: &dyn SanitizationEngine, theme_map: &ThemeMap) -> Result<()> {
+    let shell = match opts.shell {
+        Some(shell) => shell,
+        None => detect_shell(env::var("SHELL").ok().as_deref())
+            .ok_or_else(|| anyhow!("Could not detect your shell from $SHELL; pass --shell bash, zsh or fish"))?,
+    };
+    let path = match &opts.file {
+        Some(path) => path.clone(),
+        None => default_history_file(shell)?,
+    };
+    let content = fs::read(&path).with_context(|| format!("Failed to read history file {}", path.display()))?;
+
+    // Only counts are kept: the original texts are the secrets being removed.
+    let mut summary = Aggregator::with_text_limit(0);
+    let (sanitized, skipped) = sanitize_history(shell, &content, engine, &mut summary)?;
+
+    let mut stderr = io::stderr();
+    let enable_colors = stderr.is_terminal();
+    redaction_summary::print_summary(&summary.summary(), &mut stderr, theme_map, enable_colors)?;
+    if skipped > 0 {
+        warn_msg(
+            format!("{} line(s) of {} are not valid UTF-8 and were left unsanitized; check them by hand.", skipped, path.display()),
+            theme_map,
+        );
+    }
+
+    if sanitized == content {
+        info_msg(format!("No secrets found in {}.", path.display()), theme_map);
+    } else if opts.dry_run {
+        info_msg(
+            format!("Would redact {} secret(s) in {}; run without --dry-run to rewrite it.", summary.total_occurrences(), path.display()),
+            theme_map,
+        );
+    } else {
+        let backup = rewrite_with_backup(&path, &content, &sanitized)?;
+        info_msg(
+            format!(
+                "Redacted {} secret(s) in {}. The original was saved to {}; delete it once you have checked the result. Running shells may write their in-memory history back when they exit.",
+                summary.total_occurrences(),
+                path.display(),
+                backup.display()
+            ),
+            theme_map,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_only_command_text_from_each_format() {
+        assert_eq!(split_entry(Shell::Bash, "#1700000000"), ("#1700000000", ""));
+        assert_eq!(split_entry(Shell::Bash, "# note"), ("", "# note"));
+        assert_eq!(split_entry(Shell::Zsh, ": 1700000000:0;export TOKEN=abc"), (": 1700000000:0;", "export TOKEN=abc"));
+        assert_eq!(split_entry(Shell::Zsh, ": not a header;x"), ("", ": not a header;x"));
+        assert_eq!(split_entry(Shell::Fish, "- cmd: curl -u me:pw host"), ("- cmd: ", "curl -u me:pw host"));
+        assert_eq!(split_entry(Shell::Fish, "  when: 1700000000"), ("  when: 1700000000", ""));
+        assert_eq!(split_entry(Shell::Fish, "    - /home/alice/project"), ("    - /home/alice/project", ""));
+    }
+}
@@ -10,3 +10,16 @@ pub mod stats;
 pub mod uninstall;
 pub mod verify;
 pub mod sync;
+pub mod report;
+pub mod eval;
+pub mod profiles_diff;
+pub mod grep;
+pub mod rules;
+pub mod rules_impact;
+pub mod config;
+pub mod stats_history;
+pub mod stats_serve;
+pub mod shell_integration;
+pub mod history;
+pub mod verify_sanitized;
+pub mod exec;
@@ -0,0 +1,243 @@
+//! This module handles `cleansh profiles diff`, which compares the effective policy of two
+//! profiles (or of a profile and the current configuration) and prints only what differs:
+//! rules that start or stop running, rule fields that change, and profile settings such as
+//! sampling, dedupe and allowed domains. Reviewers approve a profile update by reading this
+//! delta instead of two YAML files side by side.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use anyhow::{Context, Result};
+use cleansh_core::config::{RedactionConfig, RedactionRule};
+use cleansh_core::profiles::EngineOptions;
+use cleansh_core::SCHEMA_VERSION;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Engine settings compared between two profiles, as named in the `EngineOptions` JSON.
+const SETTINGS: &[&str] = &["allowed_domains", "post_processing", "samples_config", "dedupe_config"];
+
+/// Rule fields left out of the comparison: the name is the key, and `enabled` is already
+/// reflected by the rule being added or removed.
+const IGNORED_RULE_FIELDS: &[&str] = &["name", "enabled"];
+
+/// One side of a comparison: a label for the output and the policy it stands for.
+pub struct PolicySide {
+    pub label: String,
+    pub config: RedactionConfig,
+    pub options: EngineOptions,
+}
+
+/// A field whose value differs between the two sides.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// A rule that runs on both sides with different settings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleChange {
+    pub rule: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// The differences between two policies. Rule lists are sorted by name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileDiff {
+    pub from: String,
+    pub to: String,
+    /// Rules that run only on the `to` side.
+    pub added: Vec<String>,
+    /// Rules that run only on the `from` side.
+    pub removed: Vec<String>,
+    pub changed: Vec<RuleChange>,
+    pub settings: Vec<FieldChange>,
+}
+
+/// The `--json` document: the diff, stamped with the schema version.
+#[derive(Serialize)]
+struct DiffJson<'a> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    diff: &'a ProfileDiff,
+}
+
+impl ProfileDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() && self.settings.is_empty()
+    }
+}
+
+/// The rules that actually run, keyed by name: rules a profile sets to `enabled: false`
+/// stay in the config but are skipped by the engine.
+fn active_rules(config: &RedactionConfig) -> BTreeMap<&str, &RedactionRule> {
+    config
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled != Some(false))
+        .map(|rule| (rule.name.as_str(), rule))
+        .collect()
+}
+
+/// Lists the top-level fields of two JSON objects that differ, in name order.
+fn changed_fields(from: &Value, to: &Value, fields: Option<&[&str]>, ignored: &[&str]) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let from = from.as_object().unwrap_or(&empty);
+    let to = to.as_object().unwrap_or(&empty);
+
+    let mut names: Vec<&str> = match fields {
+        Some(fields) => fields.to_vec(),
+        None => from.keys().chain(to.keys()).map(String::as_str).collect(),
+    };
+    names.sort_unstable();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| !ignored.contains(name))
+        .filter_map(|name| {
+            let before = from.get(name).cloned().unwrap_or(Value::Null);
+            let after = to.get(name).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| FieldChange { field: name.to_string(), from: before, to: after })
+        })
+        .collect()
+}
+
+/// Compares the rules that run and the engine settings of two policies.
+pub fn diff_policies(from: &PolicySide, to: &PolicySide) -> Result<ProfileDiff> {
+    let before = active_rules(&from.config);
+    let after = active_rules(&to.config);
+
+    let added = after.keys().filter(|name| !before.contains_key(*name)).map(|name| name.to_string()).collect();
+    let removed = before.keys().filter(|name| !after.contains_key(*name)).map(|name| name.to_string()).collect();
+
+    let mut changed = Vec::new();
+    for (name, old_rule) in &before {
+        let Some(new_rule) = after.get(name) else { continue };
+        let changes = changed_fields(
+            &serde_json::to_value(old_rule).context("Failed to serialize rule")?,
+            &serde_json::to_value(new_rule).context("Failed to serialize rule")?,
+            None,
+            IGNORED_RULE_FIELDS,
+        );
+        if !changes.is_empty() {
+            changed.push(RuleChange { rule: name.to_string(), changes });
+        }
+    }
+
+    let settings = changed_fields(
+        &serde_json::to_value(&from.options).context("Failed to serialize profile settings")?,
+        &serde_json::to_value(&to.options).context("Failed to serialize profile settings")?,
+        Some(SETTINGS),
+        &[],
+    );
+
+    Ok(ProfileDiff { from: from.label.clone(), to: to.label.clone(), added, removed, changed, settings })
+}
+
+/// Renders a JSON value on one line, with strings unquoted for readability.
+fn show(value: &Value) -> String {
+    match value {
+        Value::Null => "(unset)".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Writes the differences as an indented, line-per-change listing.
+pub fn write_text<W: Write>(diff: &ProfileDiff, writer: &mut W) -> Result<()> {
+    writeln!(writer, "Comparing {} with {}", diff.from, diff.to)?;
+    if diff.is_empty() {
+        writeln!(writer, "No differences.")?;
+        return Ok(());
+    }
+
+    if !diff.added.is_empty() {
+        writeln!(writer, "Rules added ({}):", diff.added.len())?;
+        for name in &diff.added {
+            writeln!(writer, "  + {}", name)?;
+        }
+    }
+    if !diff.removed.is_empty() {
+        writeln!(writer, "Rules removed ({}):", diff.removed.len())?;
+        for name in &diff.removed {
+            writeln!(writer, "  - {}", name)?;
+        }
+    }
+    if !diff.changed.is_empty() {
+        writeln!(writer, "Rules changed ({}):", diff.changed.len())?;
+        for rule in &diff.changed {
+            writeln!(writer, "  ~ {}", rule.rule)?;
+            for change in &rule.changes {
+                writeln!(writer, "      {}: {} -> {}", change.field, show(&change.from), show(&change.to))?;
+            }
+        }
+    }
+    if !diff.settings.is_empty() {
+        writeln!(writer, "Settings changed ({}):", diff.settings.len())?;
+        for change in &diff.settings {
+            writeln!(writer, "  ~ {}: {} -> {}", change.field, show(&change.from), show(&change.to))?;
+        }
+    }
+    Ok(())
+}
+
+/// The main entry point for `cleansh profiles diff`.
+pub fn run_profiles_diff<W: Write>(from: &PolicySide, to: &PolicySide, json: bool, writer: &mut W) -> Result<()> {
+    let diff = diff_policies(from, to)?;
+    if json {
+        writeln!(writer, "{}", serde_json::to_string_pretty(&DiffJson { schema_version: SCHEMA_VERSION, diff: &diff })
+                .context("Failed to serialize profile diff")?)?;
+    } else {
+        write_text(&diff, writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, replace_with: &str) -> RedactionRule {
+        RedactionRule {
+            name: name.to_string(),
+            pattern: Some("x".to_string()),
+            replace_with: replace_with.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn side(label: &str, rules: Vec<RedactionRule>, allowed_domains: &[&str]) -> PolicySide {
+        PolicySide {
+            label: label.to_string(),
+            config: RedactionConfig { rules, ..Default::default() },
+            options: EngineOptions::default().with_allowed_domains(allowed_domains.iter().map(|d| d.to_string()).collect()),
+        }
+    }
+
+    #[test]
+    fn reports_added_removed_changed_and_settings() -> Result<()> {
+        let mut disabled = rule("ipv4_address", "[IPV4]");
+        disabled.enabled = Some(false);
+        let from = side("a", vec![rule("email", "[EMAIL]"), rule("jwt_token", "[JWT]"), disabled], &[]);
+        let to = side("b", vec![rule("email", "[MAIL]"), rule("ipv4_address", "[IPV4]")], &["example.com"]);
+
+        let diff = diff_policies(&from, &to)?;
+        assert_eq!(diff.added, vec!["ipv4_address"]);
+        assert_eq!(diff.removed, vec!["jwt_token"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].rule, "email");
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![FieldChange { field: "replace_with".to_string(), from: "[EMAIL]".into(), to: "[MAIL]".into() }]
+        );
+        assert_eq!(diff.settings.len(), 1);
+        assert_eq!(diff.settings[0].field, "allowed_domains");
+
+        assert!(diff_policies(&from, &from)?.is_empty());
+        Ok(())
+    }
+}
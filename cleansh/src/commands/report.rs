@@ -0,0 +1,139 @@
+//! This module handles the `report` subcommand, which turns the JSON summary written by
+//! `cleansh scan --json-file` into a single, self-contained report file for management
+//! and compliance reviews. An optional baseline summary adds per-rule trends.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{ReportCommand, ReportFormat};
+use crate::commands::cleansh::info_msg;
+use crate::ui::report_html;
+use crate::ui::theme::ThemeMap;
+use crate::utils::rules_source;
+use crate::utils::time;
+use anyhow::{Context, Result};
+use cleansh_core::check_schema_version;
+use cleansh_core::config::{merge_rules, RedactionConfig};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// The subset of the `scan` JSON output the report is built from.
+#[derive(Debug, Deserialize)]
+struct ScanSummary {
+    schema_version: Option<String>,
+    redaction_summary: HashMap<String, usize>,
+}
+
+/// One row of the findings table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow {
+    pub rule_name: String,
+    pub description: Option<String>,
+    pub severity: String,
+    pub count: usize,
+    /// The count in the baseline scan, when a baseline was supplied.
+    pub baseline_count: Option<usize>,
+}
+
+/// Everything needed to render a report.
+#[derive(Debug, Clone)]
+pub struct ReportData {
+    pub title: String,
+    pub source: String,
+    pub baseline_source: Option<String>,
+    pub generated_at: String,
+    /// Rows ordered by descending count, then rule name.
+    pub rows: Vec<ReportRow>,
+}
+
+impl ReportData {
+    /// Total number of findings in the current scan.
+    pub fn total(&self) -> usize {
+        self.rows.iter().map(|row| row.count).sum()
+    }
+
+    /// Total number of findings in the baseline scan, if one was supplied.
+    pub fn baseline_total(&self) -> Option<usize> {
+        self.baseline_source.as_ref()?;
+        Some(self.rows.iter().filter_map(|row| row.baseline_count).sum())
+    }
+
+    /// Number of findings per severity, in descending order of count.
+    pub fn severity_breakdown(&self) -> Vec<(String, usize)> {
+        let mut by_severity: BTreeMap<&str, usize> = BTreeMap::new();
+        for row in self.rows.iter().filter(|row| row.count > 0) {
+            *by_severity.entry(row.severity.as_str()).or_default() += row.count;
+        }
+        let mut breakdown: Vec<(String, usize)> = by_severity.into_iter().map(|(s, c)| (s.to_string(), c)).collect();
+        breakdown.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        breakdown
+    }
+}
+
+/// Reads a scan summary JSON file.
+fn load_summary(path: &Path) -> Result<HashMap<String, usize>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read scan summary: {}", path.display()))?;
+    let summary: ScanSummary = serde_json::from_str(&raw)
+        .with_context(|| format!("'{}' is not a `cleansh scan` JSON summary", path.display()))?;
+    check_schema_version(summary.schema_version.as_deref())
+        .with_context(|| format!("Cannot read scan summary {}", path.display()))?;
+    Ok(summary.redaction_summary)
+}
+
+/// Combines current and baseline counts with rule metadata into report rows.
+///
+/// Rules that only appear in the baseline are kept with a count of zero so that
+/// resolved findings still show up in the trend.
+pub fn build_rows(
+    current: &HashMap<String, usize>,
+    baseline: Option<&HashMap<String, usize>>,
+    rules: &RedactionConfig,
+) -> Vec<ReportRow> {
+    let names: BTreeSet<&String> = current.keys().chain(baseline.into_iter().flat_map(|b| b.keys())).collect();
+    let mut rows: Vec<ReportRow> = names
+        .into_iter()
+        .map(|name| {
+            let rule = rules.rules.iter().find(|rule| &rule.name == name);
+            ReportRow {
+                rule_name: name.clone(),
+                description: rule.and_then(|rule| rule.description.clone()),
+                severity: rule
+                    .and_then(|rule| rule.severity.clone())
+                    .unwrap_or_else(|| "unspecified".to_string()),
+                count: current.get(name).copied().unwrap_or(0),
+                baseline_count: baseline.map(|b| b.get(name).copied().unwrap_or(0)),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.rule_name.cmp(&b.rule_name)));
+    rows
+}
+
+/// The main entry point for the `cleansh report` subcommand.
+pub fn run_report_command(opts: &ReportCommand, theme_map: &ThemeMap) -> Result<()> {
+    let current = load_summary(&opts.from)?;
+    let baseline = opts.baseline.as_deref().map(load_summary).transpose()?;
+
+    let mut rules = RedactionConfig::load_default_rules().context("Failed to load default redaction rules")?;
+    for path in &opts.config {
+        rules = merge_rules(rules, Some(rules_source::load_user_config(path)?));
+    }
+
+    let data = ReportData {
+        title: opts.title.clone(),
+        source: opts.from.display().to_string(),
+        baseline_source: opts.baseline.as_ref().map(|path| path.display().to_string()),
+        generated_at: time::now_rfc3339(),
+        rows: build_rows(&current, baseline.as_ref(), &rules),
+    };
+
+    let rendered = match opts.format {
+        ReportFormat::Html => report_html::render_html_report(&data),
+    };
+    fs::write(&opts.output, rendered)
+        .with_context(|| format!("Failed to write report to {}", opts.output.display()))?;
+    info_msg(format!("Wrote report for {} finding(s) to {}", data.total(), opts.output.display()), theme_map);
+    Ok(())
+}
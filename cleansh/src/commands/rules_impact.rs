@@ -0,0 +1,235 @@
+//! This module handles `cleansh rules impact`, which simulates proposed rules against
+//! existing logs before they are enabled. For each proposed rule it counts the matches,
+//! how many of them would be new redactions and how many overlap matches of the rules
+//! already in use (and which ones), and shows a few of the new matches, so rule authors
+//! can judge the blast radius of a rule before it goes into a production profile.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::RulesImpactCommand;
+use crate::commands::cleansh::info_msg;
+use crate::ui::theme::ThemeMap;
+use crate::utils::encoding;
+use anyhow::{Context, Result};
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::{RedactionMatch, RegexEngine, SCHEMA_VERSION};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+
+/// Longest sample text shown before it is cut off.
+const SAMPLE_CHARS: usize = 80;
+
+/// A new match of a proposed rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sample {
+    pub file: String,
+    pub line: usize,
+    pub text: String,
+}
+
+/// The `--json` document.
+#[derive(Serialize)]
+struct ImpactJson<'a> {
+    schema_version: &'static str,
+    rules: &'a [&'a RuleImpact],
+}
+
+/// The estimated impact of one proposed rule.
+#[derive(Debug, Default, Serialize)]
+pub struct RuleImpact {
+    pub rule: String,
+    pub matches: usize,
+    /// Matches no rule in use touches; these would be redacted for the first time.
+    pub additional: usize,
+    /// Matches that overlap a match of a rule in use.
+    pub overlapping: usize,
+    /// Rules in use with the number of this rule's matches that overlap theirs.
+    pub overlaps: BTreeMap<String, usize>,
+    pub samples: Vec<Sample>,
+}
+
+/// Adds the matches of the proposed rules in one input to `impacts`.
+///
+/// Matches of a rule in use that has the same name as a proposed rule are ignored, since
+/// the proposed rule would replace it. `baseline` must be sorted by start offset.
+pub fn tally(
+    impacts: &mut BTreeMap<String, RuleImpact>,
+    file: &str,
+    content: &str,
+    proposed: &[RedactionMatch],
+    baseline: &[RedactionMatch],
+    max_samples: usize,
+) {
+    let longest = baseline.iter().map(|b| b.end - b.start).max().unwrap_or(0);
+    for m in proposed {
+        let impact = impacts.entry(m.rule_name.clone()).or_insert_with(|| RuleImpact { rule: m.rule_name.clone(), ..Default::default() });
+        impact.matches += 1;
+
+        // Only matches starting within `longest` bytes before this one can reach into it.
+        let mut overlapped: Vec<&str> = baseline[..baseline.partition_point(|b| b.start < m.end)]
+            .iter()
+            .rev()
+            .take_while(|b| b.start + longest > m.start)
+            .filter(|b| b.end > m.start && b.rule_name != m.rule_name)
+            .map(|b| b.rule_name.as_str())
+            .collect();
+        overlapped.sort_unstable();
+        overlapped.dedup();
+
+        if overlapped.is_empty() {
+            impact.additional += 1;
+            if impact.samples.len() < max_samples {
+                let start = m.start as usize;
+                impact.samples.push(Sample {
+                    file: file.to_string(),
+                    line: content.get(..start).map_or(0, |before| before.matches('\n').count()) + 1,
+                    text: preview(&sanitized_line(content, m, baseline, longest)),
+                });
+            }
+        } else {
+            impact.overlapping += 1;
+            for rule in overlapped {
+                *impact.overlaps.entry(rule.to_string()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// The line of `content` that `m` is on, with `m` and the matches of `baseline` on it
+/// replaced by their placeholders, so that samples never show a secret. `longest` is the
+/// length of the longest match in `baseline`.
+fn sanitized_line(content: &str, m: &RedactionMatch, baseline: &[RedactionMatch], longest: u64) -> String {
+    let (start, end) = (m.start as usize, m.end as usize);
+    let line_start = content.get(..start).and_then(|before| before.rfind('\n')).map_or(0, |i| i + 1);
+    let line_end = content.get(end..).and_then(|after| after.find('\n')).map_or(content.len(), |i| end + i);
+
+    let mut replaced: Vec<&RedactionMatch> = baseline[..baseline.partition_point(|b| (b.start as usize) < line_end)]
+        .iter()
+        .rev()
+        .take_while(|b| (b.start + longest) as usize > line_start)
+        .filter(|b| b.end as usize > line_start)
+        .chain([m])
+        .collect();
+    replaced.sort_by_key(|r| r.start);
+
+    let mut line = String::new();
+    let mut pos = line_start;
+    for r in replaced {
+        let r_start = (r.start as usize).max(line_start);
+        // A match overlapping the previous one is already covered by its placeholder.
+        if r_start >= pos {
+            line.push_str(content.get(pos..r_start).unwrap_or_default());
+            line.push_str(&r.sanitized_string);
+        }
+        pos = pos.max(r.end as usize);
+    }
+    line.push_str(content.get(pos..line_end).unwrap_or_default());
+    line
+}
+
+/// Shortens `text` to one line of at most [`SAMPLE_CHARS`] characters.
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > SAMPLE_CHARS || line.len() < text.trim_end().len() {
+        format!("{}...", line.chars().take(SAMPLE_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Prints the estimate as a table, followed by the overlaps and samples of each rule.
+fn print_report<W: Write>(impacts: &[&RuleImpact], writer: &mut W) -> Result<()> {
+    let width = impacts.iter().map(|i| i.rule.len()).chain(["rule".len()]).max().unwrap_or(0);
+    writeln!(writer, "{:<width$}  {:>8}  {:>10}  {:>11}", "rule", "matches", "additional", "overlapping")?;
+    for impact in impacts {
+        writeln!(writer, "{:<width$}  {:>8}  {:>10}  {:>11}", impact.rule, impact.matches, impact.additional, impact.overlapping)?;
+    }
+    for impact in impacts {
+        if !impact.overlaps.is_empty() {
+            let overlaps: Vec<String> = impact.overlaps.iter().map(|(rule, count)| format!("{} ({})", rule, count)).collect();
+            writeln!(writer, "\n{} overlaps: {}", impact.rule, overlaps.join(", "))?;
+        }
+        if !impact.samples.is_empty() {
+            writeln!(writer, "\nNew matches of {}:", impact.rule)?;
+            for sample in &impact.samples {
+                writeln!(writer, "  {}:{}: {}", sample.file, sample.line, sample.text)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The main entry point for `cleansh rules impact`. `baseline` is the engine with the rules
+/// already in use.
+pub fn run_rules_impact(opts: &RulesImpactCommand, theme_map: &ThemeMap, baseline: &dyn SanitizationEngine) -> Result<()> {
+    let proposed_config = RedactionConfig::load_from_file(&opts.rule)
+        .with_context(|| format!("Failed to load proposed rules from {}", opts.rule.display()))?;
+    let proposed_names: Vec<String> = proposed_config.rules.iter().map(|r| r.name.clone()).collect();
+    let proposed = RegexEngine::new(proposed_config).context("Failed to compile the proposed rules")?;
+
+    // Every proposed rule is reported, including those that never match.
+    let mut impacts: BTreeMap<String, RuleImpact> = proposed_names
+        .iter()
+        .map(|name| (name.clone(), RuleImpact { rule: name.clone(), ..Default::default() }))
+        .collect();
+    for path in &opts.inputs {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read input: {}", path.display()))?;
+        let (content, _) = encoding::decode(&bytes);
+        let name = path.display().to_string();
+        let proposed_matches = proposed
+            .find_file_matches(&content, &name)
+            .with_context(|| format!("Failed to simulate the proposed rules on {}", path.display()))?;
+        let mut baseline_matches = baseline
+            .find_file_matches(&content, &name)
+            .with_context(|| format!("Failed to scan {} with the rules in use", path.display()))?;
+        baseline_matches.sort_by_key(|m| m.start);
+        tally(&mut impacts, &name, &content, &proposed_matches, &baseline_matches, opts.samples);
+    }
+
+    let impacts: Vec<&RuleImpact> = proposed_names.iter().filter_map(|name| impacts.get(name)).collect();
+    let mut stdout = io::stdout().lock();
+    if opts.json {
+        let json = ImpactJson { schema_version: SCHEMA_VERSION, rules: &impacts };
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&json).context("Failed to serialize the impact estimate")?)?;
+    } else {
+        print_report(&impacts, &mut stdout)?;
+        info_msg(
+            format!("Simulated {} proposed rule(s) against {} file(s).", impacts.len(), opts.inputs.len()),
+            theme_map,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support::finding;
+
+    #[test]
+    fn separates_new_matches_from_overlaps() {
+        let content = "id=ORD-1 mail ORD-2@example.com\nORD-3\n";
+        let proposed = [finding("order", "ORD-1", 3), finding("order", "ORD-2", 14), finding("order", "ORD-3", 32)];
+        let baseline = [finding("email", "ORD-2@example.com", 14)];
+
+        let mut impacts = BTreeMap::new();
+        tally(&mut impacts, "app.log", content, &proposed, &baseline, 1);
+        let order = &impacts["order"];
+        assert_eq!((order.matches, order.additional, order.overlapping), (3, 2, 1));
+        assert_eq!(order.overlaps["email"], 1);
+        // Samples show the line with every match replaced, never the matched text.
+        assert_eq!(
+            order.samples,
+            vec![Sample { file: "app.log".to_string(), line: 1, text: "id=[ORDER_REDACTED] mail [EMAIL_REDACTED]".to_string() }]
+        );
+
+        // A rule in use with the proposed rule's name is the one being replaced.
+        let mut impacts = BTreeMap::new();
+        tally(&mut impacts, "app.log", content, &proposed, &[finding("order", "ORD-1", 3)], 5);
+        assert_eq!(impacts["order"].additional, 3);
+        assert_eq!(impacts["order"].samples[2].line, 2);
+    }
+}
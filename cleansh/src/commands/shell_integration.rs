@@ -0,0 +1,226 @@
+//! This module handles `cleansh install-shell-integration`, which adds an optional `cs`
+//! shell function to the user's startup file. A hook records each command line as it is
+//! run (`PROMPT_COMMAND` in bash, `preexec` in zsh, `fish_preexec` in fish), and `cs`
+//! re-runs the last recorded command with its output piped through `cleansh sanitize`.
+//! Since the command runs again, `cs` shows it and only runs it once the user confirms.
+//!
+//! The integration is written between marker comments, so installing again replaces it
+//! and `--remove` takes exactly it out again. `--print` writes the script to stdout
+//! instead, for package managers (Homebrew, Scoop) that ship it as a file of their own.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{Shell, ShellIntegrationCommand};
+use crate::commands::cleansh::info_msg;
+use crate::ui::theme::ThemeMap;
+use anyhow::{anyhow, Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// First line of the installed block.
+const BEGIN_MARKER: &str = "# >>> cleansh shell integration >>>";
+/// Last line of the installed block.
+const END_MARKER: &str = "# <<< cleansh shell integration <<<";
+
+/// The `cs` function for bash and zsh; only the hook that records commands differs.
+const POSIX_CS_FUNCTION: &str = r#"cs() {
+    if [ -z "${__cleansh_last_command:-}" ]; then
+        echo "cs: no previous command to re-run" >&2
+        return 1
+    fi
+    local answer
+    printf 'cs: re-run `%s` with its output sanitized? [y/N] ' "$__cleansh_last_command" >&2
+    read -r answer
+    case "$answer" in
+        y|Y|yes|Yes|YES) ;;
+        *) echo "cs: cancelled" >&2; return 1 ;;
+    esac
+    eval "$__cleansh_last_command" 2>&1 | command cleansh sanitize "$@"
+}
+"#;
+
+const BASH_HOOK: &str = r#"__cleansh_record_last_command() {
+    local last
+    last=$(HISTTIMEFORMAT= builtin history 1 | sed 's/^ *[0-9]* *//')
+    case "$last" in
+        cs|cs\ *) ;;
+        *) __cleansh_last_command=$last ;;
+    esac
+}
+case "${PROMPT_COMMAND:-}" in
+    *__cleansh_record_last_command*) ;;
+    *) PROMPT_COMMAND="__cleansh_record_last_command${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac
+"#;
+
+const ZSH_HOOK: &str = r#"__cleansh_preexec() {
+    case "$1" in
+        cs|cs\ *) ;;
+        *) __cleansh_last_command=$1 ;;
+    esac
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec __cleansh_preexec
+"#;
+
+const FISH_SCRIPT: &str = r#"function __cleansh_preexec --on-event fish_preexec
+    string match -qr '^cs( |$)' -- $argv[1]; or set -g __cleansh_last_command $argv[1]
+end
+function cs --description 'Re-run the last command with its output sanitized by cleansh'
+    if not set -q __cleansh_last_command
+        echo "cs: no previous command to re-run" >&2
+        return 1
+    end
+    read -l -P "cs: re-run `$__cleansh_last_command` with its output sanitized? [y/N] " answer
+    if not contains -- "$answer" y Y yes Yes YES
+        echo "cs: cancelled" >&2
+        return 1
+    end
+    eval $__cleansh_last_command 2>&1 | command cleansh sanitize $argv
+end
+"#;
+
+/// Returns the integration for `shell`, wrapped in the marker comments.
+pub fn script(shell: Shell) -> String {
+    let body = match shell {
+        Shell::Bash => format!("{}{}", BASH_HOOK, POSIX_CS_FUNCTION),
+        Shell::Zsh => format!("{}{}", ZSH_HOOK, POSIX_CS_FUNCTION),
+        Shell::Fish => FISH_SCRIPT.to_string(),
+    };
+    format!(
+        "{}\n# Added by `cleansh install-shell-integration`; remove it with `cleansh install-shell-integration --remove`.\n{}{}\n",
+        BEGIN_MARKER, body, END_MARKER
+    )
+}
+
+/// Detects the shell from the file name in `$SHELL`.
+pub fn detect_shell(shell_var: Option<&str>) -> Option<Shell> {
+    let name = Path::new(shell_var?).file_name()?.to_str()?;
+    match name {
+        "bash" => Some(Shell::Bash),
+        "zsh" => Some(Shell::Zsh),
+        "fish" => Some(Shell::Fish),
+        _ => None,
+    }
+}
+
+/// The startup file the integration goes into by default.
+fn default_rc_file(shell: Shell) -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine your home directory; pass --rc-file"))?;
+    Ok(match shell {
+        Shell::Bash => home.join(".bashrc"),
+        Shell::Zsh => env::var_os("ZDOTDIR").map(PathBuf::from).unwrap_or(home).join(".zshrc"),
+        Shell::Fish => env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".config"))
+            .join("fish/conf.d/cleansh.fish"),
+    })
+}
+
+/// Takes an installed block out of `content`, with the blank line that separated it from
+/// the rest. Returns `None` when no complete block is found.
+pub fn remove_block(content: &str) -> Option<String> {
+    let start = content.find(BEGIN_MARKER)?;
+    let end_marker = start + content[start..].find(END_MARKER)?;
+    let end = content[end_marker..].find('\n').map_or(content.len(), |i| end_marker + i + 1);
+
+    let mut before = content[..start].to_string();
+    if before.ends_with("\n\n") {
+        before.pop();
+    }
+    Some(before + &content[end..])
+}
+
+/// Returns `content` with `block` appended after a blank line, replacing any block
+/// installed before.
+pub fn install_block(content: &str, block: &str) -> String {
+    let mut updated = remove_block(content).unwrap_or_else(|| content.to_string());
+    if !updated.is_empty() {
+        if !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        updated.push('\n');
+    }
+    updated.push_str(block);
+    updated
+}
+
+/// The main entry point for `cleansh install-shell-integration`.
+pub fn run_install_shell_integration(opts: &ShellIntegrationCommand, theme_map: &ThemeMap) -> Result<()> {
+    let shell = match opts.shell {
+        Some(shell) => shell,
+        None => detect_shell(env::var("SHELL").ok().as_deref())
+            .ok_or_else(|| anyhow!("Could not detect your shell from $SHELL; pass --shell bash, zsh or fish"))?,
+    };
+    if opts.print {
+        print!("{}", script(shell));
+        return Ok(());
+    }
+
+    let rc_file = match &opts.rc_file {
+        Some(path) => path.clone(),
+        None => default_rc_file(shell)?,
+    };
+    let existing = match fs::read_to_string(&rc_file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", rc_file.display())),
+    };
+
+    if opts.remove {
+        let Some(updated) = remove_block(&existing) else {
+            info_msg(format!("No cleansh shell integration found in {}.", rc_file.display()), theme_map);
+            return Ok(());
+        };
+        if updated.trim().is_empty() && shell == Shell::Fish && opts.rc_file.is_none() {
+            fs::remove_file(&rc_file).with_context(|| format!("Failed to remove {}", rc_file.display()))?;
+        } else {
+            fs::write(&rc_file, updated).with_context(|| format!("Failed to write {}", rc_file.display()))?;
+        }
+        info_msg(format!("Removed the cleansh shell integration from {}.", rc_file.display()), theme_map);
+        return Ok(());
+    }
+
+    if let Some(parent) = rc_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&rc_file, install_block(&existing, &script(shell)))
+        .with_context(|| format!("Failed to write {}", rc_file.display()))?;
+    info_msg(
+        format!(
+            "Installed the cleansh shell integration into {}. Open a new shell (or source the file), then run `cs` to re-run the last command with its output sanitized.",
+            rc_file.display()
+        ),
+        theme_map,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installing_twice_keeps_one_block_and_removal_restores_the_file() {
+        let original = "export PATH=$HOME/bin:$PATH\n";
+        let installed = install_block(original, &script(Shell::Bash));
+        assert!(installed.starts_with(original));
+        assert!(installed.contains("PROMPT_COMMAND"));
+
+        let reinstalled = install_block(&installed, &script(Shell::Bash));
+        assert_eq!(reinstalled, installed);
+        assert_eq!(remove_block(&reinstalled).as_deref(), Some(original));
+        assert_eq!(remove_block(original), None);
+    }
+
+    #[test]
+    fn detects_shell_from_shell_variable() {
+        assert_eq!(detect_shell(Some("/usr/local/bin/zsh")), Some(Shell::Zsh));
+        assert_eq!(detect_shell(Some("/bin/bash")), Some(Shell::Bash));
+        assert_eq!(detect_shell(Some("/bin/tcsh")), None);
+        assert_eq!(detect_shell(None), None);
+        assert!(script(Shell::Fish).contains("fish_preexec"));
+        assert!(script(Shell::Zsh).contains("add-zsh-hook preexec"));
+    }
+}
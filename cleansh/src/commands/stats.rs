@@ -4,85 +4,646 @@
 //!
 //! License: Polyform Noncommercial License 1.0.0
 
-use crate::cli::ScanCommand;
+use crate::cli::{ScanCommand, Severity};
 use crate::ui::theme::ThemeMap;
 use crate::ui::redaction_summary;
 use anyhow::{Result, Context, anyhow};
 use std::io::{self, Read, Write};
 use std::fs;
 use is_terminal::IsTerminal;
+use cleansh_core::config::RedactionConfig;
 use cleansh_core::engine::SanitizationEngine;
-use cleansh_core::RedactionMatch;
-use std::collections::HashMap;
+use cleansh_core::{RedactionMatch, RuleMetadata, TenantCounts, TenantLines, SCHEMA_VERSION};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use log::debug;
+use crate::commands::cleansh::{info_msg, progress_msg, warn_msg};
+use crate::utils::audit_trail::{AuditTrail, AuditedRedaction};
+use crate::utils::dir_walk;
+use crate::utils::encoding::{self, TextEncoding};
+use crate::utils::match_budget::{MatchStore, Truncation};
+use crate::utils::notifier;
+use crate::utils::platform;
+use crate::utils::scan_cache::ScanCache;
+use crate::utils::scan_checkpoint::ScanCheckpoint;
+use crate::utils::scan_history::{self, HistoryEntry};
+use crate::utils::scan_sampling::{Sampler, SamplingSummary};
+use crate::utils::siem::{self, SiemForwarder};
+use crate::utils::staged_diff;
+
+/// A single unit of scan input: where it came from and its text content.
+struct ScanInput {
+    source: String,
+    /// `true` if `source` is the path of a file, whose rules' `paths` scopes then apply.
+    is_file: bool,
+    content: String,
+}
+
+/// Sums the finding counts of each rule by the rule's severity, lowercased. Findings of rules
+/// without a severity are counted under `unspecified`.
+pub fn severity_counts(rule_counts: &HashMap<String, usize>, rules: &RedactionConfig) -> BTreeMap<String, usize> {
+    let mut by_severity = BTreeMap::new();
+    for (name, count) in rule_counts.iter().filter(|(_, count)| **count > 0) {
+        let severity = rules
+            .rules
+            .iter()
+            .find(|rule| &rule.name == name)
+            .and_then(|rule| rule.severity.as_deref())
+            .map_or_else(|| "unspecified".to_string(), str::to_ascii_lowercase);
+        *by_severity.entry(severity).or_default() += count;
+    }
+    by_severity
+}
+
+/// Returns the number of findings in `by_severity` of `level` or higher.
+pub fn count_at_or_above(by_severity: &BTreeMap<String, usize>, level: Severity) -> usize {
+    by_severity
+        .iter()
+        .filter(|(severity, _)| Severity::from_rule(severity).is_some_and(|found| found >= level))
+        .map(|(_, count)| count)
+        .sum()
+}
+
+/// Per-tenant finding counts. Tenants are counted under their hash labels, so that neither
+/// the summary nor a checkpoint holds customer names, unless `--tenant-names` asks for them.
+struct TenantTally {
+    counts: TenantCounts,
+    names: bool,
+}
+
+impl TenantTally {
+    /// Starts the counts when the config sets `tenant`.
+    fn new(engine: &dyn SanitizationEngine, opts: &ScanCommand) -> Option<Self> {
+        engine.get_rules().tenant.as_ref().map(|_| Self { counts: TenantCounts::new(), names: opts.tenant_names })
+    }
+
+    /// Extracts the tenant of each line of `content`, under the labels this tally counts.
+    fn lines(&self, engine: &dyn SanitizationEngine, content: &str) -> Result<Option<TenantLines>> {
+        let Some(key) = &engine.get_rules().tenant else { return Ok(None) };
+        let lines = TenantLines::new(key, engine.compiled_rules(), content)?;
+        Ok(Some(if self.names { lines } else { lines.hashed() }))
+    }
+}
+
+/// The findings of one file of a multi-file scan.
+#[derive(Debug, Default, serde::Serialize)]
+struct FileFindings {
+    total: usize,
+    redaction_summary: BTreeMap<String, usize>,
+    /// The line and rule of each finding, kept for `--staged` scans.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    findings: Vec<LineFinding>,
+}
+
+/// Where a `--staged` scan found a match.
+#[derive(Debug, serde::Serialize)]
+struct LineFinding {
+    line: u64,
+    rule_name: String,
+}
+
+impl FileFindings {
+    fn add(&mut self, matches: &[RedactionMatch]) {
+        self.total += matches.len();
+        for m in matches {
+            *self.redaction_summary.entry(m.rule_name.clone()).or_default() += 1;
+        }
+    }
+}
+
+/// What a scan has found so far: the bounded store of matches, the exact counts per file
+/// of a multi-file scan and, when the config sets `tenant`, the per-tenant counts.
+/// With `--audit-log`, every finding is also appended to the log as it is found.
+struct Findings {
+    store: MatchStore,
+    files: BTreeMap<String, FileFindings>,
+    tenants: Option<TenantTally>,
+    audit_log: Option<AuditTrail>,
+}
+
+/// Appends the findings in `input` to the `--audit-log`, if one is open.
+fn audit_findings(
+    audit_log: &mut Option<AuditTrail>,
+    engine: &dyn SanitizationEngine,
+    input: &ScanInput,
+    matches: &[RedactionMatch],
+) -> Result<()> {
+    let Some(trail) = audit_log.as_mut() else { return Ok(()) };
+    let redactions = matches.iter().map(|m| AuditedRedaction {
+        rule_name: &m.rule_name,
+        original: &m.original_string,
+        replacement: &m.sanitized_string,
+        start: m.start,
+        end: m.end,
+    });
+    trail.record(&input.source, &input.content, engine.get_rules(), redactions)
+}
+
+/// Adds the findings of one input to the per-tenant counts, which are only kept when the
+/// config sets `tenant`.
+fn count_tenants(
+    engine: &dyn SanitizationEngine,
+    content: &str,
+    matches: &[RedactionMatch],
+    tenants: &mut Option<TenantTally>,
+) -> Result<()> {
+    let Some(tally) = tenants.as_mut() else { return Ok(()) };
+    if let Some(lines) = tally.lines(engine, content)? {
+        lines.count_into(matches, &mut tally.counts);
+    }
+    Ok(())
+}
+
+/// Finds the matches in one input, or with a `sampler`, in its sampled lines until the
+/// time box runs out. Matches in sampled lines get their offsets and line numbers in the
+/// whole input.
+fn find_input_matches(
+    engine: &dyn SanitizationEngine,
+    input: &ScanInput,
+    sampler: Option<&mut Sampler>,
+    tenants: &mut Option<TenantTally>,
+) -> Result<Vec<RedactionMatch>> {
+    let Some(sampler) = sampler else {
+        let matches = if input.is_file {
+            engine.find_file_matches(&input.content, &input.source)
+        } else {
+            engine.find_matches_for_ui(&input.content, &input.source)
+        }
+        .with_context(|| format!("Failed to analyze content for statistics: {}", input.source))?;
+        count_tenants(engine, &input.content, &matches, tenants)?;
+        return Ok(matches);
+    };
+
+    let mut matches = Vec::new();
+    let mut tenant_lines = None;
+    for chunk in sampler.chunks(&input.content) {
+        if sampler.expired() {
+            break;
+        }
+        let mut found = if input.is_file {
+            engine.find_file_matches(&chunk.text, &input.source)
+        } else {
+            engine.find_matches_for_ui(&chunk.text, &input.source)
+        }
+        .with_context(|| format!("Failed to analyze content for statistics: {}", input.source))?;
+        for m in &mut found {
+            chunk.locate(m);
+        }
+        if let Some(tally) = tenants.as_mut() {
+            if tenant_lines.is_none() {
+                tenant_lines = tally.lines(engine, &input.content)?;
+            }
+            if let Some(lines) = &tenant_lines {
+                lines.count_into(&found, &mut tally.counts);
+            }
+        }
+        sampler.record_scanned(&chunk);
+        matches.extend(found);
+    }
+    Ok(matches)
+}
+
+/// Reads the `--files-from` list or lists the files of `--dir`, or returns `None` when
+/// the scan has a single input.
+fn read_file_list(opts: &ScanCommand) -> Result<Option<Vec<PathBuf>>> {
+    if let Some(dir) = &opts.dir {
+        return dir_walk::list_files(dir, opts.recursive, &opts.glob).map(Some);
+    }
+    let Some(list_path) = &opts.files_from else { return Ok(None) };
+    let list_bytes = if list_path.as_os_str() == "-" {
+        let mut buf = Vec::new();
+        io::stdin().read_to_end(&mut buf).context("Failed to read file list from stdin")?;
+        buf
+    } else {
+        fs::read(platform::long_path(list_path))
+            .with_context(|| format!("Failed to read file list: {}", list_path.display()))?
+    };
+    Ok(Some(parse_file_list(&list_bytes, opts.null_delimited)))
+}
+
+/// Reads one entry of a file list, or returns `None` if it has to be skipped.
+fn read_list_entry(path: &Path, theme_map: &ThemeMap) -> Option<ScanInput> {
+    let bytes = match fs::read(platform::long_path(path)) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn_msg(format!("Skipping '{}': {}", path.display(), e), theme_map);
+            return None;
+        }
+    };
+    // Without a BOM, non-UTF-8 files in a list are usually binaries, so they are skipped
+    // rather than decoded as Windows-1252.
+    match encoding::decode(&bytes) {
+        (_, TextEncoding::Windows1252) => {
+            debug!("Skipping non-UTF-8 file '{}'.", path.display());
+            None
+        }
+        (content, _) => Some(ScanInput { source: path.display().to_string(), is_file: true, content }),
+    }
+}
+
+/// Gathers the single input to scan from `--input-file` or stdin.
+fn read_single_input(opts: &ScanCommand) -> Result<ScanInput> {
+    if let Some(path) = &opts.input_file {
+        let bytes = fs::read(platform::long_path(path))
+            .with_context(|| format!("Failed to read input file: {}", path.display()))?;
+        let (content, _) = encoding::decode(&bytes);
+        return Ok(ScanInput { source: path.display().to_string(), is_file: true, content });
+    }
+
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes)?;
+    let (content, _) = encoding::decode(&bytes);
+    Ok(ScanInput { source: "stdin".to_string(), is_file: false, content })
+}
+
+/// Scans every entry of a file list, keeping the `--checkpoint` up to date if one is set.
+///
+/// Files found unchanged in `cache` are not scanned again; their cached findings are used
+/// instead. Adds the findings (including those carried over by `--resume`) to `findings`
+/// and returns the number of files that were scanned.
+fn scan_file_list(
+    opts: &ScanCommand,
+    paths: &[PathBuf],
+    engine: &dyn SanitizationEngine,
+    theme_map: &ThemeMap,
+    mut sampler: Option<&mut Sampler>,
+    mut cache: Option<&mut ScanCache>,
+    findings: &mut Findings,
+) -> Result<usize> {
+    let Findings { store, files, tenants, audit_log } = findings;
+    let mut checkpoint = match &opts.checkpoint {
+        Some(path) => Some(ScanCheckpoint::open(path, paths, opts.resume)?),
+        None => None,
+    };
+    let mut files_scanned = 0;
+    if let Some(checkpoint) = &checkpoint {
+        if checkpoint.files_scanned() > 0 || !checkpoint.findings().is_empty() {
+            progress_msg(
+                format!("Resuming scan: {} file(s) already scanned, {} finding(s) so far.", checkpoint.files_scanned(), checkpoint.findings().len()),
+                theme_map,
+            );
+        }
+        for m in checkpoint.findings() {
+            files.entry(m.source_id.clone()).or_default().add(std::slice::from_ref(m));
+        }
+        store.extend(checkpoint.findings().iter().cloned());
+        files_scanned = checkpoint.files_scanned();
+        if let Some(tally) = tenants.as_mut() {
+            tally.counts.clone_from(checkpoint.tenants());
+        }
+    }
+
+    for path in paths {
+        if checkpoint.as_ref().is_some_and(|c| c.is_done(path)) {
+            continue;
+        }
+        if let Some(sampler) = sampler.as_deref_mut() && sampler.expired() {
+            sampler.skip(fs::metadata(platform::long_path(path)).map(|m| m.len()).unwrap_or(0));
+            continue;
+        }
+        let matches = match read_list_entry(path, theme_map) {
+            Some(input) if let Some(cached) = cache.as_deref_mut().and_then(|c| c.get(&input.source, &input.content)) => {
+                count_tenants(engine, &input.content, &cached, tenants)?;
+                Some(cached)
+            }
+            Some(input) => {
+                match find_input_matches(engine, &input, sampler.as_deref_mut(), tenants) {
+                    Ok(matches) => {
+                        audit_findings(audit_log, engine, &input, &matches)?;
+                        if let Some(cache) = cache.as_deref() {
+                            cache.put(&input.source, &input.content, &matches);
+                        }
+                        Some(matches)
+                    }
+                    Err(e) => {
+                        // Keep the progress made so far for --resume before giving up.
+                        if let Some(checkpoint) = checkpoint.as_mut() {
+                            checkpoint.save()?;
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+            None => None,
+        };
+        if let Some(checkpoint) = checkpoint.as_mut() {
+            checkpoint.record(path, matches.as_deref());
+            if let Some(tally) = tenants.as_ref() {
+                checkpoint.set_tenants(&tally.counts);
+            }
+            checkpoint.save_if_due()?;
+        }
+        if let Some(matches) = matches {
+            files_scanned += 1;
+            if !matches.is_empty() {
+                files.entry(path.display().to_string()).or_default().add(&matches);
+            }
+            store.extend(matches);
+        }
+    }
+    debug!("Scanned {} file(s) from the file list.", files_scanned);
+
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.finish()?;
+    }
+    Ok(files_scanned)
+}
+
+/// Scans the lines added by the changes staged in the current git repository, counting
+/// findings per file with their line numbers. Returns the number of files scanned.
+fn scan_staged(
+    engine: &dyn SanitizationEngine,
+    mut sampler: Option<&mut Sampler>,
+    findings: &mut Findings,
+) -> Result<usize> {
+    let staged = staged_diff::read_staged()?;
+    for file in &staged {
+        let input = ScanInput { source: file.path.clone(), is_file: true, content: file.content.clone() };
+        let mut matches = find_input_matches(engine, &input, sampler.as_deref_mut(), &mut findings.tenants)?;
+        for m in &mut matches {
+            let line = input.content[..m.start as usize].matches('\n').count() as u64 + 1;
+            m.line_number.get_or_insert(line);
+        }
+        if !matches.is_empty() {
+            let found = findings.files.entry(file.path.clone()).or_default();
+            found.add(&matches);
+            found.findings.extend(matches.iter().map(|m| LineFinding {
+                line: m.line_number.unwrap_or_default(),
+                rule_name: m.rule_name.clone(),
+            }));
+        }
+        audit_findings(&mut findings.audit_log, engine, &input, &matches)?;
+        findings.store.extend(matches);
+    }
+    debug!("Scanned the staged lines of {} file(s).", staged.len());
+    Ok(staged.len())
+}
+
+/// Splits a file list into paths, using NUL or newline separators.
+///
+/// Empty entries are ignored. In newline mode a trailing `\r` is stripped so lists
+/// produced on Windows work too; NUL mode keeps names byte-for-byte, which is the
+/// only way to pass filenames containing newlines.
+pub fn parse_file_list(list: &[u8], null_delimited: bool) -> Vec<PathBuf> {
+    let separator = if null_delimited { b'\0' } else { b'\n' };
+    list.split(|b| *b == separator)
+        .map(|entry| {
+            if !null_delimited {
+                entry.strip_suffix(b"\r").unwrap_or(entry)
+            } else {
+                entry
+            }
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(path_from_bytes)
+        .collect()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// Sends metadata-only findings to the SIEM configured with `--forward`.
+///
+/// Forwarding problems are reported as warnings so that a SIEM outage never changes
+/// the outcome of the scan itself (including `--fail-over-threshold`).
+fn forward_findings(opts: &ScanCommand, engine: &dyn SanitizationEngine, matches: &[RedactionMatch], theme_map: &ThemeMap) {
+    let (Some(target), Some(endpoint), Some(token_env)) = (opts.forward.clone(), opts.endpoint.as_deref(), opts.token_env.as_deref()) else {
+        return;
+    };
+    let Ok(token) = std::env::var(token_env) else {
+        warn_msg(format!("Not forwarding findings: environment variable {} is not set.", token_env), theme_map);
+        return;
+    };
+
+    let findings = siem::findings_from_matches(matches, engine.get_rules());
+    let result = SiemForwarder::new(target, endpoint, token, opts.forward_batch_size)
+        .and_then(|forwarder| forwarder.forward(&findings));
+    match result {
+        Ok(sent) => info_msg(format!("Forwarded {} finding(s) to {}", sent, endpoint), theme_map),
+        Err(e) => warn_msg(format!("Failed to forward findings to {}: {:#}", endpoint, e), theme_map),
+    }
+}
+
+/// Posts a fail-over notification to the configured webhook.
+///
+/// Like SIEM forwarding, notification problems only produce a warning; the scan still
+/// fails with its usual exit code.
+fn notify_fail_over(opts: &ScanCommand, url: &str, threshold: usize, rule_counts: &HashMap<String, usize>, theme_map: &ThemeMap) {
+    let total = rule_counts.values().sum();
+    let vars = notifier::template_vars(total, threshold, rule_counts, &notifier::CiMetadata::from_env());
+    let text = notifier::render_template(opts.notify_template.as_deref().unwrap_or(notifier::DEFAULT_TEMPLATE), &vars);
+
+    match notifier::send_webhook(url, &notifier::build_payload(&opts.notify_format, &text)) {
+        Ok(()) => info_msg("Sent fail-over notification to webhook.", theme_map),
+        Err(e) => warn_msg(format!("Failed to send fail-over notification: {:#}", e), theme_map),
+    }
+}
 
 /// The main entry point for the `cleansh stats` subcommand.
-pub fn run_stats_command(opts: &ScanCommand, theme_map: &ThemeMap, engine: &dyn SanitizationEngine) -> Result<()> {
+///
+/// With `history_path`, the finding counts are appended to the scan history, including
+/// for scans that end in a fail-over. With `cache_dir`, files of a `--files-from` list or
+/// a `--dir` are looked up in and added to the scan cache.
+pub fn run_stats_command(
+    opts: &ScanCommand,
+    theme_map: &ThemeMap,
+    engine: &dyn SanitizationEngine,
+    history_path: Option<&Path>,
+    cache_dir: Option<&Path>,
+) -> Result<()> {
     // Determine if we should use colors based on the output stream's terminal status.
     // For human-readable summaries, we write to stderr.
     let enable_colors = io::stderr().is_terminal();
 
-    // Read input content
-    let input_content = if let Some(path) = &opts.input_file {
-        fs::read_to_string(path)
-            .with_context(|| format!("Failed to read input file: {}", path.display()))?
-    } else {
-        let mut content = String::new();
-        io::stdin().read_to_string(&mut content)?;
-        content
+    let mut findings = Findings {
+        store: MatchStore::new(opts.max_matches, engine.get_options().seed),
+        files: BTreeMap::new(),
+        tenants: TenantTally::new(engine, opts),
+        audit_log: match &opts.audit_log {
+            Some(path) => Some(AuditTrail::open(path, "detected")?),
+            None => None,
+        },
     };
-
-    // Corrected: Provide a default source name when reading from stdin
-    let source_name = opts.input_file.clone()
-        .unwrap_or_default()
-        .display()
-        .to_string();
-    let source_name = if source_name.is_empty() {
-        "stdin".to_string()
-    } else {
-        source_name
+    let mut sampler = Sampler::new(opts.sample_percent, opts.max_seconds);
+    let file_list = read_file_list(opts)?;
+    let multi_file = file_list.is_some() || opts.staged;
+    let source_count = match file_list {
+        _ if opts.staged => scan_staged(engine, sampler.as_mut(), &mut findings)?,
+        Some(paths) => {
+            // Sampled scans only see part of each file, so their findings are not cached.
+            // Cached findings no longer hold the matched text, which the audit log hashes.
+            let mut cache = match cache_dir.filter(|_| sampler.is_none() && opts.audit_log.is_none()) {
+                Some(dir) => Some(ScanCache::new(dir, engine)?),
+                None => None,
+            };
+            let scanned = scan_file_list(opts, &paths, engine, theme_map, sampler.as_mut(), cache.as_mut(), &mut findings)?;
+            if let Some(cache) = cache {
+                if cache.hits() > 0 {
+                    info_msg(format!("Reused cached findings for {} of {} file(s).", cache.hits(), scanned), theme_map);
+                }
+                cache.prune();
+            }
+            scanned
+        }
+        None => {
+            let input = read_single_input(opts)?;
+            let matches = find_input_matches(engine, &input, sampler.as_mut(), &mut findings.tenants)?;
+            audit_findings(&mut findings.audit_log, engine, &input, &matches)?;
+            findings.store.extend(matches);
+            1
+        }
     };
+    let Findings { store, files, tenants, .. } = findings;
 
-    let all_matches = engine.find_matches_for_ui(&input_content, &source_name)
-        .context("Failed to analyze content for statistics")?;
+    let summary_map = store.counts().clone();
+    let truncation = store.truncation();
+    if let Some(truncation) = &truncation {
+        warn_msg(
+            format!(
+                "Found {} matches; only a random sample of {} was kept in memory (--max-matches). Counts are complete, but sample matches{} cover the sample only.",
+                truncation.total_matches,
+                truncation.max_matches,
+                if opts.forward.is_some() { " and forwarded findings" } else { "" },
+            ),
+            theme_map,
+        );
+    }
 
-    let mut aggregated_matches: HashMap<String, Vec<&RedactionMatch>> = HashMap::new();
-    for m in &all_matches {
-        aggregated_matches.entry(m.rule_name.clone()).or_insert_with(Vec::new).push(m);
+    let sampling = sampler.map(|sampler| sampler.summary(&summary_map));
+    if let Some(sampling) = &sampling {
+        let estimates: Vec<String> = sampling.estimated_summary.iter().map(|(rule, n)| format!("{} ~{}", rule, n)).collect();
+        warn_msg(
+            format!(
+                "Sampled scan: {}% of the input ({} of {} bytes) was scanned{}. Counts are from the sample only; estimated totals: {}.",
+                sampling.coverage_percent,
+                sampling.scanned_bytes,
+                sampling.total_bytes,
+                if sampling.stopped_early { ", stopped by --max-seconds" } else { "" },
+                if estimates.is_empty() { "none".to_string() } else { estimates.join(", ") },
+            ),
+            theme_map,
+        );
     }
     
+    if let Some(path) = history_path {
+        // Sampled scans record their estimated totals, so they compare with full scans.
+        let mut entry = match &sampling {
+            Some(sampling) => HistoryEntry::now(&sampling.estimated_summary),
+            None => HistoryEntry::now(&summary_map),
+        };
+        if opts.history_files {
+            entry = entry.with_files(files.iter().map(|(path, found)| (path.clone(), found.total)));
+        }
+        if let Err(e) = scan_history::append(path, &entry) {
+            warn_msg(format!("Failed to record scan history: {:#}", e), theme_map);
+        }
+    }
+
+    if opts.forward.is_some() {
+        forward_findings(opts, engine, store.matches(), theme_map);
+    }
+
+    // A pre-commit hook needs the locations even when the scan fails below.
+    if opts.staged && !opts.json_stdout {
+        let mut locations: Vec<(&str, u64, &str)> = files
+            .iter()
+            .flat_map(|(path, found)| found.findings.iter().map(move |f| (path.as_str(), f.line, f.rule_name.as_str())))
+            .collect();
+        locations.sort();
+        redaction_summary::print_finding_locations(&locations, &mut io::stderr(), theme_map, enable_colors).ok();
+    }
+
     // --- Fail-over logic for stats command
     // If a threshold is set and the number of matches exceeds it, return an error.
-    if let Some(threshold) = opts.fail_over_threshold {
-        if all_matches.len() > threshold {
+    // `--strict` fails on any finding unless a threshold is given explicitly.
+    // `--fail-on-severity` takes its place, so that low-severity findings do not fail a strict scan.
+    let strict_threshold = (opts.strict && opts.fail_on_severity.is_none()).then_some(0);
+    if let Some(threshold) = opts.fail_over_threshold.or(strict_threshold) {
+        if store.total() > threshold {
             // Print the specific fail-over message before returning the error
             redaction_summary::print_stats_fail_over_message(
                 threshold,
-                all_matches.len(),
+                store.total(),
                 &mut io::stderr(),
                 theme_map,
                 enable_colors,
             ).ok(); // Use .ok() to prevent this write from causing a non-zero exit status
 
+            if let Some(url) = opts.notify_webhook.as_deref() {
+                notify_fail_over(opts, url, threshold, &summary_map, theme_map);
+            }
+
             // Then return the error to trigger a non-zero exit code
             return Err(anyhow!("FAIL-OVER threshold exceeded."));
         }
     }
+    let by_severity = severity_counts(&summary_map, engine.get_rules());
+    if let Some(level) = opts.fail_on_severity {
+        let matches_found = count_at_or_above(&by_severity, level);
+        if matches_found > 0 {
+            redaction_summary::print_severity_fail_message(
+                level,
+                matches_found,
+                &mut io::stderr(),
+                theme_map,
+                enable_colors,
+            ).ok();
+            return Err(anyhow!("FAIL-ON-SEVERITY triggered."));
+        }
+    }
     // --- End fail-over logic
 
     // Serialize the summary to JSON, as it's needed for both --json-file and --json-stdout
     #[derive(serde::Serialize)]
     struct StatsSummary {
+        schema_version: &'static str,
         redaction_summary: HashMap<String, usize>,
+        /// Number of files scanned, present for `--files-from` and `--dir` scans.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        files_scanned: Option<usize>,
+        /// Findings per file, for each file with findings, present for `--files-from` and `--dir` scans.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        files: Option<BTreeMap<String, FileFindings>>,
+        /// Finding counts per rule severity; rules without one are counted as `unspecified`.
+        severity_summary: BTreeMap<String, usize>,
+        /// Metadata of each rule in `redaction_summary`, so findings can be tied to a rule version.
+        rules: BTreeMap<String, RuleMetadata>,
+        /// Finding counts per tenant and rule, present when the config sets `tenant`. Keyed
+        /// by tenant hash labels unless `--tenant-names` is given.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tenants: Option<TenantCounts>,
+        /// Coverage and extrapolated counts, present for `--sample-percent`/`--max-seconds` scans.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sampling: Option<SamplingSummary>,
+        /// Present when more findings were found than `--max-matches` allowed to keep.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        truncation: Option<Truncation>,
     }
-    let summary_map: HashMap<String, usize> = aggregated_matches
-        .iter()
-        .map(|(rule_name, matches)| (rule_name.clone(), matches.len()))
-        .collect();
-    let json_output = serde_json::to_string_pretty(&StatsSummary { redaction_summary: summary_map })
-        .context("Failed to serialize stats summary to JSON")?;
+    let rules = engine.get_rules().rule_metadata(summary_map.keys().map(String::as_str));
+    let file_totals: Vec<(String, usize)> = files.iter().map(|(path, found)| (path.clone(), found.total)).collect();
+    let summary = StatsSummary {
+        schema_version: SCHEMA_VERSION,
+        redaction_summary: summary_map.clone(),
+        severity_summary: by_severity.clone(),
+        files_scanned: multi_file.then_some(source_count),
+        files: multi_file.then_some(files),
+        rules,
+        tenants: tenants.map(|tally| tally.counts),
+        sampling,
+        truncation,
+    };
+    let json_output = serde_json::to_string_pretty(&summary).context("Failed to serialize stats summary to JSON")?;
 
     if let Some(json_path) = &opts.json_file {
-        fs::write(json_path, json_output.as_bytes())
+        fs::write(platform::long_path(json_path), json_output.as_bytes())
             .with_context(|| format!("Failed to write JSON output to file: {}", json_path.display()))?;
     } else if opts.json_stdout {
         // Correctly handle `--json-stdout`
@@ -93,13 +654,18 @@ pub fn run_stats_command(opts: &ScanCommand, theme_map: &ThemeMap, engine: &dyn
             .context("Failed to write newline to stdout")?;
     } else {
         redaction_summary::print_summary_for_stats_mode(
-            &aggregated_matches,
+            &store,
             engine.compiled_rules(),
+            source_count,
             &mut io::stderr(),
             theme_map,
             opts.sample_matches,
             enable_colors,
         ).ok(); // Use .ok() to prevent this write from causing a non-zero exit status
+        redaction_summary::print_severity_breakdown(&by_severity, &mut io::stderr(), theme_map, enable_colors).ok();
+        if multi_file {
+            redaction_summary::print_file_breakdown(&file_totals, &mut io::stderr(), theme_map, enable_colors).ok();
+        }
     }
 
     Ok(())
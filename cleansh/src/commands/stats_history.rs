@@ -0,0 +1,74 @@
+//! This module handles `cleansh stats history`, which shows how the findings of scans run
+//! with `--record-history` develop over time: scans, findings and findings per scan for
+//! each day or week, and the change between the first and the last period shown.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{HistoryPeriod, StatsHistoryCommand};
+use crate::commands::cleansh::info_msg;
+use crate::ui::theme::ThemeMap;
+use crate::utils::scan_history::{self, HistoryBucket};
+use anyhow::{Context, Result};
+use cleansh_core::SCHEMA_VERSION;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The `--json` document.
+#[derive(Serialize)]
+struct HistoryJson<'a> {
+    schema_version: &'static str,
+    period: &'static str,
+    periods: &'a [HistoryBucket],
+}
+
+/// Prints the buckets as an aligned table followed by the overall trend.
+fn print_table<W: Write>(buckets: &[HistoryBucket], writer: &mut W) -> Result<()> {
+    let width = buckets.iter().map(|b| b.period.len()).chain(["period".len()]).max().unwrap_or(0);
+    writeln!(writer, "{:<width$}  {:>5}  {:>8}  {:>8}", "period", "scans", "findings", "per scan")?;
+    for bucket in buckets {
+        writeln!(writer, "{:<width$}  {:>5}  {:>8}  {:>8.1}", bucket.period, bucket.scans, bucket.findings, bucket.per_scan())?;
+    }
+
+    if let [first, .., last] = buckets {
+        let change = if first.per_scan() > 0.0 {
+            format!(" ({:+.0}%)", (last.per_scan() - first.per_scan()) / first.per_scan() * 100.0)
+        } else {
+            String::new()
+        };
+        writeln!(
+            writer,
+            "Trend: {:.1} -> {:.1} findings per scan{} between {} and {}.",
+            first.per_scan(),
+            last.per_scan(),
+            change,
+            first.period,
+            last.period
+        )?;
+    }
+    Ok(())
+}
+
+/// The main entry point for `cleansh stats history`.
+pub fn run_stats_history(opts: &StatsHistoryCommand, state_path: &Path, theme_map: &ThemeMap) -> Result<()> {
+    let entries = scan_history::load(&scan_history::history_path(state_path))?;
+    let mut buckets = scan_history::buckets(&entries, opts.period == HistoryPeriod::Week, opts.rule.as_deref());
+    if let Some(last) = opts.last {
+        buckets.drain(..buckets.len().saturating_sub(last));
+    }
+
+    let mut stdout = io::stdout().lock();
+    if opts.json {
+        let json = HistoryJson {
+            schema_version: SCHEMA_VERSION,
+            period: if opts.period == HistoryPeriod::Week { "week" } else { "day" },
+            periods: &buckets,
+        };
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&json).context("Failed to serialize scan history")?)?;
+    } else if buckets.is_empty() {
+        info_msg("No scan history recorded yet. Run 'cleansh scan --record-history' to start one.", theme_map);
+    } else {
+        print_table(&buckets, &mut stdout)?;
+    }
+    Ok(())
+}
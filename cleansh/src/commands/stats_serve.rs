@@ -0,0 +1,142 @@
+//! This module handles `cleansh stats serve`, a small local web dashboard over the scans
+//! recorded with `--record-history`: findings over time, top rules and top files.
+//!
+//! The server is deliberately minimal: one connection at a time, `GET` only, and the
+//! history file is read again for every request, so the page always shows the latest
+//! scans. It listens on the loopback address unless told otherwise, and nothing is sent
+//! anywhere else. `/data.json` serves the same figures for scripts.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::{HistoryPeriod, StatsServeCommand};
+use crate::commands::cleansh::{info_msg, warn_msg};
+use crate::ui::stats_dashboard::{self, DashboardData};
+use crate::ui::theme::ThemeMap;
+use crate::utils::runtime_signals::{self, RuntimeStatus};
+use crate::utils::scan_history;
+use anyhow::{Context, Result};
+use cleansh_core::SCHEMA_VERSION;
+use log::debug;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a client may take to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `/data.json` document.
+#[derive(Serialize)]
+struct DashboardJson<'a> {
+    schema_version: &'static str,
+    period: &'static str,
+    periods: &'a [scan_history::HistoryBucket],
+    top_rules: Vec<Ranked<'a>>,
+    top_files: Vec<Ranked<'a>>,
+}
+
+/// A rule or file with its finding count.
+#[derive(Serialize)]
+struct Ranked<'a> {
+    name: &'a str,
+    findings: usize,
+}
+
+fn ranked(rows: &[(String, usize)]) -> Vec<Ranked<'_>> {
+    rows.iter().map(|(name, findings)| Ranked { name, findings: *findings }).collect()
+}
+
+/// Reads the history and collects what the dashboard shows.
+fn load_dashboard(opts: &StatsServeCommand, history_path: &Path) -> Result<DashboardData> {
+    let entries = scan_history::load(history_path)?;
+    let weekly = opts.period == HistoryPeriod::Week;
+    Ok(DashboardData {
+        buckets: scan_history::buckets(&entries, weekly, None),
+        period: if weekly { "week" } else { "day" },
+        top_rules: scan_history::top_rules(&entries, opts.top),
+        top_files: scan_history::top_files(&entries, opts.top),
+    })
+}
+
+/// Answers one request on `stream`.
+fn handle_request(mut stream: TcpStream, opts: &StatsServeCommand, history_path: &Path) -> Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers are not needed, but are read so the client is not cut off mid-request.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default().split('?').next().unwrap_or_default();
+    debug!("stats serve: {} {}", method, path);
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/") => {
+            let data = load_dashboard(opts, history_path)?;
+            ("200 OK", "text/html; charset=utf-8", stats_dashboard::render_dashboard(&data))
+        }
+        ("GET", "/data.json") => {
+            let data = load_dashboard(opts, history_path)?;
+            let json = DashboardJson {
+                schema_version: SCHEMA_VERSION,
+                period: data.period,
+                periods: &data.buckets,
+                top_rules: ranked(&data.top_rules),
+                top_files: ranked(&data.top_files),
+            };
+            let body = serde_json::to_string_pretty(&json).context("Failed to serialize dashboard data")?;
+            ("200 OK", "application/json", body)
+        }
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Only GET is supported\n".to_string()),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// The main entry point for `cleansh stats serve`. Serves until interrupted.
+pub fn run_stats_serve(opts: &StatsServeCommand, state_path: &Path, theme_map: &ThemeMap) -> Result<()> {
+    let history_path = scan_history::history_path(state_path);
+    let listener = TcpListener::bind((opts.bind.as_str(), opts.port))
+        .with_context(|| format!("Failed to listen on {}:{}", opts.bind, opts.port))?;
+    let address = listener.local_addr().context("Failed to read the listening address")?;
+    if !address.ip().is_loopback() {
+        warn_msg(
+            format!("Listening on {}, so the dashboard can be reached from other machines.", address.ip()),
+            theme_map,
+        );
+    }
+    info_msg(format!("Serving the scan history dashboard on http://{}/ (press Ctrl-C to stop).", address), theme_map);
+
+    let status = RuntimeStatus::serving("stats serve");
+    runtime_signals::watch(status.clone());
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // A failed request, such as a client that hangs up, does not stop the server.
+                if let Err(e) = handle_request(stream, opts, &history_path) {
+                    debug!("stats serve: request failed: {:#}", e);
+                }
+                status.record(0);
+            }
+            Err(e) => debug!("stats serve: failed to accept a connection: {}", e),
+        }
+    }
+    Ok(())
+}
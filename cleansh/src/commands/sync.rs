@@ -6,6 +6,7 @@
 use crate::cli::SyncProfilesCommand;
 use crate::ui::theme::ThemeMap;
 use crate::ui::sync_ui;
+use crate::utils::notifications::LATEST_VERSION_FILE;
 use anyhow::{Result, anyhow, Context};
 use std::fs;
 use std::io;
@@ -14,6 +15,8 @@ use std::env;
 
 /// The default URL for the users organization server.
 const DEFAULT_SERVER_URL: &str = "https://your-org-server.com";
+/// Response header in which the organization server announces the newest cleansh release.
+const LATEST_VERSION_HEADER: &str = "X-Cleansh-Latest-Version";
 
 /// The main entry point for the `cleansh sync-profiles` subcommand.
 ///
@@ -43,6 +46,13 @@ pub fn run_sync_profiles_command(opts: &SyncProfilesCommand, theme_map: &ThemeMa
         })?;
 
     if response.status().is_success() {
+        // Remembered for the "update available" notification, which never goes online itself.
+        if let Some(latest) = response.headers().get(LATEST_VERSION_HEADER).and_then(|v| v.to_str().ok())
+            && let Some(state_dir) = dirs::data_dir().map(|dir| dir.join("cleansh"))
+            && fs::create_dir_all(&state_dir).is_ok()
+        {
+            let _ = fs::write(state_dir.join(LATEST_VERSION_FILE), latest.trim());
+        }
         let profiles_yaml = response.text().context("Failed to read response body")?;
         
         let config_dir = dirs::config_dir()
@@ -31,6 +31,7 @@ use winapi::um::fileapi::{GetTempPathW, GetTempFileNameW};
 use crate::ui::{output_format, theme};
 use crate::commands::cleansh::info_msg;
 use crate::ui::theme::ThemeMap;
+use crate::utils::platform;
 
 // Global counter to prevent infinite loops in specific scenarios
 static ELEVATION_ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
@@ -41,6 +42,22 @@ fn to_wide_string(s: &OsStr) -> Vec<u16> {
     s.encode_wide().chain(Some(0)).collect()
 }
 
+/// Quotes a path as a single-quoted PowerShell string, doubling any `'` in it so that a
+/// path such as `C:\Users\O'Brien` cannot end the string early. PowerShell also ends
+/// single-quoted strings at the typographic single quotes, so those are doubled too.
+#[cfg(target_os = "windows")]
+fn powershell_literal(path: &std::path::Path) -> String {
+    let mut quoted = String::from("'");
+    for c in path.to_string_lossy().chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
 /// Performs a Windows-specific check to see if the process is running as an administrator.
 #[cfg(target_os = "windows")]
 fn is_elevated() -> bool {
@@ -161,7 +178,10 @@ fn run_uninstaller_logic(yes_flag: bool, theme_map: &ThemeMap) -> Result<()> {
     }
     
     // --- 2. Determine Paths ---
+    // Extended-length paths keep the helper script working for installs and data
+    // directories deeper than MAX_PATH, including ones on UNC shares.
     let current_exe_path = env::current_exe()
+        .map(|path| platform::long_path(&path))
         .context("Failed to determine current executable path.")?;
     debug!("[uninstall.rs] Current executable path: {:?}", current_exe_path);
 
@@ -177,6 +197,7 @@ fn run_uninstaller_logic(yes_flag: bool, theme_map: &ThemeMap) -> Result<()> {
                 PathBuf::from("cleansh_state.json")
             }
         });
+    let app_state_file_path = platform::long_path(&app_state_file_path);
 
     let app_state_dir = app_state_file_path.parent()
         .map(PathBuf::from)
@@ -198,23 +219,24 @@ fn run_uninstaller_logic(yes_flag: bool, theme_map: &ThemeMap) -> Result<()> {
 
         let mut temp_file_path_buf = vec![0u16; 260];
         unsafe { GetTempFileNameW(temp_dir.as_os_str().encode_wide().chain(Some(0)).collect::<Vec<_>>().as_ptr(), to_wide_string(OsStr::new("ps1")).as_ptr(), 0, temp_file_path_buf.as_mut_ptr()) };
-        let temp_ps1_path = PathBuf::from(OsString::from_wide(&temp_file_path_buf));
+        let name_len = temp_file_path_buf.iter().position(|&c| c == 0).unwrap_or(temp_file_path_buf.len());
+        let temp_ps1_path = PathBuf::from(OsString::from_wide(&temp_file_path_buf[..name_len]));
         debug!("[uninstall.rs] Generated temporary PowerShell script path: {:?}", temp_ps1_path);
         
         let current_pid = std::process::id();
-        let current_exe_path_string = current_exe_path.to_string_lossy().replace("'", "''");
-        let app_state_file_path_string = app_state_file_path.to_string_lossy().replace("'", "''");
-        let app_state_dir_string = app_state_dir.to_string_lossy().replace("'", "''");
-        let log_file_string = temp_dir.join(format!("cleansh_uninstall_{}.log", current_pid)).to_string_lossy().replace("'", "''");
+        let current_exe_path_string = powershell_literal(&current_exe_path);
+        let app_state_file_path_string = powershell_literal(&app_state_file_path);
+        let app_state_dir_string = powershell_literal(&app_state_dir);
+        let log_file_string = powershell_literal(&temp_dir.join(format!("cleansh_uninstall_{}.log", current_pid)));
         
         let powershell_script = format!(
             r#"
             # This script runs in a new process to delete the original executable and data.
             $pidToWait = {}
-            $logFile = "{}"
-            $exePath = "{}"
-            $appStateFile = "{}"
-            $appStateDir = "{}"
+            $logFile = {}
+            $exePath = {}
+            $appStateFile = {}
+            $appStateDir = {}
 
             function Log($m){{ "$((Get-Date).ToString('s')) - $m" | Out-File -FilePath $logFile -Append -Encoding utf8 }}
 
@@ -237,8 +259,8 @@ fn run_uninstaller_logic(yes_flag: bool, theme_map: &ThemeMap) -> Result<()> {
             $deletionAttemptSucceeded = $false
             for ($i=0; $i -lt 30; $i++) {{
                 try {{
-                    if (Test-Path $exePath) {{
-                        Remove-Item -Path $exePath -Force -ErrorAction Stop
+                    if (Test-Path -LiteralPath $exePath) {{
+                        Remove-Item -LiteralPath $exePath -Force -ErrorAction Stop
                         Log "Executable deleted successfully on attempt $i."
                         $deletionAttemptSucceeded = $true
                         break
@@ -273,22 +295,22 @@ fn run_uninstaller_logic(yes_flag: bool, theme_map: &ThemeMap) -> Result<()> {
 
             # Remove app state
             try {{
-                if (Test-Path $appStateFile) {{ Remove-Item -Path $appStateFile -Force -ErrorAction Stop; Log "App state file deleted." }}
-                if (Test-Path $appStateDir) {{ Remove-Item -Path $appStateDir -Recurse -Force -ErrorAction Stop; Log "App state dir deleted." }}
+                if (Test-Path -LiteralPath $appStateFile) {{ Remove-Item -LiteralPath $appStateFile -Force -ErrorAction Stop; Log "App state file deleted." }}
+                if (Test-Path -LiteralPath $appStateDir) {{ Remove-Item -LiteralPath $appStateDir -Recurse -Force -ErrorAction Stop; Log "App state dir deleted." }}
             }} catch {{ Log "Failed deleting app-state: $($_.Exception.Message)" }}
 
             Log "Helper script finished."
             Write-Output "Log file: $logFile"
 
             # Clean up the helper script itself
-            Remove-Item -Path '{}' -Force -ErrorAction SilentlyContinue
+            Remove-Item -LiteralPath {} -Force -ErrorAction SilentlyContinue
             "#,
             current_pid,
             log_file_string,
             current_exe_path_string,
             app_state_file_path_string,
             app_state_dir_string,
-            temp_ps1_path.to_string_lossy().replace("'", "''")
+            powershell_literal(&temp_ps1_path)
         );
         
         let mut file = File::create(&temp_ps1_path)
@@ -0,0 +1,143 @@
+//! This module handles `cleansh verify-sanitized`, a read-only check that a sanitized
+//! file is what the current rules make of its original. It is meant for QA of
+//! sanitization done elsewhere or long ago: a third-party job, or files sanitized before
+//! a rule was added.
+//!
+//! The original is sanitized again and compared line by line with the sanitized file.
+//! Two kinds of problems are reported, by line number and rule name only, so the report
+//! itself never contains a secret:
+//!
+//! * **Missed secrets:** lines of the sanitized file that the current rules still match.
+//! * **Unexplained differences:** lines that differ from what the current rules produce,
+//!   or lines of that output that are missing from the sanitized file.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::cli::VerifySanitizedCommand;
+use crate::commands::cleansh::info_msg;
+use crate::ui::output_format::get_styled_text;
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::engine::SanitizationEngine;
+use diffy::{create_patch, Line};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+/// What is wrong with one line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// The line of the sanitized file still matches these rules.
+    MissedSecret(Vec<String>),
+    /// The line of the sanitized file is not what the current rules produce.
+    Unexplained,
+    /// This line of the expected output has no counterpart in the sanitized file.
+    Missing,
+}
+
+/// One problem found by [`compare`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// 1-based line number: in the sanitized file, or in the expected output for [`FindingKind::Missing`].
+    pub line: usize,
+    pub kind: FindingKind,
+}
+
+/// The 1-based line each byte offset of `text` is on.
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Reads `path` as text, with trailing newlines removed: whether a sanitizer ends its
+/// output with a newline says nothing about what it redacted.
+fn read_text(path: &Path) -> Result<String> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{}\n", text.trim_end_matches(['\r', '\n'])))
+}
+
+/// Compares `sanitized` with `engine`'s sanitization of `original`.
+///
+/// # Returns
+///
+/// The problems found, ordered by line; empty if the two agree.
+pub fn compare(original: &str, sanitized: &str, engine: &dyn SanitizationEngine) -> Result<Vec<Finding>> {
+    let (expected, _) = engine
+        .sanitize(original, "", "", "", "", "", "", None)
+        .context("Failed to sanitize the original")?;
+
+    // Search the whole file rather than single lines, so secrets spanning lines are found.
+    let mut missed: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+    for m in engine.find_matches_for_ui(sanitized, "")? {
+        missed.entry(line_of(sanitized, m.start as usize)).or_default().insert(m.rule_name);
+    }
+
+    let mut findings: Vec<Finding> = missed
+        .iter()
+        .map(|(line, rules)| Finding { line: *line, kind: FindingKind::MissedSecret(rules.iter().cloned().collect()) })
+        .collect();
+    let patch = create_patch(&expected, sanitized);
+    for hunk in patch.hunks() {
+        let mut expected_line = hunk.old_range().start();
+        let mut sanitized_line = hunk.new_range().start();
+        let mut deleted = Vec::new();
+        let mut inserted = 0;
+        for line in hunk.lines() {
+            match line {
+                Line::Context(_) => {
+                    expected_line += 1;
+                    sanitized_line += 1;
+                }
+                Line::Delete(_) => {
+                    deleted.push(expected_line);
+                    expected_line += 1;
+                }
+                Line::Insert(_) => {
+                    if !missed.contains_key(&sanitized_line) {
+                        findings.push(Finding { line: sanitized_line, kind: FindingKind::Unexplained });
+                    }
+                    inserted += 1;
+                    sanitized_line += 1;
+                }
+            }
+        }
+        // Changed lines are reported once, as the sanitized line; only lines that were
+        // dropped altogether are reported from the expected output.
+        findings.extend(deleted.into_iter().skip(inserted).map(|line| Finding { line, kind: FindingKind::Missing }));
+    }
+    findings.sort_by_key(|finding| finding.line);
+    Ok(findings)
+}
+
+/// The main entry point for `cleansh verify-sanitized`.
+pub fn run_verify_sanitized_command(opts: &VerifySanitizedCommand, engine: &dyn SanitizationEngine, theme_map: &ThemeMap) -> Result<()> {
+    let original = read_text(&opts.original)?;
+    let sanitized = read_text(&opts.sanitized)?;
+    let findings = compare(&original, &sanitized, engine)?;
+
+    if findings.is_empty() {
+        info_msg(
+            format!("{} is consistent with the current rules applied to {}.", opts.sanitized.display(), opts.original.display()),
+            theme_map,
+        );
+        return Ok(());
+    }
+
+    let mut stdout = io::stdout();
+    let enable_colors = stdout.is_terminal();
+    for finding in &findings {
+        let (entry, text) = match &finding.kind {
+            FindingKind::MissedSecret(rules) => (ThemeEntry::Error, format!("line {}: missed secret ({})", finding.line, rules.join(", "))),
+            FindingKind::Unexplained => (ThemeEntry::Warn, format!("line {}: differs from the current rules' output", finding.line)),
+            FindingKind::Missing => (ThemeEntry::Warn, format!("expected line {}: missing from the sanitized file", finding.line)),
+        };
+        writeln!(stdout, "{}", get_styled_text(&text, entry, theme_map, enable_colors))?;
+    }
+    let missed = findings.iter().filter(|finding| matches!(finding.kind, FindingKind::MissedSecret(_))).count();
+    Err(anyhow!(
+        "{} is not consistent with the current rules: {} line(s) with missed secrets, {} unexplained difference(s).",
+        opts.sanitized.display(),
+        missed,
+        findings.len() - missed
+    ))
+}
@@ -33,6 +33,7 @@
 // allows them to be re-exported by test_exposed.
 pub mod commands;
 pub mod cli;
+pub mod cli_compat;
 pub mod ui;
 pub mod utils;
 pub mod logger;
@@ -46,25 +47,6 @@ use std::path::Path;
 use anyhow::anyhow;
 #[cfg(not(feature = "test-exposed"))]
 use std::env;
-#[cfg(not(feature = "test-exposed"))]
-use std::fs;
-
-#[cfg(not(feature = "test-exposed"))]
-/// Try to load license token from environment or a token file next to state.json
-fn load_license_token_from_env_or_file(state_path: &Path) -> Option<String> {
-    if let Ok(tok) = env::var("CLEANSH_LICENSE") {
-        return Some(tok);
-    }
-    if let Some(parent) = state_path.parent() {
-        let license_file = parent.join("license.token");
-        if license_file.exists() {
-            if let Ok(s) = fs::read_to_string(&license_file) {
-                return Some(s.trim().to_string());
-            }
-        }
-    }
-    None
-}
 
 #[cfg(not(feature = "test-exposed"))]
 /// Helper to compute the upgrade/purchase URL to show to user on invalid license.
@@ -83,7 +65,7 @@ fn license_url() -> String {
 /// - If the feature is absent (and "*" absent) => deny.
 fn require_license_for_feature(feature: &str, state_path: &Path, app_state: &mut AppState, theme_map: &ui::theme::ThemeMap) -> Result<license_utils::LicenseToken> {
     // try to get token
-    let tok = load_license_token_from_env_or_file(state_path)
+    let tok = license_utils::load_token_from_env_or_file(state_path)
         .ok_or_else(|| anyhow!("No license provided"))?;
 
     // verify signature & expiry
@@ -116,11 +98,11 @@ fn require_license_for_feature(feature: &str, state_path: &Path, app_state: &mut
                     commands::cleansh::error_msg(format!("No remaining uses for feature '{}' on this license (used {}/{}). Visit {}", feature, used, limit, license_url()), theme_map);
                     std::process::exit(2);
                 } else {
-                    commands::cleansh::info_msg(format!("License validated — '{}' unlocked. Expires: {}. Usage for '{}': {}/{}", feature, parsed.payload.expires_at, feature, used, limit), theme_map);
+                    commands::cleansh::info_msg(format!("License validated — '{}' unlocked. Expires: {}. Usage for '{}': {}/{}", feature, crate::utils::time::rfc3339(parsed.payload.expires_at), feature, used, limit), theme_map);
                 }
             } else {
                 // unlimited
-                commands::cleansh::info_msg(format!("License validated — '{}' unlocked (unlimited). Expires: {}", feature, parsed.payload.expires_at), theme_map);
+                commands::cleansh::info_msg(format!("License validated — '{}' unlocked (unlimited). Expires: {}", feature, crate::utils::time::rfc3339(parsed.payload.expires_at)), theme_map);
             }
         }
         None => {
@@ -250,6 +232,7 @@ pub mod test_exposed {
         pub use crate::ui::diff_viewer;
         pub use crate::ui::output_format;
         pub use crate::ui::redaction_summary;
+        pub use crate::ui::explain_config;
         pub use crate::ui::theme;
         pub use crate::ui::verify_ui;
         pub use crate::ui::sync_ui;
@@ -261,6 +244,8 @@ pub mod test_exposed {
         pub use crate::utils::platform::*;
         pub use crate::utils::clipboard::*;
         pub use crate::utils::license::*;
+        pub use crate::utils::line_cache::*;
+        pub use crate::utils::crash_report::*;
     }
 
     /// CLI logger for testing
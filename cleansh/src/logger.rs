@@ -1,8 +1,55 @@
 // src/logger.rs
-use env_logger::{Builder, Target};
-use log::LevelFilter;
+use env_logger::{Builder, Logger, Target};
+use log::{LevelFilter, Log, Metadata, Record};
 use std::io::Write;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Set while debug logging has been switched on at runtime with [`toggle_debug`].
+static DEBUG_TOGGLED: AtomicBool = AtomicBool::new(false);
+
+/// The most verbose level the configured logger lets through, restored when runtime
+/// debug logging is switched off again.
+static CONFIGURED_LEVEL: OnceLock<LevelFilter> = OnceLock::new();
+
+/// Logs through the logger configured at startup, or through a debug-level one while
+/// debug logging is switched on at runtime.
+struct ToggleLogger {
+    configured: Logger,
+    debug: Logger,
+}
+
+impl ToggleLogger {
+    fn active(&self) -> &Logger {
+        if DEBUG_TOGGLED.load(Ordering::Relaxed) { &self.debug } else { &self.configured }
+    }
+}
+
+impl Log for ToggleLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.active().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        self.active().log(record);
+    }
+
+    fn flush(&self) {
+        self.active().flush();
+    }
+}
+
+/// Formats a record as `[LEVEL module] message`.
+fn format_record(buf: &mut env_logger::fmt::Formatter, record: &Record<'_>) -> std::io::Result<()> {
+    writeln!(
+        buf,
+        "[{} {}] {}",
+        record.level(),
+        record.module_path().unwrap_or(""),
+        record.args()
+    )
+}
 
 /// Initializes the application's logger with an optional explicit log level.
 ///
@@ -39,17 +86,34 @@ pub fn init_logger(explicit_level: Option<LevelFilter>) {
         }
     }
 
-    builder
+    let configured = builder.target(Target::Stderr).format(format_record).build();
+
+    let mut debug = Builder::new();
+    debug
+        .filter_level(LevelFilter::Warn)
+        .filter_module("cleansh", LevelFilter::Debug)
         .target(Target::Stderr)
-        .format(|buf, record| {
-            writeln!(
-                buf,
-                "[{} {}] {}",
-                record.level(),
-                record.module_path().unwrap_or(""),
-                record.args()
-            )
-        })
-        .try_init() // Attempt to initialize. This implicitly calls `build()`.
-        .ok();     // Ignore error if already initialized (e.g., in a test harness).
-}
\ No newline at end of file
+        .format(format_record);
+    let debug = debug.build();
+
+    let level = configured.filter();
+    // Ignore the error if a logger is already set (e.g., in a test harness).
+    if log::set_boxed_logger(Box::new(ToggleLogger { configured, debug })).is_ok() {
+        CONFIGURED_LEVEL.set(level).ok();
+        log::set_max_level(level);
+    }
+}
+
+/// Switches debug logging for cleansh on or off for the rest of the run, on top of the
+/// level chosen at startup, and returns whether it is now on.
+pub fn toggle_debug() -> bool {
+    let on = !DEBUG_TOGGLED.fetch_xor(true, Ordering::Relaxed);
+    let configured = CONFIGURED_LEVEL.get().copied().unwrap_or(LevelFilter::Warn);
+    log::set_max_level(if on { configured.max(LevelFilter::Debug) } else { configured });
+    on
+}
+
+/// Whether debug logging has been switched on at runtime.
+pub fn debug_toggled() -> bool {
+    DEBUG_TOGGLED.load(Ordering::Relaxed)
+}
@@ -28,9 +28,14 @@
 
 use cleansh_core::{
     engine::SanitizationEngine,
+    Aggregator,
+    Allowlist,
     RegexEngine,
-    config::{merge_rules, RedactionConfig},
-    RedactionSummaryItem,
+    config::{merge_rules, RedactionConfig, SkippedRule},
+    sanitize_passes,
+    validators,
+    PassStop,
+    StreamOptions,
 };
 use anyhow::{Context, Result, anyhow};
 use clap::Parser;
@@ -38,50 +43,229 @@ use std::io::{self, Read, Write, IsTerminal, BufReader, BufRead};
 use std::fs;
 use std::env;
 use std::path::{PathBuf, Path};
+use std::process::ExitCode;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
 use log::{info, LevelFilter};
 use dotenvy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use cleansh::commands;
 use cleansh::logger;
 use cleansh::ui;
-use cleansh::utils::app_state::AppState;
+use cleansh::utils::app_state::{AppState, StateIntegrity};
 use cleansh::utils::platform;
-use cleansh::cli::{Cli, Commands, EngineChoice, SanitizeCommand, ScanCommand, ProfilesCommand};
-use cleansh_core::profiles;
+use cleansh::utils::audit_trail::AuditTrail;
+use cleansh::utils::line_cache::{CachedLine, LineCache};
+use cleansh::utils::heatmap::RuleHeatmap;
+use cleansh::utils::line_template::LineTemplate;
+use cleansh::utils::notifications;
+use cleansh::ui::explain_config::ConfigLayer;
+use cleansh::utils::encoding::{self, TextEncoding};
+use cleansh::utils::crash_report;
+use cleansh::utils::runtime_signals::{self, RuntimeStatus};
+use cleansh::utils::idle_read::{self, IdleReadError};
+use cleansh::utils::follow::FollowReader;
+use cleansh::utils::url_input;
+use cleansh::utils::rules_source;
+use cleansh::utils::scan_cache;
+use cleansh::utils::scan_history;
+use cleansh::utils::post_process::{CommandPostProcessor, PostProcessor};
+use cleansh::utils::tokenize::Tokenizer;
+#[cfg(feature = "vault")]
+use cleansh::utils::tokenize::{AwsCredentials, AwsKmsTokenizer, VaultTransitTokenizer};
+#[cfg(feature = "vault")]
+use cleansh::cli::TokenizeBackend;
+use cleansh::ui::output_format::{MessageKind, Verbosity};
+use cleansh::cli_compat;
+use cleansh::commands::profiles_diff::PolicySide;
+use cleansh::cli::{Cli, Commands, ConfigCommand, ConfigShowCommand, EngineChoice, EvalCommand, ExecCommand, GrepCommand, HistoryCommand, InputFormat, OutputEncoding, SanitizeCommand, ScanCommand, ProfilesCommand, RulesCommand, RulesImpactCommand, StatsCommand, VerifySanitizedCommand};
+use cleansh_core::profiles::{self, EngineOptions};
 
 use cleansh::{check_license_for_feature, consume_license_post_success};
 use cleansh::utils::license as license_utils;
 
-/// Creates a fully configured and compiled sanitization engine based on CLI arguments.
-fn create_sanitization_engine(
-    config_path: Option<&PathBuf>,
+/// The rule set a run starts from before `--enable`/`--disable`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum RulePreset {
+    /// The rules that are on by default.
+    #[default]
+    Standard,
+    /// `--strict`: every opt-in rule is enabled as well.
+    Strict,
+    /// `--fast`: only the rules of the `fast` group, with the literal prefilter.
+    Fast,
+}
+
+impl RulePreset {
+    fn from_flags(strict: bool, fast: bool) -> Self {
+        match (strict, fast) {
+            (true, _) => RulePreset::Strict,
+            (_, true) => RulePreset::Fast,
+            _ => RulePreset::Standard,
+        }
+    }
+}
+
+/// Rule group that `--fast` keeps.
+const FAST_RULE_GROUP: &str = "fast";
+
+/// Builds the rule set and engine options that `--config` and `--profile` select, with
+/// `--enable`/`--disable` applied: the built-in rules, merged with each config file in
+/// turn (later files override rules of the same name), then overlaid by the profile if
+/// one is named. The layers are returned in merge order for `--explain-config`, followed
+/// by the rules and profile entries left out by `--skip-invalid-rules`.
+fn build_effective_config(
+    config_paths: &[PathBuf],
     profile_name: Option<&String>,
-    engine_choice: &EngineChoice,
     enable_rules: &[String],
     disable_rules: &[String],
-) -> Result<Box<dyn SanitizationEngine>> {
+    preset: RulePreset,
+    skip_invalid_rules: bool,
+) -> Result<(RedactionConfig, EngineOptions, Vec<ConfigLayer>, Vec<SkippedRule>)> {
     let mut config = RedactionConfig::load_default_rules()
         .context("Failed to load default redaction rules")?;
+    let mut options = EngineOptions::default();
+    let mut skipped = Vec::new();
+    let mut layers = vec![ConfigLayer {
+        source: "built-in rules".to_string(),
+        overridden: Vec::new(),
+        added: config.rules.iter().map(|rule| rule.name.clone()).collect(),
+    }];
+
+    for path in config_paths {
+        let user_config = if skip_invalid_rules {
+            let (user_config, skipped_rules) = rules_source::load_user_config_skipping_invalid(path)?;
+            skipped.extend(skipped_rules);
+            user_config
+        } else {
+            rules_source::load_user_config(path)?
+        };
+        let (overridden, added) = user_config
+            .rules
+            .iter()
+            .map(|rule| rule.name.clone())
+            .partition(|name| config.rules.iter().any(|rule| &rule.name == name) || config.resolve_alias(name).is_some());
+        let source = if rules_source::is_stdin(path) { "stdin".to_string() } else { path.display().to_string() };
+        layers.push(ConfigLayer { source: format!("config {}", source), overridden, added });
+        config = merge_rules(config, Some(user_config));
+    }
 
     if let Some(name) = profile_name {
-        let profile = profiles::load_profile_by_name(name)
+        let mut profile = profiles::load_profile_by_name(name)
             .context("Failed to load specified profile")?;
 
+        if skip_invalid_rules {
+            skipped.extend(profile.remove_invalid_rules(&config));
+        }
         profile.validate(&config)?;
 
+        layers.push(ConfigLayer {
+            source: format!("profile {}", name),
+            overridden: profile.rules.iter().map(|rule| rule.name.clone()).collect(),
+            added: Vec::new(),
+        });
         config = profiles::apply_profile_to_config(&profile, config);
-    } else if let Some(path) = config_path {
-        let user_config = RedactionConfig::load_from_file(path)
-            .context("Failed to load user-defined configuration file")?;
-        config = merge_rules(config, Some(user_config));
+        options = EngineOptions::from(profile);
+    }
+    // `--strict` turns on every opt-in rule, including those of user configs and profiles.
+    let mut enable_rules = enable_rules.to_vec();
+    if preset == RulePreset::Strict {
+        enable_rules.extend(config.rules.iter().filter(|rule| rule.opt_in).map(|rule| rule.name.clone()));
     }
+    config.set_active_rules(&enable_rules, disable_rules);
+    // `--fast` keeps the `fast` group and whatever `--enable` names on top of it.
+    if preset == RulePreset::Fast {
+        let keep: HashSet<String> = config
+            .expand_groups(&[FAST_RULE_GROUP.to_string()])
+            .into_iter()
+            .chain(config.expand_groups(&enable_rules))
+            .collect();
+        config.rules.retain(|rule| keep.contains(&rule.name));
+        options.prefilter = true;
+    }
+    Ok((config, options, layers, skipped))
+}
 
-    config.set_active_rules(enable_rules, disable_rules);
+/// Engine settings that come from command-line flags rather than from a config or profile.
+#[derive(Default)]
+struct EngineFlags {
+    /// `sanitize --profile-rules`.
+    profile_rules: bool,
+    /// The global `--seed`.
+    seed: Option<u64>,
+    /// `--strict` or `--fast`.
+    preset: RulePreset,
+    /// `--skip-invalid-rules`.
+    skip_invalid_rules: bool,
+    /// `sanitize --replace`, as rule name and replacement text.
+    replacements: Vec<(String, String)>,
+    /// `sanitize --keep-width`.
+    keep_width: bool,
+    /// `--allowlist`, the file of values that are never redacted.
+    allowlist: Option<PathBuf>,
+}
+
+/// Overrides the replacement text of the rules named by `--replace`, which may use a
+/// rule's current or former name.
+fn apply_replacements(config: &mut RedactionConfig, replacements: &[(String, String)]) -> Result<()> {
+    for (name, text) in replacements {
+        let name = config.resolve_alias(name).unwrap_or(name).to_string();
+        let rule = config
+            .rules
+            .iter_mut()
+            .find(|rule| rule.name == name)
+            .ok_or_else(|| anyhow!("--replace names rule '{}', which is not in the current rule set", name))?;
+        rule.replace_with = text.clone();
+    }
+    Ok(())
+}
+
+/// Applies the command-line engine settings in `flags` and `--allow-domain` on top of the
+/// config and options built from the config files and profile.
+fn apply_engine_flags(config: &mut RedactionConfig, options: &mut EngineOptions, allowed_domains: &[String], flags: &EngineFlags) -> Result<()> {
+    apply_replacements(config, &flags.replacements)?;
+    options.allowed_domains.extend(allowed_domains.iter().cloned());
+    if let Some(path) = &flags.allowlist {
+        let text = fs::read_to_string(path).with_context(|| format!("Failed to read allowlist {}", path.display()))?;
+        options.allowlist.extend(Allowlist::parse_entries(&text));
+    }
+    options.profile_rules = flags.profile_rules;
+    options.seed = flags.seed;
+    options.keep_width = flags.keep_width;
+    Ok(())
+}
+
+/// Creates a fully configured and compiled sanitization engine based on CLI arguments.
+///
+/// Together with [`build_effective_config`], this is the only place where a profile and
+/// command-line flags are combined into the effective policy: the profile's rule
+/// overrides go into the `RedactionConfig` and its engine settings (placeholder tokens,
+/// sampling, dedupe, allowed domains) into the `EngineOptions`. Every execution path
+/// (batch, line-buffered, and `scan`) uses the returned engine, so they all apply the
+/// same policy. The rules left out by `--skip-invalid-rules` are returned with it.
+fn create_sanitization_engine(
+    config_paths: &[PathBuf],
+    profile_name: Option<&String>,
+    engine_choice: &EngineChoice,
+    enable_rules: &[String],
+    disable_rules: &[String],
+    allowed_domains: &[String],
+    flags: EngineFlags,
+) -> Result<(Box<dyn SanitizationEngine>, Vec<SkippedRule>)> {
+    // The combined engine turns on the opt-in entropy rule as its second layer.
+    let mut enable_rules = enable_rules.to_vec();
+    if *engine_choice == EngineChoice::Combined {
+        enable_rules.push(validators::HIGH_ENTROPY_RULE.to_string());
+    }
+    let (mut config, mut options, _, skipped) = build_effective_config(config_paths, profile_name, &enable_rules, disable_rules, flags.preset, flags.skip_invalid_rules)?;
+    apply_engine_flags(&mut config, &mut options, allowed_domains, &flags)?;
+    options.entropy_layer = *engine_choice == EngineChoice::Combined;
 
     let engine: Box<dyn SanitizationEngine> = match engine_choice {
-        EngineChoice::Regex => {
-            Box::new(RegexEngine::new(config)
+        EngineChoice::Regex | EngineChoice::Combined => {
+            Box::new(RegexEngine::with_options(config, options)
                 .context("Failed to initialize RegexEngine")?)
         },
         EngineChoice::Entropy => {
@@ -89,90 +273,526 @@ fn create_sanitization_engine(
         }
     };
     
-    Ok(engine)
+    Ok((engine, skipped))
+}
+
+/// Warns about each rule left out by `--skip-invalid-rules`. Called once, at the end of
+/// the command, where the warning is hard to miss.
+fn warn_skipped_rules(skipped: &[SkippedRule], theme_map: &ui::theme::ThemeMap) {
+    for rule in skipped {
+        commands::cleansh::warn_msg(format!("Invalid rule '{}' was skipped (--skip-invalid-rules): {}", rule.name, rule.reason), theme_map);
+    }
 }
 
 /// Reads input content from a file or stdin, handling both terminal and non-terminal cases.
-fn read_input(input_file: &Option<PathBuf>, theme_map: &ui::theme::ThemeMap) -> Result<String> {
+///
+/// The raw bytes are decoded with [`encoding::decode`], so UTF-16 and Windows-1252 input
+/// is transcoded to UTF-8; the detected encoding is returned alongside the text.
+///
+/// A terminal on stdin is read with [`idle_read::read_with_idle_timeout`], which explains
+/// how to end the input if none arrives and gives up after `stdin_timeout`.
+fn read_input(input_file: &Option<PathBuf>, stdin_timeout: Option<Duration>, theme_map: &ui::theme::ThemeMap) -> Result<(String, TextEncoding)> {
+    let mut buffer = Vec::new();
     if let Some(path) = input_file.as_ref() {
-        commands::cleansh::info_msg(format!("Reading input from file: {}", path.display()), theme_map);
-        fs::read_to_string(path)
-            .with_context(|| format!("Failed to read input from {}", path.display()))
+        commands::cleansh::progress_msg(format!("Reading input from file: {}", path.display()), theme_map);
+        buffer = fs::read(platform::long_path(path))
+            .with_context(|| format!("Failed to read input from {}", path.display()))?;
     } else if io::stdin().is_terminal() {
-        commands::cleansh::info_msg(
+        commands::cleansh::progress_msg(
             &format!("Reading input from stdin. Press {} then Enter to finish input.", platform::eof_key_combo()),
             theme_map,
         );
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)
-            .context("Failed to read from stdin")?;
-        Ok(buffer)
+        let hint = || {
+            commands::cleansh::info_msg(
+                format!("Still waiting for input. Press {} then Enter to finish, or pass a file with -i.", platform::eof_key_combo()),
+                theme_map,
+            )
+        };
+        buffer = match idle_read::read_with_idle_timeout(io::stdin(), stdin_timeout, hint) {
+            Ok(buffer) => buffer,
+            Err(IdleReadError::Io(e)) => return Err(e).context("Failed to read from stdin"),
+            Err(IdleReadError::TimedOut { partial }) => {
+                let timeout = stdin_timeout.unwrap_or_default();
+                return Err(anyhow!(
+                    "No input on stdin for {}s (--stdin-timeout); nothing was sanitized{}. Press {} then Enter to end typed input, pipe the text in, or pass a file with -i.",
+                    timeout.as_secs_f64(),
+                    if partial > 0 { format!(", including the {} byte(s) typed so far", partial) } else { String::new() },
+                    platform::eof_key_combo()
+                ));
+            }
+        };
     } else {
         commands::cleansh::info_msg("Reading input from stdin...", theme_map);
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)
+        io::stdin().read_to_end(&mut buffer)
             .context("Failed to read from stdin")?;
-        Ok(buffer)
     }
+
+    let (content, detected) = encoding::decode(&buffer);
+    if !detected.is_plain_utf8() {
+        commands::cleansh::info_msg(format!("Detected {} input; transcoding to UTF-8 for matching.", detected.label()), theme_map);
+    }
+    Ok((content, detected))
+}
+
+/// Downloads the whole body of `--url` and decodes it like [`read_input`] does a file.
+fn read_url_input(url: &str, auth_env: Option<&str>, theme_map: &ui::theme::ThemeMap) -> Result<(String, TextEncoding)> {
+    let mut response = url_input::open(url, auth_env)?;
+    commands::cleansh::progress_msg(format!("Reading input from {}", url_input::display_url(response.url())), theme_map);
+    let mut buffer = Vec::new();
+    response.read_to_end(&mut buffer).context("Failed to read the --url response")?;
+    let (content, detected) = encoding::decode(&buffer);
+    if !detected.is_plain_utf8() {
+        commands::cleansh::info_msg(format!("Detected {} input; transcoding to UTF-8 for matching.", detected.label()), theme_map);
+    }
+    Ok((content, detected))
 }
 
-/// Reads input line-by-line from stdin, sanitizes each line using the provided engine,
-/// writes output line-by-line to stdout or a file, and maintains redaction statistics.
-fn run_line_buffered_mode(engine: Box<dyn SanitizationEngine>, opts: &SanitizeCommand, theme_map: &ui::theme::ThemeMap, quiet: bool) -> Result<()> {
-    let stdin = io::stdin().lock();
-    let mut reader = BufReader::new(stdin);
+/// Picks the encoding for sanitized output: the input's own, unless `--output-encoding utf-8`.
+fn output_encoding_for(opts: &SanitizeCommand, detected: TextEncoding) -> TextEncoding {
+    match opts.output_encoding {
+        OutputEncoding::Original => detected,
+        OutputEncoding::Utf8 => TextEncoding::default(),
+    }
+}
+
+/// Reads pasted text for `--stdin-tty-paste` until a line containing only `.` or EOF.
+/// The terminating `.` line is not part of the returned content.
+fn read_paste_input<R: BufRead>(reader: &mut R) -> Result<String> {
+    let mut content = String::new();
     let mut line = String::new();
-    let mut summary_items: HashMap<String, RedactionSummaryItem> = HashMap::new();
+    while reader.read_line(&mut line).context("Failed to read from stdin")? > 0 {
+        if line.trim_end_matches(['\r', '\n']) == "." {
+            break;
+        }
+        content.push_str(&line);
+        line.clear();
+    }
+    Ok(content)
+}
+
+/// Runs the interactive `--stdin-tty-paste` flow: collects the paste, reports how many
+/// secrets were detected, and asks for confirmation before anything is printed.
+/// Returns `None` if the user declines.
+///
+/// The answer is read from the terminal rather than stdin, so a "y" at the end of piped
+/// or pasted input cannot confirm on the user's behalf.
+fn read_paste_and_confirm(engine: &dyn SanitizationEngine, theme_map: &ui::theme::ThemeMap) -> Result<Option<String>> {
+    #[cfg(feature = "test-exposed")]
+    let terminal = match env::var_os("CLEANSH_TERMINAL_OVERRIDE_FOR_TESTS") {
+        Some(path) => fs::File::open(path),
+        None => platform::open_terminal(),
+    };
+    #[cfg(not(feature = "test-exposed"))]
+    let terminal = platform::open_terminal();
+    let mut terminal = io::BufReader::new(
+        terminal.context("--stdin-tty-paste needs a terminal to confirm on; pipe the input without it instead")?,
+    );
+    commands::cleansh::info_msg(
+        format!("Paste your text, then enter a line containing only '.' (or press {}) to finish.", platform::eof_key_combo()),
+        theme_map,
+    );
+    let mut stdin = io::stdin().lock();
+    let content = read_paste_input(&mut stdin)?;
+
+    let detected = engine.find_matches_for_ui(&content, "stdin")?.len();
+    commands::cleansh::info_msg(
+        format!("Detected {} potential secret(s) in {} line(s) of pasted input.", detected, content.lines().count()),
+        theme_map,
+    );
+
+    let stderr_supports_color = io::stderr().is_terminal();
+    ui::output_format::print_message(
+        &mut io::stderr(),
+        "Print sanitized output? (y/N): ",
+        theme_map,
+        Some(ui::theme::ThemeEntry::Prompt),
+        stderr_supports_color,
+    )?;
+    io::stderr().flush()?;
+
+    let mut confirmation = String::new();
+    terminal.read_line(&mut confirmation).context("Failed to read confirmation input.")?;
+    if confirmation.trim().to_lowercase() != "y" {
+        commands::cleansh::info_msg("Cancelled. No output was written.", theme_map);
+        return Ok(None);
+    }
+    Ok(Some(content))
+}
+
+/// Returns `true` if sanitizing a line depends only on the line itself, so results can be cached.
+///
+/// Sampling and dedupe settings track occurrences across the whole run, so a cached
+/// result could diverge from what the engine would have produced. `--post-process` and
+/// `--tokenize` rewrite the output after the engine has run, which a cached line would skip.
+fn line_cache_is_safe(engine: &dyn SanitizationEngine, opts: &SanitizeCommand) -> bool {
+    let options = engine.get_options();
+    options.samples_config.is_none()
+        && options.dedupe_config.is_none()
+        && opts.post_process.is_none()
+        && !tokenize_requested(opts)
+}
+
+/// Keeps whichever `--passes` outcome is least settled: a cycle, then a hit limit, then
+/// the fixpoint that took the most passes.
+fn worst_pass_report(current: Option<(usize, PassStop)>, next: (usize, PassStop)) -> Option<(usize, PassStop)> {
+    let rank = |stop: PassStop| match stop {
+        PassStop::Fixpoint => 0,
+        PassStop::Limit => 1,
+        PassStop::Cycle => 2,
+    };
+    match current {
+        Some(current) if (rank(current.1), current.0) >= (rank(next.1), next.0) => Some(current),
+        _ => Some(next),
+    }
+}
+
+/// Original and sanitized values kept per rule for the summary of a line-buffered stream,
+/// which may never end; occurrences are counted in full.
+const STREAM_SUMMARY_TEXT_LIMIT: usize = 100;
+
+/// How long `--follow` waits at the end of the file before looking for new lines.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Reads input line-by-line from stdin, `--url` or a `--follow`ed file, sanitizes each line
+/// using the provided engine, writes output line-by-line to stdout or a file, and maintains
+/// redaction statistics.
+fn run_line_buffered_mode(engine: &dyn SanitizationEngine, opts: &SanitizeCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let input: Box<dyn Read> = match (&opts.url, &opts.follow) {
+        (Some(url), _) => Box::new(url_input::open(url, opts.url_auth_env.as_deref())?),
+        (None, Some(path)) => {
+            let reader = FollowReader::open(&platform::long_path(path), FOLLOW_POLL_INTERVAL)
+                .with_context(|| format!("Failed to open {} to follow", path.display()))?;
+            // Ctrl-C ends the stream at the next wait, so the summary is still printed.
+            runtime_signals::stop_on_interrupt();
+            Box::new(reader.stop_when(runtime_signals::interrupted))
+        }
+        (None, None) => Box::new(io::stdin().lock()),
+    };
+    let mut reader = BufReader::new(input);
+    let mut raw_line = Vec::new();
+    let mut summary = Aggregator::with_text_limit(STREAM_SUMMARY_TEXT_LIMIT);
 
     let mut writer: Box<dyn Write> = if let Some(path) = opts.output.as_ref() {
-        Box::new(fs::File::create(path)
+        Box::new(fs::File::create(platform::long_path(path))
             .with_context(|| format!("Failed to create output file: {}", path.display()))?)
     } else {
         Box::new(io::stdout().lock())
     };
 
     let flush_per_line = opts.output.is_none();
+    // With --tee, every line also goes to stdout as soon as it is sanitized.
+    let mut tee = (opts.tee && opts.output.is_some()).then(|| io::stdout().lock());
     
-    commands::cleansh::info_msg("Using line-buffered mode...", theme_map);
+    let mode = match &opts.follow {
+        Some(path) => {
+            commands::cleansh::info_msg(format!("Following {}; press Ctrl-C to stop.", path.display()), theme_map);
+            "follow"
+        }
+        None => {
+            commands::cleansh::info_msg("Using line-buffered mode...", theme_map);
+            "line-buffered"
+        }
+    };
+
+    // SIGUSR1 toggles debug logging and SIGUSR2 prints a status snapshot while the stream runs.
+    let status = RuntimeStatus::sanitizing(mode, engine.get_rules().rules.len());
+    runtime_signals::watch(status.clone());
 
-    while reader.read_line(&mut line)? > 0 {
-        let (sanitized_line, line_summary) = engine.sanitize(&line, "", "", "", "", "", "", None)
-            .context("Sanitization failed in line-buffered mode")?;
+    let mut line_cache = match opts.dedupe_cache {
+        Some(capacity) if line_cache_is_safe(engine, opts) => {
+            info!("Line dedupe cache enabled with capacity {}.", capacity);
+            Some(LineCache::new(capacity))
+        }
+        Some(_) => {
+            commands::cleansh::warn_msg(
+                "--dedupe-cache ignored: the active rules or profile keep state across lines.",
+                theme_map,
+            );
+            None
+        }
+        None => None,
+    };
+
+    // With --passes, the least settled line decides what is reported at the end.
+    let mut pass_report: Option<(usize, PassStop)> = None;
+
+    let line_template = opts
+        .line_template
+        .as_deref()
+        .map(|template| LineTemplate::new(template, opts.line_prefix_regex.as_deref()))
+        .transpose()?;
+
+    let mut heatmap = opts.heatmap_out.as_ref().map(|_| RuleHeatmap::new());
+    let mut heatmap_saved_minute = None;
+
+    // The stream's encoding is decided from its first bytes; UTF-8 lines that turn out to
+    // be invalid fall back to Windows-1252 one at a time.
+    let (stream_encoding, bom_len) = encoding::sniff(reader.fill_buf().context("Failed to read from stdin")?);
+    reader.consume(bom_len);
+    if !stream_encoding.is_plain_utf8() {
+        commands::cleansh::info_msg(format!("Detected {} input; transcoding to UTF-8 for matching.", stream_encoding.label()), theme_map);
+    }
+    let preamble = encoding::encode("", output_encoding_for(opts, stream_encoding));
+    writer.write_all(&preamble).context("Failed to write sanitized line")?;
+    if let Some(tee) = tee.as_mut() {
+        tee.write_all(&preamble).context("Failed to write sanitized line to stdout")?;
+    }
+
+    while encoding::read_line(&mut reader, stream_encoding, &mut raw_line)? > 0 {
+        let (line, line_encoding) = match stream_encoding {
+            TextEncoding::Utf8 { .. } => match String::from_utf8(std::mem::take(&mut raw_line)) {
+                Ok(line) => (line, stream_encoding),
+                Err(e) => (encoding::decode_as(e.as_bytes(), TextEncoding::Windows1252), TextEncoding::Windows1252),
+            },
+            _ => (encoding::decode_as(&raw_line, stream_encoding), stream_encoding),
+        };
+        raw_line.clear();
+
+        let cache_key = line_cache.as_ref().map(|_| LineCache::key_for(&line));
+        let cached = match (line_cache.as_mut(), cache_key.as_ref()) {
+            (Some(cache), Some(key)) => cache.get(key).cloned(),
+            _ => None,
+        };
+
+        let (sanitized_line, line_summary) = match cached {
+            Some(hit) => (hit.sanitized, hit.summary),
+            None => {
+                let (sanitized_line, line_summary) = if opts.passes > 1 {
+                    let outcome = sanitize_passes(engine, &line, "", opts.passes)
+                        .context("Sanitization failed in line-buffered mode")?;
+                    pass_report = worst_pass_report(pass_report, (outcome.passes, outcome.stop));
+                    (outcome.sanitized, outcome.summary)
+                } else {
+                    engine.sanitize(&line, "", "", "", "", "", "", None)
+                        .context("Sanitization failed in line-buffered mode")?
+                };
+                if let (Some(cache), Some(key)) = (line_cache.as_mut(), cache_key) {
+                    cache.insert(key, CachedLine::new(sanitized_line.clone(), &line_summary));
+                }
+                (sanitized_line, line_summary)
+            }
+        };
         
-        let mut sanitized_line = sanitized_line;
+        if opts.verify_idempotent {
+            commands::cleansh::verify_idempotent(engine, &sanitized_line)?;
+        }
+
+        let mut sanitized_line = match line_template.as_ref() {
+            Some(template) => {
+                let body = sanitized_line.trim_end_matches(['\r', '\n']);
+                template.render(body) + &sanitized_line[body.len()..]
+            }
+            None => sanitized_line,
+        };
 
         if !sanitized_line.ends_with('\n') {
             sanitized_line.push('\n');
         }
 
-        writer.write_all(sanitized_line.as_bytes())
-            .context("Failed to write sanitized line")?;
+        let encoded = encoding::encode_as(&sanitized_line, output_encoding_for(opts, line_encoding));
+        writer.write_all(&encoded).context("Failed to write sanitized line")?;
 
         if flush_per_line {
             writer.flush().context("Failed to flush stdout")?;
         }
+        if let Some(tee) = tee.as_mut() {
+            tee.write_all(&encoded).and_then(|_| tee.flush()).context("Failed to write sanitized line to stdout")?;
+        }
 
-        for item in line_summary {
-            summary_items
-                .entry(item.rule_name.clone())
-                .and_modify(|existing_item| {
-                    existing_item.occurrences += item.occurrences;
-                })
-                .or_insert(item);
+        if let (Some(heatmap), Some(path)) = (heatmap.as_mut(), opts.heatmap_out.as_ref()) {
+            let now = chrono::Utc::now();
+            for item in &line_summary {
+                heatmap.record(now, &item.rule_name, item.occurrences);
+            }
+            // Rewrite the file when a new minute starts so it stays current on long streams.
+            let minute = now.timestamp() / 60;
+            if heatmap_saved_minute != Some(minute) {
+                heatmap.save(path)?;
+                heatmap_saved_minute = Some(minute);
+            }
         }
 
-        line.clear();
+        status.record(line_summary.iter().map(|item| item.occurrences).sum());
+        summary.add_summary(line_summary);
+    }
+
+    if let (Some(heatmap), Some(path)) = (heatmap.as_ref(), opts.heatmap_out.as_ref()) {
+        heatmap.save(path)?;
+        commands::cleansh::info_msg(format!("Wrote rule heatmap to {}", path.display()), theme_map);
+    }
+
+    if let Some((passes, stop)) = pass_report {
+        commands::cleansh::report_passes(passes, stop, theme_map);
+    }
+
+    if let Some(cache) = line_cache.as_ref() {
+        let (hits, misses) = cache.stats();
+        info!("Line dedupe cache: {} hits, {} misses, {} entries.", hits, misses, cache.len());
     }
     
-    if !quiet && !opts.no_summary {
-        let summary_vec: Vec<RedactionSummaryItem> = summary_items.into_values().collect();
+    if !opts.no_summary && ui::output_format::should_print(MessageKind::Summary) {
         let stderr_supports_color = io::stderr().is_terminal();
-        ui::redaction_summary::print_summary(&summary_vec, &mut io::stderr(), theme_map, stderr_supports_color)?;
+        ui::redaction_summary::print_summary(&summary.into_summary(), &mut io::stderr(), theme_map, stderr_supports_color)?;
+    }
+
+    Ok(())
+}
+
+/// Prints the effective rule configuration, and the layers it was merged from, to stdout
+/// for `--explain-config`.
+fn explain_config(
+    config_paths: &[PathBuf],
+    profile_name: Option<&String>,
+    enable_rules: &[String],
+    disable_rules: &[String],
+    allowed_domains: &[String],
+    flags: EngineFlags,
+    theme_map: &ui::theme::ThemeMap,
+) -> Result<()> {
+    let (mut config, mut options, layers, skipped) = build_effective_config(config_paths, profile_name, enable_rules, disable_rules, flags.preset, flags.skip_invalid_rules)?;
+    apply_engine_flags(&mut config, &mut options, allowed_domains, &flags)?;
+    let engine = RegexEngine::with_options(config, options).context("Failed to initialize RegexEngine")?;
+    let mut stdout = io::stdout();
+    let stdout_supports_color = stdout.is_terminal();
+    ui::explain_config::print_explain_config(
+        engine.get_rules(),
+        &engine.describe(),
+        &engine.get_options().allowed_domains,
+        &layers,
+        &mut stdout,
+        theme_map,
+        stdout_supports_color,
+    )?;
+    warn_skipped_rules(&skipped, theme_map);
+    Ok(())
+}
+
+/// Prints each input with its matches highlighted and labeled for `--annotate`.
+///
+/// The annotated text still contains every secret, so it is only written to a terminal.
+fn annotate_inputs(engine: &dyn SanitizationEngine, opts: &SanitizeCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Err(anyhow!("--annotate prints the input unredacted and only writes to a terminal, but stdout is redirected"));
+    }
+    let inputs: Vec<Option<PathBuf>> = if opts.files.len() > 1 {
+        opts.files.iter().cloned().map(Some).collect()
+    } else {
+        vec![opts.input_file.clone().or_else(|| opts.files.first().cloned())]
+    };
+    for (index, input) in inputs.iter().enumerate() {
+        let (content, _) = read_input(input, opts.stdin_timeout, theme_map)?;
+        let source = input.as_ref().map_or_else(|| "stdin".to_string(), |path| path.display().to_string());
+        if inputs.len() > 1 {
+            writeln!(stdout, "{}==> {} <==", if index == 0 { "" } else { "\n" }, source)?;
+        }
+        let matches = match input {
+            Some(_) => engine.find_file_matches(&content, &source),
+            None => engine.find_matches_for_ui(&content, &source),
+        }
+        .with_context(|| format!("Failed to find matches in {}", source))?;
+        ui::annotate::print_annotated(&content, &matches, &mut stdout, theme_map, true)?;
+    }
+    Ok(())
+}
+
+/// Builds the tokenizer requested with `--tokenize`, if any.
+#[cfg(feature = "vault")]
+fn build_tokenizer(opts: &SanitizeCommand) -> Result<Option<Box<dyn Tokenizer>>> {
+    match opts.tokenize {
+        Some(TokenizeBackend::Vault) => {
+            let addr = opts.vault_addr.as_deref()
+                .ok_or_else(|| anyhow!("--tokenize vault requires --vault-addr or the VAULT_ADDR environment variable."))?;
+            let token = env::var(&opts.vault_token_env)
+                .map_err(|_| anyhow!("--tokenize vault requires a Vault token in the {} environment variable.", opts.vault_token_env))?;
+            let tokenizer = VaultTransitTokenizer::new(addr, &opts.vault_mount, &opts.vault_transit_key, token)?;
+            Ok(Some(Box::new(tokenizer)))
+        }
+        Some(TokenizeBackend::AwsKms) => {
+            let key_id = opts.kms_key_id.as_deref()
+                .ok_or_else(|| anyhow!("--tokenize aws-kms requires --kms-key-id."))?;
+            let region = opts.kms_region.as_deref()
+                .ok_or_else(|| anyhow!("--tokenize aws-kms requires --kms-region or the AWS_REGION environment variable."))?;
+            let credential = |name: &str| env::var(name)
+                .map_err(|_| anyhow!("--tokenize aws-kms requires AWS credentials in the {} environment variable.", name));
+            let credentials = AwsCredentials {
+                access_key_id: credential("AWS_ACCESS_KEY_ID")?,
+                secret_access_key: credential("AWS_SECRET_ACCESS_KEY")?,
+                session_token: env::var("AWS_SESSION_TOKEN").ok(),
+            };
+            let tokenizer = AwsKmsTokenizer::new(region, key_id, opts.kms_endpoint.as_deref(), credentials)?;
+            Ok(Some(Box::new(tokenizer)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Builds the tokenizer requested with `--tokenize`, if any.
+#[cfg(not(feature = "vault"))]
+fn build_tokenizer(_opts: &SanitizeCommand) -> Result<Option<Box<dyn Tokenizer>>> {
+    Ok(None)
+}
+
+/// Whether `--tokenize` was given.
+#[cfg(feature = "vault")]
+fn tokenize_requested(opts: &SanitizeCommand) -> bool {
+    opts.tokenize.is_some()
+}
+
+/// Whether `--tokenize` was given.
+#[cfg(not(feature = "vault"))]
+fn tokenize_requested(_opts: &SanitizeCommand) -> bool {
+    false
+}
+
+/// Refuses outputs that would overwrite an input file, unless `--in-place` is given.
+///
+/// `File::create` truncates its target, so writing to the input (or to the file stdin is
+/// redirected from) would destroy data that has not been read yet.
+fn check_output_targets(opts: &SanitizeCommand) -> Result<()> {
+    let inputs: Vec<&PathBuf> = opts.input_file.iter().chain(&opts.files).chain(&opts.follow).collect();
+    if opts.in_place && inputs.is_empty() {
+        return Err(anyhow!("--in-place needs an input file (-i FILE or FILE arguments)"));
     }
 
+    // `--in-place` only makes the sanitized output a legitimate replacement for an input;
+    // the `--matches-out` report never is.
+    let mut targets: Vec<(PathBuf, bool)> = opts.output.iter().map(|path| (path.clone(), opts.in_place)).collect();
+    if let Some(dir) = &opts.out_dir {
+        targets.extend(opts.files.iter().filter_map(|file| file.file_name().map(|name| (dir.join(name), opts.in_place))));
+    }
+    targets.extend(opts.matches_out.iter().map(|path| (path.clone(), false)));
+    for (target, replaces_input) in &targets {
+        if inputs.is_empty() && platform::stdin_is_file(target) {
+            return Err(anyhow!(
+                "'{}' is the file being read on stdin, and writing to it would truncate it before it is read; use '-i {} --in-place' instead",
+                target.display(),
+                target.display()
+            ));
+        }
+        if !replaces_input && inputs.iter().any(|input| platform::is_same_file(input, target)) {
+            if opts.matches_out.as_ref() == Some(target) {
+                return Err(anyhow!("'{}' is also an input file; refusing to overwrite it with the --matches-out report", target.display()));
+            }
+            return Err(anyhow!(
+                "'{}' is also an input file; refusing to overwrite it. Pass --in-place to replace the input with its sanitized version",
+                target.display()
+            ));
+        }
+    }
     Ok(())
 }
 
+/// Opens the `--audit-log` file of `sanitize`, if one was given.
+fn open_audit_log(path: Option<&Path>) -> Result<Option<Rc<RefCell<AuditTrail>>>> {
+    path.map(|path| AuditTrail::open(path, "redacted").map(|trail| Rc::new(RefCell::new(trail)))).transpose()
+}
+
+/// Builds the post-processor requested with `--post-process`, if any.
+fn build_post_processor(opts: &SanitizeCommand) -> Option<Box<dyn PostProcessor>> {
+    opts.post_process.as_deref().map(|command| Box::new(CommandPostProcessor::new(command)) as Box<dyn PostProcessor>)
+}
+
 /// Handles the `cleansh sanitize` command.
 fn handle_sanitize_command(opts: &SanitizeCommand, cli: &Cli, theme_map: &ui::theme::ThemeMap) -> Result<()> {
     if opts.line_buffered && (opts.diff || opts.clipboard || opts.input_file.is_some()) {
@@ -183,47 +803,263 @@ fn handle_sanitize_command(opts: &SanitizeCommand, cli: &Cli, theme_map: &ui::th
         std::process::exit(1);
     }
     
-    let engine = create_sanitization_engine(
-        opts.config.as_ref(),
+    let input_from_stdin = opts.input_file.is_none() && opts.files.is_empty() && opts.url.is_none() && opts.follow.is_none();
+    rules_source::ensure_stdin_available(&opts.config, input_from_stdin && !opts.explain_config)?;
+    let flags = EngineFlags {
+        profile_rules: opts.profile_rules,
+        seed: cli.seed,
+        preset: RulePreset::from_flags(opts.strict, opts.fast),
+        skip_invalid_rules: opts.skip_invalid_rules,
+        replacements: opts.replace.clone(),
+        keep_width: opts.keep_width,
+        allowlist: opts.allowlist.clone(),
+    };
+    if opts.explain_config {
+        return explain_config(&opts.config, opts.profile.as_ref(), &opts.enable, &opts.disable, &opts.allow_domain, flags, theme_map);
+    }
+
+    let (engine, skipped_rules) = create_sanitization_engine(
+        &opts.config,
         opts.profile.as_ref(),
         &opts.engine,
         &opts.enable,
         &opts.disable,
+        &opts.allow_domain,
+        flags,
     )?;
+    let result = run_sanitize(&*engine, opts, cli, theme_map);
+    warn_skipped_rules(&skipped_rules, theme_map);
+    result
+}
 
-    if opts.line_buffered {
-        run_line_buffered_mode(engine, &opts, theme_map, cli.quiet)?;
+/// Sanitizes the inputs of `cleansh sanitize` with `engine`, in the mode its flags select.
+fn run_sanitize(engine: &dyn SanitizationEngine, opts: &SanitizeCommand, cli: &Cli, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    check_output_targets(opts)?;
+    if opts.annotate {
+        return annotate_inputs(engine, opts, theme_map);
+    }
+
+    if opts.line_buffered || opts.follow.is_some() {
+        run_line_buffered_mode(engine, opts, theme_map)?;
+    } else if opts.files.len() > 1 || opts.out_dir.is_some() {
+        run_multiple_files(engine, opts, cli, theme_map)?;
+    } else if let Some(path) = streamable_input(opts) {
+        run_streamed_file(engine, path, opts, theme_map)?;
     } else {
-        let input_content = read_input(&opts.input_file, theme_map)?;
+        let (input_content, detected_encoding) = if opts.stdin_tty_paste {
+            match read_paste_and_confirm(engine, theme_map)? {
+                Some(content) => (content, TextEncoding::default()),
+                None => return Ok(()),
+            }
+        } else if let Some(url) = &opts.url {
+            read_url_input(url, opts.url_auth_env.as_deref(), theme_map)?
+        } else {
+            read_input(&opts.input_file.clone().or_else(|| opts.files.first().cloned()), opts.stdin_timeout, theme_map)?
+        };
+        let output_path = match &opts.output {
+            Some(path) => Some(path.clone()),
+            None if opts.in_place => opts.input_file.clone().or_else(|| opts.files.first().cloned()),
+            None => None,
+        };
+        let source = match (&opts.url, opts.input_file.as_ref().or(opts.files.first())) {
+            (Some(url), _) => url.clone(),
+            (None, Some(path)) => path.display().to_string(),
+            (None, None) => "stdin".to_string(),
+        };
 
         let cleansh_options = commands::cleansh::CleanshOptions {
             input: input_content,
             clipboard: opts.clipboard,
+            clipboard_max_bytes: opts.clipboard_max_bytes,
             diff: opts.diff,
-            output_path: opts.output.clone(),
+            output_path,
+            tee: opts.tee,
             no_redaction_summary: opts.no_summary,
             quiet: cli.quiet,
+            matches_out: opts.matches_out.clone(),
+            audit_log: open_audit_log(opts.audit_log.as_deref())?,
+            source,
+            tokenizer: build_tokenizer(opts)?.map(Rc::from),
+            post_processor: build_post_processor(opts),
+            join_wrapped: opts.join_wrapped,
+            input_format: opts.input_format,
+            diff_headers: opts.diff_headers,
+            dotenv_allow: opts.dotenv_allow.clone(),
+            output_encoding: output_encoding_for(opts, detected_encoding),
+            passes: opts.passes,
+            verify_idempotent: opts.verify_idempotent,
+            strict_containment: opts.strict_containment || opts.in_place,
+            in_place: opts.in_place,
         };
-        commands::cleansh::run_cleansh_opts(&*engine, cleansh_options, theme_map)?;
+        commands::cleansh::run_cleansh_opts(engine, cleansh_options, theme_map)?;
+    }
+
+    if opts.profile_rules {
+        ui::rule_profile::print_rule_profile(&engine.rule_profile(), &mut io::stderr(), theme_map, io::stderr().is_terminal())?;
+    }
+    Ok(())
+}
+
+/// Input files larger than this are sanitized a chunk at a time instead of being read whole.
+const STREAM_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Returns the single input file of `opts` if it is larger than [`STREAM_THRESHOLD_BYTES`],
+/// plain UTF-8, and no flag needs the whole input or output at once.
+fn streamable_input(opts: &SanitizeCommand) -> Option<&Path> {
+    let path = match (opts.input_file.as_deref(), opts.files.as_slice()) {
+        (Some(path), _) => path,
+        (None, [path]) => path.as_path(),
+        _ => return None,
+    };
+    let needs_whole_input = opts.diff
+        || opts.clipboard
+        || opts.tee
+        || opts.in_place
+        || opts.strict_containment
+        || opts.verify_idempotent
+        || opts.matches_out.is_some()
+        || opts.audit_log.is_some()
+        || opts.join_wrapped.is_some()
+        || opts.post_process.is_some()
+        || tokenize_requested(opts)
+        || opts.passes > 1
+        || opts.input_format != InputFormat::Text;
+    if needs_whole_input || !fs::metadata(platform::long_path(path)).is_ok_and(|m| m.len() > STREAM_THRESHOLD_BYTES) {
+        return None;
+    }
+
+    // Other encodings are transcoded as a whole, like smaller files.
+    let mut prefix = Vec::new();
+    fs::File::open(platform::long_path(path)).and_then(|file| file.take(512).read_to_end(&mut prefix)).ok()?;
+    encoding::sniff(&prefix).0.is_plain_utf8().then_some(path)
+}
+
+/// Sanitizes a large input file with [`SanitizationEngine::sanitize_reader`], so that it is
+/// never held in memory whole, and writes the result to stdout or `--output`.
+fn run_streamed_file(engine: &dyn SanitizationEngine, path: &Path, opts: &SanitizeCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let file = fs::File::open(platform::long_path(path))
+        .with_context(|| format!("Failed to read input from {}", path.display()))?;
+    commands::cleansh::progress_msg(format!("Reading input from file: {}", path.display()), theme_map);
+    commands::cleansh::info_msg(
+        format!("Input is larger than {} MiB; sanitizing it in chunks.", STREAM_THRESHOLD_BYTES / (1024 * 1024)),
+        theme_map,
+    );
+
+    let mut writer: Box<dyn Write> = match opts.output.as_ref() {
+        Some(output) => Box::new(io::BufWriter::new(
+            fs::File::create(platform::long_path(output))
+                .with_context(|| format!("Failed to create output file: {}", output.display()))?,
+        )),
+        None => Box::new(io::BufWriter::new(io::stdout().lock())),
+    };
+    let summary = engine
+        .sanitize_reader(&mut BufReader::new(file), &mut writer, "", StreamOptions::default())
+        .context("Sanitization failed")?;
+
+    if !opts.no_summary && ui::output_format::should_print(MessageKind::Summary) {
+        let stderr_supports_color = io::stderr().is_terminal();
+        ui::redaction_summary::print_summary(&summary, &mut io::stderr(), theme_map, stderr_supports_color)?;
     }
-    
     Ok(())
 }
 
+/// Sanitizes several positional input files, to stdout with headers or into `--out-dir`.
+fn run_multiple_files(
+    engine: &dyn SanitizationEngine,
+    opts: &SanitizeCommand,
+    cli: &Cli,
+    theme_map: &ui::theme::ThemeMap,
+) -> Result<()> {
+    if opts.clipboard || opts.matches_out.is_some() || opts.output.is_some() {
+        return Err(anyhow!("--clipboard, --matches-out and --output take a single input; use --out-dir for several files"));
+    }
+
+    let mut destinations: HashMap<PathBuf, &Path> = HashMap::new();
+    if let Some(dir) = opts.out_dir.as_ref() {
+        fs::create_dir_all(platform::long_path(dir)).with_context(|| format!("Failed to create output directory: {}", dir.display()))?;
+        for file in &opts.files {
+            let name = file.file_name().ok_or_else(|| anyhow!("'{}' does not name a file", file.display()))?;
+            if let Some(other) = destinations.insert(dir.join(name), file) {
+                return Err(anyhow!(
+                    "'{}' and '{}' would both be written to {}",
+                    other.display(),
+                    file.display(),
+                    dir.join(name).display()
+                ));
+            }
+        }
+    }
+
+    let tokenizer: Option<Rc<dyn Tokenizer>> = build_tokenizer(opts)?.map(Rc::from);
+    let audit_log = open_audit_log(opts.audit_log.as_deref())?;
+    let strict_containment = opts.strict_containment || opts.in_place;
+    // Each file is read only when its turn comes.
+    let inputs = opts.files.iter().map(|file| {
+        let (input_content, detected_encoding) = read_input(&Some(file.clone()), None, theme_map)?;
+        let output_path = match &opts.out_dir {
+            Some(dir) => file.file_name().map(|name| dir.join(name)),
+            None if opts.in_place => Some(file.clone()),
+            None => None,
+        };
+        Ok((file.display().to_string(), commands::cleansh::CleanshOptions {
+            input: input_content,
+            clipboard: false,
+            clipboard_max_bytes: opts.clipboard_max_bytes,
+            diff: opts.diff,
+            output_path,
+            tee: false,
+            no_redaction_summary: opts.no_summary,
+            quiet: cli.quiet,
+            matches_out: None,
+            audit_log: audit_log.clone(),
+            source: file.display().to_string(),
+            tokenizer: tokenizer.clone(),
+            post_processor: build_post_processor(opts),
+            join_wrapped: opts.join_wrapped,
+            input_format: opts.input_format,
+            diff_headers: opts.diff_headers,
+            dotenv_allow: opts.dotenv_allow.clone(),
+            output_encoding: output_encoding_for(opts, detected_encoding),
+            passes: opts.passes,
+            verify_idempotent: opts.verify_idempotent,
+            strict_containment,
+            in_place: opts.in_place,
+        }))
+    });
+    commands::cleansh::run_cleansh_files(engine, inputs, strict_containment, theme_map)
+}
+
 /// Handler for the `cleansh scan` command.
-fn handle_scan_command(opts: &ScanCommand, theme_map: &ui::theme::ThemeMap, state_path: &Path, app_state: &mut AppState) -> Result<()> {
+fn handle_scan_command(opts: &ScanCommand, seed: Option<u64>, theme_map: &ui::theme::ThemeMap, state_path: &Path, app_state: &mut AppState) -> Result<()> {
+    let input_from_stdin = opts.dir.is_none() && !opts.staged && opts.files_from.as_deref().map_or(opts.input_file.is_none(), rules_source::is_stdin);
+    rules_source::ensure_stdin_available(&opts.config, input_from_stdin && !opts.explain_config)?;
+    let flags = EngineFlags {
+        seed,
+        preset: RulePreset::from_flags(opts.strict, opts.fast),
+        skip_invalid_rules: opts.skip_invalid_rules,
+        allowlist: opts.allowlist.clone(),
+        ..Default::default()
+    };
+    if opts.explain_config {
+        return explain_config(&opts.config, opts.profile.as_ref(), &opts.enable, &opts.disable, &opts.allow_domain, flags, theme_map);
+    }
+
     // Check license first before running command logic
     let token_opt = check_license_for_feature("scan", state_path, app_state, theme_map)?;
     
-    let engine = create_sanitization_engine(
-        opts.config.as_ref(),
+    let (engine, skipped_rules) = create_sanitization_engine(
+        &opts.config,
         opts.profile.as_ref(),
         &EngineChoice::Regex,
         &opts.enable,
         &opts.disable,
+        &opts.allow_domain,
+        flags,
     )?;
 
-    let res = commands::stats::run_stats_command(&opts, theme_map, &*engine);
+    let history_path = opts.record_history.then(|| scan_history::history_path(state_path));
+    let cache_dir = (!opts.no_cache).then(|| scan_cache::cache_dir(state_path));
+    let res = commands::stats::run_stats_command(&opts, theme_map, &*engine, history_path.as_deref(), cache_dir.as_deref());
     
     // Consume license only if the command was successful and a token was present
     if res.is_ok() {
@@ -232,9 +1068,123 @@ fn handle_scan_command(opts: &ScanCommand, theme_map: &ui::theme::ThemeMap, stat
         }
     }
 
+    warn_skipped_rules(&skipped_rules, theme_map);
     res
 }
 
+/// Runs `cleansh eval` with the engine built from its rule selection flags.
+fn handle_eval_command(opts: &EvalCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &[],
+        EngineFlags::default(),
+    )?
+    .0;
+    commands::eval::run_eval_command(opts, theme_map, &*engine)
+}
+
+/// Runs `cleansh rules impact` against the rules that `--config`, `--profile`, `--enable`
+/// and `--disable` select.
+fn handle_rules_impact_command(opts: &RulesImpactCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &[],
+        EngineFlags::default(),
+    )?
+    .0;
+    commands::rules_impact::run_rules_impact(opts, theme_map, &*engine)
+}
+
+/// Handles `cleansh config show`: the merged `--config` files, or with `--effective` the
+/// rule set [`build_effective_config`] builds for a run.
+fn handle_config_show_command(opts: &ConfigShowCommand) -> Result<()> {
+    rules_source::ensure_stdin_available(&opts.config, false)?;
+    let config = if opts.effective {
+        build_effective_config(&opts.config, opts.profile.as_ref(), &opts.enable, &opts.disable, RulePreset::Standard, false)?.0
+    } else {
+        let mut config = RedactionConfig::default();
+        for path in &opts.config {
+            config = merge_rules(config, Some(rules_source::load_user_config(path)?));
+        }
+        config
+    };
+    commands::config::print_config(&config)
+}
+
+/// Runs `cleansh grep`, exiting with status 1 when no line was selected, as `grep` does.
+fn handle_grep_command(opts: &GrepCommand) -> Result<()> {
+    let input_from_stdin = opts.files.is_empty();
+    rules_source::ensure_stdin_available(&opts.config, input_from_stdin)?;
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &opts.allow_domain,
+        EngineFlags::default(),
+    )?
+    .0;
+    if commands::grep::run_grep_command(opts, &*engine)? == 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Handler for the `cleansh history` command.
+fn handle_history_command(opts: &HistoryCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &[],
+        EngineFlags::default(),
+    )?
+    .0;
+    commands::history::run_history_command(opts, &*engine, theme_map)
+}
+
+/// Handles the `verify-sanitized` command.
+fn handle_verify_sanitized_command(opts: &VerifySanitizedCommand, theme_map: &ui::theme::ThemeMap) -> Result<()> {
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &[],
+        EngineFlags::default(),
+    )?
+    .0;
+    commands::verify_sanitized::run_verify_sanitized_command(opts, &*engine, theme_map)
+}
+
+/// Handler for the `cleansh exec` command. Returns the command's exit code, which `main`
+/// exits with once the app state is saved.
+fn handle_exec_command(opts: &ExecCommand, theme_map: &ui::theme::ThemeMap) -> Result<i32> {
+    let engine = create_sanitization_engine(
+        &opts.config,
+        opts.profile.as_ref(),
+        &EngineChoice::Regex,
+        &opts.enable,
+        &opts.disable,
+        &[],
+        EngineFlags::default(),
+    )?
+    .0;
+    commands::exec::run_exec_command(opts, &*engine, theme_map)
+}
+
 /// New helper function to centralize the license check, command execution, and consumption logic.
 fn gated_command<F>(feature: &str, state_path: &Path, app_state: &mut AppState, theme_map: &ui::theme::ThemeMap, f: F) -> Result<()>
 where
@@ -293,14 +1243,86 @@ fn handle_profiles_command(opts: &ProfilesCommand, _cli: &Cli, theme_map: &ui::t
                 Ok(())
             })
         },
+        ProfilesCommand::Diff { a, b, config, json } => {
+            gated_command("profiles:diff", state_path, app_state, theme_map, |token_opt| {
+                if token_opt.is_none() {
+                    commands::cleansh::warn_msg("Skipping license validation for 'profiles:diff' in test mode.", theme_map);
+                }
+                let from = profile_policy(a)?;
+                let to = match b {
+                    Some(b) => profile_policy(b)?,
+                    None => {
+                        let (rules, options, _, _) = build_effective_config(config.as_slice(), None, &[], &[], RulePreset::Standard, false)?;
+                        let label = match config {
+                            Some(path) => format!("the built-in rules merged with '{}'", path.display()),
+                            None => "the built-in rules".to_string(),
+                        };
+                        PolicySide { label, config: rules, options }
+                    }
+                };
+                commands::profiles_diff::run_profiles_diff(&from, &to, *json, &mut io::stdout().lock())
+            })
+        },
     }
 }
 
+/// Loads a profile by name or path and resolves it to the policy `--profile` would run.
+fn profile_policy(name: &String) -> Result<PolicySide> {
+    let (config, options, _, _) = build_effective_config(&[], Some(name), &[], &[], RulePreset::Standard, false)?;
+    let label = format!("profile '{}' (version {})", options.profile_meta.profile_name, options.profile_meta.version);
+    Ok(PolicySide { label, config, options })
+}
+
+/// Turns on unredacted PII in debug logs for `--unsafe-debug-pii`, after making sure the
+/// output cannot end up in a captured log (CI job, file, pipe) and warning the user loudly.
+fn enable_unsafe_debug_pii(theme_map: &ui::theme::ThemeMap) {
+    if !io::stdout().is_terminal() || !io::stderr().is_terminal() {
+        commands::cleansh::error_msg(
+            "--unsafe-debug-pii refuses to run when stdout or stderr is not a terminal, to keep PII out of captured logs.",
+            theme_map,
+        );
+        std::process::exit(1);
+    }
 
-fn main() -> Result<()> {
+    let banner = [
+        "************************************************************",
+        "*  UNSAFE: --unsafe-debug-pii is enabled.                  *",
+        "*  Debug logs will contain ORIGINAL, UNREDACTED secrets.   *",
+        "*  Do not copy, share, or record this terminal session.    *",
+        "************************************************************",
+    ];
+    let stderr_supports_color = io::stderr().is_terminal();
+    for line in banner {
+        let _ = ui::output_format::print_message(&mut io::stderr(), line, theme_map, Some(ui::theme::ThemeEntry::Warn), stderr_supports_color);
+    }
+    cleansh_core::set_pii_debug_allowed(true);
+}
+
+/// Returns `true` if stdout goes to another process or a file while stderr is still on
+/// the user's terminal, where messages around the content would only get in the way.
+/// When stderr is captured as well, as in CI, summaries are kept for the log.
+fn stdout_piped() -> bool {
+    #[cfg(feature = "test-exposed")]
+    if let Some(value) = env::var_os("CLEANSH_PIPED_OVERRIDE_FOR_TESTS") {
+        return value == "1";
+    }
+    !io::stdout().is_terminal() && io::stderr().is_terminal()
+}
+
+fn main() -> Result<ExitCode> {
     dotenvy::dotenv().ok();
     
-    let cli = Cli::parse();
+    // Rewrite deprecated flags from older releases before clap sees them.
+    let legacy = cli_compat::translate_legacy_args(env::args_os());
+    let cli = Cli::parse_from(legacy.args);
+
+    // Install the verbosity policy before anything can print. Piped output implies
+    // --quiet --no-epilogue unless --verbose asks for the terminal behavior.
+    let piped = !cli.verbose && stdout_piped();
+    ui::output_format::set_verbosity(if cli.quiet || piped { Verbosity::Quiet } else { Verbosity::Normal });
+    ui::output_format::set_summary_format(cli.summary_format);
+    // The epilogue is decided here once; --suppress-donation-prompt is its per-run subset.
+    ui::output_format::set_epilogue_enabled(!(cli.no_epilogue || cli.suppress_donation_prompt || piped));
     
     // ── Honor test override for app state path ───────────────────────────────────
     let app_state_path: PathBuf = env::var("CLEANSH_STATE_FILE_OVERRIDE_FOR_TESTS")
@@ -312,9 +1334,38 @@ fn main() -> Result<()> {
                 env::current_dir().expect("Failed to get current dir").join("cleansh_state.json")
             }
         });
+    // The lock, key and history files are derived from this path, so they inherit the prefix.
+    let app_state_path = platform::long_path(&app_state_path);
     // ── End override block ─────────────────────────────────────────────────────
+
+    // Crash reports live next to the app state so test overrides keep them isolated too.
+    let crash_report_dir = app_state_path
+        .parent()
+        .map(|dir| dir.join("crash-reports"))
+        .unwrap_or_else(env::temp_dir);
+    crash_report::install_panic_hook(crash_report_dir);
+
+    #[cfg(feature = "test-exposed")]
+    if env::var_os("CLEANSH_FORCE_PANIC_FOR_TESTS").is_some() {
+        panic!("forced panic for tests while handling user@example.com");
+    }
     
-    let theme_map = ui::theme::build_theme_map(cli.theme.as_ref())?;
+    let theme_spec = ui::theme::theme_spec(cli.theme.as_deref(), cli.command.name(), |key| env::var(key).ok());
+    let theme_map = ui::theme::build_theme_map(theme_spec.as_deref())?;
+
+    for warning in &legacy.warnings {
+        commands::cleansh::warn_msg(warning, &theme_map);
+    }
+
+    if env::var_os("CLEANSH_ALLOW_DEBUG_PII").is_some() {
+        commands::cleansh::warn_msg(
+            "CLEANSH_ALLOW_DEBUG_PII is no longer honored and debug logs stay redacted. Use --unsafe-debug-pii on an interactive terminal instead.",
+            &theme_map,
+        );
+    }
+    if cli.unsafe_debug_pii {
+        enable_unsafe_debug_pii(&theme_map);
+    }
     
     let effective_log_level = if cli.quiet {
         Some(LevelFilter::Off)
@@ -330,29 +1381,50 @@ fn main() -> Result<()> {
     
     // We only load the app state if the command is not `uninstall`.
     let mut app_state;
+    // `cleansh exec` leaves with the exit code of the command it ran.
+    let mut exit_code = 0;
     let result = match cli.command {
         Commands::Uninstall { yes } => commands::uninstall::elevate_and_run_uninstall(yes, &theme_map),
         ref opts @ _ => {
             // Load or create the AppState for all other commands
-            app_state = AppState::load(&app_state_path)?;
+            let integrity;
+            (app_state, integrity) = AppState::load_verified(&app_state_path)?;
+            if let StateIntegrity::Reset(reason) = integrity {
+                commands::cleansh::warn_msg(
+                    format!("Application state at {} could not be verified and was reset: {}", app_state_path.display(), reason),
+                    &theme_map,
+                );
+            }
             // Set donation prompts disabled state after loading, so the CLI overrides previous state.
             app_state.donation_prompts_disabled = cli.disable_donation_prompts || cli.quiet;
 
             let command_result = match opts {
                 Commands::Sanitize(sanitize_opts) => handle_sanitize_command(sanitize_opts, &cli, &theme_map),
-                Commands::Scan(scan_opts) => handle_scan_command(scan_opts, &theme_map, &app_state_path, &mut app_state),
+                Commands::Scan(scan_opts) => handle_scan_command(scan_opts, cli.seed, &theme_map, &app_state_path, &mut app_state),
                 Commands::Profiles(profile_opts) => handle_profiles_command(profile_opts, &cli, &theme_map, &app_state_path, &mut app_state),
+                Commands::Report(report_opts) => commands::report::run_report_command(report_opts, &theme_map),
+                Commands::Eval(eval_opts) => handle_eval_command(eval_opts, &theme_map),
+                Commands::Grep(grep_opts) => handle_grep_command(grep_opts),
+                Commands::Rules(RulesCommand::New(new_opts)) => commands::rules::run_rules_new(new_opts, &theme_map),
+                Commands::Rules(RulesCommand::Impact(impact_opts)) => handle_rules_impact_command(impact_opts, &theme_map),
+                Commands::Config(ConfigCommand::Init(init_opts)) => commands::config::run_config_init(init_opts, &theme_map),
+                Commands::Config(ConfigCommand::Validate(validate_opts)) => commands::config::run_config_validate(validate_opts, &theme_map),
+                Commands::Config(ConfigCommand::Show(show_opts)) => handle_config_show_command(show_opts),
+                Commands::Stats(stats_opts) => match stats_opts.as_ref() {
+                    StatsCommand::History(history_opts) => commands::stats_history::run_stats_history(history_opts, &app_state_path, &theme_map),
+                    StatsCommand::Serve(serve_opts) => commands::stats_serve::run_stats_serve(serve_opts, &app_state_path, &theme_map),
+                },
+                Commands::InstallShellIntegration(integration_opts) => commands::shell_integration::run_install_shell_integration(integration_opts, &theme_map),
+                Commands::History(history_opts) => handle_history_command(history_opts, &theme_map),
+                Commands::VerifySanitized(verify_opts) => handle_verify_sanitized_command(verify_opts, &theme_map),
+                Commands::Exec(exec_opts) => handle_exec_command(exec_opts, &theme_map).map(|code| exit_code = code),
                 Commands::Uninstall { yes: _ } => {
                     unreachable!()
                 }
             };
 
-            // Donation prompt logic
-            if !app_state.donation_prompts_disabled {
-                if let Err(e) = app_state.check_and_prompt_donation(&theme_map) {
-                    commands::cleansh::error_msg(format!("Failed to handle donation prompt: {}", e), &theme_map);
-                }
-            }
+            // Notifications are shown once, after the command's own output.
+            notifications::show_due(&mut app_state, &app_state_path, &theme_map);
 
             // Save app state at exit (ensures non-licensed changes also persist)
             if let Err(e) = app_state.save(&app_state_path) {
@@ -363,5 +1435,6 @@ fn main() -> Result<()> {
         }
     };
     
-    result
+    // Codes that do not fit the portable range, as some Windows crash codes do, fail with 1.
+    result.map(|()| u8::try_from(exit_code).map_or(ExitCode::FAILURE, ExitCode::from))
 }
\ No newline at end of file
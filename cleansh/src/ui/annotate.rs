@@ -0,0 +1,72 @@
+//! Annotated view of an input, for reviewing what the rules would redact.
+//!
+//! Instead of replacing matches, `sanitize --annotate` prints the original text with each
+//! match highlighted and followed by the name of the rule that found it. The output still
+//! contains every secret, so it is only ever written to a terminal.
+
+use crate::ui::output_format::get_styled_text;
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+use anyhow::Result;
+use cleansh_core::RedactionMatch;
+use std::io::Write;
+
+/// Prints `content` with every match highlighted and labeled with its rule name.
+///
+/// `matches` must be sorted by start offset, as returned by `find_matches_for_ui`. Where
+/// matches overlap, the first one is shown and the others are skipped.
+///
+/// # Arguments
+///
+/// * `content` - The original input.
+/// * `matches` - The matches found in `content`.
+/// * `writer` - The output writer, normally the terminal.
+/// * `theme_map` - The theme used to style matches and labels.
+/// * `enable_colors` - Whether ANSI colors should be used.
+pub fn print_annotated<W: Write>(
+    content: &str,
+    matches: &[RedactionMatch],
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    let mut position = 0;
+    for m in matches {
+        let (start, end) = (m.start as usize, m.end as usize);
+        if start < position || end > content.len() || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+            continue;
+        }
+        write!(writer, "{}", &content[position..start])?;
+        write!(
+            writer,
+            "{}{}",
+            get_styled_text(&content[start..end], ThemeEntry::MatchHighlight, theme_map, enable_colors),
+            get_styled_text(&format!("[{}]", m.rule_name), ThemeEntry::SummaryRuleName, theme_map, enable_colors)
+        )?;
+        position = end;
+    }
+    write!(writer, "{}", &content[position..])?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::engine::SanitizationEngine;
+    use cleansh_core::{RedactionConfig, RegexEngine};
+
+    #[test]
+    fn labels_each_match_with_its_rule() -> Result<()> {
+        let config = RedactionConfig::load_default_rules()?;
+        let engine = RegexEngine::new(config)?;
+        let content = "mail alice@example.com now";
+        let matches = engine.find_matches_for_ui(content, "test")?;
+
+        let mut out = Vec::new();
+        print_annotated(content, &matches, &mut out, &ThemeMap::new(), false)?;
+        assert_eq!(String::from_utf8(out)?, "mail alice@example.com[email] now\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Module for printing the effective rule configuration in Cleansh.
+//!
+//! This backs the `--explain-config` flag, which shows the final set of active rules
+//! after defaults, user configuration, profiles, and `--enable`/`--disable` have been
+//! applied. Rules are listed in the exact order the engine applies them, so users
+//! layering several rule sources can see how `priority` resolves between them. The
+//! sources themselves are listed at the end, in the order they were merged.
+
+use crate::ui::output_format;
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+use anyhow::Result;
+use cleansh_core::config::RedactionConfig;
+use cleansh_core::EngineDescription;
+use std::io::Write;
+
+/// Above this many names, a layer's rules are summarized by their count.
+const MAX_LISTED_RULES: usize = 8;
+
+/// One source of rules, in the order the sources are merged: the built-in rules, each
+/// `--config` file, then the profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+    /// What the layer is, e.g. `config team.yaml` or `profile strict`.
+    pub source: String,
+    /// Rules of earlier layers that this layer replaces or adjusts.
+    pub overridden: Vec<String>,
+    /// Rules this layer adds.
+    pub added: Vec<String>,
+}
+
+fn rule_list(names: &[String]) -> String {
+    if names.len() > MAX_LISTED_RULES {
+        format!("{} rules", names.len())
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Prints the layers in merge order.
+fn print_layers<W: Write>(layers: &[ConfigLayer], writer: &mut W, theme_map: &ThemeMap, enable_colors: bool) -> Result<()> {
+    writeln!(
+        writer,
+        "\n{}",
+        output_format::get_styled_text("Merge order (later layers override earlier ones):", ThemeEntry::Info, theme_map, enable_colors)
+    )?;
+    for (idx, layer) in layers.iter().enumerate() {
+        let mut changes = Vec::new();
+        if !layer.overridden.is_empty() {
+            changes.push(format!("overrides {}", rule_list(&layer.overridden)));
+        }
+        if !layer.added.is_empty() {
+            changes.push(format!("adds {}", rule_list(&layer.added)));
+        }
+        if changes.is_empty() {
+            changes.push("no rule changes".to_string());
+        }
+        writeln!(writer, "{:>3}. {}: {}", idx + 1, layer.source, changes.join("; "))?;
+    }
+    Ok(())
+}
+
+/// Prints the effective rule configuration in application order to the given writer.
+///
+/// # Arguments
+///
+/// * `config` - The final `RedactionConfig` used by the engine, already in application order.
+/// * `description` - The engine's description of the same rules, for their validators and literals.
+/// * `allowed_domains` - The domains from the profile and `--allow-domain` that the `hostname`
+///   validator never redacts, listed under the rules that use it.
+/// * `layers` - The rule sources in the order they were merged.
+/// * `writer` - The output writer where the explanation will be printed.
+/// * `theme_map` - A `HashMap` containing the defined `ThemeStyle`s for styling the output.
+/// * `enable_colors` - A boolean indicating whether ANSI colors should be applied.
+pub fn print_explain_config<W: Write>(
+    config: &RedactionConfig,
+    description: &EngineDescription,
+    allowed_domains: &[String],
+    layers: &[ConfigLayer],
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    let header = output_format::get_styled_text("--- Effective Rule Configuration ---", ThemeEntry::Header, theme_map, enable_colors);
+    writeln!(writer, "{}", header)?;
+
+    if config.rules.is_empty() {
+        writeln!(writer, "{}", output_format::get_styled_text("No active rules.", ThemeEntry::Info, theme_map, enable_colors))?;
+        return print_layers(layers, writer, theme_map, enable_colors);
+    }
+
+    writeln!(
+        writer,
+        "{}",
+        output_format::get_styled_text(
+            "Rules are applied top to bottom; higher priority wins when matches start at the same position.",
+            ThemeEntry::Info,
+            theme_map,
+            enable_colors,
+        )
+    )?;
+
+    for (idx, rule) in config.rules.iter().enumerate() {
+        let described = description.rule(&rule.name);
+        let mut flags = Vec::new();
+        if rule.enabled == Some(false) {
+            flags.push("disabled".to_string());
+        }
+        if rule.opt_in {
+            flags.push("opt-in".to_string());
+        }
+        if let Some(validator) = described.and_then(|d| d.validator.as_deref()) {
+            flags.push(format!("validator: {}", validator));
+        }
+        if described.is_some_and(|d| !d.compiled) {
+            flags.push("no pattern".to_string());
+        }
+        let flags_text = if flags.is_empty() { String::new() } else { format!(" [{}]", flags.join(", ")) };
+
+        let rule_name_styled = output_format::get_styled_text(&rule.name, ThemeEntry::SummaryRuleName, theme_map, enable_colors);
+        let severity = rule.severity.as_deref().unwrap_or("none");
+        let details_styled = format!(
+            "{}{}{}",
+            output_format::get_styled_text(&format!(" (priority {}, severity ", rule.priority), ThemeEntry::SummaryOccurrences, theme_map, enable_colors),
+            output_format::get_styled_text(
+                severity,
+                ThemeEntry::for_severity(severity).unwrap_or(ThemeEntry::SummaryOccurrences),
+                theme_map,
+                enable_colors,
+            ),
+            output_format::get_styled_text(&format!("){}", flags_text), ThemeEntry::SummaryOccurrences, theme_map, enable_colors),
+        );
+        writeln!(writer, "{:>3}. {}{}", idx + 1, rule_name_styled, details_styled)?;
+
+        if let Some(description) = rule.description.as_deref() {
+            writeln!(writer, "     {}", description)?;
+        }
+        if let Some(paths) = rule.paths.as_ref().filter(|paths| !paths.is_empty()) {
+            writeln!(writer, "     paths: {}", paths.join(", "))?;
+        }
+        if let Some(literals) = described.and_then(|d| d.required_literals.as_ref()) {
+            writeln!(writer, "     requires: {}", literals.join(" | "))?;
+        }
+        if !allowed_domains.is_empty() && described.and_then(|d| d.validator.as_deref()) == Some("hostname") {
+            writeln!(writer, "     allowed domains: {}", allowed_domains.join(", "))?;
+        }
+    }
+
+    print_layers(layers, writer, theme_map, enable_colors)
+}
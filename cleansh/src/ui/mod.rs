@@ -10,11 +10,26 @@ pub mod theme;
 /// Functions for displaying diff views.
 pub mod diff_viewer;
 
+/// Annotated view of matches for `sanitize --annotate`.
+pub mod annotate;
+
 /// Functions for displaying redaction summaries.
 pub mod redaction_summary;
 
+/// Per-rule timing table for `sanitize --profile-rules`.
+pub mod rule_profile;
+
+/// Functions for displaying the effective rule configuration.
+pub mod explain_config;
+
 /// UI functions for the verify-artifact subcommand.
 pub mod verify_ui;
 
 /// UI functions for the sync-profiles subcommand.
 pub mod sync_ui;
+
+/// HTML rendering for the report subcommand.
+pub mod report_html;
+
+/// HTML rendering for the `stats serve` dashboard.
+pub mod stats_dashboard;
@@ -9,8 +9,128 @@
 use crate::ui::theme::{ThemeEntry, ThemeMap};
 use owo_colors::OwoColorize;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 // Removed: use is_terminal::IsTerminal; // Not needed in this module now as we pass `enable_colors` directly
 
+/// The verbosity policy that decides which messages reach the terminal.
+///
+/// This is the single source of truth for `--quiet`: it is installed once at startup
+/// with [`set_verbosity`] and consulted by every print helper in this module, so
+/// individual commands never need to thread a `quiet` flag around themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only warnings, errors, and interactive prompts are shown.
+    Quiet,
+    /// Informational messages and redaction summaries are shown as well.
+    #[default]
+    Normal,
+}
+
+/// The kinds of non-primary output cleansh writes, used to query the [`Verbosity`] policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// Progress and status messages (e.g., "Reading input from stdin...").
+    Info,
+    /// Success confirmations (e.g., "Profile synchronization SUCCESSFUL.").
+    Success,
+    /// The redaction summary printed after sanitization.
+    Summary,
+    /// Messages printed after the command's own output, such as the donation prompt.
+    Epilogue,
+    /// Questions that require user input; always shown.
+    Prompt,
+    /// Warnings; always shown.
+    Warning,
+    /// Errors; always shown.
+    Error,
+}
+
+impl Verbosity {
+    /// Returns `true` if a message of the given kind should be printed under this policy.
+    pub fn allows(self, kind: MessageKind) -> bool {
+        match kind {
+            MessageKind::Info | MessageKind::Success | MessageKind::Summary | MessageKind::Epilogue => self >= Verbosity::Normal,
+            MessageKind::Prompt | MessageKind::Warning | MessageKind::Error => true,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            Verbosity::Quiet => 0,
+            Verbosity::Normal => 1,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Verbosity::Quiet,
+            _ => Verbosity::Normal,
+        }
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(1);
+
+/// Installs the process-wide verbosity policy. Called once from `main` after parsing arguments.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity.to_u8(), Ordering::Relaxed);
+}
+
+/// Returns the active verbosity policy.
+pub fn verbosity() -> Verbosity {
+    Verbosity::from_u8(VERBOSITY.load(Ordering::Relaxed))
+}
+
+/// How redaction summaries are rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SummaryFormat {
+    /// Per-rule details, including original and sanitized values where available.
+    #[default]
+    Full,
+    /// A single line such as `3 rules, 17 redactions, 2 files`, for CI logs.
+    Compact,
+}
+
+static SUMMARY_FORMAT: AtomicU8 = AtomicU8::new(0);
+static EPILOGUE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Installs the process-wide summary format. Called once from `main` after parsing arguments.
+pub fn set_summary_format(format: SummaryFormat) {
+    SUMMARY_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Returns the active summary format.
+pub fn summary_format() -> SummaryFormat {
+    match SUMMARY_FORMAT.load(Ordering::Relaxed) {
+        1 => SummaryFormat::Compact,
+        _ => SummaryFormat::Full,
+    }
+}
+
+/// Enables or disables [`MessageKind::Epilogue`] output for this process (`--no-epilogue`).
+pub fn set_epilogue_enabled(enabled: bool) {
+    EPILOGUE_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if the active output policy allows messages of the given kind.
+pub fn should_print(kind: MessageKind) -> bool {
+    if kind == MessageKind::Epilogue && !EPILOGUE_ENABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+    verbosity().allows(kind)
+}
+
+/// Maps a theme entry used by [`print_message`] to the kind of message it represents.
+fn message_kind_for_entry(entry: &ThemeEntry) -> MessageKind {
+    match entry {
+        ThemeEntry::Error => MessageKind::Error,
+        ThemeEntry::Warn => MessageKind::Warning,
+        ThemeEntry::Prompt => MessageKind::Prompt,
+        ThemeEntry::Success => MessageKind::Success,
+        _ => MessageKind::Info,
+    }
+}
+
 /// Helper to get a styled string based on the theme.
 ///
 /// This function applies ANSI color codes to a given `text` based on the
@@ -62,6 +182,7 @@ pub(crate) fn get_styled_text(
 ///
 /// If `theme_entry` is `None`, it defaults to `ThemeEntry::Info`.
 /// The message is automatically followed by a newline character.
+/// Info and success messages are skipped when the active [`Verbosity`] suppresses them.
 /// Colors are applied only if `enable_colors` is true.
 ///
 /// # Type Parameters
@@ -87,6 +208,9 @@ pub fn print_message<W: Write>( // <--- Removed `+ IsTerminal` trait bound
     enable_colors: bool, // <--- Added enable_colors parameter
 ) -> io::Result<()> {
     let final_theme_entry = theme_entry.unwrap_or(ThemeEntry::Info);
+    if !should_print(message_kind_for_entry(&final_theme_entry)) {
+        return Ok(());
+    }
     let styled_message = get_styled_text(&format!("{}\n", message), final_theme_entry, theme_map, enable_colors);
     write!(writer, "{}", styled_message)
 }
@@ -95,6 +219,7 @@ pub fn print_message<W: Write>( // <--- Removed `+ IsTerminal` trait bound
 ///
 /// This function uses `ThemeEntry::Info` for styling. The message is automatically
 /// followed by a newline character. Colors are applied only if `enable_colors` is true.
+/// Nothing is written when the active [`Verbosity`] suppresses informational output.
 ///
 /// # Type Parameters
 ///
@@ -116,6 +241,9 @@ pub fn print_info_message<W: Write>( // <--- Removed `+ IsTerminal` trait bound
     theme_map: &ThemeMap, // Use ThemeMap alias
     enable_colors: bool, // <--- Added enable_colors parameter
 ) -> io::Result<()> {
+    if !should_print(MessageKind::Info) {
+        return Ok(());
+    }
     let styled_message = get_styled_text(&format!("{}\n", message), ThemeEntry::Info, theme_map, enable_colors);
     write!(writer, "{}", styled_message)
 }
@@ -6,8 +6,9 @@
 //! and statistics-only mode (counting matches and optionally showing samples).
 //! Output can be colored based on the application's theme.
 
+use crate::cli::Severity;
 use crate::ui::theme::{ThemeEntry, ThemeMap};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use anyhow::Result;
 
@@ -15,7 +16,23 @@ use anyhow::Result;
 use cleansh_core::{RedactionSummaryItem, RedactionMatch, CompiledRules};
 
 // Local imports
-use crate::ui::output_format;
+use crate::ui::output_format::{self, SummaryFormat};
+use crate::utils::match_budget::MatchStore;
+
+/// Returns `"{count} {singular}"`, or the plural form when `count` is not 1.
+fn counted(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// Builds the one-line summary used by [`SummaryFormat::Compact`], e.g.
+/// `3 rules, 17 redactions, 2 files`. The file count is left out when `sources` is `None`.
+pub fn compact_summary_line(rules: usize, occurrences: usize, noun: (&str, &str), sources: Option<usize>) -> String {
+    let mut parts = vec![counted(rules, "rule", "rules"), counted(occurrences, noun.0, noun.1)];
+    if let Some(sources) = sources {
+        parts.push(counted(sources, "file", "files"));
+    }
+    parts.join(", ")
+}
 
 /// Prints a summary of actual redactions made to the given writer.
 ///
@@ -46,6 +63,13 @@ pub fn print_summary<W: Write>(
     theme_map: &ThemeMap,
     enable_colors: bool,
 ) -> Result<()> {
+    if output_format::summary_format() == SummaryFormat::Compact {
+        let total = summary.iter().map(|item| item.occurrences).sum();
+        let line = compact_summary_line(summary.len(), total, ("redaction", "redactions"), None);
+        writeln!(writer, "{}", output_format::get_styled_text(&line, ThemeEntry::Info, theme_map, enable_colors))?;
+        return Ok(());
+    }
+
     if summary.is_empty() {
         writeln!(writer, "\n{}\n", output_format::get_styled_text("No redactions applied.", ThemeEntry::Info, theme_map, enable_colors))?;
         return Ok(());
@@ -68,7 +92,7 @@ pub fn print_summary<W: Write>(
             writeln!(writer, "    {}", output_format::get_styled_text("Original Values:", ThemeEntry::Info, theme_map, enable_colors))?;
             for text in &item.original_texts {
                 let formatted_text = format!("- {}", text);
-                let styled_text = output_format::get_styled_text(&formatted_text, ThemeEntry::DiffRemoved, theme_map, enable_colors);
+                let styled_text = output_format::get_styled_text(&formatted_text, ThemeEntry::MatchHighlight, theme_map, enable_colors);
                 writeln!(writer, "        {}", styled_text)?;
             }
         }
@@ -83,7 +107,7 @@ pub fn print_summary<W: Write>(
         }
         writeln!(writer)?; // Empty line for separation
     }
-    writeln!(writer, "{}\n", output_format::get_styled_text("-------------------------", ThemeEntry::Header, theme_map, enable_colors))?;
+    writeln!(writer, "{}\n", output_format::get_styled_text("-------------------------", ThemeEntry::TableBorder, theme_map, enable_colors))?;
     Ok(())
 }
 
@@ -101,9 +125,10 @@ pub fn print_summary<W: Write>(
 ///
 /// # Arguments
 ///
-/// * `aggregated_matches` - A `HashMap` where keys are rule names (`String`) and values
-///                          are vectors of references to `RedactionMatch` instances found for that rule.
+/// * `store` - The scan's findings: exact counts per rule, and the kept matches from which
+///   samples are drawn.
 /// * `compiled_rules` - A reference to the `CompiledRules` instance, used to get all active rule names.
+/// * `source_count` - The number of scanned inputs, reported by the compact format.
 /// * `writer` - The output writer where the statistics will be printed (e.g., `&mut io::stderr()`).
 /// * `theme_map` - A `HashMap` containing the defined `ThemeStyle`s for styling the output.
 /// * `sample_matches_count` - An `Option<usize>` specifying how many unique sample matches to display
@@ -115,13 +140,23 @@ pub fn print_summary<W: Write>(
 /// A `Result` indicating `Ok(())` on successful write operations or an `Err`
 /// if any writing to the `writer` fails.
 pub fn print_summary_for_stats_mode<W: Write>(
-    aggregated_matches: &HashMap<String, Vec<&RedactionMatch>>,
+    store: &MatchStore,
     compiled_rules: &CompiledRules,
+    source_count: usize,
     writer: &mut W,
     theme_map: &ThemeMap,
     sample_matches_count: Option<usize>,
     enable_colors: bool,
 ) -> Result<()> {
+    if output_format::summary_format() == SummaryFormat::Compact {
+        let rule_counts = store.counts();
+        let rules = rule_counts.values().filter(|&&count| count > 0).count();
+        let total = rule_counts.values().sum();
+        let line = compact_summary_line(rules, total, ("match", "matches"), Some(source_count));
+        writeln!(writer, "{}", output_format::get_styled_text(&line, ThemeEntry::Info, theme_map, enable_colors))?;
+        return Ok(());
+    }
+
     let header = output_format::get_styled_text("\n--- Redaction Statistics Summary ---", ThemeEntry::Header, theme_map, enable_colors);
     writeln!(writer, "{}", header)?;
 
@@ -131,12 +166,18 @@ pub fn print_summary_for_stats_mode<W: Write>(
         .collect();
     active_rule_names.sort();
 
+    // Samples come from the kept findings, which may be fewer than were counted.
+    let mut aggregated_matches: HashMap<&str, Vec<&RedactionMatch>> = HashMap::new();
+    for m in store.matches() {
+        aggregated_matches.entry(m.rule_name.as_str()).or_default().push(m);
+    }
+
     let mut has_any_matches = false;
 
     // Iterate by reference for efficiency.
     for rule_name in &active_rule_names {
-        let matches_for_rule = aggregated_matches.get(rule_name);
-        let total_occurrences = matches_for_rule.map_or(0, |matches| matches.len());
+        let matches_for_rule = aggregated_matches.get(rule_name.as_str());
+        let total_occurrences = store.counts().get(rule_name).copied().unwrap_or(0);
 
         // Only display rules that actually had matches
         if total_occurrences == 0 {
@@ -169,7 +210,7 @@ pub fn print_summary_for_stats_mode<W: Write>(
 
                     for (i, sample) in unique_samples.iter().take(num_samples).enumerate() {
                         let formatted_sample = format!("- {}", sample);
-                        let styled_sample = output_format::get_styled_text(&formatted_sample, ThemeEntry::DiffRemoved, theme_map, enable_colors);
+                        let styled_sample = output_format::get_styled_text(&formatted_sample, ThemeEntry::MatchHighlight, theme_map, enable_colors);
                         writeln!(writer, "        {}", styled_sample)?;
                         
                         // Indicate if there are more unique samples than displayed
@@ -187,7 +228,71 @@ pub fn print_summary_for_stats_mode<W: Write>(
         writeln!(writer, "\n{}\n", output_format::get_styled_text("No redaction matches found.", ThemeEntry::Info, theme_map, enable_colors))?;
     }
 
-    writeln!(writer, "{}\n", output_format::get_styled_text("---------------------------------", ThemeEntry::Header, theme_map, enable_colors))?;
+    writeln!(writer, "{}\n", output_format::get_styled_text("---------------------------------", ThemeEntry::TableBorder, theme_map, enable_colors))?;
+    Ok(())
+}
+
+/// Prints the number of findings in each file of a multi-file scan, most findings first.
+/// Files without findings are left out; nothing is printed in the compact format.
+pub fn print_file_breakdown<W: Write>(
+    file_totals: &[(String, usize)],
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    if file_totals.is_empty() || output_format::summary_format() == SummaryFormat::Compact {
+        return Ok(());
+    }
+    let mut sorted: Vec<&(String, usize)> = file_totals.iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    writeln!(writer, "{}", output_format::get_styled_text("--- Findings per File ---", ThemeEntry::Header, theme_map, enable_colors))?;
+    for (path, total) in sorted {
+        let line = format!("{}: {} {}", path, total, if *total == 1 { "match" } else { "matches" });
+        writeln!(writer, "{}", output_format::get_styled_text(&line, ThemeEntry::SummaryRuleName, theme_map, enable_colors))?;
+    }
+    writeln!(writer, "{}\n", output_format::get_styled_text("---------------------------------", ThemeEntry::TableBorder, theme_map, enable_colors))?;
+    Ok(())
+}
+
+/// Prints one `FILE:LINE: rule` line per finding of a `scan --staged`, in the order given.
+pub fn print_finding_locations<W: Write>(
+    locations: &[(&str, u64, &str)],
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    for (path, line, rule_name) in locations {
+        let location = output_format::get_styled_text(&format!("{}:{}:", path, line), ThemeEntry::SummaryRuleName, theme_map, enable_colors);
+        writeln!(writer, "{} {}", location, rule_name)?;
+    }
+    Ok(())
+}
+
+/// The order in which severities are listed: most severe first, then unknown levels.
+const SEVERITY_ORDER: [&str; 4] = ["critical", "high", "medium", "low"];
+
+/// Prints the number of findings per rule severity, most severe first. Nothing is printed
+/// when there were no findings or in the compact format.
+pub fn print_severity_breakdown<W: Write>(
+    by_severity: &BTreeMap<String, usize>,
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    if by_severity.is_empty() || output_format::summary_format() == SummaryFormat::Compact {
+        return Ok(());
+    }
+    let mut sorted: Vec<(&String, &usize)> = by_severity.iter().collect();
+    sorted.sort_by_key(|(severity, _)| SEVERITY_ORDER.iter().position(|known| known == severity).unwrap_or(SEVERITY_ORDER.len()));
+
+    writeln!(writer, "{}", output_format::get_styled_text("--- Findings per Severity ---", ThemeEntry::Header, theme_map, enable_colors))?;
+    for (severity, total) in sorted {
+        let line = format!("{}: {} {}", severity, total, if *total == 1 { "match" } else { "matches" });
+        let entry = ThemeEntry::for_severity(severity).unwrap_or(ThemeEntry::SummaryRuleName);
+        writeln!(writer, "{}", output_format::get_styled_text(&line, entry, theme_map, enable_colors))?;
+    }
+    writeln!(writer, "{}\n", output_format::get_styled_text("---------------------------------", ThemeEntry::TableBorder, theme_map, enable_colors))?;
     Ok(())
 }
 
@@ -208,6 +313,25 @@ pub fn print_stats_fail_over_message<W: Write>(
     Ok(())
 }
 
+/// Prints a styled message when `--fail-on-severity` finds matches at or above its level.
+pub fn print_severity_fail_message<W: Write>(
+    level: Severity,
+    matches_found: usize,
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    let fail_msg = format!(
+        "FAIL-ON-SEVERITY triggered: Found {} redaction {} of severity {} or higher.",
+        matches_found,
+        if matches_found == 1 { "match" } else { "matches" },
+        level.as_str()
+    );
+    let styled_msg = output_format::get_styled_text(&fail_msg, ThemeEntry::Error, theme_map, enable_colors);
+    writeln!(writer, "{}", styled_msg)?;
+    Ok(())
+}
+
 // A private helper function to format rule names for display, keeping logic local.
 fn format_rule_name_for_json(name: &str) -> String {
     name.replace("_", " ").split_whitespace()
@@ -0,0 +1,170 @@
+// cleansh-workspace/cleansh/src/ui/report_html.rs
+
+//! HTML rendering for the `report` subcommand.
+//!
+//! The report is a single file with inline styles and CSS bar charts: no scripts, fonts,
+//! or external resources, so it can be attached to a ticket or emailed as-is. Print
+//! styles are included so that "Print to PDF" in any browser produces a clean PDF.
+
+use crate::commands::report::{ReportData, ReportRow};
+use cleansh_core::SCHEMA_VERSION;
+use std::fmt::Write;
+
+pub(crate) const STYLE: &str = "\
+body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;color:#1f2328;margin:2rem auto;max-width:960px;padding:0 1rem}\
+h1{margin-bottom:.25rem}h2{margin-top:2rem;border-bottom:1px solid #d0d7de;padding-bottom:.25rem}\
+.meta{color:#59636e;font-size:.9rem}.cards{display:flex;gap:1rem;margin-top:1rem}\
+.card{flex:1;border:1px solid #d0d7de;border-radius:6px;padding:1rem}.card .value{font-size:2rem;font-weight:600}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.4rem .5rem;border-bottom:1px solid #eaeef2;vertical-align:top}\
+td.num,th.num{text-align:right;font-variant-numeric:tabular-nums}.desc{color:#59636e;font-size:.85rem}\
+.bar{background:#eaeef2;border-radius:3px;height:.9rem;min-width:120px}.bar span{display:block;height:100%;border-radius:3px;background:#0969da}\
+.sev-critical span{background:#82071e}.sev-high span{background:#cf222e}.sev-medium span{background:#bf8700}.sev-low span{background:#1a7f37}\
+.up{color:#cf222e}.down{color:#1a7f37}.same{color:#59636e}\
+@media print{body{margin:0;max-width:none}.card,.bar{break-inside:avoid}}";
+
+/// Escapes text for use in HTML element content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats the change from `baseline` to `current` as a signed, styled cell.
+fn trend_cell(current: usize, baseline: usize) -> String {
+    match current.cmp(&baseline) {
+        std::cmp::Ordering::Greater => format!("<span class=\"up\">+{}</span>", current - baseline),
+        std::cmp::Ordering::Less => format!("<span class=\"down\">&minus;{}</span>", baseline - current),
+        std::cmp::Ordering::Equal => "<span class=\"same\">0</span>".to_string(),
+    }
+}
+
+/// Renders a horizontal bar whose width is `value` relative to `max`.
+pub(crate) fn bar(value: usize, max: usize, class: &str) -> String {
+    let percent = if max == 0 { 0.0 } else { value as f64 * 100.0 / max as f64 };
+    format!("<div class=\"bar {}\"><span style=\"width:{:.1}%\"></span></div>", class, percent)
+}
+
+/// Renders one row of the findings table.
+fn findings_row(row: &ReportRow, max: usize, with_trend: bool) -> String {
+    let description = row
+        .description
+        .as_deref()
+        .map(|d| format!("<div class=\"desc\">{}</div>", escape_html(d)))
+        .unwrap_or_default();
+    let trend = if with_trend {
+        let baseline = row.baseline_count.unwrap_or(0);
+        format!("<td class=\"num\">{}</td><td class=\"num\">{}</td>", baseline, trend_cell(row.count, baseline))
+    } else {
+        String::new()
+    };
+    format!(
+        "<tr><td><code>{}</code>{}</td><td>{}</td><td class=\"num\">{}</td>{}<td>{}</td></tr>\n",
+        escape_html(&row.rule_name),
+        description,
+        escape_html(&row.severity),
+        row.count,
+        trend,
+        bar(row.count, max, &format!("sev-{}", escape_html(&row.severity.to_lowercase()))),
+    )
+}
+
+/// Renders the complete, self-contained HTML report.
+pub fn render_html_report(data: &ReportData) -> String {
+    let total = data.total();
+    let max = data.rows.iter().map(|row| row.count).max().unwrap_or(0);
+    let with_trend = data.baseline_source.is_some();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"cleansh-schema-version\" content=\"{schema_version}\">\n<title>{title}</title>\n<style>{style}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<div class=\"meta\">Source: {source}{baseline} &middot; Generated {generated} by cleansh {version}</div>\n",
+        title = escape_html(&data.title),
+        style = STYLE,
+        source = escape_html(&data.source),
+        baseline = data
+            .baseline_source
+            .as_deref()
+            .map(|b| format!(" &middot; Baseline: {}", escape_html(b)))
+            .unwrap_or_default(),
+        generated = escape_html(&data.generated_at),
+        version = env!("CARGO_PKG_VERSION"),
+        schema_version = SCHEMA_VERSION,
+    );
+
+    // Summary cards.
+    let rules_with_findings = data.rows.iter().filter(|row| row.count > 0).count();
+    let _ = write!(
+        html,
+        "<div class=\"cards\"><div class=\"card\"><div>Total findings</div><div class=\"value\">{}</div></div><div class=\"card\"><div>Rules triggered</div><div class=\"value\">{}</div></div>",
+        total, rules_with_findings
+    );
+    if let Some(baseline_total) = data.baseline_total() {
+        let _ = write!(
+            html,
+            "<div class=\"card\"><div>Change vs. baseline ({})</div><div class=\"value\">{}</div></div>",
+            baseline_total,
+            trend_cell(total, baseline_total)
+        );
+    }
+    html.push_str("</div>\n");
+
+    // Severity breakdown.
+    html.push_str("<h2>Severity breakdown</h2>\n");
+    let breakdown = data.severity_breakdown();
+    if breakdown.is_empty() {
+        html.push_str("<p>No findings.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Severity</th><th class=\"num\">Findings</th><th></th></tr>\n");
+        for (severity, count) in &breakdown {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"num\">{}</td><td>{}</td></tr>",
+                escape_html(severity),
+                count,
+                bar(*count, total, &format!("sev-{}", escape_html(&severity.to_lowercase())))
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    // Findings per rule.
+    html.push_str("<h2>Findings by rule</h2>\n");
+    if data.rows.is_empty() {
+        html.push_str("<p>No findings.</p>\n");
+    } else {
+        let trend_headers = if with_trend { "<th class=\"num\">Baseline</th><th class=\"num\">Change</th>" } else { "" };
+        let _ = writeln!(
+            html,
+            "<table>\n<tr><th>Rule</th><th>Severity</th><th class=\"num\">Findings</th>{}<th></th></tr>",
+            trend_headers
+        );
+        for row in &data.rows {
+            html.push_str(&findings_row(row, max, with_trend));
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<p class=\"meta\">This report contains rule names and counts only; no matched values are included.</p>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup_and_formats_trends() {
+        assert_eq!(escape_html("<a href=\"x\">&'"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;");
+        assert_eq!(trend_cell(5, 2), "<span class=\"up\">+3</span>");
+        assert_eq!(trend_cell(1, 4), "<span class=\"down\">&minus;3</span>");
+    }
+}
@@ -0,0 +1,68 @@
+//! Table of per-rule evaluation counts and timings for `sanitize --profile-rules`.
+//!
+//! The table lists every active rule, slowest first, with the number of times it ran,
+//! the matches it kept and the time it took, so users can see which rules are worth
+//! disabling when sanitization is slow.
+
+use crate::ui::output_format::get_styled_text;
+use crate::ui::theme::{ThemeEntry, ThemeMap};
+use anyhow::Result;
+use cleansh_core::RuleProfile;
+use std::io::Write;
+use std::time::Duration;
+
+/// Prints `profiles` as an aligned table followed by the total time.
+///
+/// # Arguments
+///
+/// * `profiles` - The per-rule totals, in the order they should be listed.
+/// * `writer` - The output writer, normally stderr.
+/// * `theme_map` - The theme used to style the header and borders.
+/// * `enable_colors` - Whether ANSI colors should be used.
+pub fn print_rule_profile<W: Write>(
+    profiles: &[RuleProfile],
+    writer: &mut W,
+    theme_map: &ThemeMap,
+    enable_colors: bool,
+) -> Result<()> {
+    let total: Duration = profiles.iter().map(|p| p.total_time).sum();
+    let width = profiles.iter().map(|p| p.rule_name.len()).chain(["rule".len()]).max().unwrap_or(0);
+
+    writeln!(writer, "{}", get_styled_text("\n--- Rule Profile ---", ThemeEntry::Header, theme_map, enable_colors))?;
+    writeln!(writer, "{:<width$}  {:>6}  {:>8}  {:>10}  {:>6}", "rule", "evals", "matches", "time (ms)", "share")?;
+    for profile in profiles {
+        let share = if total.is_zero() { 0.0 } else { profile.total_time.as_secs_f64() / total.as_secs_f64() * 100.0 };
+        writeln!(
+            writer,
+            "{:<width$}  {:>6}  {:>8}  {:>10.3}  {:>5.1}%",
+            profile.rule_name,
+            profile.evaluations,
+            profile.matches,
+            profile.total_time.as_secs_f64() * 1000.0,
+            share
+        )?;
+    }
+    writeln!(writer, "{}", get_styled_text("--------------------", ThemeEntry::TableBorder, theme_map, enable_colors))?;
+    writeln!(writer, "Total rule time: {:.3} ms across {} rules\n", total.as_secs_f64() * 1000.0, profiles.len())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_each_rule_with_its_share() -> Result<()> {
+        let profiles = [
+            RuleProfile { rule_name: "email".to_string(), evaluations: 2, matches: 3, total_time: Duration::from_millis(3) },
+            RuleProfile { rule_name: "ipv4_address".to_string(), evaluations: 2, matches: 0, total_time: Duration::from_millis(1) },
+        ];
+        let mut out = Vec::new();
+        print_rule_profile(&profiles, &mut out, &ThemeMap::new(), false)?;
+        let text = String::from_utf8(out)?;
+        assert!(text.contains("email              2         3       3.000   75.0%"), "{}", text);
+        assert!(text.contains("ipv4_address       2         0       1.000   25.0%"), "{}", text);
+        assert!(text.contains("Total rule time: 4.000 ms across 2 rules"));
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+// cleansh-workspace/cleansh/src/ui/stats_dashboard.rs
+
+//! HTML rendering for the `stats serve` dashboard.
+//!
+//! Like the `report` output, the page is self-contained: styles are inline and the
+//! findings chart is an inline SVG, so nothing is loaded from outside the local server.
+//! It reloads itself every minute to pick up scans recorded in the meantime.
+
+use crate::ui::report_html::{bar, escape_html, STYLE};
+use crate::utils::scan_history::HistoryBucket;
+use cleansh_core::SCHEMA_VERSION;
+use std::fmt::Write;
+
+/// Size of the findings chart in SVG user units.
+const CHART_WIDTH: f64 = 900.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+/// Below this many periods, every bar of the chart is labeled.
+const MAX_LABELED_PERIODS: usize = 14;
+
+/// Extra styles for the chart, on top of the report's.
+const DASHBOARD_STYLE: &str = "svg.chart{width:100%;height:auto}svg.chart rect{fill:#0969da}svg.chart text{font-size:11px;fill:#59636e}";
+
+/// Everything the dashboard shows.
+#[derive(Debug)]
+pub struct DashboardData {
+    /// Recorded scans grouped by day or week, oldest first.
+    pub buckets: Vec<HistoryBucket>,
+    /// `day` or `week`.
+    pub period: &'static str,
+    pub top_rules: Vec<(String, usize)>,
+    pub top_files: Vec<(String, usize)>,
+}
+
+/// Renders findings per period as an SVG column chart; each column has a tooltip with
+/// the period's scans and findings.
+fn findings_chart(buckets: &[HistoryBucket]) -> String {
+    let max = buckets.iter().map(|b| b.findings).max().unwrap_or(0).max(1);
+    let slot = CHART_WIDTH / buckets.len().max(1) as f64;
+    let label_every = buckets.len().div_ceil(MAX_LABELED_PERIODS).max(1);
+    let mut svg = format!(
+        "<svg class=\"chart\" viewBox=\"0 0 {} {}\" role=\"img\" aria-label=\"Findings per period\">\n",
+        CHART_WIDTH,
+        CHART_HEIGHT + 20.0
+    );
+    for (i, bucket) in buckets.iter().enumerate() {
+        let height = bucket.findings as f64 / max as f64 * CHART_HEIGHT;
+        let x = i as f64 * slot;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\"><title>{}: {} finding(s) in {} scan(s)</title></rect>",
+            x + slot * 0.1,
+            CHART_HEIGHT - height,
+            slot * 0.8,
+            height,
+            escape_html(&bucket.period),
+            bucket.findings,
+            bucket.scans
+        );
+        if i % label_every == 0 {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                x + slot / 2.0,
+                CHART_HEIGHT + 15.0,
+                escape_html(&bucket.period)
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Renders a table of names and finding counts with a bar each.
+fn top_table(heading: &str, rows: &[(String, usize)]) -> String {
+    let max = rows.first().map_or(0, |row| row.1);
+    let mut html = format!("<table>\n<tr><th>{}</th><th class=\"num\">Findings</th><th></th></tr>\n", heading);
+    for (name, count) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><td><code>{}</code></td><td class=\"num\">{}</td><td>{}</td></tr>",
+            escape_html(name),
+            count,
+            bar(*count, max, "")
+        );
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Renders the complete dashboard page.
+pub fn render_dashboard(data: &DashboardData) -> String {
+    let scans: usize = data.buckets.iter().map(|b| b.scans).sum();
+    let findings: usize = data.buckets.iter().map(|b| b.findings).sum();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta http-equiv=\"refresh\" content=\"60\">\n<meta name=\"cleansh-schema-version\" content=\"{schema_version}\">\n<title>cleansh scan history</title>\n<style>{style}{dashboard_style}</style>\n</head>\n<body>\n<h1>cleansh scan history</h1>\n<div class=\"meta\">Scans recorded with <code>scan --record-history</code> &middot; cleansh {version}</div>\n",
+        style = STYLE,
+        dashboard_style = DASHBOARD_STYLE,
+        version = env!("CARGO_PKG_VERSION"),
+        schema_version = SCHEMA_VERSION,
+    );
+
+    if data.buckets.is_empty() {
+        html.push_str("<p>No scan history recorded yet. Run <code>cleansh scan --record-history</code> to start one.</p>\n</body>\n</html>\n");
+        return html;
+    }
+
+    let per_scan = findings as f64 / scans.max(1) as f64;
+    let _ = writeln!(
+        html,
+        "<div class=\"cards\"><div class=\"card\"><div>Scans</div><div class=\"value\">{}</div></div><div class=\"card\"><div>Findings</div><div class=\"value\">{}</div></div><div class=\"card\"><div>Findings per scan</div><div class=\"value\">{:.1}</div></div></div>",
+        scans, findings, per_scan
+    );
+
+    let _ = writeln!(html, "<h2>Findings per {}</h2>", data.period);
+    html.push_str(&findings_chart(&data.buckets));
+
+    html.push_str("<h2>Top rules</h2>\n");
+    html.push_str(&top_table("Rule", &data.top_rules));
+
+    html.push_str("<h2>Top files</h2>\n");
+    if data.top_files.is_empty() {
+        html.push_str("<p>No file names recorded. Add <code>--history-files</code> to <code>scan --dir</code> or <code>scan --files-from</code> runs to list them here.</p>\n");
+    } else {
+        html.push_str(&top_table("File", &data.top_files));
+    }
+
+    html.push_str("<p class=\"meta\">Rule names, file names and counts only; no matched values are recorded.</p>\n</body>\n</html>\n");
+    html
+}
@@ -4,18 +4,36 @@
 //! to customize the colors of various output elements. It supports 16-color
 //! ANSI named colors for foreground styling and provides functionality to
 //! load themes from YAML files and manage default theme settings.
+//!
+//! Besides YAML files, a few named themes are built into the binary (see
+//! [`BUILTIN_THEMES`]). The theme is picked with `--theme`, or from the environment:
+//! `CLEANSH_THEME_<COMMAND>` (e.g. `CLEANSH_THEME_SCAN`) for one command, and
+//! `CLEANSH_THEME` for all of them.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
-use std::path::{Path, PathBuf}; // Added PathBuf
+use std::path::Path;
 use std::str::FromStr;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use owo_colors::AnsiColors;
 
 /// Type alias for the theme map, providing a consistent type definition.
 pub type ThemeMap = HashMap<ThemeEntry, ThemeStyle>;
 
+/// Environment variable naming the theme for every command.
+pub const THEME_ENV: &str = "CLEANSH_THEME";
+
+/// Name of the theme used when none is chosen.
+pub const DEFAULT_THEME: &str = "default";
+
+/// Themes shipped in the binary, by name, with their YAML definitions.
+pub const BUILTIN_THEMES: [(&str, &str); 3] = [
+    ("solarized-dark", include_str!("../../themes/solarized-dark.yaml")),
+    ("solarized-light", include_str!("../../themes/solarized-light.yaml")),
+    ("high-contrast", include_str!("../../themes/high-contrast.yaml")),
+];
+
 /// The different logical parts of your output that can be styled.
 ///
 /// Each variant represents a distinct type of message or UI element
@@ -47,6 +65,73 @@ pub enum ThemeEntry {
     SummaryOccurrences,
     /// Style for user prompts or confirmation questions.
     Prompt,
+    /// Style for matched values: `--annotate` highlights, original values and sample matches.
+    MatchHighlight,
+    /// Style for rules of `critical` severity.
+    SeverityCritical,
+    /// Style for rules of `high` severity.
+    SeverityHigh,
+    /// Style for rules of `medium` severity.
+    SeverityMedium,
+    /// Style for rules of `low` severity.
+    SeverityLow,
+    /// Style for the separator lines framing summaries and tables.
+    TableBorder,
+    /// Style for progress messages such as "Reading input from file".
+    Progress,
+}
+
+impl ThemeEntry {
+    /// Every theme entry, in the order they are documented.
+    pub const ALL: [ThemeEntry; 19] = [
+        ThemeEntry::Header,
+        ThemeEntry::Success,
+        ThemeEntry::Info,
+        ThemeEntry::Warn,
+        ThemeEntry::Error,
+        ThemeEntry::RedactedText,
+        ThemeEntry::DiffAdded,
+        ThemeEntry::DiffRemoved,
+        ThemeEntry::DiffHeader,
+        ThemeEntry::SummaryRuleName,
+        ThemeEntry::SummaryOccurrences,
+        ThemeEntry::Prompt,
+        ThemeEntry::MatchHighlight,
+        ThemeEntry::SeverityCritical,
+        ThemeEntry::SeverityHigh,
+        ThemeEntry::SeverityMedium,
+        ThemeEntry::SeverityLow,
+        ThemeEntry::TableBorder,
+        ThemeEntry::Progress,
+    ];
+
+    /// The entry that styled this element before it had its own key.
+    ///
+    /// A custom theme that does not set the entry uses the fallback's style, so themes
+    /// written before the entry existed look the same as before.
+    pub fn fallback(&self) -> Option<ThemeEntry> {
+        match self {
+            ThemeEntry::MatchHighlight => Some(ThemeEntry::DiffRemoved),
+            ThemeEntry::SeverityCritical
+            | ThemeEntry::SeverityHigh
+            | ThemeEntry::SeverityMedium
+            | ThemeEntry::SeverityLow => Some(ThemeEntry::SummaryOccurrences),
+            ThemeEntry::TableBorder => Some(ThemeEntry::Header),
+            ThemeEntry::Progress => Some(ThemeEntry::Info),
+            _ => None,
+        }
+    }
+
+    /// Returns the entry styling a rule severity such as `high`, if it is a known level.
+    pub fn for_severity(severity: &str) -> Option<ThemeEntry> {
+        match severity.to_ascii_lowercase().as_str() {
+            "critical" => Some(ThemeEntry::SeverityCritical),
+            "high" => Some(ThemeEntry::SeverityHigh),
+            "medium" => Some(ThemeEntry::SeverityMedium),
+            "low" => Some(ThemeEntry::SeverityLow),
+            _ => None,
+        }
+    }
 }
 
 /// Represents an ANSI color that can be used in the theme.
@@ -164,29 +249,58 @@ pub struct ThemeStyle {
     pub fg: Option<ThemeColor>,
 }
 
-// MODIFIED: `build_theme_map` is now a standalone function
-/// Loads a theme configuration from a YAML file or returns the default theme.
+/// Picks the theme for `command`: the `--theme` value if given, then the
+/// `CLEANSH_THEME_<COMMAND>` environment variable (with dashes in the command name
+/// turned into underscores, e.g. `CLEANSH_THEME_INSTALL_SHELL_INTEGRATION`), then
+/// `CLEANSH_THEME`. Empty variables are ignored.
+///
+/// # Arguments
+///
+/// * `flag` - The value of `--theme`, if any.
+/// * `command` - The name of the subcommand being run, such as `scan`.
+/// * `env` - Looks up an environment variable.
+///
+/// # Returns
+///
+/// The theme name or file path to load, or `None` for the default theme.
+pub fn theme_spec(flag: Option<&str>, command: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if let Some(flag) = flag {
+        return Some(flag.to_string());
+    }
+    let command_key = format!("{}_{}", THEME_ENV, command.to_ascii_uppercase().replace('-', "_"));
+    [command_key.as_str(), THEME_ENV]
+        .into_iter()
+        .find_map(|key| env(key).filter(|value| !value.trim().is_empty()))
+}
+
+/// Loads the theme named by `spec`, or returns the default theme.
 ///
-/// If `theme_path` is provided, it attempts to load a custom theme from that path.
-/// If `theme_path` is `None` or loading from the file fails, it falls back to
-/// the default theme.
+/// `spec` is either the name of a built-in theme (see [`BUILTIN_THEMES`], plus
+/// `default`) or the path to a custom theme YAML file. Built-in names take precedence,
+/// so a file that happens to share a name must be given as a path such as `./high-contrast`.
 ///
 /// # Arguments
 ///
-/// * `theme_path` - An optional `PathBuf` pointing to a custom theme YAML file.
+/// * `spec` - An optional theme name or path, as returned by [`theme_spec`].
 ///
 /// # Returns
 ///
 /// A `Result` containing a `ThemeMap` on success, or an `anyhow::Error` if
-/// a custom theme is specified but cannot be loaded.
-pub fn build_theme_map(theme_path: Option<&PathBuf>) -> Result<ThemeMap> {
-    if let Some(path) = theme_path {
-        // Attempt to load from file. If it fails, propagate the error.
-        ThemeStyle::load_from_file(path)
-    } else {
-        // If no path is provided, return the default theme.
-        Ok(ThemeStyle::default_theme_map())
+/// a custom theme is specified but cannot be loaded, or the name is not a known theme.
+pub fn build_theme_map(spec: Option<&str>) -> Result<ThemeMap> {
+    let Some(spec) = spec.filter(|spec| *spec != DEFAULT_THEME) else {
+        return Ok(ThemeStyle::default_theme_map());
+    };
+    if let Some((name, yaml)) = BUILTIN_THEMES.iter().find(|(name, _)| *name == spec) {
+        return ThemeStyle::from_yaml(yaml).with_context(|| format!("Failed to parse built-in theme '{}'", name));
+    }
+    let path = Path::new(spec);
+    // A bare word that is not a file was most likely meant as a theme name.
+    if !path.exists() && path.extension().is_none() && path.components().count() == 1 {
+        let names: Vec<&str> = std::iter::once(DEFAULT_THEME).chain(BUILTIN_THEMES.iter().map(|(name, _)| *name)).collect();
+        bail!("Unknown theme '{}'. Built-in themes are: {}; anything else must be the path to a theme YAML file.", spec, names.join(", "));
     }
+    ThemeStyle::load_from_file(path)
 }
 
 impl ThemeStyle {
@@ -194,8 +308,9 @@ impl ThemeStyle {
     ///
     /// This function reads a YAML file specified by `path`, parses it into a `HashMap`
     /// of `ThemeEntry` to `ThemeStyle`, and then ensures that all `ThemeEntry` variants
-    /// have an associated style. If any entry is missing in the custom file, it's
-    /// filled in with a default `ThemeStyle` (foreground color set to white).
+    /// have an associated style. If any entry is missing in the custom file, it takes the
+    /// style of its [`ThemeEntry::fallback`], or a default `ThemeStyle` (foreground color
+    /// set to white) if it has none.
     ///
     /// # Type Parameters
     ///
@@ -213,24 +328,22 @@ impl ThemeStyle {
         let path = path.as_ref();
         let text = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read theme file {}", path.display()))?;
-        let mut custom: ThemeMap = // Use ThemeMap alias
-            serde_yaml::from_str(&text).with_context(|| format!("Failed to parse theme file {}", path.display()))?;
-        // Fill in missing entries with default white.
-        for entry in [
-            ThemeEntry::Header,
-            ThemeEntry::Success,
-            ThemeEntry::Info,
-            ThemeEntry::Warn,
-            ThemeEntry::Error,
-            ThemeEntry::RedactedText,
-            ThemeEntry::DiffAdded,
-            ThemeEntry::DiffRemoved,
-            ThemeEntry::DiffHeader,
-            ThemeEntry::SummaryRuleName,
-            ThemeEntry::SummaryOccurrences, // Add this here as well
-            ThemeEntry::Prompt,
-        ] {
-            custom.entry(entry).or_insert_with(|| ThemeStyle { fg: Some(ThemeColor::Named("white".into())) });
+        Self::from_yaml(&text).with_context(|| format!("Failed to parse theme file {}", path.display()))
+    }
+
+    /// Parses a theme from YAML text and merges it with default styles, like
+    /// [`ThemeStyle::load_from_file`] does for a file.
+    pub fn from_yaml(text: &str) -> Result<ThemeMap> {
+        let mut custom: ThemeMap = serde_yaml::from_str(text)?;
+        // Fill in missing entries with default white, then newer entries from their fallback.
+        for entry in ThemeEntry::ALL.iter().filter(|entry| entry.fallback().is_none()) {
+            custom.entry(entry.clone()).or_insert_with(|| ThemeStyle { fg: Some(ThemeColor::Named("white".into())) });
+        }
+        for entry in ThemeEntry::ALL.iter() {
+            if let Some(fallback) = entry.fallback() {
+                let style = custom[&fallback].clone();
+                custom.entry(entry.clone()).or_insert(style);
+            }
         }
         Ok(custom)
     }
@@ -248,21 +361,14 @@ impl ThemeStyle {
         // Set specific colors for diff-related entries
         default_theme.insert(ThemeEntry::DiffAdded, ThemeStyle { fg: Some(ThemeColor::Named("green".into())) });
         default_theme.insert(ThemeEntry::DiffRemoved, ThemeStyle { fg: Some(ThemeColor::Named("red".into())) });
+        default_theme.insert(ThemeEntry::MatchHighlight, ThemeStyle { fg: Some(ThemeColor::Named("red".into())) });
+        default_theme.insert(ThemeEntry::SeverityCritical, ThemeStyle { fg: Some(ThemeColor::Named("brightred".into())) });
+        default_theme.insert(ThemeEntry::SeverityHigh, ThemeStyle { fg: Some(ThemeColor::Named("red".into())) });
+        default_theme.insert(ThemeEntry::SeverityMedium, ThemeStyle { fg: Some(ThemeColor::Named("yellow".into())) });
 
         // Insert all other entries with their default colors (white)
-        for entry in [
-            ThemeEntry::Header,
-            ThemeEntry::Success,
-            ThemeEntry::Info,
-            ThemeEntry::Warn,
-            ThemeEntry::Error,
-            ThemeEntry::RedactedText,
-            ThemeEntry::DiffHeader,
-            ThemeEntry::SummaryRuleName,
-            ThemeEntry::SummaryOccurrences,
-            ThemeEntry::Prompt,
-        ] {
-            // Only insert if it's not already present to avoid overwriting DiffAdded/DiffRemoved
+        for entry in ThemeEntry::ALL {
+            // Only insert if it's not already present to avoid overwriting the colors set above
             default_theme.entry(entry).or_insert_with(|| ThemeStyle { fg: Some(ThemeColor::Named("white".into())) });
         }
         default_theme
@@ -280,6 +386,48 @@ mod tests {
         assert!("unknown".parse::<ThemeColor>().is_err());
     }
 
+    #[test]
+    fn missing_entries_take_their_fallback_style() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("theme.yaml");
+        std::fs::write(&path, "diff_removed:\n  fg: magenta\nseverity_high:\n  fg: brightyellow\n")?;
+        let theme = ThemeStyle::load_from_file(&path)?;
+
+        let fg = |entry: ThemeEntry| theme[&entry].fg.clone();
+        assert_eq!(fg(ThemeEntry::MatchHighlight), Some(ThemeColor::Named("magenta".into())));
+        assert_eq!(fg(ThemeEntry::SeverityHigh), Some(ThemeColor::Named("brightyellow".into())));
+        assert_eq!(fg(ThemeEntry::SeverityLow), Some(ThemeColor::Named("white".into())));
+        assert_eq!(theme.len(), ThemeEntry::ALL.len());
+        assert_eq!(ThemeStyle::default_theme_map().len(), ThemeEntry::ALL.len());
+        Ok(())
+    }
+
+    #[test]
+    fn builtin_themes_load_by_name_and_cover_every_entry() -> Result<()> {
+        for (name, _) in BUILTIN_THEMES {
+            assert_eq!(build_theme_map(Some(name))?.len(), ThemeEntry::ALL.len(), "{}", name);
+        }
+        assert_eq!(build_theme_map(Some("default"))?, ThemeStyle::default_theme_map());
+        let err = build_theme_map(Some("solarized")).unwrap_err().to_string();
+        assert!(err.contains("Unknown theme 'solarized'") && err.contains("solarized-dark"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn theme_spec_prefers_flag_then_command_then_global_variable() {
+        let env = |key: &str| match key {
+            "CLEANSH_THEME" => Some("solarized-light".to_string()),
+            "CLEANSH_THEME_SCAN" => Some("high-contrast".to_string()),
+            "CLEANSH_THEME_INSTALL_SHELL_INTEGRATION" => Some(" ".to_string()),
+            _ => None,
+        };
+        assert_eq!(theme_spec(Some("mine.yaml"), "scan", env).as_deref(), Some("mine.yaml"));
+        assert_eq!(theme_spec(None, "scan", env).as_deref(), Some("high-contrast"));
+        assert_eq!(theme_spec(None, "sanitize", env).as_deref(), Some("solarized-light"));
+        assert_eq!(theme_spec(None, "install-shell-integration", env).as_deref(), Some("solarized-light"));
+        assert_eq!(theme_spec(None, "scan", |_| None), None);
+    }
+
     #[test]
     fn to_ansi_color_roundtrip() {
         let tc: ThemeColor = "blue".parse().unwrap();
@@ -2,12 +2,17 @@
 ///
 /// This module handles the loading, saving, and encryption of the application's
 /// state, including usage statistics and license information.
+///
+/// The state file is encrypted with AES-256-GCM under a key derived from a stored random
+/// key and the machine fingerprint, so it can neither be edited by hand nor copied to
+/// another machine. A file that fails authentication is treated as tampered: the caller
+/// is warned and the state is reset to its defaults.
 // cleansh/src/utils/app_state.rs
 
 use anyhow::{Result, Context};
 use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
-use aes_gcm::aead::Aead;
-use chrono::{Utc, TimeZone};
+use aes_gcm::aead::{Aead, Payload};
+use chrono::{DateTime, Duration, Utc, TimeZone};
 use log::{warn, debug};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -16,8 +21,10 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use base64::{engine::general_purpose, Engine as _};
 
-use crate::ui::theme::ThemeMap;
-use crate::commands::cleansh::info_msg;
+use crate::utils::notifications::NotificationKind;
+use crate::utils::platform::machine_fingerprint;
+use crate::utils::time::CLOCK_SKEW_TOLERANCE;
+use sha2::{Digest, Sha256};
 
 /// AES-GCM
 use rand::RngCore;
@@ -30,6 +37,21 @@ const KEYRING_USERNAME: &str = "state-encryption";
 const LOCAL_KEY_FILENAME: &str = "state_key.b64";
 const AES_NONCE_LEN: usize = 12;
 const STATE_FILE_TMP_SUFFIX: &str = ".tmp";
+const STATE_FILE_LOCK_SUFFIX: &str = ".lock";
+/// Associated data authenticated with every v2 state blob.
+const STATE_BLOB_AAD_V2: &[u8] = b"cleansh-state-v2";
+
+/// The outcome of checking the state file's integrity on load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateIntegrity {
+    /// The file was missing or decrypted and authenticated successfully.
+    Valid,
+    /// The file was written by an older version and will be upgraded on the next save.
+    Migrated,
+    /// The file was modified, copied from another machine, or corrupted. The contained
+    /// reason describes what failed; the state has been reset to its defaults.
+    Reset(String),
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LicenseMeta {
@@ -55,10 +77,30 @@ impl Default for LicenseMeta {
 pub struct AppState {
     pub usage_count: u64,
     pub stats_only_usage_count: u64,
+    /// Time of the last donation prompt, written by versions before notification snoozes.
+    /// Read only to carry the cooldown over into `notification_snoozes`.
+    #[serde(default, skip_serializing)]
     pub last_prompt_timestamp: Option<u64>,
     pub donation_prompts_disabled: bool,
+    /// Per-notification snoozes: the Unix time until which a notification stays hidden,
+    /// keyed by [`NotificationKind::key`].
+    #[serde(default)]
+    pub notification_snoozes: HashMap<String, i64>,
     /// tracked licenses keyed by short fingerprint
     pub licenses: HashMap<String, LicenseMeta>,
+    /// Counter values as last read from or written to disk. `save` adds only the
+    /// increments made since then, so concurrent processes do not overwrite each other.
+    #[serde(skip)]
+    baseline: CounterSnapshot,
+}
+
+/// The additive counters of an `AppState` at one point in time.
+#[derive(Debug, Default, Clone)]
+struct CounterSnapshot {
+    usage_count: u64,
+    stats_only_usage_count: u64,
+    /// Per-feature usage keyed by `(license fingerprint, feature)`.
+    feature_usage: HashMap<(String, String), u64>,
 }
 
 // The Default trait for AppState must not be recursive.
@@ -69,7 +111,9 @@ impl Default for AppState {
             stats_only_usage_count: 0,
             last_prompt_timestamp: None,
             donation_prompts_disabled: false,
+            notification_snoozes: HashMap::new(),
             licenses: HashMap::new(),
+            baseline: CounterSnapshot::default(),
         }
     }
 }
@@ -81,11 +125,25 @@ impl AppState {
     }
 
     /// Load state, decrypting if needed. If file missing -> default state.
+    ///
+    /// Integrity failures are not errors: they are logged and the default state is
+    /// returned. Use [`AppState::load_verified`] to find out whether that happened.
     pub fn load(path: &Path) -> Result<Self> {
+        Self::load_verified(path).map(|(state, _)| state)
+    }
+
+    /// Load state and report whether the file passed its integrity check.
+    ///
+    /// Accepted formats are the current machine-bound `v2` blob, the older `v1` blob
+    /// (encrypted with the stored key only), and plaintext JSON written before state
+    /// encryption existed. Plaintext is only trusted while no encryption key has been
+    /// created yet; once cleansh has written an encrypted state, a plaintext file means
+    /// someone replaced it.
+    pub fn load_verified(path: &Path) -> Result<(Self, StateIntegrity)> {
         // If state file doesn't exist, return default
         if !path.exists() {
             debug!("App state file not found at {}. Using default.", path.display());
-            return Ok(AppState::new());
+            return Ok((AppState::new(), StateIntegrity::Valid));
         }
 
         // Acquire read lock on the file to avoid races
@@ -100,35 +158,63 @@ impl AppState {
         // Release shared lock (will drop)
         fs2::FileExt::unlock(&f)?;
 
-        // Try to treat file as encrypted. If parse fails, fallback to plain JSON parse.
-        if let Ok(state) = decrypt_state_blob(&raw, path) {
-            Ok(state)
+        let outcome = if raw.starts_with(b"v2.") || raw.starts_with(b"v1.") {
+            decrypt_state_blob(&raw, path).map(|state| {
+                let integrity = if raw.starts_with(b"v1.") { StateIntegrity::Migrated } else { StateIntegrity::Valid };
+                (state, integrity)
+            })
+        } else if load_state_key(path).is_some() {
+            Err(anyhow::anyhow!("State file is not encrypted, but an encryption key already exists"))
         } else {
-            // Try parse as plain json
-            match serde_json::from_slice::<AppState>(&raw) {
-                Ok(s) => Ok(s),
-                Err(e) => {
-                    warn!("Failed to parse app state (both encrypted and plaintext): {}. Returning default state.", e);
-                    Ok(AppState::new())
-                }
+            serde_json::from_slice::<AppState>(&raw)
+                .map(|state| (state, StateIntegrity::Migrated))
+                .context("State file is neither encrypted nor valid JSON")
+        };
+
+        match outcome {
+            Ok((mut state, integrity)) => {
+                state.migrate_legacy_prompt_timestamp();
+                state.baseline = state.counter_snapshot();
+                Ok((state, integrity))
+            }
+            Err(e) => {
+                let reason = format!("{:#}", e);
+                warn!("App state at {} failed its integrity check ({}). Resetting to default state.", path.display(), reason);
+                Ok((AppState::new(), StateIntegrity::Reset(reason)))
             }
         }
     }
 
     /// Save state to disk with encryption. Uses atomic write and exclusive lock.
-    pub fn save(&self, path: &Path) -> Result<()> {
+    ///
+    /// Other cleansh processes may have saved since this state was loaded, so the file is
+    /// re-read under an exclusive lock on a sibling `.lock` file and this process's
+    /// changes are merged into it: counter increments are added, license consumption and
+    /// timestamps are combined, and settings take this process's value. Afterwards `self`
+    /// holds the merged state, so saving again never counts the same increment twice.
+    pub fn save(&mut self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        // Hold the lock across read-merge-write so concurrent saves are serialized.
+        let lock_path = sibling_path(path, STATE_FILE_LOCK_SUFFIX);
+        let lock_file = OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path)
+            .with_context(|| format!("Failed to open state lock file at {}", lock_path.display()))?;
+        fs2::FileExt::lock_exclusive(&lock_file)
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+        let (on_disk, _) = Self::load_verified(path)?;
+        let mut merged = self.merged_into(on_disk);
+
         // Serialize plaintext JSON
-        let json = serde_json::to_vec_pretty(&self)?;
+        let json = serde_json::to_vec_pretty(&merged)?;
 
         // Encrypt using keyring (fallback)
         let encrypted_blob = encrypt_state_blob(&json, path)?;
 
         // Atomic write to temp + rename, with exclusive lock on temp file during write
-        let tmp_path = path.with_extension(format!("{}{}", path.extension().map(|s| s.to_string_lossy()).unwrap_or_default(), STATE_FILE_TMP_SUFFIX));
+        let tmp_path = sibling_path(path, STATE_FILE_TMP_SUFFIX);
         {
             let mut tmp = OpenOptions::new().create(true).write(true).truncate(true).open(&tmp_path)
                 .with_context(|| format!("Failed to create temp state file at {}", tmp_path.display()))?;
@@ -141,10 +227,51 @@ impl AppState {
 
         // Replace original file atomically
         fs::rename(&tmp_path, path)?;
+        fs2::FileExt::unlock(&lock_file)?;
 
+        merged.baseline = merged.counter_snapshot();
+        *self = merged;
         Ok(())
     }
 
+    /// Captures the current counter values.
+    fn counter_snapshot(&self) -> CounterSnapshot {
+        let feature_usage = self.licenses.iter()
+            .flat_map(|(fingerprint, meta)| {
+                meta.feature_usage.iter().map(move |(feature, count)| ((fingerprint.clone(), feature.clone()), *count))
+            })
+            .collect();
+        CounterSnapshot {
+            usage_count: self.usage_count,
+            stats_only_usage_count: self.stats_only_usage_count,
+            feature_usage,
+        }
+    }
+
+    /// Applies the changes made in this process since its baseline on top of `on_disk`.
+    fn merged_into(&self, mut on_disk: AppState) -> AppState {
+        let base = &self.baseline;
+        on_disk.usage_count += self.usage_count.saturating_sub(base.usage_count);
+        on_disk.stats_only_usage_count += self.stats_only_usage_count.saturating_sub(base.stats_only_usage_count);
+        on_disk.donation_prompts_disabled = self.donation_prompts_disabled;
+        for (key, until) in &self.notification_snoozes {
+            let disk_until = on_disk.notification_snoozes.entry(key.clone()).or_insert(*until);
+            *disk_until = (*disk_until).max(*until);
+        }
+
+        for (fingerprint, meta) in &self.licenses {
+            let disk_meta = on_disk.licenses.entry(fingerprint.clone()).or_default();
+            // Consumption is one-way: once any process marks a license consumed, it stays so.
+            disk_meta.consumed |= meta.consumed;
+            disk_meta.last_seen_utc = disk_meta.last_seen_utc.max(meta.last_seen_utc);
+            for (feature, count) in &meta.feature_usage {
+                let seen = base.feature_usage.get(&(fingerprint.clone(), feature.clone())).copied().unwrap_or(0);
+                *disk_meta.feature_usage.entry(feature.clone()).or_insert(0) += count.saturating_sub(seen);
+            }
+        }
+        on_disk
+    }
+
     // license helpers
 
     /// Returns whether a license fingerprint is marked consumed
@@ -175,7 +302,7 @@ impl AppState {
             .unwrap_or(0)
     }
 
-    // donation prompt logic (kept from original file)
+    // usage counters
     pub fn increment_usage(&mut self) {
         self.usage_count += 1;
         debug!("Main usage count incremented to {}", self.usage_count);
@@ -186,65 +313,74 @@ impl AppState {
         debug!("Stats-only usage count incremented to {}", self.stats_only_usage_count);
     }
 
-    pub fn should_display_donation_prompt(&mut self) -> bool {
-        if self.donation_prompts_disabled {
-            return false;
-        }
-
+    /// Returns `true` once usage has reached the donation threshold, unless donation
+    /// prompts are disabled.
+    pub fn donation_due(&self) -> bool {
         const PROMPT_THRESHOLD: u64 = 5;
-        const PROMPT_COOLDOWN_DAYS: i64 = 30;
-
-        let now = Utc::now().timestamp() as u64;
-        if self.usage_count >= PROMPT_THRESHOLD || self.stats_only_usage_count >= PROMPT_THRESHOLD {
-            if let Some(last_prompt) = self.last_prompt_timestamp {
-                let last_prompt_date = Utc.timestamp_opt(last_prompt as i64, 0).single();
-                let now_date = Utc.timestamp_opt(now as i64, 0).single();
-
-                if let (Some(last_p_date), Some(n_date)) = (last_prompt_date, now_date) {
-                    if (n_date - last_p_date).num_days() < PROMPT_COOLDOWN_DAYS {
-                        debug!("Donation prompt cooldown active. Last prompt: {} days ago.", (n_date - last_p_date).num_days());
-                        return false;
-                    }
-                } else {
-                    warn!("Failed to convert timestamps for donation prompt cooldown. Displaying prompt.");
-                }
-            }
+        !self.donation_prompts_disabled
+            && (self.usage_count >= PROMPT_THRESHOLD || self.stats_only_usage_count >= PROMPT_THRESHOLD)
+    }
 
-            debug!("Donation prompt conditions met. Displaying prompt.");
-            self.last_prompt_timestamp = Some(now);
-            true
-        } else {
-            debug!("Donation prompt threshold not met. Main count: {}, Stats count: {}", self.usage_count, self.stats_only_usage_count);
-            false
+    // notification snoozes
+
+    /// Decides whether the notification `key` may be shown at `now`, and if so snoozes it
+    /// for `snooze` so that it is shown at most once per period.
+    pub fn claim_notification(&mut self, key: &str, snooze: Duration, now: DateTime<Utc>) -> bool {
+        let snoozed_until = self.notification_snoozes.get(key).and_then(|until| Utc.timestamp_opt(*until, 0).single());
+        match snoozed_until {
+            // A snooze ending further out than a full period means the clock was moved back;
+            // restart the snooze from now instead of waiting for the clock to catch up.
+            Some(until) if until > now + snooze + CLOCK_SKEW_TOLERANCE => {
+                debug!("Snooze of notification '{}' ends in the future; restarting it.", key);
+                self.notification_snoozes.insert(key.to_string(), (now + snooze).timestamp());
+                false
+            }
+            Some(until) if until > now => {
+                debug!("Notification '{}' is snoozed until {}.", key, until);
+                false
+            }
+            _ => {
+                self.notification_snoozes.insert(key.to_string(), (now + snooze).timestamp());
+                true
+            }
         }
     }
 
-    pub fn check_and_prompt_donation(&mut self, theme_map: &ThemeMap) -> Result<()> {
-        if self.should_display_donation_prompt() {
-            info_msg(
-                "Hello! If Cleansh has been useful to you, consider donating. We rely on community support to continue development. Please consider donating to help keep this project going: https://github.com/KarmaYama/cleansh-workspace",
-                theme_map,
-            );
+    /// Carries the donation cooldown of a state written before notification snoozes over
+    /// into the `donation` snooze.
+    fn migrate_legacy_prompt_timestamp(&mut self) {
+        const LEGACY_PROMPT_COOLDOWN_DAYS: i64 = 30;
+        if let Some(last_prompt) = self.last_prompt_timestamp.take() {
+            let until = last_prompt as i64 + Duration::days(LEGACY_PROMPT_COOLDOWN_DAYS).num_seconds();
+            self.notification_snoozes.entry(NotificationKind::Donation.key().to_string()).or_insert(until);
         }
-        Ok(())
     }
 }
 
 // ---------------------- encryption & key management helpers ----------------------
 
-/// Try to fetch/generate a symmetric key (32 bytes) from keyring or fallback local key file.
-/// Returns raw key bytes.
-fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
+/// Returns `path` with `suffix` appended to its extension, e.g. `state.json.lock`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    path.with_extension(format!("{}{}", path.extension().map(|s| s.to_string_lossy()).unwrap_or_default(), suffix))
+}
+
+/// Returns the local key file used when the OS keyring is unavailable.
+fn local_key_path(state_path: &Path) -> PathBuf {
+    if let Some(parent) = state_path.parent() {
+        parent.join(LOCAL_KEY_FILENAME)
+    } else {
+        PathBuf::from(LOCAL_KEY_FILENAME)
+    }
+}
+
+/// Look up an existing 32-byte state key in the keyring, then in the local key file.
+/// Returns `None` if neither holds a usable key.
+fn load_state_key(state_path: &Path) -> Option<Vec<u8>> {
     // try keyring first
     match KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME).get_password() {
-        Ok(s) => {
-            let decoded = general_purpose::STANDARD.decode(s)
-                .context("Failed to decode base64 key from keyring")?;
-            if decoded.len() != 32 {
-                warn!("Keyring returned key of unexpected length. Generating a new key and storing it.");
-            } else {
-                return Ok(decoded);
-            }
+        Ok(s) => match general_purpose::STANDARD.decode(s) {
+            Ok(decoded) if decoded.len() == 32 => return Some(decoded),
+            _ => warn!("Keyring returned a malformed state key. Ignoring it."),
         },
         Err(e) => {
             debug!("Keyring get_password failed: {}. Will attempt local key fallback.", e);
@@ -252,30 +388,37 @@ fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
     }
 
     // Fallback local key file next to state_path
-    let key_file = if let Some(parent) = state_path.parent() {
-        parent.join(LOCAL_KEY_FILENAME)
-    } else {
-        PathBuf::from(LOCAL_KEY_FILENAME)
-    };
-
-    if key_file.exists() {
-        let s = fs::read_to_string(&key_file)?;
-        let decoded = general_purpose::STANDARD.decode(s.trim())
-            .context("Failed to decode base64 key from local key file")?;
-        if decoded.len() == 32 {
-            return Ok(decoded);
-        } else {
+    let key_file = local_key_path(state_path);
+    let s = fs::read_to_string(&key_file).ok()?;
+    match general_purpose::STANDARD.decode(s.trim()) {
+        Ok(decoded) if decoded.len() == 32 => Some(decoded),
+        _ => {
             warn!("Local key file has invalid key length; regenerating.");
+            None
         }
     }
+}
+
+/// Try to fetch/generate a symmetric key (32 bytes) from keyring or fallback local key file.
+/// Returns raw key bytes.
+fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
+    if let Some(key) = load_state_key(state_path) {
+        return Ok(key);
+    }
+    let key_file = local_key_path(state_path);
 
     // Generate new 32-byte key
     let mut key = [0u8; 32];
     OsRng.fill_bytes(&mut key);
 
-    // Try to store in keyring (best effort)
-    let b64 = general_purpose::STANDARD.encode(&key);
-    match KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME).set_password(&b64) {
+    // Try to store in keyring (best effort). Some credential stores accept the write but
+    // do not persist it, so read it back through a fresh entry before relying on it.
+    let b64 = general_purpose::STANDARD.encode(key);
+    let stored = KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME)
+        .and_then(|entry| entry.set_password(&b64))
+        .and_then(|_| KeyringEntry::new(KEYRING_SERVICE, KEYRING_USERNAME)?.get_password())
+        .and_then(|read_back| if read_back == b64 { Ok(()) } else { Err(keyring::Error::NoEntry) });
+    match stored {
         Ok(_) => {
             debug!("Stored state encryption key in OS keyring.");
         }
@@ -296,35 +439,44 @@ fn get_or_create_state_key(state_path: &Path) -> Result<Vec<u8>> {
     Ok(key.to_vec())
 }
 
+/// Derive the v2 encryption key by binding the stored key to this machine's fingerprint.
+fn derive_machine_key(stored_key: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(STATE_BLOB_AAD_V2);
+    hasher.update(stored_key);
+    hasher.update(machine_fingerprint());
+    hasher.finalize().into()
+}
+
 /// Encrypt the plaintext state and return the wrapped blob to write.
-/// Format: b"v1.<base64(nonce)>.<base64(ciphertext)>"
+/// Format: b"v2.<base64(nonce)>.<base64(ciphertext)>"
 fn encrypt_state_blob(plaintext: &[u8], state_path: &Path) -> Result<Vec<u8>> {
-    let key = get_or_create_state_key(state_path)?;
+    let key = derive_machine_key(&get_or_create_state_key(state_path)?);
     let cipher = Aes256Gcm::new_from_slice(&key).context("Failed to create AES-GCM cipher")?;
 
     let mut nonce_bytes = [0u8; AES_NONCE_LEN];
     OsRng.fill_bytes(&mut nonce_bytes);
     let nonce = Nonce::from_slice(&nonce_bytes);
 
-    let ciphertext = cipher.encrypt(nonce, plaintext)
+    let ciphertext = cipher.encrypt(nonce, Payload { msg: plaintext, aad: STATE_BLOB_AAD_V2 })
         .map_err(|e| anyhow::anyhow!("AES-GCM encryption failed: {:?}", e))?;
 
     let out_str = format!(
-        "v1.{}.{}",
-        general_purpose::STANDARD.encode(&nonce_bytes),
+        "v2.{}.{}",
+        general_purpose::STANDARD.encode(nonce_bytes),
         general_purpose::STANDARD.encode(&ciphertext)
     );
     Ok(out_str.into_bytes())
 }
 
-/// Attempt to decrypt stored blob; if format unrecognized, return Err.
+/// Attempt to decrypt and authenticate a stored `v1` or `v2` blob.
+///
+/// A missing key is an error rather than a reason to create one: a new key could never
+/// decrypt an existing blob.
 fn decrypt_state_blob(blob: &[u8], state_path: &Path) -> Result<AppState> {
     let s = std::str::from_utf8(blob).context("State file is not valid UTF-8")?;
-    // expected: v1.<base64(nonce)>.<base64(ciphertext)>
-    if !s.starts_with("v1.") {
-        return Err(anyhow::anyhow!("State file does not have expected version header"));
-    }
-    let parts: Vec<&str> = s.splitn(3, '.').collect();
+    // expected: v<N>.<base64(nonce)>.<base64(ciphertext)>
+    let parts: Vec<&str> = s.trim_end().splitn(3, '.').collect();
     if parts.len() != 3 {
         return Err(anyhow::anyhow!("Invalid encrypted state format"));
     }
@@ -332,14 +484,95 @@ fn decrypt_state_blob(blob: &[u8], state_path: &Path) -> Result<AppState> {
         .context("Failed to decode nonce")?;
     let ct_b = general_purpose::STANDARD.decode(parts[2])
         .context("Failed to decode ciphertext")?;
+    if nonce_b.len() != AES_NONCE_LEN {
+        return Err(anyhow::anyhow!("Invalid nonce length in encrypted state"));
+    }
 
-    let key = get_or_create_state_key(state_path)?;
-    let cipher = Aes256Gcm::new_from_slice(&key).context("Failed to create AES-GCM cipher")?;
+    let stored_key = load_state_key(state_path)
+        .ok_or_else(|| anyhow::anyhow!("State encryption key is missing"))?;
     let nonce = Nonce::from_slice(&nonce_b);
+    let plaintext = match parts[0] {
+        "v2" => Aes256Gcm::new_from_slice(&derive_machine_key(&stored_key))
+            .context("Failed to create AES-GCM cipher")?
+            .decrypt(nonce, Payload { msg: ct_b.as_ref(), aad: STATE_BLOB_AAD_V2 }),
+        "v1" => Aes256Gcm::new_from_slice(&stored_key)
+            .context("Failed to create AES-GCM cipher")?
+            .decrypt(nonce, ct_b.as_ref()),
+        other => return Err(anyhow::anyhow!("Unsupported state file version '{}'", other)),
+    }
+    .map_err(|_| anyhow::anyhow!("State file failed authentication (modified or written on another machine)"))?;
 
-    let plaintext = cipher.decrypt(nonce, ct_b.as_ref())
-        .map_err(|e| anyhow::anyhow!("Failed to decrypt state blob: {:?}", e))?;
     let state: AppState = serde_json::from_slice(&plaintext)
         .context("Failed to deserialize decrypted AppState JSON")?;
     Ok(state)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each `AppState` loaded here stands in for a separate process: it is loaded from the
+    // same path, modified independently, and saved without knowledge of the others.
+
+    /// Tests that two states loaded from the same file merge their counters on save instead
+    /// of the last writer discarding the other's increments.
+    #[test]
+    fn interleaved_saves_merge_counters_and_license_usage() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("state.json");
+        AppState::new().save(&path)?;
+
+        let mut first = AppState::load(&path)?;
+        let mut second = AppState::load(&path)?;
+
+        first.increment_usage();
+        first.increment_license_feature_usage("fp1", "sync");
+        second.increment_usage();
+        second.increment_stats_only_usage();
+        second.increment_license_feature_usage("fp1", "sync");
+        second.mark_license_consumed("fp2");
+
+        first.save(&path)?;
+        second.save(&path)?;
+        // Saving again must not re-apply increments that were already written.
+        first.save(&path)?;
+
+        let merged = AppState::load(&path)?;
+        assert_eq!(merged.usage_count, 2);
+        assert_eq!(merged.stats_only_usage_count, 1);
+        assert_eq!(merged.get_license_feature_usage("fp1", "sync"), 2);
+        assert!(merged.is_license_consumed("fp2"));
+        // The second save left `first` holding the merged view.
+        assert_eq!(first.usage_count, 2);
+        Ok(())
+    }
+
+    /// Tests that concurrent saves from many threads are serialized by the state lock, so no
+    /// increment is lost.
+    #[test]
+    fn concurrent_saves_do_not_lose_increments() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("state.json");
+        AppState::new().save(&path)?;
+
+        let workers: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || -> Result<()> {
+                    let mut state = AppState::load(&path)?;
+                    state.increment_usage();
+                    state.increment_license_feature_usage("fp", "scan");
+                    state.save(&path)
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().expect("worker panicked")?;
+        }
+
+        let state = AppState::load(&path)?;
+        assert_eq!(state.usage_count, 8);
+        assert_eq!(state.get_license_feature_usage("fp", "scan"), 8);
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+// cleansh-workspace/cleansh/src/utils/audit_trail.rs
+//! The `--audit-log` of `cleansh sanitize` and `cleansh scan`.
+//!
+//! Every redaction (or, for a scan, every finding) is appended to a JSON Lines file as a
+//! [`RedactionLog`] entry: the rule, the SHA-256 of the original value and of the whole
+//! input, the replacement, the input it came from, its byte offsets and a timestamp. The
+//! original value is never written, so the log can be kept for compliance reviews
+//! without becoming a store of the data it documents.
+//!
+//! All entries of one run share a `run_id`, and `user_id` is the name of the account
+//! that ran cleansh.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::utils::platform;
+use crate::utils::time;
+use anyhow::{Context, Result};
+use cleansh_core::{AuditLog, RedactionConfig, RedactionLog};
+use std::env;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One redaction to record, with its original value, which is only hashed.
+pub struct AuditedRedaction<'a> {
+    pub rule_name: &'a str,
+    pub original: &'a str,
+    pub replacement: &'a str,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// An open audit log and the details shared by every entry of the run.
+pub struct AuditTrail {
+    log: AuditLog,
+    run_id: String,
+    user_id: String,
+    /// `redacted` for `sanitize`, `detected` for `scan`.
+    outcome: &'static str,
+}
+
+impl AuditTrail {
+    /// Opens the audit log at `path` for appending, creating it if needed.
+    pub fn open(path: &Path, outcome: &'static str) -> Result<Self> {
+        let log = AuditLog::new(platform::long_path(path))
+            .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+        let run_id = RedactionLog::value_hash(&format!("{}:{}", started, std::process::id()))[..16].to_string();
+        let user_id = env::var("USER").or_else(|_| env::var("USERNAME")).unwrap_or_default();
+        Ok(Self { log, run_id, user_id, outcome })
+    }
+
+    /// Appends one entry per redaction found in `input`, read from `source`. The reason
+    /// given for each is its rule's description, or the rule name if it has none.
+    pub fn record<'a>(
+        &mut self,
+        source: &str,
+        input: &str,
+        rules: &RedactionConfig,
+        redactions: impl IntoIterator<Item = AuditedRedaction<'a>>,
+    ) -> Result<()> {
+        let timestamp = time::now_rfc3339();
+        let input_hash = RedactionLog::value_hash(input);
+        let entries: Vec<RedactionLog> = redactions
+            .into_iter()
+            .map(|redaction| RedactionLog {
+                timestamp: timestamp.clone(),
+                run_id: self.run_id.clone(),
+                file_path: source.to_string(),
+                user_id: self.user_id.clone(),
+                reason_for_redaction: rules
+                    .rules
+                    .iter()
+                    .find(|rule| rule.name == redaction.rule_name)
+                    .and_then(|rule| rule.description.clone())
+                    .unwrap_or_else(|| redaction.rule_name.to_string()),
+                redaction_outcome: self.outcome.to_string(),
+                rule_name: redaction.rule_name.to_string(),
+                input_hash: input_hash.clone(),
+                match_hash: RedactionLog::value_hash(redaction.original),
+                start: redaction.start,
+                end: redaction.end,
+                replacement: redaction.replacement.to_string(),
+            })
+            .collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.log.append_all(&entries).context("Failed to write to the audit log")
+    }
+}
@@ -8,6 +8,11 @@ use anyhow::{Result, Context};
 use arboard;
 use log::debug;
 
+/// Default upper bound for clipboard payloads (`--clipboard-max-bytes`).
+///
+/// Some clipboard backends hang or use a lot of memory with very large payloads.
+pub const DEFAULT_MAX_CLIPBOARD_BYTES: usize = 4 * 1024 * 1024;
+
 pub fn copy_to_clipboard(content: &str) -> Result<()> {
     debug!("Attempting to acquire clipboard.");
     let mut clipboard = arboard::Clipboard::new().context("Failed to initialize clipboard")?;
@@ -0,0 +1,109 @@
+// cleansh-workspace/cleansh/src/utils/crash_report.rs
+//! Sanitized crash reporting for the `cleansh` CLI.
+//!
+//! When cleansh panics, the default Rust panic output can include fragments of the
+//! data being processed (panic payloads, file paths, values in backtraces). Since
+//! cleansh is routinely fed secrets, we never want users to paste that raw output
+//! into a bug report. This module installs a panic hook that captures the panic
+//! message and a backtrace, runs everything through the default redaction rules,
+//! and writes the result to a crash report file the user can safely attach.
+
+use chrono::Utc;
+use cleansh_core::{config::RedactionConfig, RegexEngine, SanitizationEngine};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::{self, Write};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+
+use crate::utils::time;
+
+/// Installs a panic hook that writes a sanitized crash report into `report_dir`.
+///
+/// The hook replaces Rust's default panic output so that no unsanitized panic
+/// message ever reaches the terminal.
+pub fn install_panic_hook(report_dir: PathBuf) {
+    panic::set_hook(Box::new(move |info| {
+        let report = build_crash_report(&panic_message(info), &Backtrace::force_capture().to_string());
+        let mut stderr = io::stderr();
+        match write_crash_report(&report_dir, &report) {
+            Ok(path) => {
+                let _ = writeln!(
+                    stderr,
+                    "cleansh crashed unexpectedly. A sanitized crash report was written to: {}\nPlease attach this file to your bug report instead of pasting terminal output.",
+                    path.display()
+                );
+            }
+            Err(e) => {
+                let _ = writeln!(stderr, "cleansh crashed unexpectedly and the crash report could not be saved: {}", e);
+            }
+        }
+    }));
+}
+
+/// Extracts the panic payload and location into a single line.
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = if let Some(s) = info.payload().downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = info.payload().downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    };
+    match info.location() {
+        Some(loc) => format!("{} (at {}:{}:{})", payload, loc.file(), loc.line(), loc.column()),
+        None => payload,
+    }
+}
+
+/// Builds the crash report text, sanitizing the panic message and backtrace.
+///
+/// If sanitization itself fails, the sensitive sections are withheld entirely rather
+/// than written raw.
+pub fn build_crash_report(message: &str, backtrace: &str) -> String {
+    let details = format!("Panic: {}\n\nBacktrace:\n{}", message, backtrace);
+    let sanitized_details = sanitize_with_default_rules(&details)
+        .unwrap_or_else(|| "[withheld: crash details could not be sanitized]".to_string());
+
+    format!(
+        "cleansh crash report\n\
+         ====================\n\
+         Version: {}\n\
+         OS: {} ({})\n\
+         Time: {}\n\
+         \n\
+         All content below has been passed through cleansh's default redaction rules.\n\
+         \n\
+         {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        time::now_rfc3339(),
+        sanitized_details
+    )
+}
+
+/// Runs `content` through the default rule set, returning `None` on any failure.
+fn sanitize_with_default_rules(content: &str) -> Option<String> {
+    let mut config = RedactionConfig::load_default_rules().ok()?;
+    // Apply the same default rule set as `cleansh sanitize`, without opt-in rules.
+    config.set_active_rules(&[], &[]);
+    let engine = RegexEngine::new(config).ok()?;
+    engine
+        .sanitize(content, "crash_report", "", "", "", "", "", None)
+        .ok()
+        .map(|(sanitized, _)| sanitized)
+}
+
+/// Writes the report to a new, uniquely named file in `report_dir` and returns its path.
+fn write_crash_report(report_dir: &Path, report: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(report_dir)?;
+    let file_name = format!(
+        "crash-{}-{}.txt",
+        Utc::now().format("%Y%m%dT%H%M%SZ"),
+        std::process::id()
+    );
+    let path = report_dir.join(file_name);
+    fs::write(&path, report)?;
+    Ok(path)
+}
@@ -0,0 +1,173 @@
+// cleansh-workspace/cleansh/src/utils/dotenv.rs
+//! `.env` file handling for `cleansh sanitize --input-format dotenv`.
+//!
+//! `.env` files get pasted into issues and chats wholesale, and most of what they hold is
+//! a secret no rule recognizes: a database password, an internal hostname, a token
+//! without a known prefix. In this format every value is replaced, whatever it looks
+//! like, and only the keys are kept, so the file still shows which settings exist.
+//!
+//! Values of well-known settings that are not secret (`NODE_ENV`, `PORT`, ...) and of
+//! keys allowed with `--dotenv-allow` are kept, but still pass through the sanitizer, as
+//! do comments and lines that are not assignments. Comments, blank lines, `export`
+//! prefixes, quotes and the order of the entries are kept as they are. A quoted value may
+//! span several lines, as a private key often does; it is replaced as a whole.
+
+use anyhow::Result;
+
+/// Keys whose values are kept by default, since they name an environment or a setting
+/// rather than hold a secret.
+pub const DEFAULT_ALLOWED_KEYS: &[&str] = &[
+    "NODE_ENV",
+    "RAILS_ENV",
+    "RACK_ENV",
+    "APP_ENV",
+    "FLASK_ENV",
+    "ENVIRONMENT",
+    "DEBUG",
+    "LOG_LEVEL",
+    "PORT",
+    "TZ",
+];
+
+/// The rule name under which replaced values appear in the redaction summary.
+pub const DOTENV_RULE_NAME: &str = "dotenv_value";
+
+/// What a replaced value is written as.
+pub const VALUE_PLACEHOLDER: &str = "[REDACTED]";
+
+/// The result of sanitizing a `.env` file.
+#[derive(Debug)]
+pub struct DotenvOutput {
+    /// The re-emitted file.
+    pub text: String,
+    /// The values that were replaced with [`VALUE_PLACEHOLDER`], in file order.
+    pub redacted: Vec<String>,
+}
+
+/// One `KEY=value` entry, as byte offsets into the text from the start of its line.
+struct Assignment<'a> {
+    key: &'a str,
+    /// Where the value starts, after any opening quote.
+    value_start: usize,
+    /// Where the value ends, before any closing quote.
+    value_end: usize,
+    /// Where the rest of the line after the value (closing quote, inline comment,
+    /// line break) ends.
+    line_end: usize,
+}
+
+/// Parses the entry starting at the beginning of `text`, or returns `None` if the line
+/// is blank, a comment or not an assignment.
+fn parse_assignment(text: &str) -> Option<Assignment<'_>> {
+    let first_line_end = text.find('\n').map_or(text.len(), |i| i + 1);
+    let line = &text[..first_line_end];
+
+    let mut pos = line.len() - line.trim_start_matches([' ', '\t']).len();
+    if let Some(after) = line[pos..].strip_prefix("export")
+        && after.starts_with([' ', '\t'])
+    {
+        pos = line.len() - after.trim_start_matches([' ', '\t']).len();
+    }
+
+    let key_len = line[pos..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+        .unwrap_or(line.len() - pos);
+    let key = &line[pos..pos + key_len];
+    if key.is_empty() || key.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    pos += key_len;
+    pos = line.len() - line[pos..].trim_start_matches([' ', '\t']).len();
+    pos += line[pos..].strip_prefix('=').map(|_| 1)?;
+    pos = line.len() - line[pos..].trim_start_matches([' ', '\t']).len();
+
+    // A quoted value runs to its closing quote, which may be on a later line.
+    if let Some(quote) = line[pos..].chars().next().filter(|c| matches!(c, '"' | '\'' | '`'))
+        && let Some(close) = find_closing_quote(&text[pos + 1..], quote)
+    {
+        let value_end = pos + 1 + close;
+        let line_end = text[value_end..].find('\n').map_or(text.len(), |i| value_end + i + 1);
+        return Some(Assignment { key, value_start: pos + 1, value_end, line_end });
+    }
+
+    // An unquoted value runs to an inline comment or the end of the line.
+    let content = line.trim_end_matches(['\n', '\r']);
+    let comment = content[pos..]
+        .match_indices('#')
+        .find(|(i, _)| *i > 0 && content[pos..][..*i].ends_with([' ', '\t']))
+        .map_or(content.len(), |(i, _)| pos + i);
+    let value_end = pos + content[pos..comment].trim_end_matches([' ', '\t']).len();
+    Some(Assignment { key, value_start: pos, value_end, line_end: first_line_end })
+}
+
+/// Finds the closing `quote` in `text`; in double quotes, a backslash escapes the next
+/// character.
+fn find_closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Some(i);
+        }
+        if c == '\\' && quote == '"' {
+            chars.next();
+        }
+    }
+    None
+}
+
+/// Sanitizes a `.env` file. Values of keys in [`DEFAULT_ALLOWED_KEYS`] or `allowed_keys`,
+/// comments and other lines are passed to `sanitize`; every other non-empty value is
+/// replaced with [`VALUE_PLACEHOLDER`].
+pub fn sanitize_dotenv(
+    input: &str,
+    allowed_keys: &[String],
+    sanitize: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<DotenvOutput> {
+    let is_allowed = |key: &str| DEFAULT_ALLOWED_KEYS.contains(&key) || allowed_keys.iter().any(|k| k == key);
+    let mut text = String::with_capacity(input.len());
+    let mut redacted = Vec::new();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let Some(entry) = parse_assignment(rest) else {
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            text.push_str(&sanitize(&rest[..line_end])?);
+            rest = &rest[line_end..];
+            continue;
+        };
+
+        let value = &rest[entry.value_start..entry.value_end];
+        text.push_str(&rest[..entry.value_start]);
+        if value.is_empty() || is_allowed(entry.key) {
+            text.push_str(&sanitize(value)?);
+        } else {
+            text.push_str(VALUE_PLACEHOLDER);
+            redacted.push(value.to_string());
+        }
+        text.push_str(&sanitize(&rest[entry.value_end..entry.line_end])?);
+        rest = &rest[entry.line_end..];
+    }
+    Ok(DotenvOutput { text, redacted })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact_email(text: &str) -> Result<String> {
+        Ok(text.replace("ops@example.com", "[EMAIL]"))
+    }
+
+    #[test]
+    fn replaces_values_but_keeps_keys_comments_and_allowed_values() {
+        let input = "# owner: ops@example.com\nNODE_ENV=production\nexport DB_PASSWORD = \"hunter2\" # rotate\n\n\
+API_TOKEN='abc def'\nEMPTY=\nKEY=\"-----BEGIN KEY-----\nMIIB\n-----END KEY-----\"\nCONTACT=ops@example.com\nnot an assignment\n";
+        let allowed = vec!["CONTACT".to_string()];
+        let out = sanitize_dotenv(input, &allowed, &mut redact_email).unwrap();
+        assert_eq!(
+            out.text,
+            "# owner: [EMAIL]\nNODE_ENV=production\nexport DB_PASSWORD = \"[REDACTED]\" # rotate\n\n\
+API_TOKEN='[REDACTED]'\nEMPTY=\nKEY=\"[REDACTED]\"\nCONTACT=[EMAIL]\nnot an assignment\n"
+        );
+        assert_eq!(out.redacted, ["hunter2", "abc def", "-----BEGIN KEY-----\nMIIB\n-----END KEY-----"]);
+    }
+}
@@ -0,0 +1,408 @@
+// cleansh-workspace/cleansh/src/utils/eml.rs
+//! RFC 822 / MIME message handling for `cleansh sanitize --input-format eml`.
+//!
+//! Support teams often need to share customer emails, whose bodies are usually
+//! quoted-printable or base64 encoded, so plain-text sanitization never sees the actual
+//! text. This module walks the MIME tree, decodes each text part, passes the decoded
+//! text and a few address/subject headers through a sanitizer, and re-encodes the result
+//! with the part's original transfer encoding. Everything that is not changed (other
+//! headers, attachments, boundaries) is copied through byte for byte, so the output is
+//! still a valid message.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+/// Headers whose values are sanitized. Other headers are copied unchanged.
+pub const SANITIZED_HEADERS: &[&str] = &["From", "To", "Cc", "Subject"];
+
+/// Maximum line length for re-encoded quoted-printable and base64 bodies (RFC 2045).
+const MAX_ENCODED_LINE: usize = 76;
+
+/// The result of sanitizing a message.
+#[derive(Debug)]
+pub struct EmlOutput {
+    /// The re-emitted message.
+    pub message: String,
+    /// Text parts that were left unchanged because their transfer encoding or charset
+    /// could not be decoded.
+    pub skipped_parts: usize,
+}
+
+/// Sanitizes the bodies and selected headers of an RFC 822 / MIME message.
+///
+/// `sanitize` is called with decoded text and returns its sanitized form.
+///
+/// # Errors
+///
+/// Returns an error if the input does not start with a header block, or if `sanitize`
+/// fails.
+pub fn sanitize_message(message: &str, sanitize: &mut dyn FnMut(&str) -> Result<String>) -> Result<EmlOutput> {
+    let newline = if message.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut walker = Walker { sanitize, newline, skipped_parts: 0 };
+    let message = walker.entity(message, true)?;
+    Ok(EmlOutput { message, skipped_parts: walker.skipped_parts })
+}
+
+struct Walker<'a> {
+    sanitize: &'a mut dyn FnMut(&str) -> Result<String>,
+    newline: &'static str,
+    skipped_parts: usize,
+}
+
+/// A header field as it appeared in the input, including folded lines and line breaks.
+struct Header<'a> {
+    raw: &'a str,
+    name: &'a str,
+}
+
+impl Header<'_> {
+    /// The unfolded value, without the trailing line break.
+    fn value(&self) -> String {
+        let value = &self.raw[self.name.len() + 1..];
+        value.replace("\r\n", "").replace('\n', "").trim().to_string()
+    }
+}
+
+impl Walker<'_> {
+    /// Processes one entity: a header block, a blank line, and a body.
+    fn entity(&mut self, entity: &str, top_level: bool) -> Result<String> {
+        let (headers, separator, body) = split_entity(entity, top_level)?;
+
+        let mut out = String::with_capacity(entity.len());
+        for header in &headers {
+            out.push_str(&self.header(header)?);
+        }
+        out.push_str(separator);
+
+        let content_type = find_header(&headers, "Content-Type").map(|h| h.value());
+        let (mime_type, boundary) = parse_content_type(content_type.as_deref());
+        let encoding = find_header(&headers, "Content-Transfer-Encoding")
+            .map(|h| h.value().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        if mime_type.starts_with("multipart/") {
+            match boundary {
+                Some(boundary) => out.push_str(&self.multipart(body, &boundary)?),
+                None => out.push_str(body),
+            }
+        } else if mime_type == "message/rfc822" {
+            out.push_str(&self.entity(body, false)?);
+        } else if mime_type.starts_with("text/") {
+            out.push_str(&self.text_body(body, &encoding)?);
+        } else {
+            out.push_str(body);
+        }
+        Ok(out)
+    }
+
+    /// Sanitizes a header if it is one of [`SANITIZED_HEADERS`].
+    fn header(&mut self, header: &Header) -> Result<String> {
+        if !SANITIZED_HEADERS.iter().any(|name| name.eq_ignore_ascii_case(header.name)) {
+            return Ok(header.raw.to_string());
+        }
+        let decoded = decode_encoded_words(&header.value());
+        let sanitized = (self.sanitize)(&decoded)?;
+        if sanitized == decoded {
+            return Ok(header.raw.to_string());
+        }
+        let value = if sanitized.is_ascii() && !sanitized.contains("=?") {
+            sanitized
+        } else {
+            format!("=?UTF-8?B?{}?=", STANDARD.encode(sanitized.as_bytes()))
+        };
+        Ok(format!("{}: {}{}", header.name, value, self.newline))
+    }
+
+    /// Processes each part of a multipart body, keeping the preamble, epilogue, and
+    /// boundary lines as they are.
+    fn multipart(&mut self, body: &str, boundary: &str) -> Result<String> {
+        let delimiter = format!("--{}", boundary);
+        let mut out = String::with_capacity(body.len());
+        let mut part_start: Option<usize> = None;
+        let mut offset = 0;
+
+        for line in body.split_inclusive('\n') {
+            let trimmed = line.trim_end();
+            let is_delimiter = trimmed == delimiter;
+            let is_close = trimmed == format!("{}--", delimiter);
+            if is_delimiter || is_close {
+                match part_start {
+                    Some(start) => out.push_str(&self.entity(&body[start..offset], false)?),
+                    None => out.push_str(&body[..offset]),
+                }
+                out.push_str(line);
+                part_start = if is_close { None } else { Some(offset + line.len()) };
+                if is_close {
+                    out.push_str(&body[offset + line.len()..]);
+                    return Ok(out);
+                }
+            }
+            offset += line.len();
+        }
+
+        // No closing delimiter: treat whatever follows the last boundary as a part.
+        match part_start {
+            Some(start) => out.push_str(&self.entity(&body[start..], false)?),
+            None => out.push_str(body),
+        }
+        Ok(out)
+    }
+
+    /// Decodes, sanitizes, and re-encodes a text body.
+    fn text_body(&mut self, body: &str, encoding: &str) -> Result<String> {
+        let bytes = match encoding {
+            "quoted-printable" => decode_quoted_printable(body),
+            "base64" => match STANDARD.decode(body.split_ascii_whitespace().collect::<String>()) {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    self.skipped_parts += 1;
+                    return Ok(body.to_string());
+                }
+            },
+            _ => return (self.sanitize)(body),
+        };
+        let Ok(text) = String::from_utf8(bytes) else {
+            self.skipped_parts += 1;
+            return Ok(body.to_string());
+        };
+
+        let sanitized = (self.sanitize)(&text)?;
+        if sanitized == text {
+            return Ok(body.to_string());
+        }
+        let mut encoded = match encoding {
+            "quoted-printable" => encode_quoted_printable(&sanitized, self.newline),
+            _ => encode_base64(sanitized.as_bytes(), self.newline),
+        };
+        if body.ends_with('\n') && !encoded.ends_with('\n') {
+            encoded.push_str(self.newline);
+        }
+        Ok(encoded)
+    }
+}
+
+/// Splits an entity into its header fields, the blank separator line, and the body.
+fn split_entity(entity: &str, top_level: bool) -> Result<(Vec<Header<'_>>, &str, &str)> {
+    // (start offset, name) of each field; a field runs until the next one starts.
+    let mut fields: Vec<(usize, &str)> = Vec::new();
+    let mut offset = 0;
+    let mut separator = "";
+
+    for line in entity.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        if content.is_empty() {
+            separator = line;
+            break;
+        }
+        if content.starts_with([' ', '\t']) && !fields.is_empty() {
+            // Folded continuation of the previous field.
+        } else if let Some(colon) = content.find(':').filter(|&i| i > 0 && !content[..i].contains(' ')) {
+            fields.push((offset, &content[..colon]));
+        } else if top_level {
+            // The offending line is not quoted: it is unsanitized input.
+            let line_number = entity[..offset].matches('\n').count() + 1;
+            return Err(anyhow!("Input does not look like an RFC 822 message: expected a header field on line {}", line_number));
+        } else {
+            // A part without headers: everything is body.
+            return Ok((Vec::new(), "", entity));
+        }
+        offset += line.len();
+    }
+
+    let headers = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, &(start, name))| {
+            let end = fields.get(idx + 1).map_or(offset, |&(next, _)| next);
+            Header { raw: &entity[start..end], name }
+        })
+        .collect();
+    Ok((headers, separator, &entity[offset + separator.len()..]))
+}
+
+fn find_header<'a, 'b>(headers: &'b [Header<'a>], name: &str) -> Option<&'b Header<'a>> {
+    headers.iter().find(|h| h.name.eq_ignore_ascii_case(name))
+}
+
+/// Returns the lowercased MIME type and the `boundary` parameter, if any.
+///
+/// A missing Content-Type means `text/plain` (RFC 2045).
+fn parse_content_type(value: Option<&str>) -> (String, Option<String>) {
+    let Some(value) = value else {
+        return ("text/plain".to_string(), None);
+    };
+    let mut params = value.split(';');
+    let mime_type = params.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let boundary = params.find_map(|param| {
+        let (key, val) = param.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("boundary").then(|| val.trim().trim_matches('"').to_string())
+    });
+    (mime_type, boundary)
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decodes a quoted-printable body. Malformed escapes are kept literally.
+fn decode_quoted_printable(body: &str) -> Vec<u8> {
+    let bytes = body.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' {
+            // Soft line break, optionally preceded by trailing whitespace.
+            let rest = &bytes[i + 1..];
+            let ws = rest.iter().take_while(|b| **b == b' ' || **b == b'\t').count();
+            if rest[ws..].starts_with(b"\r\n") {
+                i += 1 + ws + 2;
+                continue;
+            }
+            if rest[ws..].starts_with(b"\n") {
+                i += 1 + ws + 1;
+                continue;
+            }
+            if let (Some(hi), Some(lo)) = (rest.first().and_then(|b| hex_value(*b)), rest.get(1).and_then(|b| hex_value(*b))) {
+                out.push((hi << 4) | lo);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+/// Encodes text as quoted-printable, keeping its line breaks as hard breaks.
+fn encode_quoted_printable(text: &str, newline: &str) -> String {
+    let mut out = String::with_capacity(text.len() + text.len() / 8);
+    let lines: Vec<&str> = text.split('\n').collect();
+    for (idx, line) in lines.iter().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let bytes = line.as_bytes();
+        let mut width = 0;
+        for (pos, &byte) in bytes.iter().enumerate() {
+            let at_end = pos + 1 == bytes.len();
+            let literal = ((byte == b' ' || byte == b'\t') && !at_end) || ((33..=126).contains(&byte) && byte != b'=');
+            let token = if literal { (byte as char).to_string() } else { format!("={:02X}", byte) };
+            if width + token.len() > MAX_ENCODED_LINE - 1 {
+                out.push('=');
+                out.push_str(newline);
+                width = 0;
+            }
+            width += token.len();
+            out.push_str(&token);
+        }
+        if idx + 1 < lines.len() {
+            out.push_str(newline);
+        }
+    }
+    out
+}
+
+/// Encodes bytes as base64 wrapped at [`MAX_ENCODED_LINE`] characters.
+fn encode_base64(bytes: &[u8], newline: &str) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / MAX_ENCODED_LINE * 2 + 2);
+    for chunk in encoded.as_bytes().chunks(MAX_ENCODED_LINE) {
+        out.push_str(std::str::from_utf8(chunk).expect("base64 output is ASCII"));
+        out.push_str(newline);
+    }
+    out
+}
+
+/// Decodes RFC 2047 encoded words (`=?charset?B|Q?text?=`) in a header value.
+///
+/// Words in charsets other than UTF-8, US-ASCII, and ISO-8859-1 are left encoded.
+fn decode_encoded_words(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut pending_ws = String::new();
+    let mut last_was_word = false;
+
+    while !rest.is_empty() {
+        if let Some((decoded, consumed)) = rest.strip_prefix("=?").and_then(decode_word) {
+            if !last_was_word {
+                out.push_str(&pending_ws);
+            }
+            pending_ws.clear();
+            out.push_str(&decoded);
+            rest = &rest[2 + consumed..];
+            last_was_word = true;
+            continue;
+        }
+        let c = rest.chars().next().expect("rest is not empty");
+        if c.is_whitespace() {
+            pending_ws.push(c);
+        } else {
+            out.push_str(&pending_ws);
+            pending_ws.clear();
+            out.push(c);
+            last_was_word = false;
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    out.push_str(&pending_ws);
+    out
+}
+
+/// Decodes the body of one encoded word (after `=?`), returning the text and the number
+/// of bytes consumed including the closing `?=`.
+fn decode_word(word: &str) -> Option<(String, usize)> {
+    let (charset, rest) = word.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let end = rest.find("?=")?;
+    let text = &rest[..end];
+    let bytes = match encoding {
+        "B" | "b" => STANDARD.decode(text).ok()?,
+        "Q" | "q" => decode_quoted_printable(&text.replace('_', " ")),
+        _ => return None,
+    };
+    let decoded = match charset.to_ascii_lowercase().as_str() {
+        "utf-8" | "us-ascii" => String::from_utf8(bytes).ok()?,
+        "iso-8859-1" | "latin1" => bytes.iter().map(|&b| b as char).collect(),
+        _ => return None,
+    };
+    Some((decoded, charset.len() + encoding.len() + end + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact_secret(text: &str) -> Result<String> {
+        Ok(text.replace("hunter2", "[PASSWORD]").replace("jane@example.com", "[EMAIL]"))
+    }
+
+    #[test]
+    fn sanitizes_quoted_printable_and_base64_parts() {
+        let b64 = STANDARD.encode("base64 says hunter2\n");
+        let message = format!(
+            "From: Jane <jane@example.com>\r\nSubject: =?UTF-8?Q?pw_is_hunter2?=\r\nX-Id: 7\r\nContent-Type: multipart/alternative; boundary=\"b1\"\r\n\r\npreamble\r\n--b1\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\nmy password is hun=\r\nter2 caf=C3=A9\r\n--b1\r\nContent-Type: text/html\r\nContent-Transfer-Encoding: base64\r\n\r\n{}\r\n--b1\r\nContent-Type: image/png\r\n\r\nhunter2\r\n--b1--\r\n",
+            b64
+        );
+
+        let out = sanitize_message(&message, &mut redact_secret).unwrap();
+        assert_eq!(out.skipped_parts, 0);
+        let msg = out.message;
+        assert!(msg.starts_with("From: Jane <[EMAIL]>\r\nSubject: pw is [PASSWORD]\r\nX-Id: 7\r\n"), "{}", msg);
+        assert!(msg.contains("\r\n\r\nmy password is [PASSWORD] caf=C3=A9\r\n--b1\r\n"), "{}", msg);
+        let encoded = STANDARD.encode("base64 says [PASSWORD]\n");
+        assert!(msg.contains(&format!("\r\n\r\n{}\r\n--b1\r\n", encoded)), "{}", msg);
+        assert!(msg.contains("image/png\r\n\r\nhunter2\r\n--b1--\r\n"), "Attachments must be untouched: {}", msg);
+    }
+
+    #[test]
+    fn rejects_input_without_headers() {
+        assert!(sanitize_message("just some text\nhunter2\n", &mut redact_secret).is_err());
+    }
+
+    #[test]
+    fn quoted_printable_round_trips_long_lines() {
+        let text = format!("{} = done\nnext", "x".repeat(100));
+        let encoded = encode_quoted_printable(&text, "\n");
+        assert!(encoded.lines().all(|line| line.len() <= MAX_ENCODED_LINE));
+        assert_eq!(String::from_utf8(decode_quoted_printable(&encoded)).unwrap(), text);
+    }
+}
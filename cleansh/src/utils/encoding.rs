@@ -0,0 +1,223 @@
+// cleansh-workspace/cleansh/src/utils/encoding.rs
+//! Input encoding detection and transcoding.
+//!
+//! Rules match UTF-8 text, but Windows tools often write UTF-16LE logs with a byte order
+//! mark, and older ones write Windows-1252. Read as UTF-8, such input either fails to load
+//! or turns into text with a NUL between every character, where nothing matches. This
+//! module detects the encoding of raw input, decodes it for matching, and encodes the
+//! sanitized result back so the output can be dropped in where the input came from.
+
+use std::io::{self, BufRead};
+
+/// A text encoding detected on input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8 { bom: bool },
+    Utf16Le { bom: bool },
+    Utf16Be { bom: bool },
+    /// Used for input that is not valid UTF-8 and carries no byte order mark.
+    Windows1252,
+}
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        TextEncoding::Utf8 { bom: false }
+    }
+}
+
+impl TextEncoding {
+    /// A short name for messages, e.g. `UTF-16LE (BOM)`.
+    pub fn label(&self) -> String {
+        let (name, bom) = match self {
+            TextEncoding::Utf8 { bom } => ("UTF-8", *bom),
+            TextEncoding::Utf16Le { bom } => ("UTF-16LE", *bom),
+            TextEncoding::Utf16Be { bom } => ("UTF-16BE", *bom),
+            TextEncoding::Windows1252 => ("Windows-1252", false),
+        };
+        if bom { format!("{} (BOM)", name) } else { name.to_string() }
+    }
+
+    /// Returns `true` for plain UTF-8 without a byte order mark.
+    pub fn is_plain_utf8(&self) -> bool {
+        *self == TextEncoding::Utf8 { bom: false }
+    }
+
+    fn bom_bytes(&self) -> &'static [u8] {
+        match self {
+            TextEncoding::Utf8 { bom: true } => &[0xEF, 0xBB, 0xBF],
+            TextEncoding::Utf16Le { bom: true } => &[0xFF, 0xFE],
+            TextEncoding::Utf16Be { bom: true } => &[0xFE, 0xFF],
+            _ => &[],
+        }
+    }
+}
+
+/// Detects the encoding of `prefix` from a byte order mark or, failing that, from the
+/// NUL pattern of BOM-less UTF-16 text. Returns the encoding and the BOM length.
+///
+/// Anything else is reported as UTF-8; see [`decode`] for the Windows-1252 fallback.
+pub fn sniff(prefix: &[u8]) -> (TextEncoding, usize) {
+    if prefix.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return (TextEncoding::Utf8 { bom: true }, 3);
+    }
+    if prefix.starts_with(&[0xFF, 0xFE]) {
+        return (TextEncoding::Utf16Le { bom: true }, 2);
+    }
+    if prefix.starts_with(&[0xFE, 0xFF]) {
+        return (TextEncoding::Utf16Be { bom: true }, 2);
+    }
+
+    // Mostly-ASCII UTF-16 has a NUL in every other byte, which plain text never has.
+    let sample = &prefix[..prefix.len().min(512) & !1];
+    if sample.len() >= 4 {
+        let pairs = sample.len() / 2;
+        let nul_even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let nul_odd = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        if nul_odd * 4 >= pairs * 3 && nul_even == 0 {
+            return (TextEncoding::Utf16Le { bom: false }, 0);
+        }
+        if nul_even * 4 >= pairs * 3 && nul_odd == 0 {
+            return (TextEncoding::Utf16Be { bom: false }, 0);
+        }
+    }
+    (TextEncoding::Utf8 { bom: false }, 0)
+}
+
+/// Decodes raw input into UTF-8 text, returning the text and the detected encoding.
+///
+/// Invalid UTF-16 code units and undecodable bytes become U+FFFD. Input that is neither
+/// UTF-16 nor valid UTF-8 is decoded as Windows-1252.
+pub fn decode(bytes: &[u8]) -> (String, TextEncoding) {
+    let (encoding, bom_len) = sniff(bytes);
+    let body = &bytes[bom_len..];
+    match encoding {
+        TextEncoding::Utf8 { .. } => match std::str::from_utf8(body) {
+            Ok(text) => (text.to_string(), encoding),
+            Err(_) => (decode_as(body, TextEncoding::Windows1252), TextEncoding::Windows1252),
+        },
+        _ => (decode_as(body, encoding), encoding),
+    }
+}
+
+/// Decodes `bytes` (without a BOM) as `encoding`.
+pub fn decode_as(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 { .. } => String::from_utf8_lossy(bytes).into_owned(),
+        TextEncoding::Utf16Le { .. } | TextEncoding::Utf16Be { .. } => {
+            let big_endian = matches!(encoding, TextEncoding::Utf16Be { .. });
+            let units = bytes.chunks_exact(2).map(|pair| {
+                if big_endian { u16::from_be_bytes([pair[0], pair[1]]) } else { u16::from_le_bytes([pair[0], pair[1]]) }
+            });
+            char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+        }
+        TextEncoding::Windows1252 => bytes.iter().map(|&b| windows_1252_char(b)).collect(),
+    }
+}
+
+/// Encodes `text` as `encoding`, without a BOM.
+///
+/// Characters that Windows-1252 cannot represent are written as `?`.
+pub fn encode_as(text: &str, encoding: TextEncoding) -> Vec<u8> {
+    match encoding {
+        TextEncoding::Utf8 { .. } => text.as_bytes().to_vec(),
+        TextEncoding::Utf16Le { .. } => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        TextEncoding::Utf16Be { .. } => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        TextEncoding::Windows1252 => text.chars().map(|c| windows_1252_byte(c).unwrap_or(b'?')).collect(),
+    }
+}
+
+/// Encodes `text` as `encoding`, including the BOM if the input had one.
+pub fn encode(text: &str, encoding: TextEncoding) -> Vec<u8> {
+    let mut out = encoding.bom_bytes().to_vec();
+    out.extend(encode_as(text, encoding));
+    out
+}
+
+/// Reads one line from `reader` in `encoding`, appending its raw bytes (including the
+/// line break) to `buf`. Returns the number of bytes read; 0 means end of input.
+pub fn read_line<R: BufRead>(reader: &mut R, encoding: TextEncoding, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let newline: [u8; 2] = match encoding {
+        TextEncoding::Utf16Le { .. } => [b'\n', 0],
+        TextEncoding::Utf16Be { .. } => [0, b'\n'],
+        _ => return reader.read_until(b'\n', buf),
+    };
+    let start = buf.len();
+    let mut unit = [0u8; 2];
+    loop {
+        match reader.read_exact(&mut unit) {
+            Ok(()) => {
+                buf.extend_from_slice(&unit);
+                if unit == newline {
+                    break;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(buf.len() - start)
+}
+
+/// Bytes 0x80..=0x9F of Windows-1252; the rest of the range matches ISO-8859-1.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+fn windows_1252_char(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+fn windows_1252_byte(c: char) -> Option<u8> {
+    match c as u32 {
+        0x00..=0x7F | 0xA0..=0xFF => Some(c as u8),
+        _ => WINDOWS_1252_HIGH.iter().position(|&high| high == c).map(|i| 0x80 + i as u8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_utf16_with_bom() {
+        let bytes = encode("key=AKIA\r\nnext €\r\n", TextEncoding::Utf16Le { bom: true });
+        assert!(bytes.starts_with(&[0xFF, 0xFE]));
+        let (text, encoding) = decode(&bytes);
+        assert_eq!(text, "key=AKIA\r\nnext €\r\n");
+        assert_eq!(encoding, TextEncoding::Utf16Le { bom: true });
+        assert_eq!(encode(&text, encoding), bytes);
+    }
+
+    #[test]
+    fn detects_bomless_utf16_and_windows_1252() {
+        let utf16 = encode_as("plain ascii log line\n", TextEncoding::Utf16Be { bom: false });
+        assert_eq!(decode(&utf16), ("plain ascii log line\n".to_string(), TextEncoding::Utf16Be { bom: false }));
+
+        let cp1252 = b"caf\xe9 \x80 \x93quoted\x94";
+        let (text, encoding) = decode(cp1252);
+        assert_eq!(text, "café € “quoted”");
+        assert_eq!(encoding, TextEncoding::Windows1252);
+        assert_eq!(encode(&text, encoding), cp1252.to_vec());
+
+        assert_eq!(decode(b"just utf-8 \xc3\xa9").1, TextEncoding::Utf8 { bom: false });
+    }
+
+    #[test]
+    fn reads_utf16_lines() {
+        let bytes = encode_as("one\ntwo", TextEncoding::Utf16Le { bom: false });
+        let mut reader = io::Cursor::new(bytes);
+        let mut buf = Vec::new();
+        let encoding = TextEncoding::Utf16Le { bom: false };
+        assert_eq!(read_line(&mut reader, encoding, &mut buf).unwrap(), 8);
+        assert_eq!(decode_as(&buf, encoding), "one\n");
+        buf.clear();
+        assert_eq!(read_line(&mut reader, encoding, &mut buf).unwrap(), 6);
+        assert_eq!(decode_as(&buf, encoding), "two");
+        buf.clear();
+        assert_eq!(read_line(&mut reader, encoding, &mut buf).unwrap(), 0);
+    }
+}
@@ -0,0 +1,144 @@
+// cleansh-workspace/cleansh/src/utils/follow.rs
+//! Following a growing log file for `cleansh sanitize --follow`, like `tail -F`.
+//!
+//! [`FollowReader`] starts at the current end of the file and reads what is appended to
+//! it. At the end of the file it waits and looks again, so a read only returns once there
+//! is new data. Log rotation is detected in both common forms: a file truncated in place
+//! (`copytruncate`) is read again from its start, and a file that was moved away and
+//! recreated under the same path is reopened, after what was left in the old one has been
+//! read. While the path does not exist, between a rotation and the new file's creation,
+//! the reader keeps waiting.
+
+use std::fs::{self, File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Identifies the file behind a path, so that a recreated file can be told apart from
+/// the one that was opened. Only available on Unix; elsewhere only truncation is detected.
+fn file_identity(metadata: &Metadata) -> Option<(u64, u64)> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some((metadata.dev(), metadata.ino()))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// A reader that follows a file as it grows.
+pub struct FollowReader {
+    path: PathBuf,
+    file: File,
+    identity: Option<(u64, u64)>,
+    position: u64,
+    poll_interval: Duration,
+    stop: Box<dyn Fn() -> bool + Send>,
+}
+
+impl FollowReader {
+    /// Opens `path` at its current end; `poll_interval` is how long to wait at the end of
+    /// the file before looking for new data again.
+    pub fn open(path: &Path, poll_interval: Duration) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let position = file.seek(SeekFrom::End(0))?;
+        let identity = file_identity(&file.metadata()?);
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            identity,
+            position,
+            poll_interval,
+            stop: Box::new(|| false),
+        })
+    }
+
+    /// Ends the stream, with a read of 0 bytes, once `stop` returns `true` at the end of
+    /// the file.
+    pub fn stop_when(mut self, stop: impl Fn() -> bool + Send + 'static) -> Self {
+        self.stop = Box::new(stop);
+        self
+    }
+
+    /// Switches to the start of the file, or of the new file at the same path, if the file
+    /// was rotated. Returns `true` if there may be new data to read.
+    fn follow_rotation(&mut self) -> io::Result<bool> {
+        let Ok(metadata) = fs::metadata(&self.path) else {
+            return Ok(false);
+        };
+        if file_identity(&metadata) != self.identity {
+            let Ok(file) = File::open(&self.path) else {
+                return Ok(false);
+            };
+            self.identity = file_identity(&file.metadata()?);
+            self.file = file;
+            self.position = 0;
+            return Ok(true);
+        }
+        if metadata.len() < self.position {
+            self.position = self.file.seek(SeekFrom::Start(0))?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+impl Read for FollowReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.file.read(buf)?;
+            if read > 0 {
+                self.position += read as u64;
+                return Ok(read);
+            }
+            if (self.stop)() {
+                return Ok(0);
+            }
+            if !self.follow_rotation()? {
+                thread::sleep(self.poll_interval);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // Telling a recreated file from the old one needs inode numbers.
+    #[cfg(unix)]
+    #[test]
+    fn reads_appended_lines_across_truncation_and_recreation() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old line\n")?;
+
+        // Each time the reader waits, the next step of a log's life happens to the file.
+        let log = path.clone();
+        let waits = Arc::new(AtomicUsize::new(0));
+        let steps = Arc::clone(&waits);
+        let reader = FollowReader::open(&path, Duration::from_millis(1))?.stop_when(move || {
+            match steps.fetch_add(1, Ordering::SeqCst) {
+                0 => fs::OpenOptions::new().append(true).open(&log).and_then(|mut f| f.write_all(b"first\n")).is_err(),
+                1 => fs::write(&log, "").and_then(|_| fs::write(&log, "truncated\n")).is_err(),
+                2 => {
+                    fs::rename(&log, log.with_extension("log.1")).unwrap();
+                    fs::write(&log, "after rotate\n").is_err()
+                }
+                _ => true,
+            }
+        });
+
+        let lines: Vec<String> = BufReader::new(reader).lines().collect::<io::Result<_>>()?;
+        assert_eq!(lines, ["first", "truncated", "after rotate"]);
+        assert!(waits.load(Ordering::SeqCst) >= 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+// cleansh-workspace/cleansh/src/utils/heatmap.rs
+//! Per-minute rule hit counts for `cleansh sanitize --line-buffered --heatmap-out`.
+//!
+//! When cleansh sits in front of a long-running log stream, the total summary printed at
+//! exit says *what* leaked but not *when*. The heatmap buckets every match by the minute
+//! it was seen, so a sudden column of hits shows when an application started writing
+//! secrets to its logs. It is written as a CSV table with one row per minute and one
+//! column per rule.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Match counts per rule, bucketed by minute.
+#[derive(Debug, Default)]
+pub struct RuleHeatmap {
+    /// Minute (Unix seconds at the start of the minute) -> rule -> count.
+    buckets: BTreeMap<i64, BTreeMap<String, usize>>,
+}
+
+impl RuleHeatmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `count` hits for `rule` in the minute containing `at`.
+    pub fn record(&mut self, at: DateTime<Utc>, rule: &str, count: usize) {
+        let minute = at.timestamp() - at.timestamp().rem_euclid(60);
+        *self.buckets.entry(minute).or_default().entry(rule.to_string()).or_insert(0) += count;
+    }
+
+    /// Writes the heatmap as CSV: a `minute` column in RFC 3339 followed by one column per
+    /// rule, sorted by name.
+    ///
+    /// Every minute between the first and last hit gets a row, including quiet ones, so
+    /// the rows form a continuous timeline.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let rules: BTreeSet<&str> = self.buckets.values().flat_map(|counts| counts.keys().map(String::as_str)).collect();
+
+        write!(writer, "minute")?;
+        for rule in &rules {
+            write!(writer, ",{}", rule)?;
+        }
+        writeln!(writer)?;
+
+        let (Some(&first), Some(&last)) = (self.buckets.keys().next(), self.buckets.keys().next_back()) else {
+            return Ok(());
+        };
+        let empty = BTreeMap::new();
+        for minute in (first..=last).step_by(60) {
+            let counts = self.buckets.get(&minute).unwrap_or(&empty);
+            let label = Utc
+                .timestamp_opt(minute, 0)
+                .single()
+                .map(|t| t.format("%Y-%m-%dT%H:%M:00Z").to_string())
+                .unwrap_or_else(|| minute.to_string());
+            write!(writer, "{}", label)?;
+            for rule in &rules {
+                write!(writer, ",{}", counts.get(*rule).copied().unwrap_or(0))?;
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the CSV to `path`, replacing any previous version atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv)?;
+        let tmp = path.with_extension("csv.tmp");
+        fs::write(&tmp, csv).with_context(|| format!("Failed to write heatmap to {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write heatmap to {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_quiet_minutes_with_zeros() {
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 5).unwrap();
+        let mut heatmap = RuleHeatmap::new();
+        heatmap.record(start, "email", 2);
+        heatmap.record(start + chrono::Duration::seconds(30), "aws_access_key", 1);
+        heatmap.record(start + chrono::Duration::minutes(2), "email", 1);
+
+        let mut out = Vec::new();
+        heatmap.write_csv(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "minute,aws_access_key,email\n\
+             2025-03-01T12:00:00Z,1,2\n\
+             2025-03-01T12:01:00Z,0,0\n\
+             2025-03-01T12:02:00Z,0,1\n"
+        );
+    }
+}
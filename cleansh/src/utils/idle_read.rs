@@ -0,0 +1,113 @@
+// cleansh-workspace/cleansh/src/utils/idle_read.rs
+//! Reading interactive stdin with an idle timeout.
+//!
+//! Run without a pipe or a file, cleansh reads from the terminal until EOF, and someone
+//! who does not know they have to press Ctrl-D sees a command that hangs forever. The
+//! reader here points out how to end the input once the terminal has been silent for a
+//! while, and, with `sanitize --stdin-timeout`, gives up after a set idle time.
+//!
+//! The blocking read runs on its own thread, since a terminal read cannot be
+//! interrupted portably. When the wait is given up, that thread is left blocked; the
+//! process exits soon after anyway.
+
+use std::io::{self, Read};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long the terminal may stay silent, before any input, until [`read_with_idle_timeout`]
+/// calls its hint callback.
+pub const HINT_AFTER: Duration = Duration::from_secs(10);
+
+/// Why [`read_with_idle_timeout`] gave up.
+#[derive(Debug)]
+pub enum IdleReadError {
+    /// Nothing was read for the whole timeout; `partial` is what had been read before.
+    TimedOut { partial: usize },
+    /// Reading failed.
+    Io(io::Error),
+}
+
+/// Reads `reader` to the end, giving up when no bytes arrive for `timeout`. `hint` is
+/// called once if nothing at all has arrived after [`HINT_AFTER`].
+pub fn read_with_idle_timeout<R: Read + Send + 'static>(
+    mut reader: R,
+    timeout: Option<Duration>,
+    mut hint: impl FnMut(),
+) -> Result<Vec<u8>, IdleReadError> {
+    let (sender, receiver) = mpsc::channel::<io::Result<Vec<u8>>>();
+    std::thread::spawn(move || {
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(count) => Ok(chunk[..count].to_vec()),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => Err(e),
+            };
+            // An empty chunk marks the end of the input.
+            let done = !matches!(&read, Ok(bytes) if !bytes.is_empty());
+            if sender.send(read).is_err() || done {
+                return;
+            }
+        }
+    });
+
+    let mut buffer = Vec::new();
+    let mut hinted = false;
+    let mut idle_since = Instant::now();
+    loop {
+        let idle = idle_since.elapsed();
+        let hint_at = (!hinted && buffer.is_empty()).then_some(HINT_AFTER);
+        let next_deadline = hint_at.into_iter().chain(timeout).filter(|deadline| *deadline > idle).min();
+        let received = match next_deadline {
+            Some(deadline) => receiver.recv_timeout(deadline - idle),
+            None if timeout.is_some_and(|timeout| idle >= timeout) => Err(RecvTimeoutError::Timeout),
+            None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match received {
+            Ok(Ok(bytes)) if bytes.is_empty() => return Ok(buffer),
+            Ok(Ok(bytes)) => {
+                buffer.extend_from_slice(&bytes);
+                idle_since = Instant::now();
+            }
+            Ok(Err(e)) => return Err(IdleReadError::Io(e)),
+            Err(RecvTimeoutError::Disconnected) => return Ok(buffer),
+            Err(RecvTimeoutError::Timeout) => {
+                if timeout.is_some_and(|timeout| idle_since.elapsed() >= timeout) {
+                    return Err(IdleReadError::TimedOut { partial: buffer.len() });
+                }
+                if hint_at.is_some() {
+                    hinted = true;
+                    hint();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Yields one chunk, then blocks far longer than any test waits.
+    struct Stalls(bool);
+
+    impl Read for Stalls {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if std::mem::replace(&mut self.0, true) {
+                std::thread::sleep(Duration::from_secs(3600));
+            }
+            buf[..3].copy_from_slice(b"abc");
+            Ok(3)
+        }
+    }
+
+    #[test]
+    fn gives_up_after_idle_timeout_and_reads_to_eof_otherwise() {
+        match read_with_idle_timeout(Stalls(false), Some(Duration::from_millis(50)), || {}) {
+            Err(IdleReadError::TimedOut { partial }) => assert_eq!(partial, 3),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+        let read = read_with_idle_timeout(&b"all of it"[..], Some(Duration::from_millis(50)), || {});
+        assert_eq!(read.unwrap(), b"all of it");
+    }
+}
@@ -0,0 +1,116 @@
+// cleansh-workspace/cleansh/src/utils/json_lines.rs
+//! JSON-lines handling for `cleansh sanitize --input-format json`.
+//!
+//! Structured logs from `kubectl logs`, Docker's `json-file` driver and most logging
+//! libraries put one JSON object on each line. Sanitized as plain text, a replacement can
+//! land across an escape sequence or a closing quote and leave a line that `jq` refuses.
+//! In this format every line that parses as JSON has its string values decoded, passed
+//! through the sanitizer one at a time, and encoded again, so the line stays valid JSON.
+//!
+//! Object keys, numbers, literals, whitespace and the order of the fields are copied
+//! unchanged, as is any string value the sanitizer leaves alone. Lines that are not JSON
+//! are sanitized as plain text.
+
+use anyhow::Result;
+use serde::de::IgnoredAny;
+
+/// The result of sanitizing JSON-lines input.
+#[derive(Debug)]
+pub struct JsonLinesOutput {
+    /// The re-emitted lines.
+    pub text: String,
+    /// Non-blank lines that did not parse as JSON and were sanitized as plain text.
+    pub text_lines: usize,
+}
+
+/// Finds the end of the string literal whose opening quote is at `start`, returning the
+/// offset just past its closing quote.
+fn string_end(line: &str, start: usize) -> usize {
+    let bytes = line.as_bytes();
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Sanitizes the string values of one line that is known to be valid JSON.
+fn sanitize_json_line(line: &str, sanitize: &mut dyn FnMut(&str) -> Result<String>) -> Result<String> {
+    let mut out = String::with_capacity(line.len());
+    let mut copied = 0;
+    let mut pos = 0;
+    while let Some(offset) = line[pos..].find('"') {
+        let start = pos + offset;
+        let end = string_end(line, start);
+        pos = end;
+        // In valid JSON, a string followed by a colon is an object key.
+        if line[end..].trim_start().starts_with(':') {
+            continue;
+        }
+
+        let literal = &line[start..end];
+        let value: String = serde_json::from_str(literal)?;
+        let sanitized = sanitize(&value)?;
+        if sanitized != value {
+            out.push_str(&line[copied..start]);
+            out.push_str(&serde_json::to_string(&sanitized)?);
+            copied = end;
+        }
+    }
+    out.push_str(&line[copied..]);
+    Ok(out)
+}
+
+/// Sanitizes JSON-lines input. String values of lines that parse as JSON are passed to
+/// `sanitize` one at a time and re-encoded; other lines are passed to it whole.
+pub fn sanitize_json_lines(
+    input: &str,
+    sanitize: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<JsonLinesOutput> {
+    let mut text = String::with_capacity(input.len());
+    let mut text_lines = 0;
+    for line in input.split_inclusive('\n') {
+        if serde_json::from_str::<IgnoredAny>(line).is_ok() {
+            text.push_str(&sanitize_json_line(line, sanitize)?);
+        } else {
+            if !line.trim().is_empty() {
+                text_lines += 1;
+            }
+            text.push_str(&sanitize(line)?);
+        }
+    }
+    Ok(JsonLinesOutput { text, text_lines })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact_email(text: &str) -> Result<String> {
+        Ok(text.replace("ops@example.com", "[EMAIL]"))
+    }
+
+    #[test]
+    fn sanitizes_string_values_and_keeps_lines_valid_json() {
+        let input = "{\"msg\": \"mail \\\"ops@example.com\\\"\", \"ops@example.com\": 1, \"n\": 2.50}\r\n\
+{\"log\":\"{\\\"to\\\":\\\"ops@example.com\\\"}\\n\",\"stream\":\"stdout\",\"tags\":[\"ops@example.com\",null]}\n\
+\n\
+plain ops@example.com line\n";
+        let out = sanitize_json_lines(input, &mut redact_email).unwrap();
+        assert_eq!(
+            out.text,
+            "{\"msg\": \"mail \\\"[EMAIL]\\\"\", \"ops@example.com\": 1, \"n\": 2.50}\r\n\
+{\"log\":\"{\\\"to\\\":\\\"[EMAIL]\\\"}\\n\",\"stream\":\"stdout\",\"tags\":[\"[EMAIL]\",null]}\n\
+\n\
+plain [EMAIL] line\n"
+        );
+        assert_eq!(out.text_lines, 1);
+        for line in out.text.lines().take(2) {
+            assert!(serde_json::from_str::<serde_json::Value>(line).is_ok(), "{line}");
+        }
+    }
+}
@@ -9,6 +9,11 @@ use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::time::{self, CLOCK_SKEW_TOLERANCE};
 
 /// Put your actual base64-encoded Ed25519 public key here (32 bytes -> base64).
 /// This key is used to verify signatures on licenses.
@@ -125,15 +130,65 @@ pub fn verify_token_signature(token: &LicenseToken) -> Result<()> {
         .map_err(|e| anyhow!("Signature verification failed: {}", e))
 }
 
+/// Checks that `now` falls within the license's validity window.
+///
+/// Both ends are widened by [`CLOCK_SKEW_TOLERANCE`], so a license is neither rejected as
+/// expired nor as not-yet-valid because the local clock is a few minutes off.
+pub fn check_validity_window(payload: &LicensePayload, now: DateTime<Utc>) -> Result<()> {
+    if now > payload.expires_at + CLOCK_SKEW_TOLERANCE {
+        return Err(anyhow!("License expired at {}", time::rfc3339(payload.expires_at)));
+    }
+    if now + CLOCK_SKEW_TOLERANCE < payload.issued_at {
+        return Err(anyhow!(
+            "License is not valid until {}; check the system clock",
+            time::rfc3339(payload.issued_at)
+        ));
+    }
+    Ok(())
+}
+
+/// Try to load license token from environment or a token file next to state.json
+pub fn load_token_from_env_or_file(state_path: &Path) -> Option<String> {
+    if let Ok(tok) = env::var("CLEANSH_LICENSE") {
+        return Some(tok);
+    }
+    let license_file = state_path.parent()?.join("license.token");
+    fs::read_to_string(license_file).ok().map(|s| s.trim().to_string())
+}
+
 /// Convenience: parse the compact token and verify signature & expiry checks and return the token.
 pub fn parse_and_verify_compact(token_str: &str) -> Result<LicenseToken> {
     let token = parse_compact_token(token_str)?;
     verify_token_signature(&token)?;
+    check_validity_window(&token.payload, Utc::now())?;
+    Ok(token)
+}
 
-    // expiry check
-    let now = Utc::now();
-    if token.payload.expires_at < now {
-        return Err(anyhow!("License expired at {}", token.payload.expires_at));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn payload() -> LicensePayload {
+        LicensePayload {
+            version: 1,
+            license_id: None,
+            issued_at: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            expires_at: Utc.with_ymd_and_hms(2026, 12, 31, 0, 0, 0).unwrap(),
+            features: HashMap::new(),
+            tier: None,
+        }
+    }
+
+    #[test]
+    fn validity_window_tolerates_small_clock_skew() {
+        let license = payload();
+        assert!(check_validity_window(&license, license.expires_at + Duration::minutes(4)).is_ok());
+        assert!(check_validity_window(&license, license.issued_at - Duration::minutes(4)).is_ok());
+
+        let expired = check_validity_window(&license, license.expires_at + Duration::hours(1)).unwrap_err();
+        assert_eq!(expired.to_string(), "License expired at 2026-12-31T00:00:00Z");
+        let early = check_validity_window(&license, license.issued_at - Duration::hours(1)).unwrap_err();
+        assert!(early.to_string().contains("not valid until 2026-01-01T00:00:00Z"), "{}", early);
     }
-    Ok(token)
 }
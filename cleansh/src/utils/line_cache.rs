@@ -0,0 +1,169 @@
+// cleansh-workspace/cleansh/src/utils/line_cache.rs
+//! A small, bounded LRU cache used by the line-buffered sanitization path.
+//!
+//! Logs frequently repeat identical lines (heartbeats, retries, health checks).
+//! This cache maps a SHA-256 hash of the raw line to its sanitized output and
+//! per-line redaction summary, so repeated lines can skip regex work entirely.
+//! Keys are content hashes rather than the raw lines themselves, and cached summaries
+//! keep only rule names and occurrence counts, so the cache holds no original values.
+
+use cleansh_core::RedactionSummaryItem;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// The cached result of sanitizing a single line.
+#[derive(Debug, Clone)]
+pub struct CachedLine {
+    pub sanitized: String,
+    pub summary: Vec<RedactionSummaryItem>,
+}
+
+impl CachedLine {
+    /// Caches a sanitized line, dropping the original and sanitized values from its
+    /// summary so matched secrets are not kept in memory. A repeated line only adds
+    /// to the occurrence counts.
+    pub fn new(sanitized: String, summary: &[RedactionSummaryItem]) -> Self {
+        let summary = summary
+            .iter()
+            .map(|item| RedactionSummaryItem {
+                rule_name: item.rule_name.clone(),
+                occurrences: item.occurrences,
+                original_texts: Vec::new(),
+                sanitized_texts: Vec::new(),
+            })
+            .collect();
+        Self { sanitized, summary }
+    }
+}
+
+/// A least-recently-used cache keyed by the SHA-256 hash of a raw input line.
+#[derive(Debug)]
+pub struct LineCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<[u8; 32], (CachedLine, u64)>,
+    recency: BTreeMap<u64, [u8; 32]>,
+    hits: u64,
+    misses: u64,
+}
+
+impl LineCache {
+    /// Creates a new cache holding at most `capacity` lines.
+    /// A capacity of zero produces a cache that never stores anything.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::with_capacity(capacity.min(4096)),
+            recency: BTreeMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Computes the cache key for a raw line.
+    pub fn key_for(line: &str) -> [u8; 32] {
+        Sha256::digest(line.as_bytes()).into()
+    }
+
+    /// Looks up a previously sanitized line, marking it as most recently used.
+    pub fn get(&mut self, key: &[u8; 32]) -> Option<&CachedLine> {
+        self.tick += 1;
+        let tick = self.tick;
+        match self.entries.get_mut(key) {
+            Some((_, last_used)) => {
+                self.recency.remove(last_used);
+                *last_used = tick;
+                self.recency.insert(tick, *key);
+                self.hits += 1;
+                self.entries.get(key).map(|(cached, _)| cached)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores the sanitized result for a line, evicting the least recently used entry if full.
+    pub fn insert(&mut self, key: [u8; 32], value: CachedLine) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some((_, old_tick)) = self.entries.insert(key, (value, tick)) {
+            self.recency.remove(&old_tick);
+        } else if self.entries.len() > self.capacity
+            && let Some((_, evicted)) = self.recency.pop_first()
+        {
+            self.entries.remove(&evicted);
+        }
+        self.recency.insert(tick, key);
+    }
+
+    /// Returns the number of lines currently cached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache holds no lines.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of cache hits and misses observed so far.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits, self.misses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(s: &str) -> CachedLine {
+        CachedLine { sanitized: s.to_string(), summary: Vec::new() }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LineCache::new(2);
+        let (a, b, c) = (LineCache::key_for("a"), LineCache::key_for("b"), LineCache::key_for("c"));
+        cache.insert(a, cached("A"));
+        cache.insert(b, cached("B"));
+        // Touch `a` so that `b` becomes the eviction candidate.
+        assert!(cache.get(&a).is_some());
+        cache.insert(c, cached("C"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&b).is_none());
+        assert_eq!(cache.get(&a).unwrap().sanitized, "A");
+        assert_eq!(cache.get(&c).unwrap().sanitized, "C");
+        assert_eq!(cache.stats(), (3, 1));
+    }
+
+    #[test]
+    fn cached_summary_drops_matched_values() {
+        let item = RedactionSummaryItem {
+            rule_name: "email".to_string(),
+            occurrences: 2,
+            original_texts: vec!["alice@example.com".to_string()],
+            sanitized_texts: vec!["[EMAIL_REDACTED]".to_string()],
+        };
+        let line = CachedLine::new("[EMAIL_REDACTED]\n".to_string(), &[item]);
+        assert_eq!(line.summary[0].rule_name, "email");
+        assert_eq!(line.summary[0].occurrences, 2);
+        assert!(line.summary[0].original_texts.is_empty());
+        assert!(line.summary[0].sanitized_texts.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_stores_nothing() {
+        let mut cache = LineCache::new(0);
+        let key = LineCache::key_for("line");
+        cache.insert(key, cached("x"));
+        assert!(cache.is_empty());
+        assert!(cache.get(&key).is_none());
+    }
+}
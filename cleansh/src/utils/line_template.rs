@@ -0,0 +1,150 @@
+//! Re-emitting sanitized lines in a normalized layout for `--line-template`.
+//!
+//! In line-buffered mode each sanitized line can be split into a structured prefix and the
+//! rest of the message, using a regex with named groups, and written out again through a
+//! template such as `{timestamp} {level} {sanitized}`. Log collectors downstream then see
+//! one layout, whichever program produced the lines. The prefix is taken from the
+//! sanitized line, so the fields never contain anything the rules would have redacted.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+
+/// Prefix regex used when `--line-prefix-regex` is not given: an ISO 8601 or
+/// `YYYY-MM-DD HH:MM:SS` timestamp, optionally followed by a log level, as in
+/// `2024-05-01T12:00:00Z [INFO] message`.
+pub const DEFAULT_PREFIX_REGEX: &str = r"^(?P<timestamp>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\s+(?:\[?(?P<level>TRACE|DEBUG|INFO|NOTICE|WARN|WARNING|ERROR|FATAL|CRITICAL)\]?:?\s+)?";
+
+/// The message after the prefix.
+const SANITIZED_FIELD: &str = "sanitized";
+/// The whole sanitized line, prefix included.
+const LINE_FIELD: &str = "line";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Literal(String),
+    Field(String),
+}
+
+/// A parsed `--line-template` with the regex that extracts its fields.
+#[derive(Debug, Clone)]
+pub struct LineTemplate {
+    pieces: Vec<Piece>,
+    prefix: Regex,
+}
+
+/// Splits `template` into literal text and `{field}` placeholders; `{{` and `}}` stand for
+/// literal braces.
+fn parse(template: &str) -> Result<Vec<Piece>> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) if c.is_ascii_alphanumeric() || c == '_' => name.push(c),
+                        _ => bail!("Unclosed or malformed placeholder '{{{}' in line template", name),
+                    }
+                }
+                if name.is_empty() {
+                    bail!("Empty placeholder '{{}}' in line template");
+                }
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(Piece::Field(name));
+            }
+            '}' => bail!("Unmatched '}}' in line template; write '}}}}' for a literal brace"),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+impl LineTemplate {
+    /// Parses `template` and compiles `prefix_regex` (or [`DEFAULT_PREFIX_REGEX`]).
+    ///
+    /// Every placeholder must be `{sanitized}`, `{line}` or a named group of the regex.
+    pub fn new(template: &str, prefix_regex: Option<&str>) -> Result<Self> {
+        let prefix = Regex::new(prefix_regex.unwrap_or(DEFAULT_PREFIX_REGEX)).context("Invalid --line-prefix-regex")?;
+        let pieces = parse(template)?;
+        for piece in &pieces {
+            if let Piece::Field(name) = piece
+                && name != SANITIZED_FIELD
+                && name != LINE_FIELD
+                && !prefix.capture_names().flatten().any(|group| group == name)
+            {
+                return Err(anyhow!(
+                    "Unknown placeholder '{{{}}}' in line template; use {{sanitized}}, {{line}} or a named group of the prefix regex",
+                    name
+                ));
+            }
+        }
+        Ok(Self { pieces, prefix })
+    }
+
+    /// Renders one sanitized line, given without its line ending.
+    ///
+    /// Lines the prefix regex does not match are returned unchanged. Named groups that did
+    /// not take part in the match render as empty text.
+    pub fn render(&self, line: &str) -> String {
+        let Some(captures) = self.prefix.captures(line) else {
+            return line.to_string();
+        };
+        let rest = &line[captures.get(0).map_or(0, |m| m.end())..];
+        let mut rendered = String::with_capacity(line.len());
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(text) => rendered.push_str(text),
+                Piece::Field(name) if name == SANITIZED_FIELD => rendered.push_str(rest),
+                Piece::Field(name) if name == LINE_FIELD => rendered.push_str(line),
+                Piece::Field(name) => rendered.push_str(captures.name(name).map_or("", |m| m.as_str())),
+            }
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_fields_from_the_default_prefix() -> Result<()> {
+        let template = LineTemplate::new("{timestamp} level={level} msg={sanitized}", None)?;
+        assert_eq!(
+            template.render("2024-05-01T12:00:00.123Z [ERROR] login failed for [EMAIL_REDACTED]"),
+            "2024-05-01T12:00:00.123Z level=ERROR msg=login failed for [EMAIL_REDACTED]"
+        );
+        assert_eq!(template.render("2024-05-01 12:00:00 started"), "2024-05-01 12:00:00 level= msg=started");
+        assert_eq!(template.render("no timestamp here"), "no timestamp here");
+        Ok(())
+    }
+
+    #[test]
+    fn custom_prefix_and_placeholder_checks() -> Result<()> {
+        let template = LineTemplate::new("{{\"pid\": {pid}}} {sanitized}", Some(r"^\[(?P<pid>\d+)\]\s*"))?;
+        assert_eq!(template.render("[42] ready"), "{\"pid\": 42} ready");
+
+        assert!(LineTemplate::new("{host} {sanitized}", None).unwrap_err().to_string().contains("Unknown placeholder '{host}'"));
+        assert!(LineTemplate::new("{timestamp", None).is_err());
+        assert!(LineTemplate::new("a } b", None).is_err());
+        Ok(())
+    }
+}
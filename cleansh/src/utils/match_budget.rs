@@ -0,0 +1,125 @@
+//! A memory budget for the findings a scan keeps.
+//!
+//! A scan keeps every `RedactionMatch` it finds until the summary is built, so an input
+//! with millions of matches could exhaust memory. `MatchStore` counts every finding per
+//! rule but keeps at most `--max-matches` of them (one million by default), chosen by
+//! reservoir sampling so the kept findings stay representative of the whole input. Counts,
+//! thresholds and history use the exact counts; only sample display and forwarding see
+//! the kept subset, and the output says when findings were dropped.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use cleansh_core::RedactionMatch;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Findings kept in memory when `--max-matches` is not given.
+pub const DEFAULT_MAX_MATCHES: usize = 1_000_000;
+
+/// Reported when a scan found more findings than it kept.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Truncation {
+    pub max_matches: usize,
+    pub total_matches: usize,
+}
+
+/// Per-rule counts of all findings and a bounded sample of the findings themselves.
+pub struct MatchStore {
+    max: usize,
+    kept: Vec<RedactionMatch>,
+    counts: HashMap<String, usize>,
+    seen: usize,
+    rng: StdRng,
+}
+
+impl MatchStore {
+    /// Creates a store keeping at most `max` findings, or [`DEFAULT_MAX_MATCHES`].
+    ///
+    /// The sample is drawn with `seed` (`--seed`), or with 0 when none is given, so the
+    /// same input keeps the same findings on every run either way.
+    pub fn new(max: Option<usize>, seed: Option<u64>) -> Self {
+        MatchStore {
+            max: max.unwrap_or(DEFAULT_MAX_MATCHES),
+            kept: Vec::new(),
+            counts: HashMap::new(),
+            seen: 0,
+            rng: StdRng::seed_from_u64(seed.unwrap_or(0)),
+        }
+    }
+
+    /// Counts `matches` and keeps them while within budget, then replaces kept findings
+    /// at random so that every finding has the same chance of being kept.
+    pub fn extend(&mut self, matches: impl IntoIterator<Item = RedactionMatch>) {
+        for m in matches {
+            *self.counts.entry(m.rule_name.clone()).or_default() += 1;
+            self.seen += 1;
+            if self.kept.len() < self.max {
+                self.kept.push(m);
+            } else {
+                let slot = self.rng.random_range(0..self.seen);
+                if slot < self.max {
+                    self.kept[slot] = m;
+                }
+            }
+        }
+    }
+
+    /// Number of findings counted.
+    pub fn total(&self) -> usize {
+        self.seen
+    }
+
+    /// Number of findings per rule, including those that were not kept.
+    pub fn counts(&self) -> &HashMap<String, usize> {
+        &self.counts
+    }
+
+    /// The kept findings.
+    pub fn matches(&self) -> &[RedactionMatch] {
+        &self.kept
+    }
+
+    /// Returns the truncation to report, if findings were dropped.
+    pub fn truncation(&self) -> Option<Truncation> {
+        (self.seen > self.kept.len()).then_some(Truncation { max_matches: self.max, total_matches: self.seen })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support;
+
+    fn finding(rule: &str, start: u64) -> RedactionMatch {
+        test_support::finding(rule, &format!("value{}", start), start)
+    }
+
+    #[test]
+    fn counts_everything_but_keeps_at_most_max() {
+        let mut store = MatchStore::new(Some(10), None);
+        store.extend((0..5).map(|i| finding("email", i)));
+        assert!(store.truncation().is_none());
+
+        store.extend((5..1000).map(|i| finding(if i % 2 == 0 { "email" } else { "jwt_token" }, i)));
+        assert_eq!(store.matches().len(), 10);
+        assert_eq!(store.total(), 1000);
+        assert_eq!(store.counts()["email"] + store.counts()["jwt_token"], 1000);
+        assert_eq!(store.truncation(), Some(Truncation { max_matches: 10, total_matches: 1000 }));
+        // The sample reaches beyond the first findings.
+        assert!(store.matches().iter().any(|m| m.start >= 10));
+    }
+
+    #[test]
+    fn seed_decides_which_findings_are_kept() {
+        let kept = |seed| {
+            let mut store = MatchStore::new(Some(10), seed);
+            store.extend((0..1000).map(|i| finding("email", i)));
+            store.matches().iter().map(|m| m.start).collect::<Vec<_>>()
+        };
+        assert_eq!(kept(Some(7)), kept(Some(7)));
+        assert_eq!(kept(None), kept(Some(0)));
+        assert_ne!(kept(Some(7)), kept(Some(8)));
+    }
+}
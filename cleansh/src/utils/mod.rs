@@ -3,4 +3,35 @@
 pub mod app_state;
 pub mod platform;
 pub mod clipboard;
-pub mod license;
\ No newline at end of file
+pub mod license;
+pub mod line_cache;
+pub mod crash_report;
+pub mod tokenize;
+pub mod post_process;
+pub mod siem;
+pub mod notifier;
+pub mod eml;
+pub mod runtime_signals;
+pub mod patch;
+pub mod dotenv;
+pub mod json_lines;
+pub mod pty;
+pub mod idle_read;
+pub mod follow;
+pub mod url_input;
+pub mod heatmap;
+pub mod encoding;
+pub mod time;
+pub mod rules_source;
+pub mod scan_checkpoint;
+pub mod scan_sampling;
+pub mod scan_history;
+pub mod match_budget;
+pub mod scan_cache;
+pub mod dir_walk;
+pub mod staged_diff;
+pub mod audit_trail;
+pub mod line_template;
+pub mod notifications;
+#[cfg(test)]
+pub mod test_support;
@@ -0,0 +1,188 @@
+//! Notifications shown once at the end of a run.
+//!
+//! Anything cleansh wants to tell the user outside the command's own output (a donation
+//! request, a newer release, a license about to expire, synced profiles that have gone
+//! stale) is a [`Notification`]. Each source is checked from local state only, so
+//! nothing here waits on the network. The due notifications are printed together after
+//! the command has finished, never as a question, and each kind is then snoozed for its
+//! own period in the app state, so it does not repeat on every run.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::commands::cleansh::info_msg;
+use crate::ui::output_format::{self, MessageKind};
+use crate::ui::theme::ThemeMap;
+use crate::utils::app_state::AppState;
+use crate::utils::license;
+use chrono::{DateTime, Duration, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File next to the app state holding the newest release version known to this machine.
+/// It is written by `cleansh sync-profiles` from the organization server's response.
+pub const LATEST_VERSION_FILE: &str = "latest_version";
+/// A license expiring within this many days is announced.
+const LICENSE_EXPIRY_WARNING_DAYS: i64 = 14;
+/// Synced profiles older than this many days are announced as outdated.
+const RULES_OUTDATED_DAYS: i64 = 30;
+
+/// The kinds of notification, each with its own snooze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    Donation,
+    UpdateAvailable,
+    LicenseExpiring,
+    RulesOutdated,
+}
+
+impl NotificationKind {
+    /// The key the kind's snooze is stored under in the app state.
+    pub fn key(self) -> &'static str {
+        match self {
+            NotificationKind::Donation => "donation",
+            NotificationKind::UpdateAvailable => "update_available",
+            NotificationKind::LicenseExpiring => "license_expiring",
+            NotificationKind::RulesOutdated => "rules_outdated",
+        }
+    }
+
+    /// How long the kind stays hidden after it was shown.
+    pub fn snooze(self) -> Duration {
+        match self {
+            NotificationKind::Donation => Duration::days(30),
+            NotificationKind::UpdateAvailable => Duration::days(7),
+            NotificationKind::LicenseExpiring => Duration::days(1),
+            NotificationKind::RulesOutdated => Duration::days(7),
+        }
+    }
+}
+
+/// One message for the end of the run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub message: String,
+}
+
+/// Where the notification sources look for their local state.
+#[derive(Debug, Clone)]
+pub struct Sources {
+    /// The app state file; the license token and latest version files sit next to it.
+    pub state_path: PathBuf,
+    /// Profiles written by `cleansh sync-profiles`, if any.
+    pub synced_profiles: Option<PathBuf>,
+    /// The version of this binary.
+    pub current_version: String,
+}
+
+impl Sources {
+    /// The sources of this installation.
+    pub fn for_state_file(state_path: &Path) -> Self {
+        Sources {
+            state_path: state_path.to_path_buf(),
+            synced_profiles: dirs::config_dir().map(|dir| dir.join("cleansh").join("profiles").join("synced_profiles.yaml")),
+            current_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Parses `major.minor.patch`, ignoring a leading `v` and any pre-release suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.trim().trim_start_matches('v').split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    Some((parts.next()??, parts.next().unwrap_or(Some(0))?, parts.next().unwrap_or(Some(0))?))
+}
+
+fn update_available(sources: &Sources) -> Option<Notification> {
+    let latest = fs::read_to_string(sources.state_path.parent()?.join(LATEST_VERSION_FILE)).ok()?;
+    let latest = latest.trim();
+    (parse_version(latest)? > parse_version(&sources.current_version)?).then(|| Notification {
+        kind: NotificationKind::UpdateAvailable,
+        message: format!("cleansh {} is available (you have {}).", latest, sources.current_version),
+    })
+}
+
+fn license_expiring(sources: &Sources, now: DateTime<Utc>) -> Option<Notification> {
+    let token = license::parse_and_verify_compact(&license::load_token_from_env_or_file(&sources.state_path)?).ok()?;
+    let days_left = (token.payload.expires_at - now).num_days();
+    (days_left < LICENSE_EXPIRY_WARNING_DAYS).then(|| Notification {
+        kind: NotificationKind::LicenseExpiring,
+        message: format!(
+            "Your cleansh license expires on {} ({} day(s) left).",
+            token.payload.expires_at.format("%Y-%m-%d"),
+            days_left
+        ),
+    })
+}
+
+fn rules_outdated(sources: &Sources, now: DateTime<Utc>) -> Option<Notification> {
+    let modified: DateTime<Utc> = fs::metadata(sources.synced_profiles.as_ref()?).ok()?.modified().ok()?.into();
+    let age = (now - modified).num_days();
+    (age >= RULES_OUTDATED_DAYS).then(|| Notification {
+        kind: NotificationKind::RulesOutdated,
+        message: format!("Your synced profiles were last updated {} days ago. Run `cleansh sync-profiles` to refresh them.", age),
+    })
+}
+
+/// Checks every source and returns the notifications that are due at `now`, snoozing each
+/// one returned in `app_state`.
+pub fn due(app_state: &mut AppState, sources: &Sources, now: DateTime<Utc>) -> Vec<Notification> {
+    let donation = app_state.donation_due().then(|| Notification {
+        kind: NotificationKind::Donation,
+        message: "Hello! If Cleansh has been useful to you, consider donating. We rely on community support to continue development. Please consider donating to help keep this project going: https://github.com/KarmaYama/cleansh-workspace".to_string(),
+    });
+    [update_available(sources), license_expiring(sources, now), rules_outdated(sources, now), donation]
+        .into_iter()
+        .flatten()
+        .filter(|notification| app_state.claim_notification(notification.kind.key(), notification.kind.snooze(), now))
+        .collect()
+}
+
+/// Prints the notifications due for this run, after the command's own output. Nothing is
+/// printed, and no snooze is used up, when the epilogue is off.
+pub fn show_due(app_state: &mut AppState, state_path: &Path, theme_map: &ThemeMap) {
+    if !output_format::should_print(MessageKind::Epilogue) {
+        return;
+    }
+    for notification in due(app_state, &Sources::for_state_file(state_path), Utc::now()) {
+        info_msg(notification.message, theme_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions() {
+        assert_eq!(parse_version("v0.2.0"), Some((0, 2, 0)));
+        assert_eq!(parse_version("1.4"), Some((1, 4, 0)));
+        assert_eq!(parse_version("0.1.9-rc.1"), Some((0, 1, 9)));
+        assert_eq!(parse_version("latest"), None);
+        assert!(parse_version("0.1.10") > parse_version("0.1.9"));
+    }
+
+    #[test]
+    fn each_kind_is_snoozed_separately() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let sources = Sources {
+            state_path: dir.path().join("state.json"),
+            synced_profiles: None,
+            current_version: "0.1.8".to_string(),
+        };
+        fs::write(dir.path().join(LATEST_VERSION_FILE), "0.2.0\n")?;
+        let mut state = AppState::new();
+        state.usage_count = 10;
+
+        let now = Utc::now();
+        let kinds = |notes: Vec<Notification>| notes.into_iter().map(|n| n.kind).collect::<Vec<_>>();
+        assert_eq!(kinds(due(&mut state, &sources, now)), [NotificationKind::UpdateAvailable, NotificationKind::Donation]);
+        assert!(due(&mut state, &sources, now + Duration::days(1)).is_empty());
+        // The update snooze ends long before the donation one.
+        assert_eq!(kinds(due(&mut state, &sources, now + Duration::days(8))), [NotificationKind::UpdateAvailable]);
+
+        state.donation_prompts_disabled = true;
+        assert_eq!(kinds(due(&mut state, &sources, now + Duration::days(40))), [NotificationKind::UpdateAvailable]);
+        Ok(())
+    }
+}
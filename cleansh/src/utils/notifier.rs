@@ -0,0 +1,162 @@
+// cleansh-workspace/cleansh/src/utils/notifier.rs
+//! Webhook notifications for `cleansh scan --fail-over-threshold`.
+//!
+//! When a scan exceeds its threshold in CI, the owning team can be told right away
+//! through a Slack or Microsoft Teams incoming webhook. The message is rendered from a
+//! small template with `{placeholder}` variables: per-rule counts from the scan, plus
+//! repository and job metadata read from well-known CI environment variables. Matched
+//! values are never included.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use crate::cli::NotifyFormat;
+
+/// The template used when `--notify-template` is not given.
+pub const DEFAULT_TEMPLATE: &str = "cleansh: {total} secret(s) detected in {repo} ({job}, {branch}), exceeding the fail-over threshold of {threshold}.\n{rules}\n{run_url}";
+
+/// Repository and job metadata for the current CI run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CiMetadata {
+    pub repo: Option<String>,
+    pub job: Option<String>,
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    pub run_url: Option<String>,
+}
+
+impl CiMetadata {
+    /// Reads CI metadata from the process environment.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok().filter(|v| !v.is_empty()))
+    }
+
+    /// Reads CI metadata using `lookup`, trying GitHub Actions, GitLab CI, and then
+    /// generic `CLEANSH_CI_*` variables for each field.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let first = |keys: &[&str]| keys.iter().find_map(|key| lookup(key));
+        let github_run_url = match (lookup("GITHUB_SERVER_URL"), lookup("GITHUB_REPOSITORY"), lookup("GITHUB_RUN_ID")) {
+            (Some(server), Some(repo), Some(run)) => Some(format!("{}/{}/actions/runs/{}", server, repo, run)),
+            _ => None,
+        };
+        Self {
+            repo: first(&["GITHUB_REPOSITORY", "CI_PROJECT_PATH", "CLEANSH_CI_REPO"]),
+            job: first(&["GITHUB_JOB", "CI_JOB_NAME", "CLEANSH_CI_JOB"]),
+            branch: first(&["GITHUB_REF_NAME", "CI_COMMIT_REF_NAME", "CLEANSH_CI_BRANCH"]),
+            commit: first(&["GITHUB_SHA", "CI_COMMIT_SHA", "CLEANSH_CI_COMMIT"]),
+            run_url: github_run_url.or_else(|| first(&["CI_JOB_URL", "CLEANSH_CI_RUN_URL"])),
+        }
+    }
+}
+
+/// Builds the template variables for a fail-over notification.
+pub fn template_vars(total: usize, threshold: usize, rule_counts: &HashMap<String, usize>, ci: &CiMetadata) -> HashMap<&'static str, String> {
+    // Highest counts first; ties broken by rule name so messages are stable.
+    let sorted: BTreeMap<(std::cmp::Reverse<usize>, &str), ()> = rule_counts
+        .iter()
+        .map(|(rule, count)| ((std::cmp::Reverse(*count), rule.as_str()), ()))
+        .collect();
+    let rules = sorted
+        .keys()
+        .map(|(count, rule)| format!("- {}: {}", rule, count.0))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let or_unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "unknown".to_string());
+    HashMap::from([
+        ("total", total.to_string()),
+        ("threshold", threshold.to_string()),
+        ("rules", rules),
+        ("repo", or_unknown(&ci.repo)),
+        ("job", or_unknown(&ci.job)),
+        ("branch", or_unknown(&ci.branch)),
+        ("commit", or_unknown(&ci.commit)),
+        ("run_url", ci.run_url.clone().unwrap_or_default()),
+    ])
+}
+
+/// Replaces each `{name}` in `template` with its value from `vars`.
+/// Unknown placeholders are left as-is.
+pub fn render_template(template: &str, vars: &HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}').and_then(|close| vars.get(&after[..close]).map(|value| (close, value))) {
+            Some((close, value)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim_end().to_string()
+}
+
+/// Wraps `text` in the JSON payload expected by the webhook `format`.
+pub fn build_payload(format: &NotifyFormat, text: &str) -> Value {
+    match format {
+        NotifyFormat::Slack => json!({ "text": text }),
+        NotifyFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": "cleansh fail-over threshold exceeded",
+            "themeColor": "D70000",
+            // Teams message cards need blank lines to render line breaks.
+            "text": text.replace('\n', "\n\n"),
+        }),
+    }
+}
+
+/// Posts `payload` to the webhook at `url`.
+pub fn send_webhook(url: &str, payload: &Value) -> Result<()> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .context("Failed to build HTTP client for webhook notification")?;
+    let response = client
+        .post(url)
+        .json(payload)
+        .send()
+        .context("Failed to connect to the notification webhook")?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Notification webhook returned HTTP status {}", response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_and_keeps_unknown_ones() {
+        let vars = HashMap::from([("total", "3".to_string()), ("repo", "org/app".to_string())]);
+        assert_eq!(render_template("{total} in {repo} {missing} {", &vars), "3 in org/app {missing} {");
+    }
+
+    #[test]
+    fn reads_github_metadata_and_sorts_rules_by_count() {
+        let env = HashMap::from([
+            ("GITHUB_REPOSITORY", "org/app"),
+            ("GITHUB_SERVER_URL", "https://github.com"),
+            ("GITHUB_RUN_ID", "42"),
+            ("CI_JOB_NAME", "gitlab-job"),
+        ]);
+        let ci = CiMetadata::from_lookup(|key| env.get(key).map(|v| v.to_string()));
+        assert_eq!(ci.run_url.as_deref(), Some("https://github.com/org/app/actions/runs/42"));
+        assert_eq!(ci.job.as_deref(), Some("gitlab-job"));
+
+        let counts = HashMap::from([("email".to_string(), 1), ("aws_access_key".to_string(), 4)]);
+        let vars = template_vars(5, 0, &counts, &ci);
+        assert_eq!(vars["rules"], "- aws_access_key: 4\n- email: 1");
+        assert_eq!(vars["branch"], "unknown");
+    }
+}
@@ -0,0 +1,358 @@
+// cleansh-workspace/cleansh/src/utils/patch.rs
+//! Unified diff handling for `cleansh sanitize --input-format diff`.
+//!
+//! Patches are shared with vendors and upstream projects all the time, and the lines
+//! they add or remove are exactly where a key or a password turns up. Sanitizing a
+//! patch as plain text can also rewrite its file paths and hunk headers, after which
+//! `git apply` refuses it. This module walks the patch instead: added lines are passed
+//! through a sanitizer, free text around the diffs (a `git format-patch` commit message,
+//! say) is sanitized as text, and everything that makes up the patch's structure is
+//! copied through. When a replacement collapses a multi-line secret, the hunk header is
+//! corrected to the new line count.
+//!
+//! Context and removed lines are left alone, since they have to match the file the patch
+//! is applied to; the caller is told how many of them contain a match.
+
+use anyhow::{anyhow, Result};
+
+/// Options for [`sanitize_patch`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PatchOptions {
+    /// Also sanitize file paths and the section headings after `@@ ... @@`.
+    pub headers: bool,
+}
+
+/// The result of sanitizing a patch.
+#[derive(Debug)]
+pub struct PatchOutput {
+    /// The re-emitted patch.
+    pub patch: String,
+    /// Context and removed lines that contain a match and were left unchanged.
+    pub unchanged_matches: usize,
+}
+
+/// Per-file header lines that carry a path after the given prefix.
+const PATH_HEADERS: &[&str] = &["--- ", "+++ ", "rename from ", "rename to ", "copy from ", "copy to "];
+
+/// Per-file header lines copied through unchanged.
+const OTHER_HEADERS: &[&str] = &[
+    "index ",
+    "old mode ",
+    "new mode ",
+    "deleted file mode ",
+    "new file mode ",
+    "similarity index ",
+    "dissimilarity index ",
+    "Binary files ",
+];
+
+/// Where the walk through the patch is.
+enum State {
+    /// Outside any file diff, or between the hunks of one.
+    Outside,
+    /// Inside a hunk, with the number of old and new lines still to come.
+    Hunk { old: usize, new: usize },
+    /// Inside a `GIT binary patch` block, which ends at the first empty line.
+    Binary,
+}
+
+/// Parses `-12,3` or `+7` from a hunk header into its line count.
+fn range_len(range: &str) -> Option<usize> {
+    match range[1..].split_once(',') {
+        Some((start, len)) => start.parse::<usize>().ok().and(len.parse().ok()),
+        None => range[1..].parse::<usize>().ok().map(|_| 1),
+    }
+}
+
+/// Splits `@@ -a,b +c,d @@ heading` into its old and new line counts and the byte offset
+/// where the heading starts.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let rest = line.strip_prefix("@@ ")?;
+    let end = rest.find(" @@")?;
+    let (old, new) = rest[..end].split_once(' ')?;
+    if !old.starts_with('-') || !new.starts_with('+') {
+        return None;
+    }
+    Some((range_len(old)?, range_len(new)?, "@@ ".len() + end + " @@".len()))
+}
+
+/// Splits a line into its text and its line ending.
+fn split_ending(line: &str) -> (&str, &str) {
+    let body = line.trim_end_matches(['\r', '\n']);
+    (body, &line[body.len()..])
+}
+
+/// Accumulates runs of lines that are sanitized together, so secrets spanning several
+/// lines (a private key, say) are still found.
+struct Run {
+    /// The marker each line of the run starts with: `+`, or empty for free text.
+    marker: &'static str,
+    lines: Vec<String>,
+    endings: Vec<String>,
+}
+
+impl Run {
+    fn new() -> Self {
+        Self { marker: "", lines: Vec::new(), endings: Vec::new() }
+    }
+
+    fn push(&mut self, marker: &'static str, body: &str, ending: &str, out: &mut Output, sanitize: &mut dyn FnMut(&str) -> Result<String>) -> Result<()> {
+        if marker != self.marker {
+            self.flush(out, sanitize)?;
+            self.marker = marker;
+        }
+        self.lines.push(body.to_string());
+        self.endings.push(ending.to_string());
+        Ok(())
+    }
+
+    /// Sanitizes the run and writes it to `out`. A replacement that swallows or adds line
+    /// breaks changes the number of lines; for added lines, the open hunk's header is
+    /// corrected when the hunk is written.
+    fn flush(&mut self, out: &mut Output, sanitize: &mut dyn FnMut(&str) -> Result<String>) -> Result<()> {
+        if self.lines.is_empty() {
+            return Ok(());
+        }
+        let sanitized = sanitize(&self.lines.join("\n"))?;
+        let last = self.lines.len() - 1;
+        let count = sanitized.split('\n').count();
+        for (index, line) in sanitized.split('\n').enumerate() {
+            // Lines a replacement added take the ending of the line they came from; the
+            // last line keeps the run's last ending, which may be none at end of input.
+            let ending = if index + 1 == count { &self.endings[last] } else { &self.endings[index.min(last)] };
+            let ending = if ending.is_empty() && index + 1 < count { "\n" } else { ending.as_str() };
+            out.push_str(self.marker);
+            out.push_str(line);
+            out.push_str(ending);
+        }
+        if self.marker == "+" {
+            out.add_new_lines(count);
+        }
+        self.lines.clear();
+        self.endings.clear();
+        Ok(())
+    }
+}
+
+/// A hunk whose lines are still being written, held back until its new line count is
+/// known.
+struct OpenHunk {
+    /// The header up to and including `+`, e.g. `@@ -1,3 +`.
+    old_part: String,
+    new_start: usize,
+    new_len: usize,
+    /// The header from ` @@` on, including the (possibly sanitized) heading and ending.
+    rest: String,
+    /// The original header, written as it was when the new range did not change.
+    original: String,
+    lines: String,
+    /// New-side lines written so far.
+    new_lines: usize,
+}
+
+/// The re-emitted patch, with the hunk being written held back.
+struct Output {
+    patch: String,
+    hunk: Option<OpenHunk>,
+    /// How many lines the hunks of the current file have gained or lost so far, which
+    /// moves the new start of the hunks after them.
+    shift: isize,
+}
+
+impl Output {
+    fn push_str(&mut self, text: &str) {
+        match self.hunk.as_mut() {
+            Some(hunk) => hunk.lines.push_str(text),
+            None => self.patch.push_str(text),
+        }
+    }
+
+    fn add_new_lines(&mut self, count: usize) {
+        if let Some(hunk) = self.hunk.as_mut() {
+            hunk.new_lines += count;
+        }
+    }
+
+    /// Writes the open hunk, with its new range corrected for lines a replacement
+    /// collapsed or added.
+    fn close_hunk(&mut self) {
+        let Some(hunk) = self.hunk.take() else { return };
+        let new_start = hunk.new_start.saturating_add_signed(self.shift);
+        if new_start == hunk.new_start && hunk.new_lines == hunk.new_len {
+            self.patch.push_str(&hunk.original);
+        } else {
+            self.patch.push_str(&format!("{}{},{}{}", hunk.old_part, new_start, hunk.new_lines, hunk.rest));
+        }
+        self.patch.push_str(&hunk.lines);
+        self.shift += hunk.new_lines as isize - hunk.new_len as isize;
+    }
+}
+
+/// Sanitizes the added lines of a unified diff, keeping it applyable.
+///
+/// `sanitize` is called with the text of a run of added lines or a block of free text,
+/// without the `+` markers; `has_match` is called for each context and removed line,
+/// which are copied unchanged. With [`PatchOptions::headers`], paths and hunk section
+/// headings are passed to `sanitize` as well.
+///
+/// # Errors
+///
+/// Returns an error if the input contains no file diff, if a hunk ends early, or if
+/// `sanitize` fails.
+pub fn sanitize_patch(
+    patch: &str,
+    options: PatchOptions,
+    sanitize: &mut dyn FnMut(&str) -> Result<String>,
+    has_match: &mut dyn FnMut(&str) -> Result<bool>,
+) -> Result<PatchOutput> {
+    let mut out = Output { patch: String::with_capacity(patch.len()), hunk: None, shift: 0 };
+    let mut run = Run::new();
+    let mut state = State::Outside;
+    let mut hunks = 0;
+    let mut unchanged_matches = 0;
+
+    for (number, line) in (1..).zip(patch.split_inclusive('\n')) {
+        let (body, ending) = split_ending(line);
+        match state {
+            State::Hunk { old, new } => {
+                let (marker, old_used, new_used) = match body.chars().next() {
+                    // Some tools strip the space from empty context lines.
+                    Some(' ') | None => ("", 1, 1),
+                    Some('-') => ("-", 1, 0),
+                    Some('+') => ("+", 0, 1),
+                    // `\ No newline at end of file` belongs to the previous line.
+                    Some('\\') => ("\\", 0, 0),
+                    Some(_) => return Err(anyhow!("Hunk ended early at line {}", number)),
+                };
+                if old < old_used || new < new_used {
+                    return Err(anyhow!("Hunk has more lines than its header says at line {}", number));
+                }
+                if marker == "+" {
+                    run.push(marker, &body[1..], ending, &mut out, sanitize)?;
+                } else {
+                    run.flush(&mut out, sanitize)?;
+                    if marker != "\\" && has_match(body.get(1..).unwrap_or(""))? {
+                        unchanged_matches += 1;
+                    }
+                    out.push_str(line);
+                    out.add_new_lines(new_used);
+                }
+                state = match (old - old_used, new - new_used) {
+                    (0, 0) => State::Outside,
+                    (old, new) => State::Hunk { old, new },
+                };
+                continue;
+            }
+            State::Binary => {
+                out.push_str(line);
+                if body.is_empty() {
+                    state = State::Outside;
+                }
+                continue;
+            }
+            State::Outside => {}
+        }
+
+        // A `\ No newline` marker can follow the last line of a hunk.
+        if body.starts_with('\\') && hunks > 0 {
+            run.flush(&mut out, sanitize)?;
+            out.push_str(line);
+            continue;
+        }
+        run.flush(&mut out, sanitize)?;
+        out.close_hunk();
+        if let Some((old, new, heading)) = parse_hunk_header(body) {
+            hunks += 1;
+            let plus = body.find(" +").map_or(0, |at| at + 2);
+            let new_start = body[plus..].split([',', ' ']).next().and_then(|start| start.parse().ok()).unwrap_or(0);
+            let mut rest = body[plus..heading].trim_start_matches(|c: char| c.is_ascii_digit() || c == ',').to_string();
+            let mut original = body[..heading].to_string();
+            let heading_text = if options.headers && heading < body.len() { sanitize(&body[heading..])? } else { body[heading..].to_string() };
+            for part in [&mut rest, &mut original] {
+                part.push_str(&heading_text);
+                part.push_str(ending);
+            }
+            out.hunk = Some(OpenHunk { old_part: body[..plus].to_string(), new_start, new_len: new, rest, original, lines: String::new(), new_lines: 0 });
+            if old > 0 || new > 0 {
+                state = State::Hunk { old, new };
+            }
+        } else if let Some(paths) = body.strip_prefix("diff ").filter(|paths| paths.starts_with('-')) {
+            out.shift = 0;
+            out.push_str("diff ");
+            out.push_str(&if options.headers { sanitize(paths)? } else { paths.to_string() });
+            out.push_str(ending);
+        } else if let Some(prefix) = PATH_HEADERS.iter().find(|prefix| body.starts_with(**prefix)) {
+            if *prefix == "--- " {
+                out.shift = 0;
+            }
+            out.push_str(prefix);
+            let path = &body[prefix.len()..];
+            out.push_str(&if options.headers { sanitize(path)? } else { path.to_string() });
+            out.push_str(ending);
+        } else if OTHER_HEADERS.iter().any(|prefix| body.starts_with(prefix)) {
+            out.push_str(line);
+        } else if body == "GIT binary patch" {
+            out.push_str(line);
+            state = State::Binary;
+        } else {
+            run.push("", body, ending, &mut out, sanitize)?;
+        }
+    }
+    if let State::Hunk { .. } = state {
+        return Err(anyhow!("The last hunk is shorter than its header says"));
+    }
+    run.flush(&mut out, sanitize)?;
+    out.close_hunk();
+
+    if hunks == 0 && !patch.lines().any(|line| line.starts_with("diff -")) {
+        return Err(anyhow!("The input contains no unified diff hunks"));
+    }
+    Ok(PatchOutput { patch: out.patch, unchanged_matches })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact_secret(text: &str) -> Result<String> {
+        Ok(text.replace("hunter2", "[PASSWORD]").replace("src/secret", "src/[PATH]"))
+    }
+
+    fn contains_secret(text: &str) -> Result<bool> {
+        Ok(text.contains("hunter2"))
+    }
+
+    const PATCH: &str = "From 1234 Mon Sep 17 00:00:00 2001\nSubject: [PATCH] rotate hunter2\n---\n\
+diff --git a/src/secret.rs b/src/secret.rs\nindex 83db48f..bf269f4 100644\n--- a/src/secret.rs\n+++ b/src/secret.rs\n\
+@@ -1,3 +1,3 @@ fn hunter2()\n keep hunter2 here\n-let pw = \"hunter2\";\n+let pw = env(\"PW\");\n \n\
+@@ -9 +9 @@\n-old hunter2\n\\ No newline at end of file\n+new\n";
+
+    #[test]
+    fn sanitizes_content_lines_but_not_structure() {
+        let out = sanitize_patch(PATCH, PatchOptions::default(), &mut redact_secret, &mut contains_secret).unwrap();
+        assert_eq!(out.unchanged_matches, 3);
+        assert_eq!(
+            out.patch,
+            PATCH.replacen("rotate hunter2", "rotate [PASSWORD]", 1)
+        );
+
+        let out = sanitize_patch(PATCH, PatchOptions { headers: true }, &mut redact_secret, &mut contains_secret).unwrap();
+        assert!(out.patch.contains("\ndiff --git a/src/[PATH].rs b/src/[PATH].rs\n"), "{}", out.patch);
+        assert!(out.patch.contains("\n+++ b/src/[PATH].rs\n@@ -1,3 +1,3 @@ fn [PASSWORD]()\n"), "{}", out.patch);
+    }
+
+    #[test]
+    fn corrects_hunk_headers_when_a_secret_spans_lines() {
+        let patch = "--- a/k\n+++ b/k\n@@ -0,0 +1,3 @@\n+BEGIN\n+abc\n+END\n@@ -5 +8 @@ main\n-x\n+y\n";
+        let mut collapse = |text: &str| Ok(text.replace("BEGIN\nabc\nEND", "[KEY]"));
+        let out = sanitize_patch(patch, PatchOptions::default(), &mut collapse, &mut contains_secret).unwrap();
+        assert_eq!(out.patch, "--- a/k\n+++ b/k\n@@ -0,0 +1,1 @@\n+[KEY]\n@@ -5 +6,1 @@ main\n-x\n+y\n");
+    }
+
+    #[test]
+    fn rejects_text_and_truncated_hunks() {
+        assert!(sanitize_patch("just text\n", PatchOptions::default(), &mut redact_secret, &mut contains_secret).is_err());
+        let truncated = "--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n-a\n";
+        assert!(sanitize_patch(truncated, PatchOptions::default(), &mut redact_secret, &mut contains_secret).is_err());
+    }
+}
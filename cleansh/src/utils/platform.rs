@@ -18,4 +18,136 @@ pub fn eof_key_combo() -> &'static str {
     } else {
         "Ctrl+D"
     }
-}
\ No newline at end of file
+}
+
+/// Returns a stable SHA-256 fingerprint of the current machine and user account.
+///
+/// The fingerprint mixes the OS machine identifier (`/etc/machine-id` on Linux, when
+/// available) with the Windows computer name, the user's home directory, and the target
+/// OS. Only values that do not depend on how the shell was started are used. It is used to
+/// bind the encrypted application state to the machine that wrote it, so it only needs to
+/// be stable, not secret.
+///
+/// # Returns
+///
+/// The 32-byte digest of all identifiers that could be read.
+pub fn machine_fingerprint() -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .unwrap_or_default();
+    let computer_name = if cfg!(windows) { std::env::var("COMPUTERNAME").unwrap_or_default() } else { String::new() };
+    let home = dirs::home_dir().map(|p| p.display().to_string()).unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"cleansh-machine-fingerprint-v1");
+    for part in [
+        machine_id.as_str(),
+        &computer_name,
+        &home,
+        std::env::consts::OS,
+    ] {
+        // Length-prefix each part so that adjacent values cannot run together.
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// Opens the terminal the process was started from for reading, independently of where
+/// stdin is redirected: `/dev/tty` on Unix, `CONIN$` on Windows.
+///
+/// # Errors
+///
+/// Fails when the process has no terminal, as under CI or a service manager.
+pub fn open_terminal() -> std::io::Result<std::fs::File> {
+    let name = if cfg!(windows) { "CONIN$" } else { "/dev/tty" };
+    std::fs::OpenOptions::new().read(true).write(cfg!(windows)).open(name)
+}
+
+/// Returns `true` if `a` and `b` name the same existing file, even through different
+/// paths, symbolic links or hard links.
+pub fn is_same_file(a: &std::path::Path, b: &std::path::Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => (a.dev(), a.ino()) == (b.dev(), b.ino()),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Returns `true` if stdin is redirected from the file at `path` (`cleansh ... < path`).
+///
+/// Only detectable on Unix, where `/dev/stdin` resolves to the redirected file.
+pub fn stdin_is_file(path: &std::path::Path) -> bool {
+    #[cfg(unix)]
+    {
+        is_same_file(std::path::Path::new("/dev/stdin"), path)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Length from which Windows file APIs refuse paths without the extended-length prefix.
+#[cfg(windows)]
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Adds the extended-length prefix to an absolute Windows path, so that file APIs accept
+/// it beyond `MAX_PATH`: `C:\dir` becomes `\\?\C:\dir` and the UNC share path
+/// `\\server\share\dir` becomes `\\?\UNC\server\share\dir`. Forward slashes are turned into
+/// backslashes, since prefixed paths are passed to the file system as they are.
+///
+/// Paths that already carry a `\\?\` or `\\.\` prefix, and relative paths, are returned
+/// unchanged. The conversion is plain string handling, so it can be checked on any
+/// platform; [`long_path`] applies it where it matters.
+pub fn windows_extended_path(path: &str) -> String {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return path.to_string();
+    }
+    let normalized = path.replace('/', "\\");
+    if let Some(share) = normalized.strip_prefix(r"\\") {
+        return format!(r"\\?\UNC\{}", share);
+    }
+    let bytes = normalized.as_bytes();
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        return format!(r"\\?\{}", normalized);
+    }
+    path.to_string()
+}
+
+/// Returns `path` in a form the file APIs of this platform accept whatever its length.
+///
+/// On Windows, a path whose absolute form reaches `MAX_PATH` (260 characters) is made
+/// absolute, with `.` and `..` resolved, and given the extended-length prefix (see
+/// [`windows_extended_path`]); this covers both drive paths and UNC shares. Shorter paths
+/// and paths on other platforms are returned unchanged, so messages keep showing the path
+/// as the user wrote it.
+pub fn long_path(path: &std::path::Path) -> std::path::PathBuf {
+    #[cfg(windows)]
+    {
+        // Names that are not valid Unicode are left alone rather than altered.
+        match std::path::absolute(path).ok().as_deref().and_then(std::path::Path::to_str) {
+            Some(text) if text.len() >= WINDOWS_MAX_PATH => std::path::PathBuf::from(windows_extended_path(text)),
+            _ => path.to_path_buf(),
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
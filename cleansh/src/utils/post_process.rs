@@ -0,0 +1,140 @@
+// cleansh-workspace/cleansh/src/utils/post_process.rs
+//! Post-processing of sanitized output for `cleansh sanitize --post-process`.
+//!
+//! A post-processor sees the sanitized output of each input, together with its redaction
+//! summary, just before it is written, and returns the text that is written instead. It
+//! can append an attestation footer, or turn placeholders such as `[EMAIL_REDACTED]`
+//! into an organization's own markers, without touching the output logic itself.
+//!
+//! The summary handed over holds rule names and counts only, never the original values,
+//! so a post-processor cannot undo the redaction.
+
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::RedactionSummaryItem;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+/// Environment variable holding the summary, as JSON, for `--post-process` commands.
+pub const SUMMARY_ENV: &str = "CLEANSH_SUMMARY";
+/// Environment variable holding the total number of redactions for `--post-process` commands.
+pub const REDACTIONS_ENV: &str = "CLEANSH_REDACTIONS";
+
+/// Rewrites sanitized output before it is written.
+pub trait PostProcessor {
+    /// A short, human-readable name for the post-processor, used in messages.
+    fn name(&self) -> &str;
+
+    /// Returns the text to write in place of `sanitized`.
+    fn process(&self, sanitized: &str, summary: &[RedactionSummaryItem]) -> Result<String>;
+}
+
+/// One rule's line in the summary a post-processor receives.
+#[derive(Debug, Serialize)]
+struct SummaryCount<'a> {
+    rule: &'a str,
+    occurrences: usize,
+}
+
+/// Renders `summary` as the JSON in [`SUMMARY_ENV`]: rule names and counts only.
+pub fn summary_json(summary: &[RedactionSummaryItem]) -> Result<String> {
+    let counts: Vec<SummaryCount> = summary
+        .iter()
+        .map(|item| SummaryCount { rule: &item.rule_name, occurrences: item.occurrences })
+        .collect();
+    serde_json::to_string(&counts).context("Failed to serialize the redaction summary")
+}
+
+/// Runs a shell command as the post-processor: the sanitized output goes to its stdin, the
+/// summary into [`SUMMARY_ENV`] and [`REDACTIONS_ENV`], and its stdout is written instead.
+/// A command that fails stops the run, so nothing is written.
+///
+/// Like any text piped between commands, the input ends with a newline; one trailing
+/// newline is taken off the command's output again, since the writer adds its own.
+pub struct CommandPostProcessor {
+    command: String,
+}
+
+impl CommandPostProcessor {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self { command: command.into() }
+    }
+
+    fn shell(&self) -> Command {
+        if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C").arg(&self.command);
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c").arg(&self.command);
+            shell
+        }
+    }
+}
+
+impl PostProcessor for CommandPostProcessor {
+    fn name(&self) -> &str {
+        &self.command
+    }
+
+    fn process(&self, sanitized: &str, summary: &[RedactionSummaryItem]) -> Result<String> {
+        let total: usize = summary.iter().map(|item| item.occurrences).sum();
+        let mut child = self
+            .shell()
+            .env(SUMMARY_ENV, summary_json(summary)?)
+            .env(REDACTIONS_ENV, total.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start the post-process command '{}'", self.command))?;
+
+        let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Post-process command has no stdin"))?;
+        let mut stdout = child.stdout.take().ok_or_else(|| anyhow!("Post-process command has no stdout"))?;
+        // Feed stdin from another thread, so a command that writes before it has read
+        // everything cannot block on a full pipe.
+        let input = if sanitized.ends_with('\n') { sanitized.to_string() } else { format!("{}\n", sanitized) };
+        let output = std::thread::scope(|scope| {
+            let writer = scope.spawn(move || stdin.write_all(input.as_bytes()));
+            let mut output = Vec::new();
+            let read = stdout.read_to_end(&mut output);
+            let written = writer.join().map_err(|_| anyhow!("Writing to the post-process command panicked"))?;
+            // A command that stops reading early (`head`, say) closes the pipe; that is not an error.
+            if let Err(e) = written
+                && e.kind() != std::io::ErrorKind::BrokenPipe
+            {
+                return Err(e).context("Failed to write to the post-process command");
+            }
+            read.context("Failed to read the post-process command's output")?;
+            Ok::<_, anyhow::Error>(output)
+        })?;
+
+        let status = child.wait().context("Failed to wait for the post-process command")?;
+        if !status.success() {
+            return Err(anyhow!("Post-process command '{}' failed ({}); nothing was written", self.command, status));
+        }
+        let mut output = String::from_utf8(output)
+            .map_err(|_| anyhow!("Post-process command '{}' wrote output that is not UTF-8", self.command))?;
+        if output.ends_with('\n') {
+            output.pop();
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_json_carries_counts_but_no_values() -> Result<()> {
+        let summary = [RedactionSummaryItem {
+            rule_name: "email".to_string(),
+            occurrences: 2,
+            original_texts: vec!["alice@example.com".to_string()],
+            sanitized_texts: vec!["[EMAIL_REDACTED]".to_string()],
+        }];
+        assert_eq!(summary_json(&summary)?, r#"[{"rule":"email","occurrences":2}]"#);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+// cleansh-workspace/cleansh/src/utils/pty.rs
+//! Pseudo-terminals for `cleansh exec --pty`.
+//!
+//! Many tools only print colors, progress bars or their interactive layout when their
+//! output is a terminal, and some buffer their output until exit when it is a pipe. A
+//! pseudo-terminal gives such a tool a terminal to write to while cleansh reads, and
+//! sanitizes, what it writes from the other side.
+//!
+//! Output post-processing is turned off on the terminal, so line breaks arrive as the
+//! tool wrote them instead of as `\r\n`.
+
+use std::fs::File;
+use std::io;
+
+/// The two ends of a pseudo-terminal.
+#[derive(Debug)]
+pub struct Pty {
+    /// The side cleansh reads from.
+    pub master: File,
+    /// The side handed to the child as its stdout or stderr.
+    pub slave: File,
+}
+
+/// Opens a pseudo-terminal with the window size of cleansh's own stdout, if that is a
+/// terminal, so the child lays its output out for the real screen.
+#[cfg(unix)]
+pub fn open() -> io::Result<Pty> {
+    use std::os::fd::FromRawFd;
+
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: TIOCGWINSZ only writes a `winsize` into the struct passed.
+    let have_size = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    let size_ptr: *mut libc::winsize = if have_size { &mut size } else { std::ptr::null_mut() };
+
+    let (mut master, mut slave) = (-1, -1);
+    // SAFETY: openpty writes two file descriptors; the name buffer and termios are not used.
+    if unsafe { libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null_mut(), size_ptr as *mut _) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: both descriptors were just opened and are owned by nobody else.
+    let pty = unsafe { Pty { master: File::from_raw_fd(master), slave: File::from_raw_fd(slave) } };
+
+    for fd in [master, slave] {
+        // Keep the descriptors out of the child: the child gets the slave through its
+        // stdio, and the master must close when cleansh is done with it.
+        // SAFETY: fcntl on descriptors owned by `pty`.
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    // SAFETY: tcgetattr/tcsetattr on the slave descriptor owned by `pty`.
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(slave, &mut termios) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        termios.c_oflag &= !libc::OPOST;
+        if libc::tcsetattr(slave, libc::TCSANOW, &termios) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(pty)
+}
+
+/// Pseudo-terminals are only available on Unix.
+#[cfg(not(unix))]
+pub fn open() -> io::Result<Pty> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "pseudo-terminals are not supported on this platform"))
+}
+
+/// Whether a read error from the master side means the child has closed the terminal.
+/// Linux reports that as `EIO` rather than as end of file.
+pub fn is_closed(error: &io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::EIO)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = error;
+        false
+    }
+}
@@ -0,0 +1,52 @@
+//! Loading user rule files given with `--config`, where `-` means the rules are read
+//! from stdin. Wrapper scripts and CI jobs can then generate a rule set on the fly and
+//! pipe it in without a temporary file, as long as the content to sanitize comes from a
+//! file.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::config::{RedactionConfig, SkippedRule};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Returns `true` if `path` is `-`, the stdin placeholder.
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Loads the rules in `path`, or from stdin if `path` is `-`.
+pub fn load_user_config(path: &Path) -> Result<RedactionConfig> {
+    if !is_stdin(path) {
+        return RedactionConfig::load_from_file(path)
+            .with_context(|| format!("Failed to load user-defined configuration file {}", path.display()));
+    }
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text).context("Failed to read rules from stdin")?;
+    RedactionConfig::from_yaml_str(&text).context("Failed to load rules read from stdin (--config -)")
+}
+
+/// Like [`load_user_config`], but leaves out the rules that are invalid or do not compile
+/// and returns them (`--skip-invalid-rules`).
+pub fn load_user_config_skipping_invalid(path: &Path) -> Result<(RedactionConfig, Vec<SkippedRule>)> {
+    if !is_stdin(path) {
+        return RedactionConfig::load_from_file_skipping_invalid(path)
+            .with_context(|| format!("Failed to load user-defined configuration file {}", path.display()));
+    }
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text).context("Failed to read rules from stdin")?;
+    RedactionConfig::from_yaml_str_skipping_invalid(&text).context("Failed to load rules read from stdin (--config -)")
+}
+
+/// Rejects `--config -` when the input would also be read from stdin, or when it is given
+/// more than once.
+pub fn ensure_stdin_available(configs: &[PathBuf], input_from_stdin: bool) -> Result<()> {
+    match configs.iter().filter(|path| is_stdin(path)).count() {
+        0 => Ok(()),
+        1 if input_from_stdin => Err(anyhow!(
+            "--config - reads the rules from stdin, so the input must come from a file rather than stdin"
+        )),
+        1 => Ok(()),
+        _ => Err(anyhow!("--config - can only be given once, since stdin can only be read once")),
+    }
+}
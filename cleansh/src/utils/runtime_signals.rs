@@ -0,0 +1,166 @@
+// cleansh-workspace/cleansh/src/utils/runtime_signals.rs
+//! Runtime control of the long-running modes through signals: `sanitize --line-buffered`
+//! (and `--follow`) and `stats serve`.
+//!
+//! Once [`watch`] has been called, `SIGUSR1` switches debug logging on or off without a
+//! restart, and `SIGUSR2` prints a status snapshot to stderr: the mode, the rules loaded
+//! and what has been processed so far. The signal handlers only set a flag; a background
+//! thread acts on it, so a snapshot is printed even while the mode waits for input.
+//! Signals exist only on Unix; elsewhere [`watch`] does nothing.
+
+use crate::logger;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often the background thread checks for received signals.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Set by `SIGUSR1`.
+static TOGGLE_DEBUG: AtomicBool = AtomicBool::new(false);
+
+/// Set by `SIGUSR2`.
+static DUMP_STATUS: AtomicBool = AtomicBool::new(false);
+
+/// Set by the first `SIGINT` once [`stop_on_interrupt`] has been called.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Guards the installation of the handlers and the start of the background thread.
+#[cfg(unix)]
+static WATCH: std::sync::Once = std::sync::Once::new();
+
+/// What a long-running mode reports in a status snapshot.
+#[derive(Debug)]
+pub struct RuntimeStatus {
+    mode: &'static str,
+    /// Number of rules loaded, for the modes that sanitize.
+    rules: Option<usize>,
+    /// What `processed` counts, such as `lines` or `requests`.
+    unit: &'static str,
+    started: Instant,
+    processed: AtomicU64,
+    findings: AtomicU64,
+}
+
+impl RuntimeStatus {
+    /// Status of a mode that sanitizes with `rules` rules and counts processed lines.
+    pub fn sanitizing(mode: &'static str, rules: usize) -> Arc<Self> {
+        Self::new(mode, Some(rules), "lines")
+    }
+
+    /// Status of a mode that serves requests.
+    pub fn serving(mode: &'static str) -> Arc<Self> {
+        Self::new(mode, None, "requests")
+    }
+
+    fn new(mode: &'static str, rules: Option<usize>, unit: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            mode,
+            rules,
+            unit,
+            started: Instant::now(),
+            processed: AtomicU64::new(0),
+            findings: AtomicU64::new(0),
+        })
+    }
+
+    /// Counts one processed line or request and the findings it produced.
+    pub fn record(&self, findings: usize) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.findings.fetch_add(findings as u64, Ordering::Relaxed);
+    }
+
+    /// The one-line snapshot printed for `SIGUSR2`.
+    pub fn snapshot(&self) -> String {
+        let mut parts = vec![format!("mode {}", self.mode)];
+        if let Some(rules) = self.rules {
+            parts.push(format!("{} rules loaded", rules));
+        }
+        parts.push(format!("{} {} processed", self.processed.load(Ordering::Relaxed), self.unit));
+        if self.rules.is_some() {
+            parts.push(format!("{} findings", self.findings.load(Ordering::Relaxed)));
+        }
+        parts.push(format!("debug logging {}", if logger::debug_toggled() { "on" } else { "off" }));
+        parts.push(format!("running for {}s", self.started.elapsed().as_secs()));
+        format!("cleansh status: {}", parts.join(", "))
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    if signal == libc::SIGUSR1 {
+        TOGGLE_DEBUG.store(true, Ordering::SeqCst);
+    } else {
+        DUMP_STATUS.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    // A second Ctrl-C ends the process as usual.
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+    }
+}
+
+/// Makes the first `SIGINT` (Ctrl-C) ask a mode that would otherwise run forever to
+/// finish, so that it can still print its summary; check for it with [`interrupted`].
+/// Does nothing outside Unix, where Ctrl-C ends the process right away.
+pub fn stop_on_interrupt() {
+    #[cfg(unix)]
+    unsafe {
+        let handler = on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+    }
+}
+
+/// Returns `true` once a `SIGINT` has been received after [`stop_on_interrupt`].
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Installs the `SIGUSR1` and `SIGUSR2` handlers and starts the thread that acts on them,
+/// reporting `status`. Only the first call in a process does this; later calls do nothing.
+/// Does nothing outside Unix.
+pub fn watch(status: Arc<RuntimeStatus>) {
+    #[cfg(unix)]
+    WATCH.call_once(move || {
+        unsafe {
+            let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            libc::signal(libc::SIGUSR1, handler);
+            libc::signal(libc::SIGUSR2, handler);
+        }
+        std::thread::spawn(move || loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if TOGGLE_DEBUG.swap(false, Ordering::SeqCst) {
+                let on = logger::toggle_debug();
+                let _ = writeln!(io::stderr(), "cleansh: debug logging switched {}.", if on { "on" } else { "off" });
+            }
+            if DUMP_STATUS.swap(false, Ordering::SeqCst) {
+                let _ = writeln!(io::stderr(), "{}", status.snapshot());
+            }
+        });
+    });
+    #[cfg(not(unix))]
+    drop(status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_reports_counters() {
+        let status = RuntimeStatus::sanitizing("line-buffered", 12);
+        status.record(2);
+        status.record(0);
+        let snapshot = status.snapshot();
+        assert!(snapshot.starts_with("cleansh status: mode line-buffered, 12 rules loaded, 2 lines processed, 2 findings, debug logging off"), "{}", snapshot);
+
+        let serving = RuntimeStatus::serving("stats serve");
+        serving.record(0);
+        assert!(serving.snapshot().starts_with("cleansh status: mode stats serve, 1 requests processed, debug logging"), "{}", serving.snapshot());
+    }
+}
@@ -0,0 +1,214 @@
+//! Content-addressed cache of per-file scan findings.
+//!
+//! Repeated scans of a large repository mostly re-read files that have not changed. For
+//! every file scanned from a `--files-from` list, `scan` stores the file's findings under
+//! the data directory, keyed by the SHA-256 of the file's content and a hash of the
+//! effective configuration (rules, engine options and cleansh version). A later scan with
+//! the same configuration reuses the findings of unchanged files instead of running the
+//! rules on them again. `--no-cache` bypasses the cache.
+//!
+//! Like checkpoints, cached findings are stored without their original text, so samples
+//! shown for cached files (`--sample-matches`) display the rule's replacement. When a rule
+//! is limited to certain `paths`, the file's path is part of the key as well, since the
+//! same content can then have different findings in different places.
+//!
+//! The cache is bounded: after every scan that used it, entries not used for
+//! [`MAX_ENTRY_AGE`] are removed, including those of configurations no longer in use, and
+//! then the least recently used entries until the cache is at most [`MAX_CACHE_BYTES`].
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::utils::scan_checkpoint::scrub;
+use anyhow::{Context, Result};
+use cleansh_core::engine::SanitizationEngine;
+use cleansh_core::RedactionMatch;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Name of the cache directory, next to the app state file.
+const CACHE_DIRNAME: &str = "scan-cache";
+
+/// Format version written to, and required of, cache entries.
+const CACHE_VERSION: u32 = 1;
+
+/// Entries not used for this long are removed.
+pub const MAX_ENTRY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Total size of the entries the cache is pruned down to.
+pub const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Returns the cache directory belonging to the app state file at `state_path`.
+pub fn cache_dir(state_path: &Path) -> PathBuf {
+    state_path.with_file_name(CACHE_DIRNAME)
+}
+
+/// The cached findings of one file.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    version: u32,
+    findings: Vec<RedactionMatch>,
+}
+
+/// The cache entries of one effective configuration.
+pub struct ScanCache {
+    root: PathBuf,
+    dir: PathBuf,
+    path_scoped: bool,
+    hits: usize,
+}
+
+impl ScanCache {
+    /// Opens the part of the cache at `root` that belongs to `engine`'s configuration.
+    pub fn new(root: &Path, engine: &dyn SanitizationEngine) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(serde_json::to_vec(engine.get_rules()).context("Failed to hash the rule configuration")?);
+        hasher.update(serde_json::to_vec(engine.get_options()).context("Failed to hash the engine options")?);
+        Ok(ScanCache {
+            root: root.to_path_buf(),
+            dir: root.join(hex::encode(hasher.finalize())),
+            path_scoped: engine.get_rules().rules.iter().any(|rule| rule.paths.is_some()),
+            hits: 0,
+        })
+    }
+
+    fn entry_path(&self, source: &str, content: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        if self.path_scoped {
+            hasher.update([0]);
+            hasher.update(source.as_bytes());
+        }
+        self.dir.join(format!("{}.json", hex::encode(hasher.finalize())))
+    }
+
+    /// Returns the cached findings for `content`, attributed to `source`, if there are any.
+    pub fn get(&mut self, source: &str, content: &str) -> Option<Vec<RedactionMatch>> {
+        let path = self.entry_path(source, content);
+        let bytes = fs::read(&path).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&bytes).ok().filter(|e: &CacheEntry| e.version == CACHE_VERSION)?;
+        self.hits += 1;
+        // The modification time records when an entry was last used, for pruning.
+        if let Err(e) = fs::File::options().write(true).open(&path).and_then(|file| file.set_modified(SystemTime::now())) {
+            debug!("Failed to mark scan cache entry {} as used: {}", path.display(), e);
+        }
+        Some(entry.findings.into_iter().map(|m| RedactionMatch { source_id: source.to_string(), ..m }).collect())
+    }
+
+    /// Stores the findings of `content`. A cache that cannot be written only costs speed,
+    /// so failures are logged rather than returned.
+    pub fn put(&self, source: &str, content: &str, matches: &[RedactionMatch]) {
+        let path = self.entry_path(source, content);
+        let entry = CacheEntry { version: CACHE_VERSION, findings: matches.iter().map(scrub).collect() };
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|_| {
+                let tmp = path.with_extension("tmp");
+                fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+                fs::rename(&tmp, &path)
+            });
+        if let Err(e) = result {
+            debug!("Failed to write scan cache entry {}: {}", path.display(), e);
+        }
+    }
+
+    /// Number of files whose findings came from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Removes the entries of every configuration that were not used for [`MAX_ENTRY_AGE`],
+    /// then the least recently used ones until at most [`MAX_CACHE_BYTES`] remain. Like
+    /// writes, failures are only logged.
+    pub fn prune(&self) {
+        prune(&self.root, MAX_ENTRY_AGE, MAX_CACHE_BYTES);
+    }
+}
+
+/// Prunes the cache at `root` to entries used within `max_age`, at most `max_bytes` in
+/// total, and removes configuration directories left empty.
+fn prune(root: &Path, max_age: Duration, max_bytes: u64) {
+    let now = SystemTime::now();
+    let mut entries = Vec::new();
+    for dir in fs::read_dir(root).into_iter().flatten().flatten() {
+        for file in fs::read_dir(dir.path()).into_iter().flatten().flatten() {
+            let Ok(metadata) = file.metadata() else { continue };
+            let used = metadata.modified().unwrap_or(now);
+            entries.push((used, metadata.len(), file.path()));
+        }
+    }
+    // Most recently used first, so the oldest entries are the ones past the size limit.
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.0));
+    let mut kept_bytes = 0;
+    for (used, len, path) in entries {
+        let expired = now.duration_since(used).is_ok_and(|age| age > max_age);
+        if !expired && kept_bytes + len <= max_bytes {
+            kept_bytes += len;
+        } else if let Err(e) = fs::remove_file(&path) {
+            debug!("Failed to remove scan cache entry {}: {}", path.display(), e);
+        }
+    }
+    for dir in fs::read_dir(root).into_iter().flatten().flatten() {
+        // Only succeeds for empty directories.
+        let _ = fs::remove_dir(dir.path());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::{RedactionConfig, RegexEngine};
+
+    #[test]
+    fn reuses_findings_for_unchanged_content_only() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let engine = RegexEngine::new(RedactionConfig::load_default_rules()?)?;
+        let content = "contact admin@example.com";
+        let matches = engine.find_matches_for_ui(content, "a.log")?;
+
+        let mut cache = ScanCache::new(dir.path(), &engine)?;
+        assert!(cache.get("a.log", content).is_none());
+        cache.put("a.log", content, &matches);
+
+        let cached = cache.get("b.log", content).expect("same content is cached");
+        assert_eq!(cached.len(), 1);
+        assert_eq!((cached[0].start, cached[0].end), (matches[0].start, matches[0].end));
+        assert_eq!(cached[0].source_id, "b.log");
+        assert!(cache.get("a.log", "contact someone@example.com").is_none());
+        assert_eq!(cache.hits(), 1);
+
+        let stored = fs::read_dir(&cache.dir)?.map(|e| fs::read_to_string(e?.path())).collect::<std::io::Result<String>>()?;
+        assert!(!stored.contains("admin@example.com"));
+        Ok(())
+    }
+
+    #[test]
+    fn prune_removes_old_entries_then_least_recently_used() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config_dir = dir.path().join("config");
+        let stale_dir = dir.path().join("stale");
+        fs::create_dir_all(&config_dir)?;
+        fs::create_dir_all(&stale_dir)?;
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(60 * 60);
+        for (path, used) in [
+            (config_dir.join("new.json"), now),
+            (config_dir.join("older.json"), now - hour),
+            (config_dir.join("oldest.json"), now - 2 * hour),
+            (stale_dir.join("expired.json"), now - MAX_ENTRY_AGE - hour),
+        ] {
+            fs::write(&path, [0u8; 10])?;
+            fs::File::options().write(true).open(&path)?.set_modified(used)?;
+        }
+
+        prune(dir.path(), MAX_ENTRY_AGE, 20);
+        assert!(config_dir.join("new.json").exists());
+        assert!(config_dir.join("older.json").exists());
+        assert!(!config_dir.join("oldest.json").exists());
+        assert!(!stale_dir.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Progress checkpoints for long `scan --files-from` runs.
+//!
+//! While a file list is scanned, the files completed so far and their findings are saved
+//! to a checkpoint file every few seconds. After an interruption, `--resume` reloads it,
+//! skips the completed files and carries their findings into the final summary, so a
+//! multi-hour scan of an archive share continues where it stopped. The checkpoint is
+//! removed once the scan completes.
+//!
+//! Findings are stored without their original text: `original_string` is replaced by the
+//! rule's replacement, so a checkpoint left on disk never holds a secret. Samples shown
+//! for resumed findings (`--sample-matches`) therefore display that replacement. Tenants
+//! are saved under their hash labels, unless `--tenant-names` asks for the names.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::config::RedactionRule;
+use cleansh_core::{RedactionMatch, TenantCounts};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Format version written to, and required of, checkpoint files.
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// Minimum time between two checkpoint writes.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The saved state of an interrupted scan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointState {
+    pub version: u32,
+    /// SHA-256 of the file list, so a checkpoint is never applied to a different list.
+    pub list_hash: String,
+    /// Files whose scan finished, as listed in `--files-from`.
+    pub completed: Vec<String>,
+    /// Completed files that were decoded and scanned (the rest were skipped as binary or unreadable).
+    pub files_scanned: usize,
+    /// Findings from the completed files, with their original text removed.
+    pub findings: Vec<RedactionMatch>,
+    /// Per-tenant finding counts so far, when the config sets `tenant`, keyed by tenant
+    /// hash labels unless `--tenant-names` is given.
+    #[serde(default, skip_serializing_if = "TenantCounts::is_empty")]
+    pub tenants: TenantCounts,
+}
+
+/// A checkpoint being kept up to date while a file list is scanned.
+pub struct ScanCheckpoint {
+    path: PathBuf,
+    state: CheckpointState,
+    done: HashSet<String>,
+    last_save: Instant,
+}
+
+/// Hashes the list of files to scan.
+pub fn hash_file_list(paths: &[PathBuf]) -> String {
+    let mut hasher = Sha256::new();
+    for path in paths {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Returns `m` without its original text, for findings that are written to disk.
+pub fn scrub(m: &RedactionMatch) -> RedactionMatch {
+    RedactionMatch {
+        original_string: m.sanitized_string.clone(),
+        rule: RedactionRule::default(),
+        ..m.clone()
+    }
+}
+
+impl ScanCheckpoint {
+    /// Starts a checkpoint at `path` for the given file list.
+    ///
+    /// With `resume`, an existing checkpoint is loaded and must have been written for the
+    /// same list; without it, any previous checkpoint is overwritten on the first save.
+    pub fn open(path: &Path, paths: &[PathBuf], resume: bool) -> Result<Self> {
+        let list_hash = hash_file_list(paths);
+        let state = if resume && path.exists() {
+            let raw = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read checkpoint: {}", path.display()))?;
+            let state: CheckpointState = serde_json::from_str(&raw)
+                .with_context(|| format!("'{}' is not a scan checkpoint", path.display()))?;
+            if state.version != CHECKPOINT_VERSION {
+                return Err(anyhow!("Checkpoint {} has unsupported version {}", path.display(), state.version));
+            }
+            if state.list_hash != list_hash {
+                return Err(anyhow!(
+                    "Checkpoint {} was written for a different file list; remove it or run without --resume",
+                    path.display()
+                ));
+            }
+            state
+        } else {
+            CheckpointState { version: CHECKPOINT_VERSION, list_hash, ..Default::default() }
+        };
+        let done = state.completed.iter().cloned().collect();
+        Ok(ScanCheckpoint { path: path.to_path_buf(), state, done, last_save: Instant::now() })
+    }
+
+    /// Returns `true` if `path` was completed before the scan was interrupted.
+    pub fn is_done(&self, path: &Path) -> bool {
+        self.done.contains(path.to_string_lossy().as_ref())
+    }
+
+    /// Number of completed files that were decoded and scanned.
+    pub fn files_scanned(&self) -> usize {
+        self.state.files_scanned
+    }
+
+    /// Findings carried over from completed files.
+    pub fn findings(&self) -> &[RedactionMatch] {
+        &self.state.findings
+    }
+
+    /// Per-tenant finding counts carried over from completed files.
+    pub fn tenants(&self) -> &TenantCounts {
+        &self.state.tenants
+    }
+
+    /// Replaces the saved per-tenant counts with the running totals.
+    pub fn set_tenants(&mut self, tenants: &TenantCounts) {
+        self.state.tenants.clone_from(tenants);
+    }
+
+    /// Records a finished file, `matches` being `None` when the file was skipped.
+    pub fn record(&mut self, path: &Path, matches: Option<&[RedactionMatch]>) {
+        let name = path.to_string_lossy().into_owned();
+        self.done.insert(name.clone());
+        self.state.completed.push(name);
+        if let Some(matches) = matches {
+            self.state.files_scanned += 1;
+            self.state.findings.extend(matches.iter().map(scrub));
+        }
+    }
+
+    /// Saves the checkpoint if [`SAVE_INTERVAL`] has passed since the last save.
+    pub fn save_if_due(&mut self) -> Result<()> {
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the checkpoint, replacing the previous one atomically.
+    pub fn save(&mut self) -> Result<()> {
+        let json = serde_json::to_vec(&self.state).context("Failed to serialize scan checkpoint")?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, json).with_context(|| format!("Failed to write checkpoint: {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path).with_context(|| format!("Failed to write checkpoint: {}", self.path.display()))?;
+        self.last_save = Instant::now();
+        Ok(())
+    }
+
+    /// Removes the checkpoint after the scan has completed.
+    pub fn finish(self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove checkpoint: {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support;
+
+    fn finding(source: &str) -> RedactionMatch {
+        RedactionMatch { line_number: Some(1), source_id: source.to_string(), ..test_support::finding("email", "admin@example.com", 3) }
+    }
+
+    #[test]
+    fn resumes_completed_files_without_original_text() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("scan.checkpoint");
+        let files = vec![PathBuf::from("a.log"), PathBuf::from("b.log"), PathBuf::from("c.bin")];
+
+        let mut checkpoint = ScanCheckpoint::open(&path, &files, false)?;
+        checkpoint.record(&files[0], Some(&[finding("a.log")]));
+        checkpoint.record(&files[2], None);
+        checkpoint.save()?;
+        assert!(!fs::read_to_string(&path)?.contains("admin@example.com"));
+
+        let resumed = ScanCheckpoint::open(&path, &files, true)?;
+        assert!(resumed.is_done(&files[0]) && resumed.is_done(&files[2]) && !resumed.is_done(&files[1]));
+        assert_eq!(resumed.files_scanned(), 1);
+        assert_eq!(resumed.findings()[0].original_string, "[EMAIL_REDACTED]");
+
+        assert!(ScanCheckpoint::open(&path, &files[..2], true).is_err());
+        resumed.finish()?;
+        assert!(!path.exists());
+        Ok(())
+    }
+}
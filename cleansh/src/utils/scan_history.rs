@@ -0,0 +1,226 @@
+//! Local history of scan results, for following leak frequency over time.
+//!
+//! `scan --record-history` appends one JSON line per run to `scan-history.jsonl`, next to
+//! the app state file: when the scan ran and how many findings each rule produced. No
+//! matched values are stored, and file names only when the scan asks for it with
+//! `--history-files`. `cleansh stats history` groups these entries by day or week to show
+//! whether remediation campaigns are paying off, and `cleansh stats serve` charts them.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use crate::utils::time;
+use anyhow::{Context, Result};
+use cleansh_core::{check_schema_version, SCHEMA_VERSION};
+use chrono::{DateTime, Datelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const HISTORY_FILENAME: &str = "scan-history.jsonl";
+
+/// The results of one recorded scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub total: usize,
+    pub rules: BTreeMap<String, usize>,
+    /// Findings per file, recorded for multi-file scans run with `--history-files`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub files: BTreeMap<String, usize>,
+}
+
+/// One line of the history file: the entry, stamped with the schema version.
+#[derive(Serialize)]
+struct HistoryLine<'a> {
+    schema_version: &'static str,
+    #[serde(flatten)]
+    entry: &'a HistoryEntry,
+}
+
+/// A line as read back. Lines written before schema versions existed carry none.
+#[derive(Deserialize)]
+struct StoredLine {
+    schema_version: Option<String>,
+    #[serde(flatten)]
+    entry: HistoryEntry,
+}
+
+/// Scans recorded in one day or week.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryBucket {
+    /// `2026-10-16` for a day, `2026-W42` for an ISO week.
+    pub period: String,
+    pub scans: usize,
+    pub findings: usize,
+    pub rules: BTreeMap<String, usize>,
+}
+
+impl HistoryBucket {
+    /// Average findings per scan, the figure compared between periods.
+    pub fn per_scan(&self) -> f64 {
+        self.findings as f64 / self.scans.max(1) as f64
+    }
+}
+
+/// Where the history lives, given the path of the app state file.
+pub fn history_path(state_path: &Path) -> PathBuf {
+    state_path.with_file_name(HISTORY_FILENAME)
+}
+
+impl HistoryEntry {
+    /// An entry for a scan finishing now with the given per-rule counts.
+    pub fn now<'a>(counts: impl IntoIterator<Item = (&'a String, &'a usize)>) -> Self {
+        let rules: BTreeMap<String, usize> = counts.into_iter().map(|(rule, n)| (rule.clone(), *n)).collect();
+        HistoryEntry { timestamp: time::now_rfc3339(), total: rules.values().sum(), rules, files: BTreeMap::new() }
+    }
+
+    /// Adds the findings per file of a multi-file scan.
+    pub fn with_files(mut self, files: impl IntoIterator<Item = (String, usize)>) -> Self {
+        self.files = files.into_iter().collect();
+        self
+    }
+}
+
+/// Appends `entry` to the history file, creating it if needed.
+pub fn append(path: &Path, entry: &HistoryEntry) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(&HistoryLine { schema_version: SCHEMA_VERSION, entry }).context("Failed to serialize history entry")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to write scan history: {}", path.display()))
+}
+
+/// Reads every entry of the history file; a missing file is an empty history.
+///
+/// Lines that cannot be parsed (for example a write cut short) are skipped. A line with a
+/// `schema_version` from another major version is refused rather than misread.
+pub fn load(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read scan history: {}", path.display())),
+    };
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<StoredLine>(line).ok())
+        .map(|line| {
+            check_schema_version(line.schema_version.as_deref())
+                .with_context(|| format!("Failed to read scan history: {}", path.display()))?;
+            Ok(line.entry)
+        })
+        .collect()
+}
+
+/// The period an entry falls in: its UTC day, or its ISO week when `weekly`.
+fn period_of(entry: &HistoryEntry, weekly: bool) -> String {
+    match DateTime::parse_from_rfc3339(&entry.timestamp) {
+        Ok(at) if weekly => {
+            let week = at.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+        _ => entry.timestamp.chars().take(10).collect(),
+    }
+}
+
+/// Groups entries by period in chronological order, counting only `rule` if given.
+pub fn buckets(entries: &[HistoryEntry], weekly: bool, rule: Option<&str>) -> Vec<HistoryBucket> {
+    let mut by_period: BTreeMap<String, HistoryBucket> = BTreeMap::new();
+    for entry in entries {
+        let period = period_of(entry, weekly);
+        let bucket = by_period.entry(period.clone()).or_insert_with(|| HistoryBucket {
+            period,
+            scans: 0,
+            findings: 0,
+            rules: BTreeMap::new(),
+        });
+        bucket.scans += 1;
+        for (name, count) in &entry.rules {
+            if rule.is_some_and(|r| r != name) {
+                continue;
+            }
+            bucket.findings += count;
+            *bucket.rules.entry(name.clone()).or_default() += count;
+        }
+    }
+    by_period.into_values().collect()
+}
+
+/// The `n` keys with the most findings summed over `counts`, most first; ties are
+/// broken by name.
+fn top<'a>(counts: impl Iterator<Item = &'a BTreeMap<String, usize>>, n: usize) -> Vec<(String, usize)> {
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    for map in counts {
+        for (key, count) in map {
+            *totals.entry(key).or_default() += count;
+        }
+    }
+    let mut totals: Vec<(String, usize)> = totals.into_iter().map(|(key, count)| (key.to_string(), count)).collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals.truncate(n);
+    totals
+}
+
+/// The `n` rules with the most findings across `entries`, most first.
+pub fn top_rules(entries: &[HistoryEntry], n: usize) -> Vec<(String, usize)> {
+    top(entries.iter().map(|entry| &entry.rules), n)
+}
+
+/// The `n` files with the most findings across `entries`, most first. Only scans run
+/// with `--history-files` contribute.
+pub fn top_files(entries: &[HistoryEntry], n: usize) -> Vec<(String, usize)> {
+    top(entries.iter().map(|entry| &entry.files), n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cleansh_core::SCHEMA_VERSION_FIELD;
+
+    fn entry(timestamp: &str, rules: &[(&str, usize)]) -> HistoryEntry {
+        let rules: BTreeMap<String, usize> = rules.iter().map(|(r, n)| (r.to_string(), *n)).collect();
+        HistoryEntry { timestamp: timestamp.to_string(), total: rules.values().sum(), rules, files: BTreeMap::new() }
+    }
+
+    #[test]
+    fn records_and_groups_by_period() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = history_path(&dir.path().join("state.json"));
+        assert!(load(&path)?.is_empty());
+
+        append(&path, &entry("2026-10-12T09:00:00Z", &[("email", 6), ("jwt_token", 2)]))?;
+        append(&path, &entry("2026-10-12T17:00:00Z", &[("email", 4)]))?;
+        append(&path, &entry("2026-10-19T09:00:00Z", &[("email", 3)]))?;
+        let entries = load(&path)?;
+        assert_eq!(entries.len(), 3);
+
+        let daily = buckets(&entries, false, None);
+        assert_eq!(daily.len(), 2);
+        assert_eq!((daily[0].period.as_str(), daily[0].scans, daily[0].findings), ("2026-10-12", 2, 12));
+        assert_eq!(daily[0].per_scan(), 6.0);
+
+        let weekly = buckets(&entries, true, Some("email"));
+        assert_eq!(weekly.iter().map(|b| b.period.as_str()).collect::<Vec<_>>(), ["2026-W42", "2026-W43"]);
+        assert_eq!(weekly[0].findings, 10);
+
+        let first: serde_json::Value = serde_json::from_str(fs::read_to_string(&path)?.lines().next().unwrap())?;
+        assert_eq!(first[SCHEMA_VERSION_FIELD], SCHEMA_VERSION);
+        let newer = dir.path().join("newer-history.jsonl");
+        fs::write(&newer, r#"{"schema_version":"2.0","timestamp":"2026-10-12T09:00:00Z","total":0,"rules":{}}"#)?;
+        assert!(load(&newer).is_err(), "a history line from another major version must be refused");
+
+        assert_eq!(top_rules(&entries, 1), [("email".to_string(), 13)]);
+        assert!(top_files(&entries, 5).is_empty());
+        let with_files = entry("2026-10-20T09:00:00Z", &[("email", 3)]).with_files([("b.log".to_string(), 2), ("a.log".to_string(), 2)]);
+        append(&path, &with_files)?;
+        assert_eq!(load(&path)?[3], with_files);
+        assert_eq!(top_files(&load(&path)?, 5), [("a.log".to_string(), 2), ("b.log".to_string(), 2)]);
+        Ok(())
+    }
+}
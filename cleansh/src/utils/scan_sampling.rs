@@ -0,0 +1,186 @@
+//! Sampled and time-boxed scans, for quick triage of inputs too large to scan in full.
+//!
+//! With `--sample-percent`, only an evenly spread share of the lines is scanned: every
+//! tenth line at 10%, counted across all inputs so that short files contribute too. With
+//! `--max-seconds`, lines are scanned in chunks and the scan stops at the first chunk
+//! boundary after the deadline. Either way, the bytes scanned and the bytes seen are
+//! tracked, so finding counts can be extrapolated to the whole input and reported as
+//! estimates.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use cleansh_core::RedactionMatch;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Lines scanned per engine call, so that the deadline is checked regularly.
+const CHUNK_LINES: usize = 5_000;
+
+/// Selected lines of an input, scanned with one engine call.
+pub struct Chunk {
+    pub text: String,
+    /// Per selected line: where it starts in `text`, where it starts in the input, and
+    /// its 1-based line number in the input.
+    lines: Vec<(usize, usize, u64)>,
+}
+
+impl Chunk {
+    /// Moves a match found in `text` to its place in the whole input: its offsets into the
+    /// input and the number of the line it starts on.
+    pub fn locate(&self, m: &mut RedactionMatch) {
+        let to_input = |offset: u64| {
+            let line = self.lines.partition_point(|&(start, _, _)| start as u64 <= offset).saturating_sub(1);
+            let (start, input_start, number) = self.lines[line];
+            (input_start as u64 + offset - start as u64, number)
+        };
+        let (start, line_number) = to_input(m.start);
+        // A match ending at a line end belongs to that line, not the next selected one.
+        let (end, _) = to_input(m.end.saturating_sub(1).max(m.start));
+        m.end = if m.end > m.start { end + 1 } else { start };
+        m.start = start;
+        m.line_number = Some(line_number);
+    }
+}
+
+/// Tracks what a sampled scan has covered.
+pub struct Sampler {
+    percent: u8,
+    deadline: Option<Instant>,
+    /// Index of the next line across all inputs, which decides whether it is selected.
+    line_index: u64,
+    scanned_bytes: u64,
+    total_bytes: u64,
+    stopped_early: bool,
+}
+
+/// How much of the input a sampled scan covered, with the extrapolated finding counts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SamplingSummary {
+    pub scanned_bytes: u64,
+    pub total_bytes: u64,
+    pub coverage_percent: f64,
+    /// `true` if `--max-seconds` ended the scan before all selected lines were scanned.
+    pub stopped_early: bool,
+    /// Finding counts per rule extrapolated to the whole input.
+    pub estimated_summary: BTreeMap<String, usize>,
+}
+
+impl Sampler {
+    /// Returns a sampler for the given options, or `None` for a full scan.
+    pub fn new(percent: Option<u8>, max_seconds: Option<u64>) -> Option<Self> {
+        if percent.is_none() && max_seconds.is_none() {
+            return None;
+        }
+        Some(Sampler {
+            percent: percent.unwrap_or(100),
+            deadline: max_seconds.map(|secs| Instant::now() + Duration::from_secs(secs)),
+            line_index: 0,
+            scanned_bytes: 0,
+            total_bytes: 0,
+            stopped_early: false,
+        })
+    }
+
+    fn is_selected(&self, index: u64) -> bool {
+        let percent = u64::from(self.percent);
+        (index * percent) / 100 != ((index + 1) * percent) / 100
+    }
+
+    /// Returns `true`, and remembers that the scan was cut short, once the deadline passed.
+    pub fn expired(&mut self) -> bool {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.stopped_early = true;
+        }
+        self.stopped_early
+    }
+
+    /// Splits the selected lines of `content` into chunks and counts `content` as seen.
+    pub fn chunks(&mut self, content: &str) -> Vec<Chunk> {
+        self.total_bytes += content.len() as u64;
+        let mut chunks = Vec::new();
+        let mut chunk = Chunk { text: String::new(), lines: Vec::new() };
+        let mut offset = 0;
+        for (number, line) in (1..).zip(content.split_inclusive('\n')) {
+            let index = self.line_index;
+            self.line_index += 1;
+            let line_start = offset;
+            offset += line.len();
+            if !self.is_selected(index) {
+                continue;
+            }
+            chunk.lines.push((chunk.text.len(), line_start, number));
+            chunk.text.push_str(line);
+            if chunk.lines.len() == CHUNK_LINES {
+                chunks.push(std::mem::replace(&mut chunk, Chunk { text: String::new(), lines: Vec::new() }));
+            }
+        }
+        if !chunk.text.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    /// Counts a chunk as scanned.
+    pub fn record_scanned(&mut self, chunk: &Chunk) {
+        self.scanned_bytes += chunk.text.len() as u64;
+    }
+
+    /// Counts an input of `bytes` that was not read because the deadline passed.
+    pub fn skip(&mut self, bytes: u64) {
+        self.total_bytes += bytes;
+    }
+
+    /// Summarizes the coverage and extrapolates `counts` to the whole input.
+    pub fn summary(&self, counts: &HashMap<String, usize>) -> SamplingSummary {
+        let ratio = if self.scanned_bytes == 0 { 0.0 } else { self.total_bytes as f64 / self.scanned_bytes as f64 };
+        let coverage_percent = if self.total_bytes == 0 {
+            100.0
+        } else {
+            (self.scanned_bytes as f64 * 1000.0 / self.total_bytes as f64).round() / 10.0
+        };
+        SamplingSummary {
+            scanned_bytes: self.scanned_bytes,
+            total_bytes: self.total_bytes,
+            coverage_percent,
+            stopped_early: self.stopped_early,
+            estimated_summary: counts
+                .iter()
+                .map(|(rule, &count)| (rule.clone(), (count as f64 * ratio).round() as usize))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_support;
+
+    #[test]
+    fn selects_evenly_and_extrapolates() {
+        let mut sampler = Sampler::new(Some(10), None).unwrap();
+        let content: String = (0..25).map(|i| format!("line {:02}\n", i)).collect();
+        let chunks = sampler.chunks(&content);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "line 09\nline 19\n");
+        sampler.record_scanned(&chunks[0]);
+
+        // Matches in a chunk are moved to their offset and line in the input.
+        let mut m = RedactionMatch { start: 8, end: 15, ..test_support::finding("line", "line 19", 0) };
+        chunks[0].locate(&mut m);
+        assert_eq!((m.start, m.end, m.line_number), (152, 159, Some(20)));
+        assert_eq!(&content[152..159], "line 19");
+
+        // The line index carries over, so the next input continues the stride.
+        assert_eq!(sampler.chunks("a\nb\nc\nd\ne\n")[0].text, "e\n");
+
+        let summary = sampler.summary(&HashMap::from([("email".to_string(), 2)]));
+        assert_eq!((summary.scanned_bytes, summary.total_bytes), (16, 210));
+        assert_eq!(summary.coverage_percent, 7.6);
+        assert_eq!(summary.estimated_summary["email"], 26);
+        assert!(!summary.stopped_early);
+
+        assert!(Sampler::new(None, None).is_none());
+    }
+}
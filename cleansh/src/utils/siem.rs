@@ -0,0 +1,176 @@
+// cleansh-workspace/cleansh/src/utils/siem.rs
+//! Forwarding of `cleansh scan` findings to a SIEM.
+//!
+//! Findings are reduced to metadata (rule, severity, source, offsets) before they leave
+//! the process; matched values and their hashes are never sent. Findings are posted in
+//! batches, and transient failures (connection errors, HTTP 429 and 5xx) are retried
+//! with exponential backoff.
+
+use anyhow::{anyhow, Context, Result};
+use cleansh_core::{RedactionConfig, RedactionMatch};
+use log::debug;
+use serde::Serialize;
+use serde_json::json;
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::ForwardTarget;
+use crate::utils::time;
+
+/// Maximum number of attempts per batch, including the first one.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// A single scan finding, stripped of the matched value.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub rule_name: String,
+    /// Version of the rule that produced the finding, for pinning results to a rule revision.
+    pub rule_version: String,
+    pub rule_tags: Vec<String>,
+    pub severity: Option<String>,
+    pub source: String,
+    pub start: u64,
+    pub end: u64,
+    pub cleansh_version: &'static str,
+    pub scanned_at: String,
+}
+
+/// Converts engine matches into metadata-only findings.
+pub fn findings_from_matches(matches: &[RedactionMatch], config: &RedactionConfig) -> Vec<Finding> {
+    let metadata = config.rule_metadata(matches.iter().map(|m| m.rule_name.as_str()));
+    let scanned_at = time::now_rfc3339();
+
+    matches
+        .iter()
+        .map(|m| {
+            let rule = metadata.get(&m.rule_name);
+            Finding {
+                rule_name: m.rule_name.clone(),
+                rule_version: rule.map(|r| r.version.clone()).unwrap_or_default(),
+                rule_tags: rule.map(|r| r.tags.clone()).unwrap_or_default(),
+                severity: rule.and_then(|r| r.severity.clone()),
+                source: if m.source_id.is_empty() { "stdin".to_string() } else { m.source_id.clone() },
+                start: m.start,
+                end: m.end,
+                cleansh_version: env!("CARGO_PKG_VERSION"),
+                scanned_at: scanned_at.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Posts findings to a Splunk HTTP Event Collector or an Elasticsearch `_bulk` endpoint.
+pub struct SiemForwarder {
+    client: reqwest::blocking::Client,
+    target: ForwardTarget,
+    endpoint: String,
+    token: String,
+    batch_size: usize,
+    retry_delay: Duration,
+}
+
+impl SiemForwarder {
+    /// Creates a forwarder for `target` at `endpoint`, authenticating with `token`.
+    ///
+    /// For Splunk, `endpoint` is the HEC event URL (e.g. `https://splunk:8088/services/collector/event`).
+    /// For Elastic, it is the `_bulk` URL of the target index (e.g. `https://es:9200/cleansh-findings/_bulk`).
+    pub fn new(target: ForwardTarget, endpoint: &str, token: String, batch_size: usize) -> Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build HTTP client for SIEM forwarding")?;
+        Ok(Self {
+            client,
+            target,
+            endpoint: endpoint.to_string(),
+            token,
+            batch_size: batch_size.max(1),
+            retry_delay: Duration::from_millis(200),
+        })
+    }
+
+    /// Sends all findings in batches and returns the number of findings delivered.
+    pub fn forward(&self, findings: &[Finding]) -> Result<usize> {
+        let mut sent = 0;
+        for batch in findings.chunks(self.batch_size) {
+            self.send_with_retry(batch)
+                .with_context(|| format!("Forwarded {} of {} findings before failing", sent, findings.len()))?;
+            sent += batch.len();
+        }
+        Ok(sent)
+    }
+
+    fn send_with_retry(&self, batch: &[Finding]) -> Result<()> {
+        let mut attempt = 1;
+        loop {
+            match self.send_batch(batch) {
+                Ok(()) => return Ok(()),
+                Err(SendError::Retryable(e)) if attempt < MAX_ATTEMPTS => {
+                    let delay = self.retry_delay * 2u32.pow(attempt - 1);
+                    debug!("SIEM forwarding attempt {} failed ({}); retrying in {:?}.", attempt, e, delay);
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(SendError::Retryable(e)) | Err(SendError::Fatal(e)) => return Err(e),
+            }
+        }
+    }
+
+    fn send_batch(&self, batch: &[Finding]) -> std::result::Result<(), SendError> {
+        let request = match self.target {
+            ForwardTarget::SplunkHec => {
+                let body: String = batch
+                    .iter()
+                    .map(|finding| json!({ "sourcetype": "cleansh:finding", "event": finding }).to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.client
+                    .post(&self.endpoint)
+                    .header("Authorization", format!("Splunk {}", self.token))
+                    .header("Content-Type", "application/json")
+                    .body(body)
+            }
+            ForwardTarget::Elastic => {
+                let mut body = String::new();
+                for finding in batch {
+                    body.push_str("{\"index\":{}}\n");
+                    body.push_str(&json!(finding).to_string());
+                    body.push('\n');
+                }
+                self.client
+                    .post(&self.endpoint)
+                    .header("Authorization", format!("ApiKey {}", self.token))
+                    .header("Content-Type", "application/x-ndjson")
+                    .body(body)
+            }
+        };
+
+        let response = request
+            .send()
+            .map_err(|e| SendError::Retryable(anyhow!("Failed to connect to {}: {}", self.endpoint, e)))?;
+        let status = response.status();
+        if status.as_u16() == 429 || status.is_server_error() {
+            return Err(SendError::Retryable(anyhow!("SIEM endpoint returned HTTP status {}", status)));
+        }
+        if !status.is_success() {
+            return Err(SendError::Fatal(anyhow!("SIEM endpoint rejected findings with HTTP status {}", status)));
+        }
+
+        // Elasticsearch reports per-document failures with a 200 and `"errors": true`.
+        if matches!(self.target, ForwardTarget::Elastic) {
+            let body: serde_json::Value = response
+                .json()
+                .map_err(|e| SendError::Fatal(anyhow!("Failed to parse Elasticsearch bulk response: {}", e)))?;
+            if body.get("errors").and_then(|v| v.as_bool()).unwrap_or(false) {
+                return Err(SendError::Fatal(anyhow!("Elasticsearch rejected one or more findings")));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether a failed batch is worth retrying.
+enum SendError {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
+}
@@ -0,0 +1,144 @@
+// cleansh-workspace/cleansh/src/utils/staged_diff.rs
+//! Reading the lines staged for commit, for `cleansh scan --staged`.
+//!
+//! A pre-commit hook only cares about what the commit adds: a secret that is already in
+//! the repository, or one the commit removes, is not something the hook can stop. The
+//! staged diff is read with `git diff --cached` and, for each file, the added lines are
+//! put back at their line numbers with every other line left empty. Scanning that text
+//! reports findings on the line they have in the staged file, and a secret spread over
+//! several added lines (a private key, say) is still found as a whole.
+//!
+//! Deleted files and binary files have no added lines and are left out.
+
+use anyhow::{anyhow, Context, Result};
+use std::process::Command;
+
+/// The added lines of one staged file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+    /// The path of the file, relative to the root of the repository.
+    pub path: String,
+    /// The added lines at their line numbers in the staged file, with every other line
+    /// empty. Ends after the last added line.
+    pub content: String,
+}
+
+/// Parses `-12,3` or `+7` from a hunk header into its first line and line count.
+fn parse_range(range: &str) -> Option<(u64, u64)> {
+    match range[1..].split_once(',') {
+        Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+        None => Some((range[1..].parse().ok()?, 1)),
+    }
+}
+
+/// Splits `@@ -a,b +c,d @@` into the old line count, the first new line and the new
+/// line count.
+fn parse_hunk_header(line: &str) -> Option<(u64, u64, u64)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split(' ');
+    let (old, new) = (ranges.next()?, ranges.next()?);
+    if !old.starts_with('-') || !new.starts_with('+') {
+        return None;
+    }
+    let (_, old_len) = parse_range(old)?;
+    let (new_start, new_len) = parse_range(new)?;
+    Some((old_len, new_start, new_len))
+}
+
+/// Returns the path of a `+++ b/path` line, or `None` for a deleted file.
+fn new_path(target: &str) -> Option<String> {
+    if target == "/dev/null" {
+        return None;
+    }
+    let target = target.strip_prefix('"').and_then(|t| t.strip_suffix('"')).unwrap_or(target);
+    Some(target.strip_prefix("b/").unwrap_or(target).to_string())
+}
+
+/// Collects the added lines of each file in `diff`, a unified diff as printed by
+/// `git diff --cached`. Files without added lines are left out.
+pub fn parse_added_lines(diff: &str) -> Vec<StagedFile> {
+    let mut files: Vec<StagedFile> = Vec::new();
+    let mut current: Option<StagedFile> = None;
+    let mut lines_written = 0;
+    // The next new line number and the old and new lines left in the current hunk.
+    let mut next_line = 0;
+    let (mut old_left, mut new_left): (u64, u64) = (0, 0);
+
+    for line in diff.lines() {
+        if old_left > 0 || new_left > 0 {
+            match line.chars().next() {
+                Some('+') => {
+                    if let Some(file) = current.as_mut() {
+                        // Pad with empty lines up to the line number of this one.
+                        while lines_written + 1 < next_line {
+                            file.content.push('\n');
+                            lines_written += 1;
+                        }
+                        file.content.push_str(&line[1..]);
+                        file.content.push('\n');
+                        lines_written += 1;
+                    }
+                    next_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                Some('-') => old_left = old_left.saturating_sub(1),
+                Some('\\') => {}
+                _ => {
+                    next_line += 1;
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+            }
+        } else if let Some(target) = line.strip_prefix("+++ ") {
+            files.extend(current.take().filter(|file| !file.content.is_empty()));
+            current = new_path(target).map(|path| StagedFile { path, content: String::new() });
+            lines_written = 0;
+        } else if let Some((old_len, new_start, new_len)) = parse_hunk_header(line) {
+            (old_left, next_line, new_left) = (old_len, new_start, new_len);
+        }
+    }
+    files.extend(current.filter(|file| !file.content.is_empty()));
+    files
+}
+
+/// Reads the staged changes of the git repository in the current directory.
+///
+/// # Errors
+///
+/// Returns an error if git cannot be run, for example outside a repository.
+pub fn read_staged() -> Result<Vec<StagedFile>> {
+    let output = Command::new("git")
+        .args(["-c", "core.quotePath=false", "diff", "--cached", "--unified=0", "--no-color", "--no-ext-diff", "--no-textconv"])
+        .output()
+        .context("Failed to run git to read the staged changes")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git diff --cached failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_added_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/app/config.py b/app/config.py\nindex 83db48f..bf269f4 100644\n--- a/app/config.py\n+++ b/app/config.py\n\
+@@ -2,0 +3,2 @@ import os\n+API_KEY = \"sk-live\"\n+DEBUG = True\n@@ -9 +11 @@ def main():\n-    old()\n+    new()\n\
+diff --git a/gone.txt b/gone.txt\ndeleted file mode 100644\n--- a/gone.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-bye\n\
+diff --git a/logo.png b/logo.png\nnew file mode 100644\nBinary files /dev/null and b/logo.png differ\n\
+diff --git \"a/my notes.txt\" \"b/my notes.txt\"\nnew file mode 100644\n--- /dev/null\n+++ \"b/my notes.txt\"\n@@ -0,0 +1,2 @@\n+hello\n+++ not a header\n";
+
+    #[test]
+    fn keeps_added_lines_at_their_line_numbers() {
+        let files = parse_added_lines(DIFF);
+        assert_eq!(
+            files,
+            [
+                StagedFile { path: "app/config.py".to_string(), content: "\n\nAPI_KEY = \"sk-live\"\nDEBUG = True\n\n\n\n\n\n\n    new()\n".to_string() },
+                StagedFile { path: "my notes.txt".to_string(), content: "hello\n++ not a header\n".to_string() },
+            ]
+        );
+        assert_eq!(files[0].content.lines().nth(10), Some("    new()"));
+    }
+}
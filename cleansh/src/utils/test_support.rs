@@ -0,0 +1,23 @@
+// cleansh-workspace/cleansh/src/utils/test_support.rs
+//! Fixtures shared by the unit tests of the CLI modules.
+
+use cleansh_core::RedactionMatch;
+
+/// A match of `rule` on `text` at byte offset `start`, replaced with
+/// `[<RULE>_REDACTED]`. Tests that need other values override fields with struct update
+/// syntax.
+pub fn finding(rule: &str, text: &str, start: u64) -> RedactionMatch {
+    RedactionMatch {
+        rule_name: rule.to_string(),
+        original_string: text.to_string(),
+        sanitized_string: format!("[{}_REDACTED]", rule.to_uppercase()),
+        start,
+        end: start + text.len() as u64,
+        line_number: None,
+        sample_hash: None,
+        match_context_hash: None,
+        timestamp: None,
+        rule: Default::default(),
+        source_id: "test".to_string(),
+    }
+}
@@ -0,0 +1,23 @@
+//! Timestamp helpers shared by every machine-readable output.
+//!
+//! All times are handled in UTC and rendered as RFC 3339 with a `Z` suffix and whole
+//! seconds (e.g. `2026-10-16T09:30:00Z`), so reports, findings and crash reports from
+//! machines in different timezones compare and sort the same way.
+//!
+//! License: Polyform Noncommercial License 1.0.0
+
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+
+/// How far a local clock may be off before time-bounded checks (such as license expiry)
+/// start failing.
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::minutes(5);
+
+/// Formats `time` as an RFC 3339 UTC timestamp.
+pub fn rfc3339(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// The current time as an RFC 3339 UTC timestamp.
+pub fn now_rfc3339() -> String {
+    rfc3339(Utc::now())
+}
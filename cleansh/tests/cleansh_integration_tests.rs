@@ -25,6 +25,7 @@ use cleansh_core::{
     RegexEngine,
 };
 use cleansh::commands::cleansh::CleanshOptions;
+use cleansh::utils::clipboard::DEFAULT_MAX_CLIPBOARD_BYTES;
 
 
 /// This module ensures that logging (e.g., from `pii_debug!` macro) is set up for tests.
@@ -129,6 +130,12 @@ fn test_run_cleansh_basic_sanitization() -> Result<()> {
                 enabled: Some(true),
                 severity: Some("low".to_string()),
                 tags: Some(vec!["integration_test".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
             cleansh::test_exposed::config::RedactionRule {
                 name: "us_ssn".to_string(),
@@ -147,8 +154,15 @@ fn test_run_cleansh_basic_sanitization() -> Result<()> {
                 enabled: Some(true),
                 severity: Some("high".to_string()),
                 tags: Some(vec!["integration_test".to_string(), "pii".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
 
     let temp_dir = tempfile::tempdir()?;
@@ -162,10 +176,26 @@ fn test_run_cleansh_basic_sanitization() -> Result<()> {
     let opts = CleanshOptions {
         input: input.to_string(),
         clipboard: false,
+        clipboard_max_bytes: DEFAULT_MAX_CLIPBOARD_BYTES,
         diff: false,
         output_path: Some(output_file_path.clone()),
         no_redaction_summary: false,
         quiet: false,
+        matches_out: None,
+        audit_log: None,
+        source: "stdin".to_string(),
+        tokenizer: None,
+        post_processor: None,
+        join_wrapped: None,
+        input_format: Default::default(),
+        diff_headers: false,
+        dotenv_allow: Vec::new(),
+        tee: false,
+        output_encoding: Default::default(),
+        passes: 1,
+        verify_idempotent: false,
+        strict_containment: false,
+        in_place: false,
     };
     let theme_map = get_default_theme_map();
 
@@ -224,6 +254,12 @@ fn test_run_cleansh_no_redaction_summary() -> Result<()> {
                 enabled: Some(true),
                 severity: Some("low".to_string()),
                 tags: Some(vec!["integration_test".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
             cleansh::test_exposed::config::RedactionRule {
                 name: "us_ssn".to_string(),
@@ -242,8 +278,15 @@ fn test_run_cleansh_no_redaction_summary() -> Result<()> {
                 enabled: Some(true),
                 severity: Some("high".to_string()),
                 tags: Some(vec!["integration_test".to_string(), "pii".to_string()]),
+                priority: 0,
+                context: None,
+                paths: None,
+                deprecated: false,
+                aliases: None,
+                exceptions: None,
             },
         ],
+        ..Default::default()
     };
 
     let temp_dir = tempfile::tempdir()?;
@@ -257,10 +300,26 @@ fn test_run_cleansh_no_redaction_summary() -> Result<()> {
     let opts = CleanshOptions {
         input: input.to_string(),
         clipboard: false,
+        clipboard_max_bytes: DEFAULT_MAX_CLIPBOARD_BYTES,
         diff: false,
         output_path: Some(output_file_path.clone()),
         no_redaction_summary: true,
         quiet: false,
+        matches_out: None,
+        audit_log: None,
+        source: "stdin".to_string(),
+        tokenizer: None,
+        post_processor: None,
+        join_wrapped: None,
+        input_format: Default::default(),
+        diff_headers: false,
+        dotenv_allow: Vec::new(),
+        tee: false,
+        output_encoding: Default::default(),
+        passes: 1,
+        verify_idempotent: false,
+        strict_containment: false,
+        in_place: false,
     };
     let theme_map = get_default_theme_map();
 
@@ -330,7 +389,14 @@ fn test_run_cleansh_clipboard_copy() -> Result<()> {
             enabled: Some(true),
             severity: Some("low".to_string()),
             tags: Some(vec!["integration_test".to_string()]),
+            priority: 0,
+            context: None,
+            paths: None,
+            deprecated: false,
+            aliases: None,
+            exceptions: None,
         }],
+        ..Default::default()
     };
 
     let temp_dir = tempfile::tempdir()?;
@@ -344,10 +410,26 @@ fn test_run_cleansh_clipboard_copy() -> Result<()> {
     let opts = CleanshOptions {
         input: input.to_string(),
         clipboard: true,
+        clipboard_max_bytes: DEFAULT_MAX_CLIPBOARD_BYTES,
         diff: false,
         output_path: Some(output_file_path.clone()),
         no_redaction_summary: true,
         quiet: false,
+        matches_out: None,
+        audit_log: None,
+        source: "stdin".to_string(),
+        tokenizer: None,
+        post_processor: None,
+        join_wrapped: None,
+        input_format: Default::default(),
+        diff_headers: false,
+        dotenv_allow: Vec::new(),
+        tee: false,
+        output_encoding: Default::default(),
+        passes: 1,
+        verify_idempotent: false,
+        strict_containment: false,
+        in_place: false,
     };
     let theme_map = get_default_theme_map();
 
@@ -412,7 +494,14 @@ fn test_run_cleansh_diff_output() -> Result<()> {
             enabled: Some(true),
             severity: Some("low".to_string()),
             tags: Some(vec!["integration_test".to_string()]),
+            priority: 0,
+            context: None,
+            paths: None,
+            deprecated: false,
+            aliases: None,
+            exceptions: None,
         }],
+        ..Default::default()
     };
 
     let temp_dir = tempfile::tempdir()?;
@@ -426,10 +515,26 @@ fn test_run_cleansh_diff_output() -> Result<()> {
     let opts = CleanshOptions {
         input: input.to_string(),
         clipboard: false,
+        clipboard_max_bytes: DEFAULT_MAX_CLIPBOARD_BYTES,
         diff: true,
         output_path: Some(output_file_path.clone()),
         no_redaction_summary: true,
         quiet: false,
+        matches_out: None,
+        audit_log: None,
+        source: "stdin".to_string(),
+        tokenizer: None,
+        post_processor: None,
+        join_wrapped: None,
+        input_format: Default::default(),
+        diff_headers: false,
+        dotenv_allow: Vec::new(),
+        tee: false,
+        output_encoding: Default::default(),
+        passes: 1,
+        verify_idempotent: false,
+        strict_containment: false,
+        in_place: false,
     };
     let theme_map = get_default_theme_map();
 
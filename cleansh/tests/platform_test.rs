@@ -1,6 +1,7 @@
 // cleansh-workspace/cleansh/tests/platform_test.rs
 
-use cleansh::utils::platform::eof_key_combo;
+use cleansh::utils::platform::{eof_key_combo, long_path, windows_extended_path};
+use std::path::Path;
 
 #[test]
 fn test_eof_key_combo_is_correct() {
@@ -12,4 +13,41 @@ fn test_eof_key_combo_is_correct() {
         // This is the expected path for your Ubuntu CI runner
         assert_eq!(eof_key_combo(), "Ctrl+D");
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_windows_extended_path_prefixes_absolute_paths() {
+    assert_eq!(windows_extended_path(r"C:\logs\app.log"), r"\\?\C:\logs\app.log");
+    assert_eq!(windows_extended_path("C:/logs/app.log"), r"\\?\C:\logs\app.log");
+    assert_eq!(windows_extended_path(r"\\server\share\app.log"), r"\\?\UNC\server\share\app.log");
+    assert_eq!(windows_extended_path("//server/share/app.log"), r"\\?\UNC\server\share\app.log");
+}
+
+#[test]
+fn test_windows_extended_path_leaves_prefixed_and_relative_paths_alone() {
+    assert_eq!(windows_extended_path(r"\\?\C:\logs\app.log"), r"\\?\C:\logs\app.log");
+    assert_eq!(windows_extended_path(r"\\?\UNC\server\share\x"), r"\\?\UNC\server\share\x");
+    assert_eq!(windows_extended_path(r"\\.\pipe\cleansh"), r"\\.\pipe\cleansh");
+    assert_eq!(windows_extended_path(r"logs\app.log"), r"logs\app.log");
+    assert_eq!(windows_extended_path("logs/app.log"), "logs/app.log");
+}
+
+#[test]
+fn test_long_path_leaves_short_paths_unchanged() {
+    assert_eq!(long_path(Path::new("app.log")), Path::new("app.log"));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_long_path_reaches_files_beyond_max_path() -> anyhow::Result<()> {
+    let root = tempfile::tempdir()?;
+    let mut dir = root.path().to_path_buf();
+    while dir.as_os_str().len() < 300 {
+        dir.push("a_fairly_long_directory_name_for_testing");
+    }
+    std::fs::create_dir_all(long_path(&dir))?;
+    let file = dir.join("secrets.log");
+    std::fs::write(long_path(&file), "token")?;
+    assert_eq!(std::fs::read_to_string(long_path(&file))?, "token");
+    Ok(())
+}
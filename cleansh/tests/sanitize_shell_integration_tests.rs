@@ -55,6 +55,12 @@ fn create_test_rule(
         enabled: Some(true),
         severity: Some("low".to_string()),
         tags: Some(vec!["test".to_string()]),
+        priority: 0,
+        context: None,
+        paths: None,
+        deprecated: false,
+        aliases: None,
+        exceptions: None,
     }
 }
 
@@ -85,7 +91,7 @@ fn test_compile_rules_basic() -> Result<()> {
         create_test_rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[EMAIL]", false, None, false, false, false),
         create_test_rule("ip", r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b", "[IP]", false, None, false, false, false),
     ];
-    let config = RedactionConfig { rules: rules_vec };
+    let config = RedactionConfig { rules: rules_vec, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 2);
     Ok(())
@@ -99,7 +105,7 @@ fn test_compile_rules_opt_in_not_enabled() -> Result<()> {
         create_test_rule("aws_key", "AKIA[A-Z0-9]{16}", "[AWS_KEY]", true, None, false, false, false),
     ];
     let filtered_rules = filter_rules(rules_vec, &[], &[]);
-    let config = RedactionConfig { rules: filtered_rules };
+    let config = RedactionConfig { rules: filtered_rules, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 1);
     assert_eq!(compiled.get_rules().rules[0].name, "email");
@@ -113,7 +119,7 @@ fn test_compile_rules_opt_in_missing_returns_empty() -> Result<()> {
         create_test_rule("secret_key", r"secret_\w+", "[REDACTED]", true, None, false, false, false),
     ];
     let filtered_rules = filter_rules(rules_vec, &[], &[]);
-    let config = RedactionConfig { rules: filtered_rules };
+    let config = RedactionConfig { rules: filtered_rules, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 0);
     Ok(())
@@ -127,7 +133,7 @@ fn test_compile_rules_opt_in_enabled() -> Result<()> {
         create_test_rule("aws_key", "AKIA[A-Z0-9]{16}", "[AWS_KEY]", true, None, false, false, false),
     ];
     let filtered_rules = filter_rules(rules_vec, &["aws_key".to_string()], &[]);
-    let config = RedactionConfig { rules: filtered_rules };
+    let config = RedactionConfig { rules: filtered_rules, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 2);
     assert!(compiled.get_rules().rules.iter().any(|r| r.name == "aws_key"));
@@ -142,7 +148,7 @@ fn test_compile_rules_disabled() -> Result<()> {
         create_test_rule("aws_key", "AKIA[A-Z0-9]{16}", "[AWS_KEY]", true, None, false, false, false),
     ];
     let filtered_rules = filter_rules(rules_vec, &["aws_key".to_string()], &["email".to_string()]);
-    let config = RedactionConfig { rules: filtered_rules };
+    let config = RedactionConfig { rules: filtered_rules, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 1);
     assert_eq!(compiled.get_rules().rules[0].name, "aws_key");
@@ -156,7 +162,7 @@ fn test_compile_rules_opt_in_and_disabled_conflict() -> Result<()> {
         create_test_rule("sensitive_data", "sensitive_text", "[REDACTED]", true, None, false, false, false),
     ];
     let filtered_rules = filter_rules(rules_vec, &["sensitive_data".to_string()], &["sensitive_data".to_string()]);
-    let config = RedactionConfig { rules: filtered_rules };
+    let config = RedactionConfig { rules: filtered_rules, ..Default::default() };
     let compiled = RegexEngine::new(config)?;
     assert_eq!(compiled.get_rules().rules.len(), 0);
     Ok(())
@@ -167,7 +173,7 @@ fn test_overlapping_rules_priority() -> Result<()> {
     test_setup::setup_logger();
     let rule_email = create_test_rule("email", r"(\w+)@example\.com", "[EMAIL]", false, None, false, false, false);
     let rule_generic = create_test_rule("example_match", r"example\.com", "[DOMAIN]", false, None, false, false, false);
-    let config = RedactionConfig { rules: vec![rule_email, rule_generic] };
+    let config = RedactionConfig { rules: vec![rule_email, rule_generic], ..Default::default() };
     let compiled = RegexEngine::new(config)?;
 
     let input = "user@example.com";
@@ -183,7 +189,7 @@ fn test_overlapping_rules_priority() -> Result<()> {
 fn test_sanitize_content_basic() -> Result<()> {
     test_setup::setup_logger();
     let rule = create_test_rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[EMAIL_REDACTED]", false, None, false, false, false);
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input = "My email is test@example.com.";
@@ -199,7 +205,7 @@ fn test_sanitize_content_basic() -> Result<()> {
 fn test_sanitize_content_multiple_matches_same_rule() -> Result<()> {
     test_setup::setup_logger();
     let rule = create_test_rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[EMAIL_REDACTED]", false, None, false, false, false);
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input = "test1@example.com and test2@example.com.";
@@ -220,7 +226,7 @@ fn test_sanitize_content_multiple_rules() -> Result<()> {
     let email_rule = create_test_rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[EMAIL]", false, None, false, false, false);
     let ip_rule = create_test_rule("ipv4_address", r"\b\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}\b", "[IPV4]", false, None, false, false, false);
 
-    let config = RedactionConfig { rules: vec![email_rule, ip_rule] };
+    let config = RedactionConfig { rules: vec![email_rule, ip_rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input = "Email: a@b.com, IP: 192.168.1.1.";
@@ -236,7 +242,7 @@ fn test_sanitize_content_multiple_rules() -> Result<()> {
 fn test_sanitize_content_with_ansi_escapes() -> Result<()> {
     test_setup::setup_logger();
     let rule = create_test_rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[EMAIL]", false, None, false, false, false);
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input_with_ansi = "Hello \x1b[31mtest@example.com\x1b[0m world.";
@@ -262,7 +268,7 @@ fn test_us_ssn_programmatic_validation_valid() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_valid = "My SSN is 123-45-6789. Another is 789-12-3456.";
@@ -284,7 +290,7 @@ fn test_us_ssn_programmatic_validation_invalid_area_000() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_invalid_area_000 = "Invalid SSN: 000-12-3456.";
@@ -306,7 +312,7 @@ fn test_us_ssn_programmatic_validation_invalid_area_666() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_invalid_area_666 = "Another invalid: 666-78-9012.";
@@ -328,7 +334,7 @@ fn test_us_ssn_programmatic_validation_invalid_area_9xx() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_invalid_area_9xx = "Area 9: 900-11-2222.";
@@ -350,7 +356,7 @@ fn test_us_ssn_programmatic_validation_invalid_group_00() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_invalid_group_00 = "Group 00: 123-00-4567.";
@@ -372,7 +378,7 @@ fn test_us_ssn_programmatic_validation_invalid_serial_0000() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let text_invalid_serial_0000 = "Serial 0000: 123-45-0000.";
@@ -394,7 +400,7 @@ fn test_uk_nino_programmatic_validation_valid() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input = "Valid NINO: AB123456A. Valid Spaced NINO: AA 12 34 56 B.";
@@ -416,7 +422,7 @@ fn test_uk_nino_programmatic_validation_invalid_prefix() -> Result<()> {
         false, None, false, false,
         true,
     );
-    let config = RedactionConfig { rules: vec![rule] };
+    let config = RedactionConfig { rules: vec![rule], ..Default::default() };
     let compiled_rules = RegexEngine::new(config)?;
 
     let input = "Invalid BG: BG123456A. Invalid GB: GB123456B. Invalid ZZ: ZZ123456C. Invalid DF: DF123456A. Invalid QV: QV123456B.";
@@ -435,7 +441,7 @@ fn test_compile_rules_invalid_regex_fails_fast() {
         create_test_rule("valid_rule", "abc", "[REDACTED]", false, None, false, false, false),
         create_test_rule("invalid_rule", "[", "[ERROR]", false, None, false, false, false),
     ];
-    let config = RedactionConfig { rules: rules_vec };
+    let config = RedactionConfig { rules: rules_vec, ..Default::default() };
     let result = RegexEngine::new(config);
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -451,7 +457,7 @@ fn test_compile_rules_pattern_too_long_fails_fast() {
         create_test_rule("valid_rule", "abc", "[REDACTED]", false, None, false, false, false),
         create_test_rule("long_pattern_rule", &long_pattern, "[TOO_LONG]", false, None, false, false, false),
     ];
-    let config = RedactionConfig { rules: rules_vec };
+    let config = RedactionConfig { rules: rules_vec, ..Default::default() };
     let result = RegexEngine::new(config);
     assert!(result.is_err());
     let err = result.unwrap_err();
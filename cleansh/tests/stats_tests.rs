@@ -38,7 +38,7 @@ fn get_test_paths(test_name: &str) -> anyhow::Result<TestPaths> {
     let app_state_file_path = temp_dir.path().join("app_state.json");
 
     // Initialize a default AppState and save it to the test-specific path.
-    let initial_state = AppState::new();
+    let mut initial_state = AppState::new();
     initial_state.save(&app_state_file_path)?;
     debug!("Test setup: App state file created at {:?}", app_state_file_path);
 
@@ -58,7 +58,7 @@ fn run_cleansh_cmd(app_state_file: &PathBuf) -> Command {
 
     // --- IMPORTANT: Clear potentially interfering environment variables for each command call ---
     cmd.env_remove("RUST_LOG");
-    cmd.env_remove("CLEANSH_ALLOW_DEBUG_PII"); // Clear PII debug flag
+    cmd.env_remove("CLEANSH_ALLOW_DEBUG_PII"); // Clear the legacy PII debug variable
     debug!("Command setup: CLEANSH_STATE_FILE_OVERRIDE_FOR_TESTS set to {:?}", app_state_file);
     cmd
 }
@@ -111,7 +111,7 @@ fn test_stats_only_with_simple_matches() -> anyhow::Result<()> {
     assert!(output.status.success()); // Assert the command succeeded
     assert!(stderr.contains("Redaction Statistics Summary"));
     assert!(stderr.contains("Email: 1 match"));
-    assert!(stderr.contains("Ipv4 Address: 1 match"));
+    assert!(stderr.contains("Ipv4 Private: 1 match"));
 
     Ok(())
 }
@@ -140,6 +140,7 @@ fn test_stats_with_json_file_output() -> anyhow::Result<()> {
 
     // Parse and verify the content
     let json: Value = serde_json::from_str(&json_content)?;
+    assert_eq!(json["schema_version"], "1.0");
     let email_count = json["redaction_summary"]["email"].as_u64().unwrap_or(0);
     assert_eq!(email_count, 2);
 
@@ -189,7 +190,552 @@ fn test_stats_rule_disable() -> anyhow::Result<()> {
     assert!(output.status.success()); // Assert the command succeeded
     // Email should not be counted
     assert!(!stderr.contains("Email:"));
-    assert!(stderr.contains("Ipv4 Address: 1 match"));
+    assert!(stderr.contains("Ipv4 Private: 1 match"));
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_scan_files_from_stdin_null_delimited() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_files_from_stdin_null_delimited")?;
+    debug!("Running test_scan_files_from_stdin_null_delimited");
+
+    // Filenames with spaces and newlines must survive NUL-delimited lists intact.
+    let dir = test_paths._temp_dir.path();
+    let spaced = dir.join("with space.txt");
+    let newlined = dir.join("with\nnewline.txt");
+    fs::write(&spaced, "contact: first@example.com")?;
+    fs::write(&newlined, "contact: second@example.com and 10.0.0.1")?;
+
+    let mut list = Vec::new();
+    for path in [&spaced, &newlined] {
+        list.extend_from_slice(path.to_str().unwrap().as_bytes());
+        list.push(0);
+    }
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .write_stdin(list)
+        .args(["scan", "--files-from", "-", "-0", "--json-stdout"])
+        .output()?;
+
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"].as_u64(), Some(2));
+    assert_eq!(json["redaction_summary"]["ipv4_private"].as_u64(), Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn test_scan_files_from_newline_list_skips_missing() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_files_from_newline_list_skips_missing")?;
+    debug!("Running test_scan_files_from_newline_list_skips_missing");
+
+    let dir = test_paths._temp_dir.path();
+    let present = dir.join("present.log");
+    fs::write(&present, "user@example.com")?;
+    let list_path = dir.join("files.txt");
+    fs::write(&list_path, format!("{}\n{}\n", present.display(), dir.join("missing.log").display()))?;
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .arg("scan")
+        .arg("--files-from")
+        .arg(&list_path)
+        .arg("--json-stdout")
+        .output()?;
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipping"));
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"].as_u64(), Some(1));
+    assert!(json["rules"]["email"]["version"].is_string(), "Missing rule metadata: {}", json);
+
+    Ok(())
+}
+
+#[test]
+fn test_scan_forward_splunk_hec_sends_metadata_in_batches() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_forward_splunk_hec_sends_metadata_in_batches")?;
+    debug!("Running test_scan_forward_splunk_hec_sends_metadata_in_batches");
+
+    let mut server = mockito::Server::new();
+    // Three findings with a batch size of two means two requests.
+    let mock = server
+        .mock("POST", "/services/collector/event")
+        .match_header("authorization", "Splunk hec-token")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex("cleansh:finding".to_string()),
+            mockito::Matcher::Regex("\"rule_version\":\"[0-9.]+\"".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"text":"Success","code":0}"#)
+        .expect(2)
+        .create();
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .env("TEST_HEC_TOKEN", "hec-token")
+        .args(["scan", "--json-stdout", "--forward", "splunk-hec", "--token-env", "TEST_HEC_TOKEN", "--forward-batch-size", "2", "--endpoint"])
+        .arg(format!("{}/services/collector/event", server.url()))
+        .write_stdin("a@example.com b@example.com 10.0.0.1")
+        .output()?;
+
+    mock.assert();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Forwarded 3 finding(s)"));
+    Ok(())
+}
+
+#[test]
+fn test_scan_forward_never_sends_values_and_failures_are_warnings() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_forward_never_sends_values_and_failures_are_warnings")?;
+    debug!("Running test_scan_forward_never_sends_values_and_failures_are_warnings");
+
+    let mut server = mockito::Server::new();
+    // Any request body containing the secret would match this mock instead and fail the test.
+    let leak = server
+        .mock("POST", "/findings/_bulk")
+        .match_body(mockito::Matcher::Regex("secret@example\\.com".to_string()))
+        .expect(0)
+        .create();
+    // Server errors are retried (three attempts in total) before giving up.
+    let unavailable = server
+        .mock("POST", "/findings/_bulk")
+        .match_header("authorization", "ApiKey es-key")
+        .with_status(503)
+        .expect(3)
+        .create();
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .env("TEST_ES_KEY", "es-key")
+        .args(["scan", "--json-stdout", "--forward", "elastic", "--token-env", "TEST_ES_KEY", "--endpoint"])
+        .arg(format!("{}/findings/_bulk", server.url()))
+        .write_stdin("secret@example.com")
+        .output()?;
+
+    leak.assert();
+    unavailable.assert();
+    // The scan itself still succeeds and reports its results.
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"].as_u64(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("WARNING: Failed to forward findings"));
+    Ok(())
+}
+
+#[test]
+fn test_fail_over_posts_webhook_notification() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_fail_over_posts_webhook_notification")?;
+    debug!("Running test_fail_over_posts_webhook_notification");
+
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/hooks/team")
+        .match_body(mockito::Matcher::Json(serde_json::json!({
+            "text": "2 found in org/app by scan-job: - email: 2"
+        })))
+        .with_status(200)
+        .expect(1)
+        .create();
+
+    let mut cmd = run_cleansh_cmd(&test_paths.app_state_file_path);
+    for key in ["GITHUB_REPOSITORY", "CI_PROJECT_PATH", "GITHUB_JOB", "CI_JOB_NAME"] {
+        cmd.env_remove(key);
+    }
+    let output = cmd
+        .env("CLEANSH_CI_REPO", "org/app")
+        .env("CLEANSH_CI_JOB", "scan-job")
+        .env("CLEANSH_NOTIFY_WEBHOOK", format!("{}/hooks/team", server.url()))
+        .args(["scan", "--fail-over-threshold", "1", "--notify-template", "{total} found in {repo} by {job}: {rules}"])
+        .write_stdin("a@example.com and b@example.com")
+        .output()?;
+
+    mock.assert();
+    assert!(!output.status.success(), "Fail-over must still fail the scan.");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Sent fail-over notification"));
+    assert!(!stderr.contains("hooks/team"), "The webhook URL must not be echoed.");
+    Ok(())
+}
+
+#[test]
+fn test_scan_resume_skips_completed_files() -> anyhow::Result<()> {
+    use cleansh::utils::scan_checkpoint::ScanCheckpoint;
+    let test_paths = get_test_paths("test_scan_resume_skips_completed_files")?;
+
+    let dir = test_paths._temp_dir.path();
+    let first = dir.join("first.log");
+    let second = dir.join("second.log");
+    fs::write(&first, "one@example.com")?;
+    fs::write(&second, "two@example.com and 10.0.0.1")?;
+    let list_path = dir.join("files.txt");
+    fs::write(&list_path, format!("{}\n{}\n", first.display(), second.display()))?;
+    let checkpoint_path = dir.join("scan.checkpoint");
+
+    // A completed scan leaves no checkpoint behind.
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--files-from", list_path.to_str().unwrap(), "--checkpoint", checkpoint_path.to_str().unwrap(), "--json-stdout"])
+        .output()?;
+    assert!(output.status.success());
+    assert!(!checkpoint_path.exists());
+
+    // Simulate a scan interrupted after the first file, which has since become unreadable.
+    let paths = vec![first.clone(), second.clone()];
+    let mut checkpoint = ScanCheckpoint::open(&checkpoint_path, &paths, false)?;
+    let email = cleansh_core::RedactionMatch {
+        rule_name: "email".to_string(),
+        original_string: "one@example.com".to_string(),
+        sanitized_string: "[EMAIL_REDACTED]".to_string(),
+        start: 0,
+        end: 15,
+        line_number: Some(1),
+        sample_hash: None,
+        match_context_hash: None,
+        timestamp: None,
+        rule: Default::default(),
+        source_id: first.display().to_string(),
+    };
+    checkpoint.record(&first, Some(&[email]));
+    checkpoint.save()?;
+    fs::remove_file(&first)?;
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--files-from", list_path.to_str().unwrap(), "--checkpoint", checkpoint_path.to_str().unwrap(), "--resume", "--json-stdout"])
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Resuming scan: 1 file(s) already scanned, 1 finding(s) so far."), "{}", stderr);
+    assert!(!stderr.contains("Skipping"), "Completed file was read again: {}", stderr);
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"].as_u64(), Some(2));
+    assert_eq!(json["redaction_summary"]["ipv4_private"].as_u64(), Some(1));
+    assert!(!checkpoint_path.exists());
+
+    // A checkpoint for another list is refused rather than silently misapplied.
+    ScanCheckpoint::open(&checkpoint_path, &paths[1..], false)?.save()?;
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--files-from", list_path.to_str().unwrap(), "--checkpoint", checkpoint_path.to_str().unwrap(), "--resume"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("different file list"));
+
+    // A --dir scan keeps a checkpoint too; a single input has nothing to resume.
+    let logs = dir.join("logs");
+    fs::create_dir(&logs)?;
+    fs::write(logs.join("app.log"), "three@example.com")?;
+    fs::remove_file(&checkpoint_path)?;
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--dir", logs.to_str().unwrap(), "--checkpoint", checkpoint_path.to_str().unwrap(), "--json-stdout"])
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(serde_json::from_slice::<Value>(&output.stdout)?["redaction_summary"]["email"].as_u64(), Some(1));
+    assert!(!checkpoint_path.exists());
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--checkpoint", checkpoint_path.to_str().unwrap()])
+        .write_stdin("four@example.com")
+        .assert()
+        .failure();
+    Ok(())
+}
+
+#[test]
+fn test_scan_json_counts_findings_per_tenant() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_json_counts_findings_per_tenant")?;
+
+    let dir = test_paths._temp_dir.path();
+    let config_path = dir.join("tenants.yaml");
+    fs::write(
+        &config_path,
+        "rules:\n  - name: tenant_id\n    pattern: 'tenant=(?P<id>[a-z0-9-]+)'\n    replace_with: 'tenant=[TENANT]'\ntenant:\n  rule: tenant_id\n  group: id\n",
+    )?;
+    let input = "tenant=acme login a@example.com\ntenant=globex login b@example.com from c@example.com\nstartup done for ops@example.com\n";
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--config", config_path.to_str().unwrap(), "--json-stdout"])
+        .write_stdin(input)
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["tenants"]["sha256:822b33ad87c148a0"]["email"], 1);
+    assert_eq!(json["tenants"]["(none)"]["email"], 1);
+    assert!(json["tenants"].get("acme").is_none());
+    assert_eq!(json["redaction_summary"]["email"], 4);
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--config", config_path.to_str().unwrap(), "--json-stdout", "--tenant-names"])
+        .write_stdin(input)
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["tenants"]["acme"]["email"], 1);
+    assert_eq!(json["tenants"]["globex"]["email"], 2);
+    Ok(())
+}
+
+#[test]
+fn test_scan_sample_percent_reports_estimates() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_sample_percent_reports_estimates")?;
+    let input: String = (0..100).map(|i| format!("request {} from user{}@example.com\n", i, i)).collect();
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--sample-percent", "10", "--json-stdout"])
+        .write_stdin(input)
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("Sampled scan:"), "{}", stderr);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"], 10);
+    let estimate = json["sampling"]["estimated_summary"]["email"].as_u64().unwrap();
+    assert!((90..=110).contains(&estimate), "estimate {} should be close to 100", estimate);
+    assert_eq!(json["sampling"]["stopped_early"], false);
+    Ok(())
+}
+
+#[test]
+fn test_scan_max_matches_keeps_counts_exact() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_max_matches_keeps_counts_exact")?;
+    let input: String = (0..50).map(|i| format!("user{}@example.com\n", i)).collect();
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--max-matches", "5", "--json-stdout"])
+        .write_stdin(input)
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("only a random sample of 5 was kept"), "{}", stderr);
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"], 50);
+    assert_eq!(json["truncation"]["max_matches"], 5);
+    assert_eq!(json["truncation"]["total_matches"], 50);
+    Ok(())
+}
+
+#[test]
+fn test_scan_reuses_cached_findings_for_unchanged_files() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_reuses_cached_findings_for_unchanged_files")?;
+    let dir = test_paths._temp_dir.path();
+    let first = dir.join("first.log");
+    let second = dir.join("second.log");
+    fs::write(&first, "one@example.com")?;
+    fs::write(&second, "two@example.com and three@example.com")?;
+    let list_path = dir.join("files.txt");
+    fs::write(&list_path, format!("{}\n{}\n", first.display(), second.display()))?;
+
+    let scan = |extra: &[&str]| -> anyhow::Result<(serde_json::Value, String)> {
+        let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+            .args(["scan", "--files-from", list_path.to_str().unwrap(), "--json-stdout"])
+            .args(extra)
+            .output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        assert!(output.status.success(), "{}", stderr);
+        Ok((serde_json::from_slice(&output.stdout)?, stderr))
+    };
+
+    let (json, stderr) = scan(&[])?;
+    assert_eq!(json["redaction_summary"]["email"], 3);
+    assert!(!stderr.contains("Reused cached findings"), "{}", stderr);
+
+    fs::write(&first, "four@example.com and 8.8.8.8")?;
+    let (json, stderr) = scan(&[])?;
+    assert!(stderr.contains("Reused cached findings for 1 of 2 file(s)."), "{}", stderr);
+    assert_eq!(json["redaction_summary"]["email"], 3);
+    assert_eq!(json["redaction_summary"]["ipv4_address"], 1);
+
+    let (json, stderr) = scan(&["--no-cache"])?;
+    assert!(!stderr.contains("Reused cached findings"), "{}", stderr);
+    assert_eq!(json["redaction_summary"]["email"], 3);
+    Ok(())
+}
+
+#[test]
+fn test_scan_seed_is_accepted_before_and_after_the_subcommand() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_seed_is_accepted_before_and_after_the_subcommand")?;
+    let input: String = (0..50).map(|i| format!("user{}@example.com\n", i)).collect();
+
+    for args in [&["--seed", "42", "scan", "--max-matches", "5", "--json-stdout"][..], &["scan", "--max-matches", "5", "--json-stdout", "--seed", "42"][..]] {
+        let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+            .args(args)
+            .write_stdin(input.clone())
+            .output()?;
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        assert_eq!(json["redaction_summary"]["email"], 50);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_stats_history_shows_recorded_scans() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_stats_history_shows_recorded_scans")?;
+
+    for input in ["a@example.com b@example.com", "c@example.com", "nothing to see"] {
+        let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+            .args(["scan", "--record-history", "--json-stdout"])
+            .write_stdin(input)
+            .output()?;
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+    // Scans without the flag are not recorded.
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--json-stdout"])
+        .write_stdin("d@example.com")
+        .output()?;
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["stats", "history", "--json"])
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["schema_version"], "1.0");
+    assert_eq!(json["period"], "day");
+    assert_eq!(json["periods"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["periods"][0]["scans"], 3);
+    assert_eq!(json["periods"][0]["findings"], 3);
+    assert_eq!(json["periods"][0]["rules"]["email"], 3);
+
+    let history = fs::read_to_string(test_paths.app_state_file_path.with_file_name("scan-history.jsonl"))?;
+    assert!(!history.contains("example.com"), "Matched values must not be recorded.");
+    assert!(history.lines().all(|line| line.starts_with(r#"{"schema_version":"1.0","#)));
+    Ok(())
+}
+
+/// Sends a `GET` request to the dashboard at `address` and returns the response.
+fn http_get(address: &str, path: &str) -> anyhow::Result<String> {
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(address)?;
+    write!(stream, "GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", path, address)?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    Ok(response)
+}
+
+#[test]
+fn test_stats_serve_charts_recorded_scans() -> anyhow::Result<()> {
+    use std::io::BufRead;
+    let test_paths = get_test_paths("test_stats_serve_charts_recorded_scans")?;
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("a.log"), "a@example.com b@example.com\n")?;
+    fs::write(dir.path().join("b.log"), "c@example.com\n")?;
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--dir", dir.path().to_str().unwrap(), "--record-history", "--history-files", "--json-stdout"])
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin("cleansh"))
+        .env("CLEANSH_STATE_FILE_OVERRIDE_FOR_TESTS", &test_paths.app_state_file_path)
+        .env_remove("RUST_LOG")
+        .args(["stats", "serve", "--port", "0"])
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    let stderr = std::io::BufReader::new(server.stderr.take().unwrap());
+    let address = stderr
+        .lines()
+        .map_while(Result::ok)
+        .find_map(|line| line.split("http://").nth(1).and_then(|rest| rest.split('/').next()).map(str::to_string));
+    let result = (|| -> anyhow::Result<()> {
+        let address = address.ok_or_else(|| anyhow::anyhow!("The server did not report its address"))?;
+
+        let page = http_get(&address, "/")?;
+        assert!(page.starts_with("HTTP/1.1 200 OK"), "{}", page);
+        assert!(page.contains("<svg") && page.contains("<code>email</code>"), "{}", page);
+
+        let data = http_get(&address, "/data.json")?;
+        let body = data.split("\r\n\r\n").nth(1).unwrap_or_default();
+        let json: Value = serde_json::from_str(body)?;
+        assert_eq!(json["schema_version"], "1.0");
+        assert_eq!(json["periods"][0]["findings"], 3);
+        assert_eq!(json["top_rules"][0]["name"], "email");
+        assert!(json["top_files"][0]["name"].as_str().unwrap_or_default().ends_with("a.log"), "{}", body);
+        assert_eq!(json["top_files"][0]["findings"], 2);
+
+        assert!(http_get(&address, "/missing")?.starts_with("HTTP/1.1 404"));
+        Ok(())
+    })();
+    server.kill()?;
+    server.wait()?;
+    result
+}
+
+#[test]
+fn test_scan_fail_on_severity_ignores_lower_findings() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_fail_on_severity_ignores_lower_findings")?;
+
+    let dir = test_paths._temp_dir.path();
+    let config_path = dir.join("severities.yaml");
+    fs::write(
+        &config_path,
+        "rules:\n  - name: build_id\n    pattern: 'BUILD-[0-9]+'\n    replace_with: '[BUILD]'\n    severity: low\n  - name: deploy_key\n    pattern: 'DK-[0-9]+'\n    replace_with: '[DEPLOY_KEY]'\n    severity: Critical\n",
+    )?;
+    let config = config_path.to_str().unwrap();
+
+    // Low-severity and unrated findings do not fail a scan gated on `high`.
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--config", config, "--fail-on-severity", "high", "--json-stdout"])
+        .write_stdin("BUILD-1 BUILD-2 by a@example.com")
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["severity_summary"]["low"], 2);
+    assert_eq!(json["severity_summary"]["unspecified"], 1);
+
+    // Nor does --strict, which otherwise fails on any finding.
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--config", config, "--strict", "--fail-on-severity", "high"])
+        .write_stdin("BUILD-1")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("--- Findings per Severity ---"))
+        .stderr(predicate::str::contains("low: 1 match"));
+
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .args(["scan", "--config", config, "--fail-on-severity", "high"])
+        .write_stdin("BUILD-1 and DK-42")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("FAIL-ON-SEVERITY triggered: Found 1 redaction match of severity high or higher."));
+    Ok(())
+}
+
+#[test]
+fn test_scan_staged_reports_added_lines_only() -> anyhow::Result<()> {
+    let test_paths = get_test_paths("test_scan_staged_reports_added_lines_only")?;
+
+    let repo = test_paths._temp_dir.path().join("repo");
+    fs::create_dir_all(&repo)?;
+    let git = |args: &[&str]| -> anyhow::Result<()> {
+        let status = std::process::Command::new("git").args(args).current_dir(&repo).status()?;
+        anyhow::ensure!(status.success(), "git {:?} failed", args);
+        Ok(())
+    };
+    git(&["init", "--quiet"])?;
+    git(&["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "--allow-empty", "-m", "init"])?;
+    fs::write(repo.join("notes.txt"), "owner: old@example.com\n")?;
+    git(&["add", "notes.txt"])?;
+    git(&["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "notes"])?;
+
+    // Only the added third line is scanned; the committed address and unstaged changes are not.
+    fs::write(repo.join("notes.txt"), "owner: old@example.com\n\nbackup: new@example.com\n")?;
+    git(&["add", "notes.txt"])?;
+    fs::write(repo.join("notes.txt"), "owner: old@example.com\n\nbackup: new@example.com\nlater: unstaged@example.com\n")?;
+
+    let output = run_cleansh_cmd(&test_paths.app_state_file_path)
+        .current_dir(&repo)
+        .args(["scan", "--staged", "--json-stdout"])
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let json: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(json["redaction_summary"]["email"], 1);
+    assert_eq!(json["files"]["notes.txt"]["findings"][0]["line"], 3);
+
+    run_cleansh_cmd(&test_paths.app_state_file_path)
+        .current_dir(&repo)
+        .args(["scan", "--staged", "--fail-over-threshold", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("notes.txt:3: email"));
+    Ok(())
+}
@@ -0,0 +1,99 @@
+// tests/vault_tokenize_tests.rs
+//! Integration tests for `cleansh sanitize --tokenize vault` and `--tokenize aws-kms`.
+//!
+//! These tests only build with the `vault` feature. `mockito` stands in for the Vault
+//! transit and AWS KMS APIs so we can verify the request cleansh sends and how tokens are
+//! spliced in.
+#![cfg(feature = "vault")]
+
+use anyhow::Result;
+use assert_cmd::Command;
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+/// Builds a `cleansh sanitize --tokenize vault` command pointed at the mock server.
+fn tokenize_cmd(server: &Server) -> Command {
+    let mut cmd = Command::cargo_bin("cleansh").unwrap();
+    cmd.env("VAULT_ADDR", server.url())
+        .env("VAULT_TOKEN", "test-vault-token")
+        .env_remove("RUST_LOG")
+        .args(["sanitize", "--tokenize", "vault", "--no-redaction-summary"]);
+    cmd
+}
+
+#[test]
+fn test_tokenize_vault_replaces_each_distinct_secret_once() -> Result<()> {
+    let mut server = Server::new();
+    // "test@example.com" in base64; the repeated email is only sent once.
+    let mock = server
+        .mock("POST", "/v1/transit/encrypt/cleansh")
+        .match_header("x-vault-token", "test-vault-token")
+        .match_body(Matcher::Json(json!({ "batch_input": [{ "plaintext": "dGVzdEBleGFtcGxlLmNvbQ==" }] })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "data": { "batch_results": [{ "ciphertext": "vault:v1:abc123" }] } }).to_string())
+        .expect(1)
+        .create();
+
+    let output = tokenize_cmd(&server)
+        .write_stdin("from test@example.com to test@example.com")
+        .output()?;
+
+    mock.assert();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim_end(), "from [VAULT:vault:v1:abc123] to [VAULT:vault:v1:abc123]");
+    Ok(())
+}
+
+#[test]
+fn test_tokenize_vault_fails_closed_on_vault_error() -> Result<()> {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("POST", "/v1/transit/encrypt/cleansh")
+        .with_status(403)
+        .with_body(r#"{"errors":["permission denied"]}"#)
+        .create();
+
+    let output = tokenize_cmd(&server).write_stdin("Email: test@example.com").output()?;
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "Nothing should be printed when tokenization fails.");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Tokenization with the 'vault' backend failed"));
+    Ok(())
+}
+
+#[test]
+fn test_tokenize_aws_kms_sends_signed_encrypt_requests() -> Result<()> {
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/")
+        .match_header("x-amz-target", "TrentService.Encrypt")
+        .match_header("content-type", "application/x-amz-json-1.1")
+        .match_header(
+            "authorization",
+            Matcher::Regex(r"^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/\d{8}/eu-west-1/kms/aws4_request, SignedHeaders=content-type;host;x-amz-date;x-amz-target, Signature=[0-9a-f]{64}$".to_string()),
+        )
+        .match_body(Matcher::Json(json!({ "KeyId": "alias/cleansh", "Plaintext": "dGVzdEBleGFtcGxlLmNvbQ==" })))
+        .with_status(200)
+        .with_header("content-type", "application/x-amz-json-1.1")
+        .with_body(json!({ "CiphertextBlob": "AQICAHg=", "KeyId": "arn:aws:kms:eu-west-1:111122223333:key/abc" }).to_string())
+        .expect(1)
+        .create();
+
+    let output = Command::cargo_bin("cleansh")?
+        .env("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE")
+        .env("AWS_SECRET_ACCESS_KEY", "test-secret-key")
+        .env_remove("AWS_SESSION_TOKEN")
+        .env_remove("RUST_LOG")
+        .args(["sanitize", "--tokenize", "aws-kms", "--kms-key-id", "alias/cleansh", "--kms-region", "eu-west-1"])
+        .args(["--kms-endpoint", &server.url(), "--no-redaction-summary"])
+        .write_stdin("from test@example.com to test@example.com")
+        .output()?;
+
+    mock.assert();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim_end(), "from [KMS:AQICAHg=] to [KMS:AQICAHg=]");
+    Ok(())
+}
@@ -0,0 +1,125 @@
+// tests/verbosity_tests.rs
+//! Integration tests that lock down what `cleansh` writes to stderr at each verbosity level.
+//!
+//! `--quiet` is enforced by a single verbosity policy in `ui::output_format`, so these
+//! tests exercise several commands and output paths to make sure informational
+//! messages and summaries are suppressed everywhere, while warnings, errors, and
+//! primary output (stdout, scan reports) are unaffected.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::{tempdir, TempDir};
+
+/// Builds a `cleansh` command isolated from the developer's environment and app state.
+fn cleansh_cmd(state_dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("cleansh").unwrap();
+    cmd.env("CLEANSH_STATE_FILE_OVERRIDE_FOR_TESTS", state_dir.path().join("state.json"));
+    cmd.env_remove("RUST_LOG");
+    cmd.env_remove("CLEANSH_ALLOW_DEBUG_PII");
+    cmd
+}
+
+#[test]
+fn test_sanitize_normal_verbosity_prints_info_and_summary() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    cleansh_cmd(&state_dir)
+        .arg("sanitize")
+        .write_stdin("Email: test@example.com")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[EMAIL_REDACTED]"))
+        .stderr(
+            predicate::str::contains("Reading input from stdin...")
+                .and(predicate::str::contains("Writing sanitized content to stdout."))
+                .and(predicate::str::contains("--- Redaction Summary ---")),
+        );
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_quiet_prints_nothing_to_stderr() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    cleansh_cmd(&state_dir)
+        .args(["--quiet", "sanitize"])
+        .write_stdin("Email: test@example.com")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[EMAIL_REDACTED]"))
+        .stderr(predicate::str::is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_sanitize_quiet_with_input_and_output_files() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    let input_path = state_dir.path().join("input.txt");
+    let output_path = state_dir.path().join("output.txt");
+    fs::write(&input_path, "IP: 192.168.1.1")?;
+
+    cleansh_cmd(&state_dir)
+        .args(["--quiet", "sanitize", "--input-file"])
+        .arg(&input_path)
+        .arg("--output")
+        .arg(&output_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::is_empty());
+
+    assert!(fs::read_to_string(&output_path)?.contains("[PRIVATE_IP_REDACTED]"));
+    Ok(())
+}
+
+#[test]
+fn test_scan_quiet_keeps_report_but_drops_info() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    let output = cleansh_cmd(&state_dir)
+        .args(["--quiet", "scan"])
+        .write_stdin("Email: test@example.com")
+        .output()?;
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // The statistics report is the scan's primary output and is always printed.
+    assert!(stderr.contains("Redaction Statistics Summary"));
+    assert!(!stderr.contains("License check bypassed"));
+    Ok(())
+}
+
+#[test]
+fn test_quiet_still_prints_warnings() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    let list_path = state_dir.path().join("files.txt");
+    fs::write(&list_path, state_dir.path().join("does_not_exist.log").display().to_string())?;
+
+    cleansh_cmd(&state_dir)
+        .args(["--quiet", "scan", "--files-from"])
+        .arg(&list_path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("WARNING: Skipping"));
+    Ok(())
+}
+
+#[test]
+fn test_piped_stdout_behaves_as_quiet_unless_verbose() -> anyhow::Result<()> {
+    let state_dir = tempdir()?;
+    cleansh_cmd(&state_dir)
+        .env("CLEANSH_PIPED_OVERRIDE_FOR_TESTS", "1")
+        .arg("sanitize")
+        .write_stdin("Email: test@example.com")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[EMAIL_REDACTED]"))
+        .stderr(predicate::str::is_empty());
+
+    cleansh_cmd(&state_dir)
+        .env("CLEANSH_PIPED_OVERRIDE_FOR_TESTS", "1")
+        .args(["sanitize", "--verbose"])
+        .write_stdin("Email: test@example.com")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Reading input from stdin...").and(predicate::str::contains("--- Redaction Summary ---")));
+    Ok(())
+}